@@ -1,6 +1,8 @@
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use git2::{Repository, Signature};
-use reponest::core::git_ops::{RepoInfoWorker, get_repos_info_parallel};
+use reponest::core::git_ops::{RepoInfoWorker, append_unique_repos, get_repos_info_parallel};
+use reponest::core::{RepoInfo, RepoInfoOptions};
+use std::collections::HashSet;
 use std::fs;
 use std::hint::black_box;
 use std::path::{Path, PathBuf};
@@ -171,7 +173,8 @@ fn bench_parallel_simple(c: &mut Criterion) {
             let paths = create_test_repos(temp_dir.path(), count, "simple");
 
             b.iter(|| {
-                let results = get_repos_info_parallel(black_box(&paths));
+                let results =
+                    get_repos_info_parallel(black_box(&paths), RepoInfoOptions::default());
                 assert_eq!(results.len(), count);
             });
         });
@@ -190,7 +193,8 @@ fn bench_parallel_complex(c: &mut Criterion) {
             let paths = create_test_repos(temp_dir.path(), count, "complex");
 
             b.iter(|| {
-                let results = get_repos_info_parallel(black_box(&paths));
+                let results =
+                    get_repos_info_parallel(black_box(&paths), RepoInfoOptions::default());
                 assert_eq!(results.len(), count);
             });
         });
@@ -210,7 +214,7 @@ fn bench_worker_simple(c: &mut Criterion) {
             let paths = create_test_repos(temp_dir.path(), count, "simple");
 
             b.to_async(&rt).iter(|| async {
-                let worker = Arc::new(RepoInfoWorker::for_repo_info());
+                let worker = Arc::new(RepoInfoWorker::for_repo_info(RepoInfoOptions::default()));
                 worker.submit_repos(black_box(&paths));
 
                 let mut results = Vec::new();
@@ -244,7 +248,7 @@ fn bench_worker_complex(c: &mut Criterion) {
             let paths = create_test_repos(temp_dir.path(), count, "complex");
 
             b.to_async(&rt).iter(|| async {
-                let worker = Arc::new(RepoInfoWorker::for_repo_info());
+                let worker = Arc::new(RepoInfoWorker::for_repo_info(RepoInfoOptions::default()));
                 worker.submit_repos(black_box(&paths));
 
                 let mut results = Vec::new();
@@ -279,7 +283,8 @@ fn bench_repo_types(c: &mut Criterion) {
                 let paths = create_test_repos(temp_dir.path(), 10, repo_type);
 
                 b.iter(|| {
-                    let results = get_repos_info_parallel(black_box(&paths));
+                    let results =
+                        get_repos_info_parallel(black_box(&paths), RepoInfoOptions::default());
                     assert_eq!(results.len(), 10);
                 });
             },
@@ -300,14 +305,14 @@ fn bench_worker_vs_parallel(c: &mut Criterion) {
 
     group.bench_function("parallel_30_repos", |b| {
         b.iter(|| {
-            let results = get_repos_info_parallel(black_box(&paths));
+            let results = get_repos_info_parallel(black_box(&paths), RepoInfoOptions::default());
             assert_eq!(results.len(), 30);
         });
     });
 
     group.bench_function("worker_30_repos", |b| {
         b.to_async(&rt).iter(|| async {
-            let worker = Arc::new(RepoInfoWorker::for_repo_info());
+            let worker = Arc::new(RepoInfoWorker::for_repo_info(RepoInfoOptions::default()));
             worker.submit_repos(black_box(&paths));
 
             let mut results = Vec::new();
@@ -339,7 +344,7 @@ fn bench_extreme_many_repos(c: &mut Criterion) {
         let paths = create_test_repos(temp_dir.path(), 100, "simple");
 
         b.iter(|| {
-            let results = get_repos_info_parallel(black_box(&paths));
+            let results = get_repos_info_parallel(black_box(&paths), RepoInfoOptions::default());
             assert_eq!(results.len(), 100);
         });
     });
@@ -349,7 +354,7 @@ fn bench_extreme_many_repos(c: &mut Criterion) {
         let paths = create_test_repos(temp_dir.path(), 100, "simple");
 
         b.to_async(&rt).iter(|| async {
-            let worker = Arc::new(RepoInfoWorker::for_repo_info());
+            let worker = Arc::new(RepoInfoWorker::for_repo_info(RepoInfoOptions::default()));
             worker.submit_repos(black_box(&paths));
 
             let mut results = Vec::new();
@@ -372,7 +377,7 @@ fn bench_extreme_many_repos(c: &mut Criterion) {
         let paths = create_test_repos(temp_dir.path(), 50, "complex");
 
         b.iter(|| {
-            let results = get_repos_info_parallel(black_box(&paths));
+            let results = get_repos_info_parallel(black_box(&paths), RepoInfoOptions::default());
             assert_eq!(results.len(), 50);
         });
     });
@@ -380,6 +385,68 @@ fn bench_extreme_many_repos(c: &mut Criterion) {
     group.finish();
 }
 
+// Benchmark appending scan results to the repo list with deduplication
+fn bench_append_unique(c: &mut Criterion) {
+    let mut group = c.benchmark_group("append_unique");
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("repo");
+    create_test_repo(&path);
+    let template = RepoInfo::from_path(path).unwrap();
+    let make_repos = |count: usize| -> Vec<RepoInfo> {
+        (0..count)
+            .map(|i| {
+                let mut repo = template.clone();
+                repo.basic.path = PathBuf::from(format!("/repos/{}", i));
+                repo
+            })
+            .collect()
+    };
+
+    for count in [1000, 5000] {
+        let batches: Vec<Vec<RepoInfo>> = make_repos(count)
+            .chunks(50)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        // Previous approach: scan the whole list for each result
+        group.bench_with_input(
+            BenchmarkId::new("linear_scan", count),
+            &batches,
+            |b, batches| {
+                b.iter(|| {
+                    let mut repos: Vec<RepoInfo> = Vec::new();
+                    for batch in batches {
+                        for repo in batch.iter().cloned() {
+                            if !repos.iter().any(|r| r.basic.path == repo.basic.path) {
+                                repos.push(repo);
+                            }
+                        }
+                    }
+                    assert_eq!(black_box(repos).len(), count);
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("hash_set", count),
+            &batches,
+            |b, batches| {
+                b.iter(|| {
+                    let mut repos = Vec::new();
+                    let mut seen = HashSet::new();
+                    for batch in batches {
+                        append_unique_repos(&mut repos, &mut seen, batch.iter().cloned());
+                    }
+                    assert_eq!(black_box(repos).len(), count);
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_parallel_simple,
@@ -388,7 +455,8 @@ criterion_group!(
     bench_worker_complex,
     bench_repo_types,
     bench_worker_vs_parallel,
-    bench_extreme_many_repos
+    bench_extreme_many_repos,
+    bench_append_unique
 );
 
 criterion_main!(benches);
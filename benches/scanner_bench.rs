@@ -1,5 +1,6 @@
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use reponest::config::AppConfig;
+use reponest::core::exclude::ExcludeMatcher;
 use std::fs;
 use std::hint::black_box;
 use std::path::Path;
@@ -101,7 +102,7 @@ fn create_realistic_structure(base: &Path, num_projects: usize) {
             for j in 0..5 {
                 let subdir = dir.join(format!("subdir{}", j));
                 fs::create_dir_all(&subdir).unwrap();
-                fs::write(subdir.join("output.bin"), &[0u8; 100]).unwrap();
+                fs::write(subdir.join("output.bin"), [0u8; 100]).unwrap();
             }
         }
 
@@ -307,6 +308,58 @@ fn bench_exclude_patterns(c: &mut Criterion) {
     group.finish();
 }
 
+// Linear check of every pattern per name, as the scanner did before patterns were compiled
+fn matches_any_pattern(name: &str, patterns: &[String]) -> bool {
+    name.starts_with('.')
+        || patterns
+            .iter()
+            .any(|pattern| match pattern.split_once('*') {
+                None => name == pattern,
+                Some((_, suffix)) if suffix.contains('*') => name == pattern,
+                Some((prefix, suffix)) => {
+                    name.len() >= prefix.len() + suffix.len()
+                        && name.starts_with(prefix)
+                        && name.ends_with(suffix)
+                }
+            })
+}
+
+// Benchmark per-name exclude checks: linear pattern scan vs compiled matcher
+fn bench_exclude_matching(c: &mut Criterion) {
+    let mut group = c.benchmark_group("exclude_matching");
+
+    let patterns = AppConfig::default().internal.exclude_dirs;
+    let names: Vec<String> = (0..1000)
+        .map(|i| match i % 4 {
+            0 => format!("noise{}", i),
+            1 => "node_modules".to_string(),
+            2 => format!("src{}", i),
+            _ => "target".to_string(),
+        })
+        .collect();
+
+    group.bench_function("linear_default_patterns", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter(|name| matches_any_pattern(black_box(name), black_box(&patterns)))
+                .count()
+        });
+    });
+
+    let matcher = ExcludeMatcher::new(&patterns);
+    group.bench_function("compiled_default_patterns", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter(|name| black_box(&matcher).is_excluded(black_box(name)))
+                .count()
+        });
+    });
+
+    group.finish();
+}
+
 // Benchmark realistic large workspace scenario
 fn bench_realistic_large_workspace(c: &mut Criterion) {
     let rt = tokio::runtime::Runtime::new().unwrap();
@@ -475,6 +528,7 @@ criterion_group!(
     bench_max_depth_limits,
     bench_scan_multiple_dirs,
     bench_exclude_patterns,
+    bench_exclude_matching,
     bench_realistic_large_workspace,
     bench_nested_noise,
     bench_extreme_case
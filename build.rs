@@ -0,0 +1,7 @@
+//! Build script passing the target triple to `reponest --version`
+
+fn main() {
+    let target = std::env::var("TARGET").unwrap_or_default();
+    println!("cargo:rustc-env=REPONEST_TARGET={}", target);
+    println!("cargo:rerun-if-changed=build.rs");
+}
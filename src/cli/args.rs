@@ -1,4 +1,12 @@
 use clap::{Parser, Subcommand, builder::Styles};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use crate::config::{
+    ColorBy, CompareAgainst, NotifyBackend, RepoRootScope, WorkdirMtime, parse_assignment,
+};
+use crate::core::duration::parse_duration;
 
 /// Styles for clap output
 const STYLES: Styles = Styles::styled()
@@ -7,10 +15,42 @@ const STYLES: Styles = Styles::styled()
     .literal(clap::builder::styling::AnsiColor::Cyan.on_default().bold())
     .placeholder(clap::builder::styling::AnsiColor::Yellow.on_default());
 
+/// Output of `--version`, with the linked libgit2 for bug reports (`-V` prints the version only)
+static LONG_VERSION: LazyLock<String> = LazyLock::new(build_info);
+
+/// Crate version, linked libgit2 version and features, and target triple
+pub fn build_info() -> String {
+    let git = git2::Version::get();
+    let (major, minor, patch) = git.libgit2_version();
+    let features: Vec<&str> = [
+        ("https", git.https()),
+        ("ssh", git.ssh()),
+        ("threads", git.threads()),
+        ("vendored", git.vendored()),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+    format!(
+        "{}\nlibgit2 {}.{}.{} (git2 {})\nlibgit2 features: {}\ntarget: {}",
+        env!("CARGO_PKG_VERSION"),
+        major,
+        minor,
+        patch,
+        git.crate_version(),
+        match features.is_empty() {
+            true => "none".to_string(),
+            false => features.join(", "),
+        },
+        env!("REPONEST_TARGET"),
+    )
+}
+
 /// Command line arguments
 #[derive(Parser, Debug)]
 #[command(name = "reponest")]
 #[command(author, version, about)]
+#[command(long_version = LONG_VERSION.as_str())]
 #[command(styles = STYLES)]
 #[command(
     long_about = "A TUI/CLI tool for managing multiple git repositories written in Rust.\n\n\
@@ -18,23 +58,58 @@ const STYLES: Styles = Styles::styled()
     Use specified subcommands for non-interactive CLI output."
 )]
 #[command(after_long_help = "Examples:\n  \
-    reponest [PATH]                   # Launch interactive TUI\n  \
+    reponest [PATH]...                # Launch interactive TUI\n  \
     reponest --dirty [PATH]           # Launch TUI, show only dirty repos\n  \
-    reponest list [PATH]              # List all repos (CLI)\n  \
-    reponest list --detail [PATH]     # List all repos with details (CLI)")]
+    reponest --repo NAME [PATH]       # Launch TUI, show details of repo NAME\n  \
+    reponest --watch-dirty [PATH]...  # Notify when a repo turns dirty\n  \
+    reponest list [PATH]...           # List all repos (CLI)\n  \
+    reponest list --detail [PATH]     # List all repos with details (CLI)\n  \
+    reponest list --tree [PATH]       # List repos nested by directory (CLI)\n  \
+    reponest list --dirty --interactive  # Open the dirty repos in the TUI\n  \
+    reponest scan --explain [PATH]    # Show why directories were scanned or skipped\n  \
+    reponest diff SNAPSHOT [PATH]     # Show changes since a `list --json` snapshot")]
 pub struct CliArgs {
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Option<CliSubCommands>,
 
-    /// Path to scan for repos (default: home directory)
+    /// Paths to scan for repos (default: scan_dirs from config, or home directory)
     #[arg(global = true, value_name = "PATH")]
-    pub path: Option<String>,
+    pub path: Vec<String>,
+
+    /// Scan the parent of the git repo enclosing the current directory (=root: the repo itself)
+    #[arg(
+        global = true,
+        long,
+        value_name = "SCOPE",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "parent",
+        conflicts_with = "path"
+    )]
+    pub from_repo_root: Option<RepoRootScope>,
 
     /// Maximum scan depth (0 = unlimited)
     #[arg(global = true, long, value_name = "DEPTH")]
     pub max_depth: Option<usize>,
 
+    /// Only look for repos in the scan roots and their direct subdirectories
+    #[arg(global = true, long, conflicts_with = "max_depth")]
+    pub flat: bool,
+
+    /// Stop scanning after DURATION (e.g. 500ms, 10s, 2m) and show what was found
+    #[arg(global = true, long, value_name = "DURATION", value_parser = parse_duration)]
+    pub timeout: Option<Duration>,
+    /// Save scan progress to FILE, so an interrupted scan can be resumed (not used with --flat)
+    /// Save scan progress to FILE, so an interrupted scan can be resumed
+    #[arg(global = true, long, value_name = "FILE")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Skip subtrees that the checkpoint file records as already scanned
+    #[arg(global = true, long, requires = "checkpoint")]
+    pub resume: bool,
+
     /// Show only repos with uncommitted changes
     #[arg(global = true, long)]
     pub dirty: bool,
@@ -43,6 +118,86 @@ pub struct CliArgs {
     #[arg(global = true, long)]
     pub conflict: bool,
 
+    /// Show only repos without any remote
+    #[arg(global = true, long)]
+    pub no_remote: bool,
+
+    /// Show only repos whose index is locked by a leftover index.lock
+    #[arg(global = true, long)]
+    pub locked: bool,
+
+    /// Show only repos with a detached HEAD, e.g. after a bisect or checking out a tag
+    #[arg(global = true, long)]
+    pub detached: bool,
+
+    /// Show only repos carrying TAG in the tags file
+    #[arg(global = true, long, value_name = "TAG")]
+    pub tag: Option<String>,
+
+    /// TOML file mapping repo names or paths to tags
+    #[arg(global = true, long, value_name = "FILE")]
+    pub tags_file: Option<String>,
+
+    /// Show only repos with ignored files or tracked files matched by .gitignore (slower)
+    #[arg(global = true, long)]
+    pub anomalies: bool,
+
+    /// Show only repos with tags not pushed to any remote (as of the last fetch)
+    #[arg(global = true, long)]
+    pub unpushed_tags: bool,
+
+    /// Show only repos with local branches not merged into their upstream or the default branch
+    #[arg(global = true, long)]
+    pub unmerged: bool,
+
+    /// Show only repos where you hold Git LFS locks (runs git-lfs)
+    #[arg(global = true, long)]
+    pub lfs_locks: bool,
+
+    /// Show only repos with files flagged assume-unchanged or skip-worktree (reads the index)
+    #[arg(global = true, long)]
+    pub assume_unchanged: bool,
+
+    /// Compute ahead/behind against the upstream or the default branch
+    #[arg(global = true, long, value_name = "BASE", value_enum)]
+    pub compare_against: Option<CompareAgainst>,
+
+    /// Find when each working tree was last edited: off, dir (cheap) or files (accurate)
+    #[arg(global = true, long, value_name = "MODE", value_enum)]
+    pub workdir_mtime: Option<WorkdirMtime>,
+
+    /// Count conflict hunks by reading conflicted files
+    #[arg(global = true, long)]
+    pub conflict_hunks: bool,
+
+    /// Count hunks not staged yet by diffing tracked files against the index
+    #[arg(global = true, long)]
+    pub unstaged_hunks: bool,
+
+    /// Count distinct commit authors over the last 500 commits of each repo
+    #[arg(global = true, long)]
+    pub contributors: bool,
+
+    /// Gather information of at most N repos at once while the TUI loads
+    #[arg(long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Load each repo's working tree status only when it is selected in the TUI
+    #[arg(long)]
+    pub lazy_info: bool,
+
+    /// Instead of the TUI, keep checking repos and notify when one turns dirty or conflicted
+    #[arg(long, conflicts_with = "repo")]
+    pub watch_dirty: bool,
+
+    /// How --watch-dirty announces changes
+    #[arg(long, value_name = "BACKEND", value_enum, requires = "watch_dirty")]
+    pub notify: Option<NotifyBackend>,
+
+    /// Open the TUI in detail view for the repo with this name
+    #[arg(long, value_name = "NAME")]
+    pub repo: Option<String>,
+
     /// Configuration file to load
     #[arg(
         global = true,
@@ -62,15 +217,88 @@ pub struct CliArgs {
     )]
     pub theme: Option<String>,
 
+    /// Override a setting, e.g. --set main.max_depth=3 (repeatable, applied last)
+    #[arg(
+        global = true,
+        long = "set",
+        visible_alias = "config-override",
+        value_name = "KEY=VALUE",
+        value_parser = parse_assignment,
+        help_heading = "Configuration"
+    )]
+    pub set: Vec<(String, String)>,
+
+    /// Never run external commands or write operations
+    #[arg(global = true, long, help_heading = "Configuration")]
+    pub safe: bool,
+
+    /// Color repo names by status or by age of the last commit
+    #[arg(
+        global = true,
+        long,
+        value_name = "BY",
+        value_enum,
+        help_heading = "Configuration"
+    )]
+    pub color_by: Option<ColorBy>,
+
+    /// Mask the host of remote URLs, including in JSON output
+    #[arg(global = true, long, help_heading = "Configuration")]
+    pub redact: bool,
+
     /// Print current configuration and exit
     #[arg(global = true, long, help_heading = "Configuration")]
     pub print_config: bool,
 
+    /// Format of log output on stderr (logging is enabled with RUST_LOG)
+    #[arg(
+        global = true,
+        long,
+        value_name = "FORMAT",
+        value_enum,
+        default_value_t,
+        env = "REPONEST_LOG_FORMAT"
+    )]
+    pub log_format: LogFormat,
+
     /// Write the cwd on exit to FILE
     #[arg(global = true, long, value_name = "FILE")]
     pub cwd_file: Option<String>,
 }
 
+/// Format of log output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable text
+    #[default]
+    Human,
+    /// One JSON object per line
+    Json,
+}
+
+/// Key the listed repos are sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Number of uncommitted changes, most first
+    Changes,
+    /// When the working tree was last edited, most recent first
+    Edited,
+    /// Health score, least healthy first
+    Health,
+}
+
+/// Layout of the default `list` output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ListStyle {
+    /// Space-aligned compact list
+    #[default]
+    List,
+    /// Table with a header row, bordered when printed to a terminal
+    Table,
+    /// Dense grid of repo names colored by status, filling the terminal width
+    Grid,
+}
+
 /// Subcommands and their arguments
 #[derive(Subcommand, Debug)]
 pub enum CliSubCommands {
@@ -84,5 +312,118 @@ pub enum CliSubCommands {
         /// Output as JSON format
         #[arg(long)]
         json: bool,
+
+        /// Pretty-print JSON output (default)
+        #[arg(long, requires = "json", overrides_with = "compact")]
+        pretty: bool,
+
+        /// Print JSON output on a single line
+        #[arg(long, requires = "json", overrides_with = "pretty")]
+        compact: bool,
+
+        /// Show nested repos as a tree by directory structure
+        #[arg(long, conflicts_with_all = ["detail", "json"])]
+        tree: bool,
+
+        /// Print only repo paths, without reading git status (fast)
+        #[arg(long, conflicts_with_all = ["detail", "json", "tree"])]
+        paths_only: bool,
+
+        /// Print one line per repo in a stable format for scripts (see README)
+        #[arg(long, conflicts_with_all = ["detail", "json", "tree", "paths_only"])]
+        porcelain: bool,
+
+        /// Layout of the repo list (table: with a header row and borders)
+        #[arg(
+            long,
+            value_name = "STYLE",
+            value_enum,
+            conflicts_with_all = ["detail", "json", "tree", "paths_only", "porcelain"]
+        )]
+        output: Option<ListStyle>,
+
+        /// Report only top-level repos, not those inside another repo's working tree
+        #[arg(long)]
+        no_nested: bool,
+
+        /// Separate paths with NUL instead of newline (for xargs -0)
+        #[arg(long, requires = "paths_only")]
+        null: bool,
+
+        /// Write output to PATH atomically instead of stdout ("-" for stdout)
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+
+        /// Show only repos whose last commit is older than DURATION (e.g. 180d)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        stale: Option<Duration>,
+
+        /// Sort repos by KEY: changes (most uncommitted changes first) or edited
+        #[arg(long, value_name = "KEY", value_enum, conflicts_with = "sort_edited")]
+        sort: Option<SortKey>,
+
+        /// Sort repos by when their working tree was last edited, most recent first
+        #[arg(long)]
+        sort_edited: bool,
+
+        /// Print to stderr where scan time went, with the slowest repos
+        #[arg(long, conflicts_with = "paths_only")]
+        profile_timing: bool,
+
+        /// Open the listed repos in the TUI instead of printing them
+        #[arg(long, conflicts_with_all = ["detail", "json", "tree", "paths_only", "porcelain", "out"])]
+        interactive: bool,
+    },
+
+    /// Show what changed since a snapshot saved with `list --json`
+    Diff {
+        /// Snapshot to compare against
+        #[arg(value_name = "SNAPSHOT")]
+        old: PathBuf,
+
+        /// Compare with this snapshot instead of a fresh scan
+        #[arg(long, value_name = "SNAPSHOT")]
+        with: Option<PathBuf>,
+    },
+
+    /// Find repos without reading git info, printing their paths
+    Scan {
+        /// Print every directory entered or skipped, and why it was skipped
+        #[arg(long)]
+        explain: bool,
+    },
+
+    /// Show how many repos have each branch checked out
+    Branches {
+        /// Output as JSON format
+        #[arg(long)]
+        json: bool,
     },
+
+    /// Print the effective TUI key bindings, including config overrides
+    Keys {
+        /// Output as JSON format
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let git = git2::Version::get();
+        let (major, minor, patch) = git.libgit2_version();
+        let info = build_info();
+
+        assert!(info.starts_with(env!("CARGO_PKG_VERSION")));
+        assert!(info.contains(&format!("libgit2 {}.{}.{}", major, minor, patch)));
+        assert!(info.contains(&format!("(git2 {})", git.crate_version())));
+        assert_eq!(info.contains("threads"), git.threads());
+        assert_eq!(info.contains("https"), git.https());
+        assert_eq!(info.contains("ssh"), git.ssh());
+        assert!(info.ends_with(&format!("target: {}", env!("REPONEST_TARGET"))));
+    }
 }
@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use crossterm::style::{Color, Stylize};
+use std::fmt::Write as _;
+
+use super::list::{ListOptions, collect_repos};
+use crate::cli::output::write_output;
+use crate::config::AppConfig;
+use crate::core::branches::{BranchCount, HeadKind, summarize_branches};
+use crate::core::text::display_width;
+
+/// Widest a histogram bar gets, for the most common branch
+const MAX_BAR_WIDTH: usize = 40;
+
+/// Show how many repos have each branch checked out
+pub async fn summarize_repo_branches(config: &AppConfig, json: bool) -> Result<()> {
+    let repos = collect_repos(config, &ListOptions::default())
+        .await
+        .context("Failed to collect repositories")?;
+    let summary = summarize_branches(&repos);
+
+    let output = if json {
+        serde_json::to_string_pretty(&summary).context("Failed to serialize branch summary")? + "\n"
+    } else {
+        format_branch_histogram(&summary)
+    };
+    write_output(&output, None)
+}
+
+/// Label of a bucket: the branch name, or what HEAD points to in parentheses
+fn bucket_label(count: &BranchCount) -> &str {
+    match (count.kind, count.branch.as_deref()) {
+        (HeadKind::Branch, Some(name)) => name,
+        (HeadKind::Detached, _) => "(detached)",
+        (HeadKind::Unborn, _) => "(unborn)",
+        _ => "(unknown)",
+    }
+}
+
+/// Format the summary as a histogram, one line per bucket
+fn format_branch_histogram(summary: &[BranchCount]) -> String {
+    let mut out = String::new();
+    if summary.is_empty() {
+        let _ = writeln!(out, "No repositories found");
+        return out;
+    }
+
+    let max_label = summary
+        .iter()
+        .map(|c| display_width(bucket_label(c)))
+        .max()
+        .unwrap_or(0);
+    let max_count = summary.iter().map(|c| c.count).max().unwrap_or(1);
+    let count_width = max_count.to_string().len();
+    for count in summary {
+        let label = bucket_label(count);
+        let color = match count.kind {
+            HeadKind::Branch => Color::Cyan,
+            HeadKind::Detached => Color::Yellow,
+            _ => Color::DarkGrey,
+        };
+        let bar_width = (count.count * MAX_BAR_WIDTH).div_ceil(max_count);
+        let _ = writeln!(
+            out,
+            "{}{}  {:>count_width$}  {}",
+            label.with(color).bold(),
+            " ".repeat(max_label - display_width(label)),
+            count.count,
+            "█".repeat(bar_width).with(color)
+        );
+    }
+
+    let repos: usize = summary.iter().map(|c| c.count).sum();
+    let branches = summary
+        .iter()
+        .filter(|c| c.kind == HeadKind::Branch)
+        .count();
+    let _ = writeln!(
+        out,
+        "{}",
+        format!("{} repos on {} branches", repos, branches).with(Color::DarkGrey)
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::strip_ansi_codes;
+    use crate::core::RepoInfo;
+
+    #[test]
+    fn test_format_branch_histogram() {
+        let repos: Vec<RepoInfo> = (0..4)
+            .map(|i| RepoInfo::stub_on_branch(&format!("app{}", i), "main"))
+            .chain([
+                RepoInfo::stub_on_branch("lib", "dev"),
+                RepoInfo::stub_on_branch("old", "HEAD"),
+            ])
+            .collect();
+        let out = strip_ansi_codes(&format_branch_histogram(&summarize_branches(&repos)));
+        let bar = |n: usize| "█".repeat(n);
+        assert_eq!(
+            out,
+            format!(
+                "main        4  {}\n\
+                 dev         1  {}\n\
+                 (detached)  1  {}\n\
+                 6 repos on 2 branches\n",
+                bar(40),
+                bar(10),
+                bar(10)
+            )
+        );
+
+        assert_eq!(format_branch_histogram(&[]), "No repositories found\n");
+    }
+
+    #[test]
+    fn test_branch_summary_json() {
+        let summary = summarize_branches(&[
+            RepoInfo::stub_on_branch("app", "main"),
+            RepoInfo::stub_on_branch("old", "HEAD"),
+        ]);
+        let json: serde_json::Value = serde_json::to_value(&summary).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"kind": "branch", "branch": "main", "count": 1, "repos": ["/work/app"]},
+                {"kind": "detached", "branch": null, "count": 1, "repos": ["/work/old"]},
+            ])
+        );
+    }
+}
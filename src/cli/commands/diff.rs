@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use crossterm::style::{Color, Stylize};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use super::list::{ListOptions, collect_repos};
+use crate::cli::output::write_output;
+use crate::config::AppConfig;
+use crate::core::snapshot::{RepoChange, RepoDiff, diff_snapshots, load_snapshot};
+use crate::core::text::display_width;
+
+/// Show what changed between a snapshot and a fresh scan, or a second snapshot
+pub async fn diff_repos(config: &AppConfig, old: &Path, with: Option<&Path>) -> Result<()> {
+    let old_repos = load_snapshot(old).map_err(anyhow::Error::msg)?;
+    let new_repos = match with {
+        Some(path) => load_snapshot(path).map_err(anyhow::Error::msg)?,
+        None => collect_repos(config, &ListOptions::default())
+            .await
+            .context("Failed to collect repositories")?,
+    };
+
+    write_output(&format_diff(&diff_snapshots(&old_repos, &new_repos)), None)
+}
+
+/// Format the changes as a changelog, one line per repository
+fn format_diff(diffs: &[RepoDiff]) -> String {
+    let mut out = String::new();
+    if diffs.is_empty() {
+        let _ = writeln!(out, "No changes");
+        return out;
+    }
+
+    let max_name = diffs
+        .iter()
+        .map(|d| display_width(&d.name))
+        .max()
+        .unwrap_or(0);
+    for diff in diffs {
+        let (marker, color) = match diff.changes.as_slice() {
+            [RepoChange::Added] => ("+", Color::Green),
+            [RepoChange::Removed] => ("-", Color::Red),
+            _ => ("~", Color::Yellow),
+        };
+        let changes: Vec<String> = diff.changes.iter().map(|c| c.to_string()).collect();
+        let _ = writeln!(
+            out,
+            "{} {}{}  {}",
+            marker.with(color).bold(),
+            diff.name.as_str().with(Color::Cyan).bold(),
+            " ".repeat(max_name - display_width(&diff.name)),
+            changes.join(", ")
+        );
+    }
+    let _ = writeln!(
+        out,
+        "{}",
+        format!("{} repos changed", diffs.len()).with(Color::DarkGrey)
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::strip_ansi_codes;
+    use std::path::PathBuf;
+
+    fn diff(name: &str, changes: Vec<RepoChange>) -> RepoDiff {
+        RepoDiff {
+            path: PathBuf::from(format!("/work/{}", name)),
+            name: name.to_string(),
+            changes,
+        }
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let diffs = vec![
+            diff("new-repo", vec![RepoChange::Added]),
+            diff(
+                "app",
+                vec![
+                    RepoChange::NewCommit { message: None },
+                    RepoChange::Ahead { from: 0, to: 1 },
+                ],
+            ),
+            diff("old", vec![RepoChange::Removed]),
+        ];
+        let out = strip_ansi_codes(&format_diff(&diffs));
+        assert_eq!(
+            out,
+            "+ new-repo  appeared\n\
+             ~ app       new commit, ahead 0 -> 1\n\
+             - old       disappeared\n\
+             3 repos changed\n"
+        );
+
+        assert_eq!(format_diff(&[]), "No changes\n");
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::{Context, Result};
+use crossterm::style::{Color, Stylize};
+use std::fmt::Write as _;
+
+use crate::cli::output::write_output;
+use crate::config::KeyBindings;
+
+/// Print every action of the TUI with the keys bound to it
+pub fn print_keys(keybindings: &KeyBindings, json: bool) -> Result<()> {
+    let output = if json {
+        serde_json::to_string_pretty(keybindings).context("Failed to serialize key bindings")?
+            + "\n"
+    } else {
+        format_keys_table(keybindings)
+    };
+    write_output(&output, None)
+}
+
+/// Format key bindings as a two-column table of actions and keys
+fn format_keys_table(keybindings: &KeyBindings) -> String {
+    let max_action = KeyBindings::ACTIONS
+        .iter()
+        .map(|a| a.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{}{}  {}",
+        "ACTION".with(Color::DarkGrey),
+        " ".repeat(max_action.saturating_sub("ACTION".len())),
+        "KEYS".with(Color::DarkGrey)
+    );
+    for action in KeyBindings::ACTIONS {
+        let keys = keybindings.keys(action).unwrap_or_default();
+        let keys = match keys.is_empty() {
+            true => "(unbound)".with(Color::DarkGrey).to_string(),
+            false => keys.join(", ").with(Color::Cyan).bold().to_string(),
+        };
+        let _ = writeln!(
+            out,
+            "{}{}  {}",
+            action,
+            " ".repeat(max_action - action.len()),
+            keys
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::output::strip_ansi_codes;
+
+    #[test]
+    fn test_keys_table_with_overrides() {
+        let keybindings: KeyBindings =
+            toml::from_str("quit = [\"x\", \"Ctrl-q\"]\nnext_conflict = []").unwrap();
+        let table = strip_ansi_codes(&format_keys_table(&keybindings));
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), KeyBindings::ACTIONS.len() + 1);
+        assert!(lines[0].starts_with("ACTION"));
+        for (line, action) in lines[1..].iter().zip(KeyBindings::ACTIONS) {
+            let expected = match keybindings.keys(action).unwrap() {
+                [] => "(unbound)".to_string(),
+                keys => keys.join(", "),
+            };
+            assert_eq!(line.split_whitespace().next(), Some(action));
+            assert!(line.trim_end().ends_with(&expected), "{}", line);
+        }
+        assert!(table.contains("quit                 x, Ctrl-q"));
+        assert!(table.contains("move_down            j, Down"));
+        assert!(table.contains("next_conflict        (unbound)"));
+    }
+
+    #[test]
+    fn test_keys_json_lists_every_action() {
+        let keybindings: KeyBindings = toml::from_str("open = [\"Enter\"]").unwrap();
+        let json = serde_json::to_value(&keybindings).unwrap();
+        for action in KeyBindings::ACTIONS {
+            assert!(json.get(action).is_some(), "missing {}", action);
+        }
+        assert_eq!(json["open"], serde_json::json!(["Enter"]));
+    }
+}
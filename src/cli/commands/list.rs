@@ -1,41 +1,259 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use crossterm::style::{Color, Stylize};
-use std::time::Instant;
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{debug, info};
 
-use crate::config::AppConfig;
+use crate::cli::SortKey;
+use crate::cli::output::{strip_ansi_codes, write_output};
+use crate::config::{
+    AppConfig, ColorBy, CompareAgainst, DetailLayout, DetailSection, FileLayout, ListColumn,
+    StatusPrefixes, WorkdirMtime,
+};
 use crate::core::{
-    self,
+    self, RepoInfoOptions, ScanOutcome,
+    author::initials_and_color,
+    grid::{CELL_GAP, GridLayout},
+    health::{apply_health, health_grade},
+    heatmap::commit_time_color,
+    profile::ScanProfile,
+    remote_url::{display_remote_url, redact_remote_url},
     repo_info::{
-        FileChangeStatus, RepoBasicInfo, RepoCommitInfo, RepoFileChanges, RepoInfo, RepoRemoteInfo,
-        RepoStashInfo, RepoSyncStatus, RepoWorkingStatus,
+        FileChange, FileChangeStatus, RepoAnomalies, RepoBasicInfo, RepoCommitInfo,
+        RepoFileChanges, RepoInfo, RepoRemoteInfo, RepoStashInfo, RepoSyncStatus,
+        RepoWorkingStatus,
+    },
+    sorting::{
+        group_file_changes, sort_file_changes, sort_least_healthy_first, sort_most_changes_first,
+        sort_pinned_first, sort_recently_edited_first,
     },
+    status::{StatusColor, StatusRule, overall_status, porcelain_code},
+    tags::TagStore,
+    text::{
+        display_width, format_relative_time, label_prefix_len, truncate_start_to_width,
+        truncate_to_width, wrap_ranges,
+    },
+    tree::{RepoTreeNode, build_repo_tree, retain_top_level},
 };
 
+/// Sections of the detail output unless configured otherwise
+const DEFAULT_DETAIL_SECTIONS: [DetailSection; 7] = [
+    DetailSection::Basic,
+    DetailSection::Working,
+    DetailSection::Anomalies,
+    DetailSection::Sync,
+    DetailSection::Stash,
+    DetailSection::Remote,
+    DetailSection::Commit,
+];
+
+/// Maximum display width of the branch column in the compact list
+const MAX_BRANCH_WIDTH: usize = 32;
+
+/// Narrowest the path column gets when fitting the compact list to the terminal
+const MIN_PATH_WIDTH: usize = 12;
+
+/// Width the `--output grid` layout fills when not printing to a terminal
+const DEFAULT_GRID_WIDTH: usize = 80;
+
+/// Number of slowest repos shown by `--profile-timing`
+const PROFILE_SLOWEST_REPOS: usize = 10;
+
+/// Options of the list command
+#[derive(Debug, Default)]
+pub struct ListOptions {
+    pub detail: bool,
+    pub json: bool,
+    pub compact: bool,
+    pub tree: bool,
+    /// Print only repo paths, skipping git info
+    pub paths_only: bool,
+    /// Print the stable, script-friendly format
+    pub porcelain: bool,
+    /// Print the compact list as a table with a header row
+    pub table: bool,
+    /// Print repo names as a grid colored by status
+    pub grid: bool,
+    /// Leave out repos nested inside another repo's working tree
+    pub no_nested: bool,
+    /// Separate paths with NUL instead of newline
+    pub null: bool,
+    pub dirty_filter: bool,
+    pub conflict_filter: bool,
+    /// Show only repos without any remote
+    pub no_remote_filter: bool,
+    /// Show only repos whose index is locked
+    pub locked_filter: bool,
+    /// Show only repos with a detached HEAD
+    pub detached_filter: bool,
+    /// Show only repos with ignore rule anomalies
+    pub anomalies_filter: bool,
+    /// Show only repos with tags not pushed to any remote
+    pub unpushed_tags_filter: bool,
+    /// Show only repos with local branches holding unmerged work
+    pub unmerged_filter: bool,
+    /// Show only repos with Git LFS locks held locally
+    pub lfs_locks_filter: bool,
+    /// Show only repos with files flagged assume-unchanged or skip-worktree
+    pub assume_unchanged_filter: bool,
+    /// Show only repos carrying this tag
+    pub tag_filter: Option<String>,
+    /// Mask the host of remote URLs in JSON output
+    pub redact_json: bool,
+    /// File to write the output to (`-` or None for stdout)
+    pub out: Option<PathBuf>,
+    /// Show only repos whose last commit is older than this
+    pub stale: Option<Duration>,
+    /// Key to sort repos by, instead of by path
+    pub sort: Option<SortKey>,
+    /// Print a breakdown of scan time to stderr
+    pub profile_timing: bool,
+}
+
+impl ListOptions {
+    /// Flags of the filters that are active
+    pub fn active_filters(&self) -> Vec<&'static str> {
+        [
+            (self.dirty_filter, "--dirty"),
+            (self.conflict_filter, "--conflict"),
+            (self.no_remote_filter, "--no-remote"),
+            (self.locked_filter, "--locked"),
+            (self.detached_filter, "--detached"),
+            (self.anomalies_filter, "--anomalies"),
+            (self.unpushed_tags_filter, "--unpushed-tags"),
+            (self.unmerged_filter, "--unmerged"),
+            (self.lfs_locks_filter, "--lfs-locks"),
+            (self.assume_unchanged_filter, "--assume-unchanged"),
+            (self.stale.is_some(), "--stale"),
+            (self.tag_filter.is_some(), "--tag"),
+        ]
+        .into_iter()
+        .filter_map(|(active, flag)| active.then_some(flag))
+        .collect()
+    }
+}
+
 /// List repositories in the specified path
-pub async fn list_repos(
-    config: AppConfig,
-    detail: bool,
-    json: bool,
-    dirty_filter: bool,
-    conflict_filter: bool,
-) -> Result<()> {
-    let start = Instant::now();
+pub async fn list_repos(config: AppConfig, opts: ListOptions) -> Result<()> {
+    if opts.paths_only && !opts.active_filters().is_empty() {
+        bail!(
+            "--paths-only cannot be combined with --dirty, --conflict, --no-remote, --locked, --detached, --anomalies, --unpushed-tags, --unmerged, --lfs-locks, --assume-unchanged, --stale or --tag"
+        );
+    }
 
-    // Scan directories asynchronously to find Git repositories
-    let repo_paths = core::scan_directories(&config.main.scan_dirs, &config)
-        .await
-        .context("Failed to scan directories")?;
+    let out = opts.out.as_deref().filter(|p| *p != Path::new("-"));
+    if opts.paths_only {
+        let scan = scan_repo_paths(&config, &opts).await?;
+        return write_output(&format_repo_paths(&scan.paths, opts.null), out);
+    }
+
+    let repos = collect_repos(&config, &opts).await?;
+    let filtered_repos: Vec<&RepoInfo> = repos.iter().collect();
 
+    let rules = &config.ui.status_rules;
+    let heatmap = (config.ui.color_by == ColorBy::Age).then(|| config.main.heatmap_horizon());
+    let output = if opts.json {
+        format_repos_json(&filtered_repos, opts.compact, opts.redact_json)? + "\n"
+    } else if opts.detail {
+        let sections = config.ui.detail.sections.as_deref();
+        format_repos_detail(
+            &filtered_repos,
+            sections.unwrap_or(&DEFAULT_DETAIL_SECTIONS),
+            config.ui.redact_remotes,
+            config.ui.detail.file_layout,
+            match config.ui.detail.layout {
+                DetailLayout::Stacked => None,
+                DetailLayout::Columns => Some(terminal_width(out).unwrap_or(usize::MAX)),
+            },
+        )
+    } else if opts.porcelain {
+        format_repos_porcelain(&filtered_repos)
+    } else if opts.tree {
+        format_repos_tree(&filtered_repos, rules, heatmap)
+    } else if opts.grid {
+        format_repos_grid(
+            &filtered_repos,
+            rules,
+            &config.ui.status_prefixes,
+            heatmap,
+            terminal_width(out).unwrap_or(DEFAULT_GRID_WIDTH),
+        )
+    } else {
+        let mut columns = config.ui.list_columns.clone();
+        if opts.sort == Some(SortKey::Changes) && !columns.contains(&ListColumn::Changes) {
+            columns.push(ListColumn::Changes);
+        }
+        if opts.sort == Some(SortKey::Health) && !columns.contains(&ListColumn::Health) {
+            columns.push(ListColumn::Health);
+        }
+        if opts.table {
+            let borders = match config.ui.ascii_symbols {
+                true => &ASCII_BORDERS,
+                false => &BOX_BORDERS,
+            };
+            format_repos_table(
+                &filtered_repos,
+                rules,
+                &config.ui.status_prefixes,
+                heatmap,
+                &columns,
+                writes_to_terminal(out).then_some(borders),
+            )
+        } else {
+            format_repos_list(
+                &filtered_repos,
+                rules,
+                &config.ui.status_prefixes,
+                heatmap,
+                &columns,
+                terminal_width(out),
+            )
+        }
+    };
+
+    write_output(&output, out)
+}
+
+/// Scan for repositories, gather their info and keep those passing the filters of `opts`
+pub async fn collect_repos(config: &AppConfig, opts: &ListOptions) -> Result<Vec<RepoInfo>> {
+    let start = Instant::now();
+    let scan = scan_repo_paths(config, opts).await?;
     let scan_elapsed = start.elapsed();
-    debug!(
-        paths_found = repo_paths.len(),
-        elapsed = ?scan_elapsed,
-        "Async directory scan finished"
-    );
 
     // Process repositories in parallel to gather Git information
-    let repos = core::get_repos_info_parallel(&repo_paths);
+    let mut options = RepoInfoOptions::from_config(config);
+    if opts.sort == Some(SortKey::Edited) && options.workdir_mtime == WorkdirMtime::Off {
+        debug!("Sorting by last edit: using the repo directory modification time");
+        options.workdir_mtime = WorkdirMtime::Dir;
+    }
+    let mut repos = if opts.profile_timing {
+        let (repos, repo_timings) = core::get_repos_info_parallel_timed(&scan.paths, options);
+        let profile = ScanProfile {
+            dirs_visited: scan.dirs_visited,
+            repos_found: scan.paths.len(),
+            scan: scan_elapsed,
+            git_info: start.elapsed() - scan_elapsed,
+            repo_timings,
+        };
+        eprint!("{}", profile.report(PROFILE_SLOWEST_REPOS));
+        repos
+    } else {
+        core::get_repos_info_parallel(&scan.paths, options)
+    };
+    TagStore::from_config(config)
+        .map_err(anyhow::Error::msg)?
+        .apply(&mut repos);
+    let now = SystemTime::now();
+    apply_health(&mut repos, &config.health, now);
+    match opts.sort {
+        Some(SortKey::Changes) => sort_most_changes_first(&mut repos),
+        Some(SortKey::Edited) => sort_recently_edited_first(&mut repos),
+        Some(SortKey::Health) => sort_least_healthy_first(&mut repos),
+        None => {}
+    }
+    sort_pinned_first(&mut repos, &config.ui.pinned);
 
     info!(
         repo_count = repos.len(),
@@ -44,172 +262,848 @@ pub async fn list_repos(
         "Repository processing finished"
     );
 
-    let filtered_repos: Vec<&RepoInfo> = repos
-        .iter()
-        .filter(|r| !dirty_filter || r.working.is_dirty)
-        .filter(|r| !conflict_filter || r.working.conflicts > 0)
-        .collect();
+    repos.retain(|r| {
+        (!config.main.hide_submodules || !r.is_submodule())
+            && (!opts.dirty_filter || r.working.is_dirty)
+            && (!opts.conflict_filter || r.working.conflicts > 0)
+            && (!opts.no_remote_filter || r.remote.count == 0)
+            && (!opts.locked_filter || r.working.locked)
+            && (!opts.detached_filter || r.basic.is_detached)
+            && (!opts.anomalies_filter || r.anomalies.as_ref().is_some_and(|a| a.has_any()))
+            && (!opts.unpushed_tags_filter || r.sync.unpushed_tags.is_some_and(|n| n > 0))
+            && (!opts.unmerged_filter || r.sync.unmerged_branches.is_some_and(|n| n > 0))
+            && (!opts.lfs_locks_filter || r.working.lfs_locks.is_some_and(|n| n > 0))
+            && (!opts.assume_unchanged_filter || r.working.assume_unchanged.is_some_and(|n| n > 0))
+            && opts.tag_filter.as_deref().is_none_or(|tag| r.has_tag(tag))
+            && opts
+                .stale
+                .is_none_or(|threshold| r.is_stale(threshold, now))
+    });
+    Ok(repos)
+}
 
-    if json {
-        print_repos_json(&filtered_repos)?;
-    } else if detail {
-        print_repos_detail(&filtered_repos);
-    } else {
-        print_repos_list(&filtered_repos);
+/// Scan directories asynchronously to find Git repositories
+///
+/// With `--no-nested`, repositories inside another one found are dropped.
+async fn scan_repo_paths(config: &AppConfig, opts: &ListOptions) -> Result<ScanOutcome> {
+    let start = Instant::now();
+    let mut scan = core::scan_directories(&config.main.scan_dirs, config)
+        .await
+        .context("Failed to scan directories")?;
+    if opts.no_nested {
+        retain_top_level(&mut scan.paths);
+    }
+    if scan.truncated {
+        eprintln!(
+            "{}",
+            "Warning: scan timed out, results may be incomplete".with(Color::Yellow)
+        );
+    }
+
+    debug!(
+        paths_found = scan.paths.len(),
+        elapsed = ?start.elapsed(),
+        "Async directory scan finished"
+    );
+    Ok(scan)
+}
+
+/// Whether output goes to a terminal rather than a file or a pipe
+fn writes_to_terminal(out: Option<&Path>) -> bool {
+    out.is_none() && std::io::stdout().is_terminal()
+}
+
+/// Width of the terminal output goes to, or None if it is not a terminal
+fn terminal_width(out: Option<&Path>) -> Option<usize> {
+    if !writes_to_terminal(out) {
+        return None;
     }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(cols, _)| cols as usize)
+}
 
-    Ok(())
+/// Format repository paths one per line, or NUL-terminated
+fn format_repo_paths(paths: &[PathBuf], null: bool) -> String {
+    let terminator = if null { '\0' } else { '\n' };
+    let mut out = String::new();
+    for path in paths {
+        out.push_str(&path.to_string_lossy());
+        out.push(terminator);
+    }
+    out
 }
 
-/// Print repositories in JSON format
-fn print_repos_json(repos: &[&RepoInfo]) -> Result<()> {
-    let json =
-        serde_json::to_string_pretty(&repos).context("Failed to serialize repositories to JSON")?;
-    println!("{}", json);
-    Ok(())
+/// Serialize repositories to JSON, either pretty-printed or on a single line
+///
+/// Keys follow struct field order, so the output is stable across runs.
+/// With `redact`, the host of remote URLs is masked.
+fn format_repos_json(repos: &[&RepoInfo], compact: bool, redact: bool) -> Result<String> {
+    if redact {
+        let redacted: Vec<RepoInfo> = repos
+            .iter()
+            .map(|repo| {
+                let mut repo = (*repo).clone();
+                repo.remote.url = repo.remote.url.as_deref().map(redact_remote_url);
+                repo
+            })
+            .collect();
+        let refs: Vec<&RepoInfo> = redacted.iter().collect();
+        return format_repos_json(&refs, compact, false);
+    }
+
+    let json = if compact {
+        serde_json::to_string(&repos)
+    } else {
+        serde_json::to_string_pretty(&repos)
+    };
+    json.context("Failed to serialize repositories to JSON")
 }
 
-/// Print repositories in simple list format
-fn print_repos_list(repos: &[&RepoInfo]) {
+/// Format repositories in simple list format
+///
+/// With `max_width`, paths are truncated from the left so that lines fit.
+/// Optional `columns` are shown after the branch. With a `heatmap` horizon,
+/// names are colored by the age of their last commit.
+fn format_repos_list(
+    repos: &[&RepoInfo],
+    rules: &[StatusRule],
+    prefixes: &StatusPrefixes,
+    heatmap: Option<Duration>,
+    columns: &[ListColumn],
+    max_width: Option<usize>,
+) -> String {
+    let mut out = String::new();
     if repos.is_empty() {
         info!("No repositories found");
-        return;
+        return out;
     }
 
     info!(count = repos.len(), "Listing repositories");
 
-    let views: Vec<CompactRepoView> = repos.iter().map(|repo| repo.to_compact_view()).collect();
+    let views: Vec<CompactRepoView> = repos
+        .iter()
+        .map(|repo| repo.to_compact_view(rules))
+        .collect();
+
+    let names: Vec<String> = views
+        .iter()
+        .map(|v| prefixes.apply(&v.status, &v.name))
+        .collect();
 
     // Calculate column widths
-    let max_name = views.iter().map(|v| v.name.len()).max().unwrap_or(0);
+    let max_name = names
+        .iter()
+        .map(|name| display_width(name))
+        .max()
+        .unwrap_or(0);
     let max_status = views.iter().map(|v| v.status.len()).max().unwrap_or(0);
-    let max_branch = views.iter().map(|v| v.branch.len()).max().unwrap_or(0);
+    let max_branch = views
+        .iter()
+        .map(|v| display_width(&v.branch))
+        .max()
+        .unwrap_or(0);
+    let column_widths: Vec<usize> = columns
+        .iter()
+        .map(|&column| {
+            views
+                .iter()
+                .map(|v| display_width(&v.column_cell(column).0))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let max_remotes = views.iter().map(|v| v.remotes.len()).max().unwrap_or(0);
+    let max_tags = views
+        .iter()
+        .map(|v| display_width(&v.tags))
+        .max()
+        .unwrap_or(0);
+    // Columns before the path, each followed by two spaces, and the tags after it
+    let path_width = max_width.map(|width| {
+        let tags_width = if max_tags > 0 { max_tags + 2 } else { 0 };
+        let optional_width: usize = column_widths.iter().map(|w| w + 2).sum();
+        let used =
+            max_name + max_status + max_branch + optional_width + max_remotes + 4 * 2 + tags_width;
+        width.saturating_sub(used).max(MIN_PATH_WIDTH)
+    });
 
-    // Print each repository
-    for view in &views {
-        let name_pad = max_name.saturating_sub(view.name.len());
+    // Format each repository
+    for (view, name) in views.iter().zip(&names) {
+        let name_pad = max_name.saturating_sub(display_width(name));
         let status_pad = max_status.saturating_sub(view.status.len());
-        let branch_pad = max_branch.saturating_sub(view.branch.len());
+        let branch_pad = max_branch.saturating_sub(display_width(&view.branch));
+        let remotes_pad = max_remotes.saturating_sub(view.remotes.len());
 
-        println!(
-            "{}{}  {}{}  {}{}  {}",
-            view.name.as_str().with(Color::Cyan).bold(),
+        let mut optional = String::new();
+        for (&column, &width) in columns.iter().zip(&column_widths) {
+            let (cell, color) = view.column_cell(column);
+            let _ = write!(
+                optional,
+                "{}{}  ",
+                cell.as_str().with(color),
+                " ".repeat(width - display_width(&cell))
+            );
+        }
+        let tags = match view.tags.is_empty() {
+            true => String::new(),
+            false => format!("  {}", view.tags.as_str().with(Color::Magenta)),
+        };
+        let _ = writeln!(
+            out,
+            "{}{}  {}{}  {}{}  {}{}{}  {}{}",
+            name.as_str()
+                .with(view.name_color(heatmap, Color::Cyan))
+                .bold(),
             " ".repeat(name_pad),
             view.status.as_str().with(view.status_color()).bold(),
             " ".repeat(status_pad),
             &view.branch,
             " ".repeat(branch_pad),
-            view.path.as_str().with(Color::DarkGrey)
+            optional,
+            view.remotes.as_str().with(view.remotes_color()),
+            " ".repeat(remotes_pad),
+            path_width
+                .map(|width| truncate_start_to_width(&view.path, width))
+                .unwrap_or_else(|| view.path.clone())
+                .with(Color::DarkGrey),
+            tags
+        );
+    }
+    out
+}
+
+/// Format repository names as a grid colored by status, filling `width` columns
+fn format_repos_grid(
+    repos: &[&RepoInfo],
+    rules: &[StatusRule],
+    prefixes: &StatusPrefixes,
+    heatmap: Option<Duration>,
+    width: usize,
+) -> String {
+    let mut out = String::new();
+    if repos.is_empty() {
+        info!("No repositories found");
+        return out;
+    }
+
+    let cells: Vec<(String, Color)> = repos
+        .iter()
+        .map(|repo| {
+            let view = repo.to_compact_view(rules);
+            (
+                prefixes.apply(&view.status, &view.name),
+                view.name_color(heatmap, view.status_color()),
+            )
+        })
+        .collect();
+    let layout = GridLayout::fit(cells.iter().map(|(name, _)| display_width(name)), width);
+    for row in cells.chunks(layout.columns) {
+        let mut line = String::new();
+        for (i, (name, color)) in row.iter().enumerate() {
+            let name = truncate_to_width(name, layout.cell_width);
+            // The last cell of a row is not padded, to keep lines free of trailing spaces
+            let pad = match i + 1 < row.len() {
+                true => layout.cell_width - display_width(&name) + CELL_GAP,
+                false => 0,
+            };
+            let _ = write!(line, "{}{}", name.with(*color), " ".repeat(pad));
+        }
+        let _ = writeln!(out, "{}", line);
+    }
+    out
+}
+
+/// Characters drawing the borders of the `--output table` layout
+struct TableBorders {
+    /// Left, middle and right ends of the rule above the header
+    top: [char; 3],
+    /// Left, middle and right ends of the rule under the header
+    separator: [char; 3],
+    /// Left, middle and right ends of the rule under the last row
+    bottom: [char; 3],
+    horizontal: char,
+    vertical: char,
+}
+
+/// Box-drawing table borders
+const BOX_BORDERS: TableBorders = TableBorders {
+    top: ['┌', '┬', '┐'],
+    separator: ['├', '┼', '┤'],
+    bottom: ['└', '┴', '┘'],
+    horizontal: '─',
+    vertical: '│',
+};
+
+/// Table borders for terminals without box-drawing glyphs (`ui.ascii_symbols`)
+const ASCII_BORDERS: TableBorders = TableBorders {
+    top: ['+', '+', '+'],
+    separator: ['+', '+', '+'],
+    bottom: ['+', '+', '+'],
+    horizontal: '-',
+    vertical: '|',
+};
+
+impl TableBorders {
+    /// Horizontal rule with the given ends, crossing columns of `widths`
+    fn rule(&self, [left, middle, right]: [char; 3], widths: &[usize]) -> String {
+        let segments: Vec<String> = widths
+            .iter()
+            .map(|width| self.horizontal.to_string().repeat(width + 2))
+            .collect();
+        format!("{}{}{}\n", left, segments.join(&middle.to_string()), right)
+    }
+}
+
+/// Format repositories as a table with a header row
+///
+/// Without `borders`, columns are separated by two spaces and only the header
+/// row is added, so that the output stays easy to process when piped.
+fn format_repos_table(
+    repos: &[&RepoInfo],
+    rules: &[StatusRule],
+    prefixes: &StatusPrefixes,
+    heatmap: Option<Duration>,
+    columns: &[ListColumn],
+    borders: Option<&TableBorders>,
+) -> String {
+    let mut out = String::new();
+    if repos.is_empty() {
+        info!("No repositories found");
+        return out;
+    }
+
+    info!(count = repos.len(), "Listing repositories as a table");
+
+    let views: Vec<CompactRepoView> = repos
+        .iter()
+        .map(|repo| repo.to_compact_view(rules))
+        .collect();
+    let show_tags = views.iter().any(|v| !v.tags.is_empty());
+
+    let mut header = vec!["NAME", "STATUS", "BRANCH"];
+    header.extend(columns.iter().map(|&column| column_header(column)));
+    header.extend(["REMOTES", "PATH"]);
+    if show_tags {
+        header.push("TAGS");
+    }
+    let header: Vec<(String, Color)> = header
+        .into_iter()
+        .map(|title| (title.to_string(), Color::Reset))
+        .collect();
+
+    let rows: Vec<Vec<(String, Color)>> = views
+        .iter()
+        .map(|view| {
+            let mut row = vec![
+                (
+                    prefixes.apply(&view.status, &view.name),
+                    view.name_color(heatmap, Color::Cyan),
+                ),
+                (view.status.clone(), view.status_color()),
+                (view.branch.clone(), Color::Reset),
+            ];
+            row.extend(columns.iter().map(|&column| view.column_cell(column)));
+            row.push((view.remotes.clone(), view.remotes_color()));
+            row.push((view.path.clone(), Color::DarkGrey));
+            if show_tags {
+                row.push((view.tags.clone(), Color::Magenta));
+            }
+            row
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| display_width(&row[i].0))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    if let Some(borders) = borders {
+        out.push_str(&borders.rule(borders.top, &widths));
+    }
+    out.push_str(&table_row(&header, &widths, borders, true));
+    if let Some(borders) = borders {
+        out.push_str(&borders.rule(borders.separator, &widths));
+    }
+    for row in &rows {
+        out.push_str(&table_row(row, &widths, borders, false));
+    }
+    if let Some(borders) = borders {
+        out.push_str(&borders.rule(borders.bottom, &widths));
+    }
+    out
+}
+
+/// One line of the table, with cells padded to `widths`
+fn table_row(
+    cells: &[(String, Color)],
+    widths: &[usize],
+    borders: Option<&TableBorders>,
+    bold: bool,
+) -> String {
+    let padded: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .map(|((text, color), &width)| {
+            let styled = text.as_str().with(*color);
+            let styled = if bold { styled.bold() } else { styled };
+            format!("{}{}", styled, " ".repeat(width - display_width(text)))
+        })
+        .collect();
+    match borders {
+        Some(borders) => {
+            let vertical = borders.vertical;
+            let separator = format!(" {} ", vertical);
+            format!("{} {} {}\n", vertical, padded.join(&separator), vertical)
+        }
+        None => format!("{}\n", padded.join("  ").trim_end()),
+    }
+}
+
+/// Header of an optional column in the table output
+fn column_header(column: ListColumn) -> &'static str {
+    match column {
+        ListColumn::TrackingRemote => "REMOTE",
+        ListColumn::Changes => "CHANGES",
+        ListColumn::Author => "AUTHOR",
+        ListColumn::Health => "HEALTH",
+    }
+}
+
+/// Format repositories in the stable `--porcelain` layout
+///
+/// Each line holds the status code, the staged, modified, untracked and
+/// conflicted file counts, the commits ahead and behind, the branch (`-` if
+/// unknown) and the path, separated by single spaces. The path comes last, so
+/// it may contain spaces. This layout is a contract with scripts and must not
+/// change; new fields may only be added before the path in a new flag.
+fn format_repos_porcelain(repos: &[&RepoInfo]) -> String {
+    let mut out = String::new();
+    for repo in repos {
+        // A branch that could not be read is `?`, which git refuses in branch names
+        let branch = match repo.basic.branch.as_str() {
+            "" | "?" => "-",
+            branch => branch,
+        };
+        let _ = writeln!(
+            out,
+            "{} {} {} {} {} {} {} {} {}",
+            porcelain_code(repo),
+            repo.working.staged,
+            repo.working.modified,
+            repo.working.untracked,
+            repo.working.conflicts,
+            repo.sync.ahead,
+            repo.sync.behind,
+            branch,
+            repo.basic.path.display()
         );
     }
+    out
 }
 
-/// Print repositories in detailed format
-fn print_repos_detail(repos: &[&RepoInfo]) {
+/// Format repositories in detailed format
+///
+/// With `columns`, labels are aligned in a column and values wrapped to fit
+/// that many columns in total.
+fn format_repos_detail(
+    repos: &[&RepoInfo],
+    sections: &[DetailSection],
+    redact: bool,
+    file_layout: FileLayout,
+    columns: Option<usize>,
+) -> String {
+    let mut out = String::new();
     if repos.is_empty() {
         info!("No repositories found");
-        return;
+        return out;
     }
 
     info!(
         count = repos.len(),
         "Displaying detailed repository information"
     );
-    println!("Found {} repos:\n", repos.len());
+    let _ = writeln!(out, "Found {} repos:\n", repos.len());
 
     for (idx, repo) in repos.iter().enumerate() {
         if idx > 0 {
-            println!();
+            out.push('\n');
+        }
+
+        let _ = writeln!(out, "{}", "─".repeat(70).with(Color::DarkGrey));
+        let _ = writeln!(out, "{}", repo.basic.name.as_str().with(Color::Cyan).bold());
+
+        let mut lines = repo.to_detail_lines(sections, redact, file_layout);
+        if let Some(width) = columns {
+            // Leave room for the indent
+            lines = column_lines(lines, width.saturating_sub(2));
+        }
+        for line in lines {
+            let _ = writeln!(out, "  {}", line);
         }
+    }
+
+    let _ = writeln!(out, "\n{}", "─".repeat(70).with(Color::DarkGrey));
+    out
+}
+
+/// Align the labels of styled detail lines in a column and wrap their values to `width`
+///
+/// Lines without a `Label: ` prefix are kept as they are. ANSI escape
+/// sequences take no columns and are carried over to the wrapped lines.
+fn column_lines(lines: Vec<String>, width: usize) -> Vec<String> {
+    let plain: Vec<String> = lines.iter().map(|line| strip_ansi_codes(line)).collect();
+    let column = plain
+        .iter()
+        .filter_map(|text| label_prefix_len(text).map(|len| display_width(&text[..len])))
+        .max()
+        .unwrap_or(0);
 
-        println!("{}", "─".repeat(70).with(Color::DarkGrey));
-        println!("{}", repo.basic.name.as_str().with(Color::Cyan).bold());
+    let mut out = Vec::with_capacity(lines.len());
+    for (line, text) in lines.into_iter().zip(&plain) {
+        let Some(label_len) = label_prefix_len(text) else {
+            out.push(line);
+            continue;
+        };
+        let split = ansi_byte_index(&line, 0, label_len);
+        let (label, value) = line.split_at(split);
+        let pad = column - display_width(&text[..label_len]);
+
+        let mut previous_end = 0;
+        let ranges = wrap_ranges(&text[label_len..], width.saturating_sub(column));
+        for (i, range) in ranges.iter().enumerate() {
+            let start = ansi_byte_index(value, 0, previous_end);
+            let gap_end = ansi_byte_index(value, start, range.start - previous_end);
+            let end = match i + 1 == ranges.len() {
+                true => value.len(),
+                false => ansi_byte_index(value, gap_end, range.len()),
+            };
+            // Drop the spaces at the break but keep the escapes between them
+            let gap: String = value[start..gap_end]
+                .chars()
+                .filter(|&c| c != ' ')
+                .collect();
+            let indent = match i {
+                0 => format!("{}{}", label, " ".repeat(pad)),
+                _ => " ".repeat(column),
+            };
+            out.push(format!("{}{}{}", indent, gap, &value[gap_end..end]));
+            previous_end = range.end;
+        }
+    }
+    out
+}
+
+/// Byte index in `text` after `visible` more bytes outside ANSI escape sequences, from `from`
+fn ansi_byte_index(text: &str, from: usize, visible: usize) -> usize {
+    let mut seen = 0;
+    let mut chars = text[from..].char_indices();
+    while seen < visible {
+        match chars.next() {
+            Some((_, '\x1b')) => {
+                if chars.next().is_some_and(|(_, c)| c == '[') {
+                    chars.by_ref().find(|(_, c)| ('@'..='~').contains(c));
+                }
+            }
+            Some((_, c)) => seen += c.len_utf8(),
+            None => return text.len(),
+        }
+    }
+    from + chars.offset()
+}
+
+/// Format repositories as a tree following their filesystem nesting
+fn format_repos_tree(
+    repos: &[&RepoInfo],
+    rules: &[StatusRule],
+    heatmap: Option<Duration>,
+) -> String {
+    let mut out = String::new();
+    if repos.is_empty() {
+        info!("No repositories found");
+        return out;
+    }
+
+    info!(count = repos.len(), "Listing repositories as tree");
+
+    for line in tree_lines(repos) {
+        let view = repos[line.index].to_compact_view(rules);
+        let path = match line.depth {
+            0 => format!("  {}", view.path.as_str().with(Color::DarkGrey)),
+            _ => String::new(),
+        };
+        let _ = writeln!(
+            out,
+            "{}{}  {}  {}{}",
+            line.prefix.as_str().with(Color::DarkGrey),
+            line.label
+                .as_str()
+                .with(view.name_color(heatmap, Color::Cyan))
+                .bold(),
+            view.status.as_str().with(view.status_color()).bold(),
+            &view.branch,
+            path
+        );
+    }
+    out
+}
+
+/// A single row of the tree output
+struct TreeLine {
+    /// Box-drawing connectors preceding the label
+    prefix: String,
+    /// Repository name for roots, path relative to the parent repository otherwise
+    label: String,
+    index: usize,
+    depth: usize,
+}
+
+/// Lay out the repository tree as rows with box-drawing connectors
+fn tree_lines(repos: &[&RepoInfo]) -> Vec<TreeLine> {
+    fn walk(
+        repos: &[&RepoInfo],
+        nodes: &[RepoTreeNode],
+        parent: Option<usize>,
+        indent: &str,
+        depth: usize,
+        out: &mut Vec<TreeLine>,
+    ) {
+        for (pos, node) in nodes.iter().enumerate() {
+            let is_last = pos + 1 == nodes.len();
+            let repo = &repos[node.index].basic;
+            let (prefix, child_indent, label) = match parent {
+                None => (String::new(), String::new(), repo.name.clone()),
+                Some(parent) => {
+                    let connector = if is_last { "└── " } else { "├── " };
+                    let continuation = if is_last { "    " } else { "│   " };
+                    let label = repo
+                        .path
+                        .strip_prefix(&repos[parent].basic.path)
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_else(|_| repo.name.clone());
+                    (
+                        format!("{}{}", indent, connector),
+                        format!("{}{}", indent, continuation),
+                        label,
+                    )
+                }
+            };
 
-        for line in repo.to_detail_lines() {
-            println!("  {}", line);
+            out.push(TreeLine {
+                prefix,
+                label,
+                index: node.index,
+                depth,
+            });
+            walk(
+                repos,
+                &node.children,
+                Some(node.index),
+                &child_indent,
+                depth + 1,
+                out,
+            );
         }
     }
 
-    println!("\n{}", "─".repeat(70).with(Color::DarkGrey));
+    let paths: Vec<_> = repos.iter().map(|r| r.basic.path.as_path()).collect();
+    let mut lines = Vec::new();
+    walk(repos, &build_repo_tree(&paths), None, "", 0, &mut lines);
+    lines
 }
 
 /// Trait for RepoInfo formatting
 trait RepoDisplay {
-    fn to_compact_view(&self) -> CompactRepoView;
-    fn to_detail_lines(&self) -> Vec<String>;
+    fn to_compact_view(&self, rules: &[StatusRule]) -> CompactRepoView;
+    fn to_detail_lines(
+        &self,
+        sections: &[DetailSection],
+        redact: bool,
+        file_layout: FileLayout,
+    ) -> Vec<String>;
 }
 
 impl RepoDisplay for RepoInfo {
-    fn to_compact_view(&self) -> CompactRepoView {
-        CompactRepoView::from_repo(self)
+    fn to_compact_view(&self, rules: &[StatusRule]) -> CompactRepoView {
+        CompactRepoView::from_repo(self, rules)
     }
 
-    fn to_detail_lines(&self) -> Vec<String> {
+    /// With `redact`, the host of the remote URL is masked
+    fn to_detail_lines(
+        &self,
+        sections: &[DetailSection],
+        redact: bool,
+        file_layout: FileLayout,
+    ) -> Vec<String> {
         let mut lines = Vec::new();
 
-        lines.extend(self.basic.format_for_detail());
-        lines.extend(self.working.format_for_detail());
-
-        if self.sync.has_content() {
-            lines.extend(self.sync.format_for_detail());
-        }
-        if self.stash.has_content() {
-            lines.extend(self.stash.format_for_detail());
-        }
-        if self.remote.has_content() {
-            lines.extend(self.remote.format_for_detail());
-        }
-        if self.commit.has_content() {
-            lines.extend(self.commit.format_for_detail());
+        for section in sections {
+            let component: &dyn DetailViewFormat = match section {
+                DetailSection::Basic => &self.basic,
+                DetailSection::Sync => &self.sync,
+                DetailSection::Working => &self.working,
+                DetailSection::Anomalies => match self.anomalies {
+                    Some(ref anomalies) => anomalies,
+                    None => continue,
+                },
+                DetailSection::Stash => &self.stash,
+                DetailSection::Remote => &RemoteView {
+                    remote: &self.remote,
+                    redact,
+                },
+                DetailSection::Commit => &self.commit,
+                DetailSection::Files => &FilesView {
+                    files: &self.files,
+                    layout: file_layout,
+                },
+            };
+            if component.has_content() {
+                lines.extend(component.format_for_detail());
+            }
         }
 
         lines
     }
 }
 
+/// Initials of a commit author, in the terminal color derived from their email
+fn author_initials(name: &str, email: Option<&str>) -> (String, Color) {
+    use ratatui::style::Color as AuthorColor;
+
+    let (initials, color) = initials_and_color(name, email);
+    let color = match color {
+        AuthorColor::Red => Color::Red,
+        AuthorColor::Green => Color::Green,
+        AuthorColor::Yellow => Color::Yellow,
+        AuthorColor::Blue => Color::Blue,
+        AuthorColor::Magenta => Color::Magenta,
+        AuthorColor::Cyan => Color::Cyan,
+        _ => Color::White,
+    };
+    (initials, color)
+}
+
 /// Compact display data for list view
 struct CompactRepoView {
     name: String,
     branch: String,
     status: String,
+    color: StatusColor,
+    remotes: String,
+    remote_count: usize,
+    /// Remote the branch tracks, `-` if none
+    tracking_remote: String,
+    /// Number of uncommitted changes
+    changes: usize,
+    /// Initials of the last commit's author and their color, if there is a commit
+    author: Option<(String, Color)>,
+    /// Health score, if computed
+    health: Option<u8>,
     path: String,
+    /// Tags joined for display, empty if the repo has none
+    tags: String,
+    /// Time of the last commit, for coloring by age
+    commit_time: Option<SystemTime>,
 }
 
 impl CompactRepoView {
-    fn from_repo(repo: &RepoInfo) -> Self {
+    fn from_repo(repo: &RepoInfo, rules: &[StatusRule]) -> Self {
         let name = repo.basic.name.clone();
-        let branch = repo.basic.branch.clone();
+        let branch = truncate_to_width(&repo.basic.branch, MAX_BRANCH_WIDTH);
         let path = repo.basic.path.display().to_string();
-
-        let status = if repo.working.conflicts > 0 {
-            "conflict".to_string()
-        } else if repo.working.is_dirty {
-            "dirty".to_string()
-        } else if repo.sync.ahead > 0 {
-            "unpushed".to_string()
-        } else if repo.sync.behind > 0 {
-            "unpulled".to_string()
-        } else {
-            "clean".to_string()
+        let status = overall_status(repo, rules);
+        let remotes = match repo.remote.count {
+            0 => "no remote".to_string(),
+            1 => "1 remote".to_string(),
+            n => format!("{} remotes", n),
         };
 
         Self {
             name,
             branch,
-            status,
+            status: status.label,
+            color: status.color,
+            remotes,
+            remote_count: repo.remote.count,
+            tracking_remote: repo
+                .remote
+                .tracking_remote
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            changes: repo.working.total_changes(),
+            author: repo
+                .commit
+                .author
+                .as_deref()
+                .map(|name| author_initials(name, repo.commit.author_email.as_deref())),
+            health: repo.health,
             path,
+            tags: repo.basic.tags.join(","),
+            commit_time: repo.commit.time,
+        }
+    }
+
+    /// Color of the name: by commit age with a `heatmap` horizon, or else `color`
+    ///
+    /// Under the heatmap, repos without commits are muted.
+    fn name_color(&self, heatmap: Option<Duration>, color: Color) -> Color {
+        use ratatui::style::Color as HeatColor;
+
+        let Some(horizon) = heatmap else {
+            return color;
+        };
+        match commit_time_color(self.commit_time, horizon) {
+            Some(HeatColor::Rgb(r, g, b)) => Color::Rgb { r, g, b },
+            _ => Color::DarkGrey,
+        }
+    }
+
+    /// Text and color of an optional column
+    fn column_cell(&self, column: ListColumn) -> (String, Color) {
+        match column {
+            ListColumn::TrackingRemote => (self.tracking_remote.clone(), Color::Blue),
+            ListColumn::Changes => match self.changes {
+                0 => ("0".to_string(), Color::DarkGrey),
+                n => (n.to_string(), Color::Yellow),
+            },
+            ListColumn::Author => self
+                .author
+                .clone()
+                .unwrap_or_else(|| ("-".to_string(), Color::DarkGrey)),
+            ListColumn::Health => match self.health {
+                Some(score) => {
+                    let color = match health_grade(score) {
+                        'A' | 'B' => Color::Green,
+                        'C' => Color::Yellow,
+                        _ => Color::Red,
+                    };
+                    (format!("{} {}", score, health_grade(score)), color)
+                }
+                None => ("-".to_string(), Color::DarkGrey),
+            },
+        }
+    }
+
+    fn remotes_color(&self) -> Color {
+        match self.remote_count {
+            0 => Color::Yellow,
+            _ => Color::DarkGrey,
         }
     }
 
     fn status_color(&self) -> Color {
-        if self.status.contains("conflict") {
-            Color::Red
-        } else if self.status.contains("dirty") {
-            Color::Yellow
-        } else if self.status.contains("unpushed") || self.status.contains("unpulled") {
-            Color::Cyan
-        } else {
-            Color::Green
+        match self.color {
+            StatusColor::Conflict => Color::Red,
+            StatusColor::Dirty => Color::Yellow,
+            StatusColor::Sync => Color::Cyan,
+            StatusColor::Gone => Color::Magenta,
+            StatusColor::Muted => Color::DarkGrey,
+            StatusColor::Clean => Color::Green,
         }
     }
 }
@@ -226,54 +1120,133 @@ trait DetailViewFormat {
 
 impl DetailViewFormat for RepoBasicInfo {
     fn format_for_detail(&self) -> Vec<String> {
-        vec![
+        let unborn = match (self.is_unborn, self.is_detached) {
+            (true, _) => " (empty, no commits)".with(Color::DarkGrey).to_string(),
+            (_, true) => " (detached)".with(Color::Yellow).to_string(),
+            _ => String::new(),
+        };
+        let worktree = match self.worktree_of {
+            Some(ref main) => format!(" (worktree of {})", main)
+                .with(Color::DarkGrey)
+                .to_string(),
+            None => String::new(),
+        };
+        let mut lines = vec![
             format!(
                 "{}{}",
                 "Path: ".with(Color::DarkGrey),
                 self.path.display().to_string().with(Color::White)
             ),
             format!(
-                "{}{}",
+                "{}{}{}{}",
                 "Branch: ".with(Color::DarkGrey),
-                self.branch.as_str().with(Color::Green)
+                self.branch.as_str().with(Color::Green),
+                unborn,
+                worktree
             ),
-        ]
+        ];
+        if let Some(ref superproject) = self.superproject {
+            lines.push(format!(
+                "{}{}",
+                "Submodule of: ".with(Color::DarkGrey),
+                superproject.display().to_string().with(Color::White)
+            ));
+        }
+        if !self.tags.is_empty() {
+            lines.push(format!(
+                "{}{}",
+                "Tags: ".with(Color::DarkGrey),
+                self.tags.join(", ").with(Color::Magenta)
+            ));
+        }
+        lines
     }
 }
 
 impl DetailViewFormat for RepoSyncStatus {
     fn format_for_detail(&self) -> Vec<String> {
-        if self.ahead == 0 && self.behind == 0 {
-            return vec![];
+        let mut lines = vec![format_sync_line(self)];
+        if self.default_branch_changed {
+            lines.push(
+                "Default branch changed upstream (see origin/HEAD)"
+                    .with(Color::Magenta)
+                    .to_string(),
+            );
+        }
+        if let Some(count) = self.unpushed_tags.filter(|&n| n > 0) {
+            lines.push(format!(
+                "{}{}",
+                "Unpushed tags: ".with(Color::DarkGrey),
+                count.to_string().with(Color::Cyan)
+            ));
+        }
+        if let Some(count) = self.unmerged_branches.filter(|&n| n > 0) {
+            lines.push(format!(
+                "{}{}",
+                "Unmerged branches: ".with(Color::DarkGrey),
+                count.to_string().with(Color::Yellow)
+            ));
         }
+        lines
+    }
+}
 
-        let sync_info = if self.ahead > 0 && self.behind > 0 {
-            format!(
-                "{}{} ahead, {} behind",
-                "Sync: ".with(Color::DarkGrey),
-                format!("↑{}", self.ahead).with(Color::Cyan),
-                format!("↓{}", self.behind).with(Color::Yellow)
-            )
-        } else if self.ahead > 0 {
-            format!(
-                "{}{} ahead",
-                "Sync: ".with(Color::DarkGrey),
-                format!("↑{}", self.ahead).with(Color::Cyan)
-            )
-        } else {
-            format!(
-                "{}{} behind",
-                "Sync: ".with(Color::DarkGrey),
-                format!("↓{}", self.behind).with(Color::Yellow)
-            )
-        };
+/// Format the ahead/behind state of a repository
+fn format_sync_line(sync: &RepoSyncStatus) -> String {
+    if sync.upstream_gone {
+        return format!(
+            "{}{}",
+            "Sync: ".with(Color::DarkGrey),
+            "upstream gone (deleted on remote?)".with(Color::Magenta)
+        );
+    }
 
-        vec![sync_info]
+    if !sync.has_upstream {
+        let text = match sync.compare_against {
+            CompareAgainst::Upstream => "no upstream",
+            CompareAgainst::DefaultBranch => "no default branch",
+        };
+        return format!(
+            "{}{}",
+            "Sync: ".with(Color::DarkGrey),
+            text.with(Color::DarkGrey)
+        );
     }
 
-    fn has_content(&self) -> bool {
-        self.ahead > 0 || self.behind > 0
+    let compare_base =
+        format!(" (vs {})", sync.compare_ref.as_deref().unwrap_or("?")).with(Color::DarkGrey);
+
+    if sync.ahead == 0 && sync.behind == 0 {
+        return format!(
+            "{}{}{}",
+            "Sync: ".with(Color::DarkGrey),
+            "in sync".with(Color::Cyan),
+            compare_base
+        );
     }
+
+    let sync_info = if sync.ahead > 0 && sync.behind > 0 {
+        format!(
+            "{}{} ahead, {} behind",
+            "Sync: ".with(Color::DarkGrey),
+            format!("↑{}", sync.ahead).with(Color::Cyan),
+            format!("↓{}", sync.behind).with(Color::Yellow)
+        )
+    } else if sync.ahead > 0 {
+        format!(
+            "{}{} ahead",
+            "Sync: ".with(Color::DarkGrey),
+            format!("↑{}", sync.ahead).with(Color::Cyan)
+        )
+    } else {
+        format!(
+            "{}{} behind",
+            "Sync: ".with(Color::DarkGrey),
+            format!("↓{}", sync.behind).with(Color::Yellow)
+        )
+    };
+
+    format!("{}{}", sync_info, compare_base)
 }
 
 impl DetailViewFormat for RepoWorkingStatus {
@@ -281,9 +1254,14 @@ impl DetailViewFormat for RepoWorkingStatus {
         let label = "Status: ".with(Color::DarkGrey);
 
         let status_text = if self.conflicts > 0 {
-            let content = format!("CONFLICT (conflicts: {})", self.conflicts)
-                .with(Color::Red)
-                .bold();
+            let content = match self.conflict_hunks {
+                Some(hunks) => {
+                    format!("CONFLICT (conflicts: {}, hunks: {})", self.conflicts, hunks)
+                }
+                None => format!("CONFLICT (conflicts: {})", self.conflicts),
+            }
+            .with(Color::Red)
+            .bold();
             format!("{}{}", label, content)
         } else if self.is_dirty {
             let content = format!(
@@ -298,17 +1276,111 @@ impl DetailViewFormat for RepoWorkingStatus {
             format!("{}{}", label, content)
         };
 
-        vec![status_text]
+        let mut lines = vec![status_text];
+        if let Some(hunks) = self.unstaged_hunks.filter(|&n| n > 0) {
+            lines.push(format!(
+                "{}{}",
+                "Unstaged hunks: ".with(Color::DarkGrey),
+                hunks.to_string().with(Color::Yellow)
+            ));
+        }
+        if let Some(time) = self.workdir_mtime {
+            lines.push(format!(
+                "{}{}",
+                "Last edit: ".with(Color::DarkGrey),
+                format_relative_time(time).with(Color::White)
+            ));
+        }
+        if self.locked {
+            lines.push(format!(
+                "{}{}",
+                "Index: ".with(Color::DarkGrey),
+                "LOCKED (index.lock exists)".with(Color::Red).bold()
+            ));
+        }
+        if let Some(count) = self.lfs_locks.filter(|&n| n > 0) {
+            lines.push(format!(
+                "{}{}",
+                "LFS locks: ".with(Color::DarkGrey),
+                count.to_string().with(Color::Yellow)
+            ));
+        }
+        if let Some(count) = self.assume_unchanged.filter(|&n| n > 0) {
+            lines.push(format!(
+                "{}{}{}",
+                "Hidden from status: ".with(Color::DarkGrey),
+                count.to_string().with(Color::Yellow),
+                " (assume-unchanged or skip-worktree)".with(Color::DarkGrey)
+            ));
+        }
+        if let Some(operation) = self.operation {
+            lines.push(format!(
+                "{}{}",
+                "Operation: ".with(Color::DarkGrey),
+                format!("{} in progress", operation.label())
+                    .with(Color::Yellow)
+                    .bold()
+            ));
+        }
+        lines
+    }
+}
+
+impl DetailViewFormat for RepoAnomalies {
+    fn format_for_detail(&self) -> Vec<String> {
+        vec![format!(
+            "{}{}",
+            "Anomalies: ".with(Color::DarkGrey),
+            format!(
+                "{} ignored present, {} tracked but ignored",
+                self.ignored, self.tracked_ignored
+            )
+            .with(Color::Yellow)
+        )]
+    }
+
+    fn has_content(&self) -> bool {
+        self.has_any()
     }
 }
 
-impl DetailViewFormat for RepoRemoteInfo {
+/// Remote information as shown in the detail view
+struct RemoteView<'a> {
+    remote: &'a RepoRemoteInfo,
+    /// Mask the host of the remote URL
+    redact: bool,
+}
+
+impl DetailViewFormat for RemoteView<'_> {
     fn format_for_detail(&self) -> Vec<String> {
-        if let Some(ref url) = self.url {
+        let remote = self.remote;
+        if let Some(ref url) = remote.url {
+            let last_fetch = remote
+                .last_fetch
+                .map(format_relative_time)
+                .unwrap_or_else(|| "never".to_string());
+            let others = match remote.count {
+                0 | 1 => String::new(),
+                n => format!(" (+{} more)", n - 1),
+            };
+            vec![
+                format!(
+                    "{}{}{}",
+                    "Remote: ".with(Color::DarkGrey),
+                    display_remote_url(url, self.redact).with(Color::Blue),
+                    others.with(Color::DarkGrey)
+                ),
+                format!(
+                    "{}{}",
+                    "Last fetch: ".with(Color::DarkGrey),
+                    last_fetch.with(Color::White)
+                ),
+            ]
+        } else if remote.count == 0 {
             vec![format!(
                 "{}{}",
                 "Remote: ".with(Color::DarkGrey),
-                url.as_str().with(Color::Blue)
+                "none (local only)".with(Color::Yellow)
             )]
         } else {
             vec![]
@@ -316,7 +1388,7 @@ impl DetailViewFormat for RepoRemoteInfo {
     }
 
     fn has_content(&self) -> bool {
-        self.url.is_some()
+        self.remote.url.is_some() || self.remote.count == 0
     }
 }
 
@@ -331,12 +1403,28 @@ impl DetailViewFormat for RepoCommitInfo {
                 msg.as_str().with(Color::White)
             ));
             if let Some(ref author) = self.author {
+                let (initials, color) = author_initials(author, self.author_email.as_deref());
                 lines.push(format!(
-                    "{}{}",
+                    "{}{} {}",
                     "Author: ".with(Color::DarkGrey),
+                    initials.with(color).bold(),
                     author.as_str().with(Color::White)
                 ));
             }
+            if let Some(time) = self.time {
+                lines.push(format!(
+                    "{}{}",
+                    "Date: ".with(Color::DarkGrey),
+                    format_relative_time(time).with(Color::White)
+                ));
+            }
+            if let Some(count) = self.contributors {
+                lines.push(format!(
+                    "{}{}",
+                    "Contributors: ".with(Color::DarkGrey),
+                    count.to_string().with(Color::White)
+                ));
+            }
         }
 
         lines
@@ -365,27 +1453,971 @@ impl DetailViewFormat for RepoStashInfo {
     }
 }
 
-impl DetailViewFormat for RepoFileChanges {
+/// File changes as shown in the detail view
+struct FilesView<'a> {
+    files: &'a RepoFileChanges,
+    layout: FileLayout,
+}
+
+impl DetailViewFormat for FilesView<'_> {
     fn format_for_detail(&self) -> Vec<String> {
-        if self.changes.is_empty() {
+        let changes = &self.files.changes;
+        if changes.is_empty() {
             return vec![];
         }
 
         let mut lines = vec!["Files:".with(Color::DarkGrey).to_string()];
-        for change in &self.changes {
-            let (marker, color) = match change.status {
-                FileChangeStatus::Staged => ("[S]", Color::Green),
-                FileChangeStatus::Modified => ("[M]", Color::Yellow),
-                FileChangeStatus::Untracked => ("[U]", Color::Cyan),
-                FileChangeStatus::Conflicted => ("[C]", Color::Red),
-            };
-            lines.push(format!("  {} {}", marker.with(color).bold(), change.path));
+        match self.layout {
+            FileLayout::Sorted => {
+                for change in sort_file_changes(changes) {
+                    lines.push(format!("  {}", format_file_change(change)));
+                }
+            }
+            FileLayout::Grouped => {
+                for (status, files) in group_file_changes(changes) {
+                    lines.push(format!(
+                        "  {}",
+                        format!("{} ({}):", status.label(), files.len()).with(Color::DarkGrey)
+                    ));
+                    for change in files {
+                        lines.push(format!("    {}", format_file_change(change)));
+                    }
+                }
+            }
         }
 
         lines
     }
 
     fn has_content(&self) -> bool {
-        !self.changes.is_empty()
+        !self.files.changes.is_empty()
+    }
+}
+
+/// Format a file change with its status marker
+fn format_file_change(change: &FileChange) -> String {
+    let (marker, color) = match change.status {
+        FileChangeStatus::Staged => ("[S]", Color::Green),
+        FileChangeStatus::Modified => ("[M]", Color::Yellow),
+        FileChangeStatus::Untracked => ("[U]", Color::Cyan),
+        FileChangeStatus::Conflicted => ("[C]", Color::Red),
+    };
+    format!("{} {}", marker.with(color).bold(), change.path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::repo_info::FileChange;
+
+    /// Create a dirty repository info for formatting tests
+    fn sample_repo() -> RepoInfo {
+        let mut repo = RepoInfo::stub("sample", "/tmp/sample");
+        repo.sync.ahead = 1;
+        repo.sync.has_upstream = true;
+        repo.working.is_dirty = true;
+        repo.working.staged = 1;
+        repo.files.changes.push(FileChange {
+            path: "a.txt".to_string(),
+            status: FileChangeStatus::Staged,
+            conflict_hunks: None,
+        });
+        repo
+    }
+
+    #[test]
+    fn test_format_json_compact() {
+        let repo = sample_repo();
+        let json = format_repos_json(&[&repo], true, false).unwrap();
+        assert!(!json.contains('\n'));
+        assert!(json.contains(r#""status":"staged""#));
+    }
+
+    #[test]
+    fn test_format_json_pretty() {
+        let repo = sample_repo();
+        let json = format_repos_json(&[&repo], false, false).unwrap();
+        assert!(json.lines().count() > 1);
+    }
+
+    #[test]
+    fn test_format_json_round_trip() {
+        let repo = sample_repo();
+        let compact = format_repos_json(&[&repo], true, false).unwrap();
+        let pretty = format_repos_json(&[&repo], false, false).unwrap();
+
+        let compact: serde_json::Value = serde_json::from_str(&compact).unwrap();
+        let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+        assert_eq!(compact, pretty);
+    }
+
+    #[test]
+    fn test_format_json_stable_key_order() {
+        let repo = sample_repo();
+        let json = format_repos_json(&[&repo], true, false).unwrap();
+        let keys = [
+            "\"basic\"",
+            "\"sync\"",
+            "\"working\"",
+            "\"remote\"",
+            "\"files\"",
+        ];
+        let positions: Vec<usize> = keys.iter().map(|k| json.find(k).unwrap()).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_compact_status_synced_vs_no_upstream() {
+        let mut synced = sample_repo();
+        synced.working.is_dirty = false;
+        synced.working.staged = 0;
+        synced.sync.ahead = 0;
+        assert_eq!(CompactRepoView::from_repo(&synced, &[]).status, "clean");
+
+        let mut untracked = synced.clone();
+        untracked.sync.has_upstream = false;
+        assert_eq!(
+            CompactRepoView::from_repo(&untracked, &[]).status,
+            "no upstream"
+        );
+    }
+
+    #[test]
+    fn test_detail_sync_synced_vs_no_upstream() {
+        let mut synced = sample_repo().sync;
+        synced.ahead = 0;
+        let lines = synced.format_for_detail();
+        assert!(lines[0].contains("in sync"));
+
+        synced.has_upstream = false;
+        let lines = synced.format_for_detail();
+        assert!(lines[0].contains("no upstream"));
+    }
+
+    #[test]
+    fn test_redact_remote_in_detail_and_json() {
+        let mut repo = sample_repo();
+        repo.remote.url = Some("git@git.internal.corp:owner/repo.git".to_string());
+        repo.remote.count = 1;
+
+        let lines = repo.to_detail_lines(&[DetailSection::Remote], true, FileLayout::Sorted);
+        assert!(lines[0].contains("git@*****:owner/repo.git"));
+        assert!(!lines[0].contains("internal"));
+        let lines = repo.to_detail_lines(&[DetailSection::Remote], false, FileLayout::Sorted);
+        assert!(lines[0].contains("git.internal.corp"));
+
+        // JSON keeps the stored URL unless redaction is requested
+        let json = format_repos_json(&[&repo], true, false).unwrap();
+        assert!(json.contains("git.internal.corp"));
+        let json = format_repos_json(&[&repo], true, true).unwrap();
+        assert!(json.contains("git@*****:owner/repo.git"));
+        assert_eq!(
+            repo.remote.url.as_deref(),
+            Some("git@git.internal.corp:owner/repo.git")
+        );
+    }
+
+    #[test]
+    fn test_detail_custom_sections() {
+        let repo = sample_repo();
+        let sections = [DetailSection::Files, DetailSection::Sync];
+        let lines = repo.to_detail_lines(&sections, false, FileLayout::Sorted);
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("Files:"));
+        assert!(lines[1].contains("a.txt"));
+        assert!(lines[2].contains("ahead"));
+
+        // The default sections leave out the file list
+        let lines = repo.to_detail_lines(&DEFAULT_DETAIL_SECTIONS, false, FileLayout::Sorted);
+        assert!(!lines.iter().any(|l| l.contains("a.txt")));
+    }
+
+    #[test]
+    fn test_detail_file_layouts() {
+        let mut repo = sample_repo();
+        repo.files.changes.clear();
+        for (path, status) in [
+            ("notes.txt", FileChangeStatus::Untracked),
+            ("src/lib.rs", FileChangeStatus::Modified),
+            ("Cargo.toml", FileChangeStatus::Staged),
+            ("src/main.rs", FileChangeStatus::Conflicted),
+            ("build.rs", FileChangeStatus::Modified),
+        ] {
+            repo.files.changes.push(FileChange {
+                path: path.to_string(),
+                status,
+                conflict_hunks: None,
+            });
+        }
+        let sections = [DetailSection::Files];
+
+        let lines: Vec<String> = repo
+            .to_detail_lines(&sections, false, FileLayout::Sorted)
+            .iter()
+            .map(|l| strip_ansi_codes(l))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Files:",
+                "  [C] src/main.rs",
+                "  [S] Cargo.toml",
+                "  [M] build.rs",
+                "  [M] src/lib.rs",
+                "  [U] notes.txt",
+            ]
+        );
+
+        let lines: Vec<String> = repo
+            .to_detail_lines(&sections, false, FileLayout::Grouped)
+            .iter()
+            .map(|l| strip_ansi_codes(l))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Files:",
+                "  Conflicted (1):",
+                "    [C] src/main.rs",
+                "  Staged (1):",
+                "    [S] Cargo.toml",
+                "  Modified (2):",
+                "    [M] build.rs",
+                "    [M] src/lib.rs",
+                "  Untracked (1):",
+                "    [U] notes.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_lines_nested_repos() {
+        let repos = [
+            RepoInfo::stub("mono", "/work/mono"),
+            RepoInfo::stub("lib-a", "/work/mono/vendor/lib-a"),
+            RepoInfo::stub("inner", "/work/mono/vendor/lib-a/inner"),
+            RepoInfo::stub("lib-b", "/work/mono/vendor/lib-b"),
+            RepoInfo::stub("other", "/work/other"),
+        ];
+        let refs: Vec<&RepoInfo> = repos.iter().collect();
+
+        let rendered: Vec<String> = tree_lines(&refs)
+            .iter()
+            .map(|l| format!("{}{}", l.prefix, l.label))
+            .collect();
+
+        assert_eq!(
+            rendered,
+            vec![
+                "mono",
+                "├── vendor/lib-a",
+                "│   └── inner",
+                "└── vendor/lib-b",
+                "other",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_repo_paths() {
+        let paths = [PathBuf::from("/a/one"), PathBuf::from("/a/two")];
+        assert_eq!(format_repo_paths(&paths, false), "/a/one\n/a/two\n");
+        assert_eq!(format_repo_paths(&paths, true), "/a/one\0/a/two\0");
+    }
+
+    #[tokio::test]
+    async fn test_paths_only_skips_git_info() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        // A bare ".git" directory is found by the scanner but is not a valid
+        // repository, so it would be dropped if git info were read
+        let broken = temp_dir.path().join("broken");
+        std::fs::create_dir_all(broken.join(".git")).unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![temp_dir.path().to_string_lossy().to_string()];
+        let out = temp_dir.path().join("paths.txt");
+        let opts = ListOptions {
+            paths_only: true,
+            out: Some(out.clone()),
+            ..Default::default()
+        };
+
+        list_repos(config, opts).await.unwrap();
+
+        let written = std::fs::read_to_string(&out).unwrap();
+        assert_eq!(written, format!("{}\n", broken.display()));
+    }
+
+    #[tokio::test]
+    async fn test_paths_only_rejects_status_filters() {
+        let opts = ListOptions {
+            paths_only: true,
+            dirty_filter: true,
+            ..Default::default()
+        };
+        assert!(list_repos(AppConfig::default(), opts).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hide_submodules() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("source");
+        let scan_root = temp_dir.path().join("scan");
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        for path in [&source, &scan_root.join("super")] {
+            let repo = git2::Repository::init(path).unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+        let superproject = git2::Repository::open(scan_root.join("super")).unwrap();
+        let mut submodule = superproject
+            .submodule(source.to_str().unwrap(), Path::new("sub"), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+
+        let list_names = |hide_submodules: bool| {
+            let mut config = AppConfig::default();
+            config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+            config.main.hide_submodules = hide_submodules;
+            let out = temp_dir
+                .path()
+                .join(format!("out-{}.json", hide_submodules));
+            let opts = ListOptions {
+                json: true,
+                out: Some(out.clone()),
+                ..Default::default()
+            };
+            async move {
+                list_repos(config, opts).await.unwrap();
+                let json: serde_json::Value =
+                    serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+                json.as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|r| r["basic"]["dir_name"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(list_names(false).await, vec!["super", "sub"]);
+        assert_eq!(list_names(true).await, vec!["super"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_nested_drops_inner_repos() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scan_root = temp_dir.path().join("scan");
+        for path in ["app", "app/vendor/lib", "app/vendor/lib/inner", "tool"] {
+            git2::Repository::init(scan_root.join(path)).unwrap();
+        }
+
+        let list_paths = |no_nested: bool| {
+            let mut config = AppConfig::default();
+            config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+            let out = temp_dir.path().join(format!("out-{}.txt", no_nested));
+            let opts = ListOptions {
+                paths_only: true,
+                no_nested,
+                out: Some(out.clone()),
+                ..Default::default()
+            };
+            async move {
+                list_repos(config, opts).await.unwrap();
+                let mut names: Vec<String> = std::fs::read_to_string(out)
+                    .unwrap()
+                    .lines()
+                    .map(|line| line.rsplit('/').next().unwrap().to_string())
+                    .collect();
+                names.sort();
+                names
+            }
+        };
+
+        assert_eq!(list_paths(false).await, ["app", "inner", "lib", "tool"]);
+        assert_eq!(list_paths(true).await, ["app", "tool"]);
+    }
+
+    #[tokio::test]
+    async fn test_no_remote_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scan_root = temp_dir.path().join("scan");
+        let local = git2::Repository::init(scan_root.join("local")).unwrap();
+        let cloned = git2::Repository::init(scan_root.join("cloned")).unwrap();
+        cloned
+            .remote("origin", "https://example.com/cloned.git")
+            .unwrap();
+        drop((local, cloned));
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+        let out = temp_dir.path().join("out.json");
+        let opts = ListOptions {
+            json: true,
+            no_remote_filter: true,
+            out: Some(out.clone()),
+            ..Default::default()
+        };
+        list_repos(config, opts).await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        let repos = json.as_array().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0]["basic"]["name"], "local");
+        assert_eq!(repos[0]["remote"]["count"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_locked_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scan_root = temp_dir.path().join("scan");
+        let stuck = git2::Repository::init(scan_root.join("stuck")).unwrap();
+        let fine = git2::Repository::init(scan_root.join("fine")).unwrap();
+        std::fs::write(stuck.path().join("index.lock"), "").unwrap();
+        drop((stuck, fine));
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+        let out = temp_dir.path().join("out.json");
+        let opts = ListOptions {
+            json: true,
+            locked_filter: true,
+            out: Some(out.clone()),
+            ..Default::default()
+        };
+        list_repos(config, opts).await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        let repos = json.as_array().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0]["basic"]["name"], "stuck");
+        assert_eq!(repos[0]["working"]["locked"], true);
+    }
+
+    #[tokio::test]
+    async fn test_detached_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scan_root = temp_dir.path().join("scan");
+        for name in ["attached", "bisecting"] {
+            let repo = git2::Repository::init(scan_root.join(name)).unwrap();
+            let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+            let tree = repo
+                .find_tree(repo.index().unwrap().write_tree().unwrap())
+                .unwrap();
+            let oid = repo
+                .commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+            if name == "bisecting" {
+                repo.set_head_detached(oid).unwrap();
+            }
+        }
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+        let out = temp_dir.path().join("out.json");
+        let opts = ListOptions {
+            json: true,
+            detached_filter: true,
+            out: Some(out.clone()),
+            ..Default::default()
+        };
+        assert_eq!(opts.active_filters(), ["--detached"]);
+        list_repos(config, opts).await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        let repos = json.as_array().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0]["basic"]["name"], "bisecting");
+        assert_eq!(repos[0]["basic"]["is_detached"], true);
+    }
+
+    #[tokio::test]
+    async fn test_tag_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scan_root = temp_dir.path().join("scan");
+        git2::Repository::init(scan_root.join("api")).unwrap();
+        git2::Repository::init(scan_root.join("dotfiles")).unwrap();
+        let tags_file = temp_dir.path().join("tags.toml");
+        std::fs::write(
+            &tags_file,
+            format!(
+                "dotfiles = [\"personal\"]\n{:?} = [\"work\"]\n",
+                scan_root.join("api")
+            ),
+        )
+        .unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+        config.main.tags_file = Some(tags_file.to_string_lossy().to_string());
+        let out = temp_dir.path().join("out.json");
+        let opts = ListOptions {
+            json: true,
+            tag_filter: Some("work".to_string()),
+            out: Some(out.clone()),
+            ..Default::default()
+        };
+        list_repos(config, opts).await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        let repos = json.as_array().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0]["basic"]["name"], "api");
+        assert_eq!(repos[0]["basic"]["tags"], serde_json::json!(["work"]));
+    }
+
+    #[tokio::test]
+    async fn test_anomalies_filter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let scan_root = temp_dir.path().join("scan");
+        git2::Repository::init(scan_root.join("tidy")).unwrap();
+        git2::Repository::init(scan_root.join("messy")).unwrap();
+        std::fs::write(scan_root.join("messy/.gitignore"), "*.log\n").unwrap();
+        std::fs::write(scan_root.join("messy/debug.log"), "log").unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![scan_root.to_string_lossy().to_string()];
+        config.main.scan_anomalies = true;
+        let out = temp_dir.path().join("out.json");
+        let opts = ListOptions {
+            json: true,
+            anomalies_filter: true,
+            out: Some(out.clone()),
+            ..Default::default()
+        };
+        list_repos(config, opts).await.unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        let repos = json.as_array().unwrap();
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0]["basic"]["name"], "messy");
+        assert_eq!(repos[0]["anomalies"]["ignored"], 1);
+    }
+
+    #[test]
+    fn test_list_paths_fit_width() {
+        let mut repo = sample_repo();
+        repo.basic.path = PathBuf::from("/home/user/some/very/deeply/nested/projects/sample");
+
+        let full = strip_ansi_codes(&format_repos_list(
+            &[&repo],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[],
+            None,
+        ));
+        assert!(full.contains("/home/user/some/very/deeply/nested/projects/sample"));
+
+        let fitted = strip_ansi_codes(&format_repos_list(
+            &[&repo],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[],
+            Some(60),
+        ));
+        let line = fitted.lines().next().unwrap();
+        assert_eq!(display_width(line), 60);
+        assert!(line.ends_with("  …eply/nested/projects/sample"));
+    }
+
+    #[test]
+    fn test_list_shows_tags_after_path() {
+        let mut repo = sample_repo();
+        repo.basic.path = PathBuf::from("/home/user/some/very/deeply/nested/projects/sample");
+        repo.basic.tags = vec!["personal".to_string(), "work".to_string()];
+
+        let fitted = strip_ansi_codes(&format_repos_list(
+            &[&repo],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[],
+            Some(60),
+        ));
+        let line = fitted.lines().next().unwrap();
+        assert_eq!(display_width(line), 60);
+        assert!(line.ends_with("/sample  personal,work"), "{}", line);
+    }
+
+    #[test]
+    fn test_table_columns() {
+        let mut long = sample_repo();
+        long.basic.name = "long-name".to_string();
+        long.basic.path = PathBuf::from("/tmp/long-name");
+        let short = sample_repo();
+
+        let out = strip_ansi_codes(&format_repos_table(
+            &[&long, &short],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[ListColumn::Changes],
+            Some(&BOX_BORDERS),
+        ));
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(
+            lines[0],
+            "┌───────────┬────────┬────────┬─────────┬───────────┬────────────────┐"
+        );
+        assert_eq!(
+            lines[1],
+            "│ NAME      │ STATUS │ BRANCH │ CHANGES │ REMOTES   │ PATH           │"
+        );
+        assert_eq!(
+            lines[2],
+            "├───────────┼────────┼────────┼─────────┼───────────┼────────────────┤"
+        );
+        assert_eq!(
+            lines[4],
+            "│ sample    │ dirty  │ main   │ 1       │ no remote │ /tmp/sample    │"
+        );
+        assert!(lines[5].starts_with('└') && lines[5].ends_with('┘'));
+        assert!(lines.iter().all(|line| display_width(line) == 70));
+    }
+
+    #[test]
+    fn test_column_lines() {
+        let lines = vec![
+            format!(
+                "{}{}",
+                "Branch: ".with(Color::DarkGrey),
+                "main".with(Color::Green)
+            ),
+            format!(
+                "{}{}, {}",
+                "Changes: ".with(Color::DarkGrey),
+                "3 staged".with(Color::Green),
+                "12 modified".with(Color::Yellow)
+            ),
+            "Files:".to_string(),
+        ];
+        let columned = column_lines(lines, 20);
+        let plain: Vec<String> = columned.iter().map(|l| strip_ansi_codes(l)).collect();
+        assert_eq!(
+            plain,
+            [
+                "Branch:  main",
+                "Changes: 3 staged,",
+                "         12 modified",
+                "Files:",
+            ]
+        );
+        // The colors of the wrapped value are kept
+        assert!(columned[2].contains(&"12 modified".with(Color::Yellow).to_string()));
+
+        // Without a width limit, only the labels are aligned
+        let lines = vec!["Tags: a, b".to_string(), "Branch: main".to_string()];
+        assert_eq!(
+            column_lines(lines, usize::MAX),
+            ["Tags:   a, b", "Branch: main"]
+        );
+    }
+
+    #[test]
+    fn test_table_plain_and_ascii() {
+        let repo = sample_repo();
+        let plain = strip_ansi_codes(&format_repos_table(
+            &[&repo],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[],
+            None,
+        ));
+        assert_eq!(
+            plain,
+            "NAME    STATUS  BRANCH  REMOTES    PATH\n\
+             sample  dirty   main    no remote  /tmp/sample\n"
+        );
+
+        let ascii = strip_ansi_codes(&format_repos_table(
+            &[&repo],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[],
+            Some(&ASCII_BORDERS),
+        ));
+        assert!(ascii.is_ascii());
+        assert!(ascii.lines().nth(2).unwrap().starts_with("+--------+"));
+    }
+
+    #[test]
+    fn test_grid_output() {
+        let names = ["api", "web", "billing-service", "docs", "infra"];
+        let repos: Vec<RepoInfo> = names
+            .iter()
+            .map(|name| RepoInfo::stub(name, &format!("/repos/{}", name)))
+            .collect();
+        let refs: Vec<&RepoInfo> = repos.iter().collect();
+
+        // Cells are as wide as the longest name, 15 columns plus a gap of 2
+        let out = strip_ansi_codes(&format_repos_grid(
+            &refs,
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            40,
+        ));
+        assert_eq!(
+            out,
+            "api              web\n\
+             billing-service  docs\n\
+             infra\n"
+        );
+
+        let out = strip_ansi_codes(&format_repos_grid(
+            &refs,
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            60,
+        ));
+        assert_eq!(out.lines().count(), 2);
+        assert!(format_repos_grid(&[], &[], &StatusPrefixes::default(), None, 60).is_empty());
+    }
+
+    #[test]
+    fn test_heatmap_name_colors() {
+        let mut fresh = sample_repo();
+        fresh.commit.time = Some(SystemTime::now());
+        let unborn = sample_repo();
+
+        let out = format_repos_list(
+            &[&fresh, &unborn],
+            &[],
+            &StatusPrefixes::default(),
+            Some(Duration::from_secs(3600)),
+            &[],
+            None,
+        );
+        let lines: Vec<&str> = out.lines().collect();
+        let green = Color::Rgb { r: 0, g: 255, b: 0 };
+        assert!(lines[0].contains(&"sample".with(green).bold().to_string()));
+        assert!(lines[1].contains(&"sample".with(Color::DarkGrey).bold().to_string()));
+
+        // Without a heatmap, names keep their usual color
+        let out = format_repos_list(&[&fresh], &[], &StatusPrefixes::default(), None, &[], None);
+        assert!(out.contains(&"sample".with(Color::Cyan).bold().to_string()));
+    }
+
+    #[test]
+    fn test_tracking_remote_column() {
+        let mut tracked = sample_repo();
+        tracked.basic.name = "tracked".to_string();
+        tracked.remote.tracking_remote = Some("upstream".to_string());
+        let untracked = sample_repo();
+
+        let columns = [ListColumn::TrackingRemote];
+        let out = strip_ansi_codes(&format_repos_list(
+            &[&tracked, &untracked],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &columns,
+            None,
+        ));
+        let lines: Vec<Vec<&str>> = out
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        let branch = lines[0].iter().position(|c| *c == "main").unwrap();
+        assert_eq!(lines[0][branch + 1], "upstream");
+        assert_eq!(lines[1][branch + 1], "-");
+
+        // The column is left out unless configured
+        let out = strip_ansi_codes(&format_repos_list(
+            &[&tracked],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[],
+            None,
+        ));
+        assert!(!out.contains("upstream"));
+    }
+
+    #[test]
+    fn test_changes_column() {
+        let mut busy = sample_repo();
+        busy.working.modified = 12;
+        let mut clean = sample_repo();
+        clean.working = Default::default();
+
+        let columns = [ListColumn::Changes, ListColumn::TrackingRemote];
+        let out = strip_ansi_codes(&format_repos_list(
+            &[&busy, &clean],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &columns,
+            None,
+        ));
+        let lines: Vec<Vec<&str>> = out
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        let branch = lines[0].iter().position(|c| *c == "main").unwrap();
+        assert_eq!(lines[0][branch + 1..branch + 3], ["13", "-"]);
+        assert_eq!(lines[1][branch + 1..branch + 3], ["0", "-"]);
+    }
+
+    #[test]
+    fn test_health_column() {
+        let mut healthy = sample_repo();
+        healthy.health = Some(92);
+        let mut unscored = sample_repo();
+        unscored.health = None;
+
+        let out = strip_ansi_codes(&format_repos_list(
+            &[&healthy, &unscored],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &[ListColumn::Health],
+            None,
+        ));
+        let lines: Vec<Vec<&str>> = out
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        let branch = lines[0].iter().position(|c| *c == "main").unwrap();
+        assert_eq!(lines[0][branch + 1..branch + 3], ["92", "A"]);
+        assert_eq!(lines[1][branch + 1], "-");
+    }
+
+    #[test]
+    fn test_author_column() {
+        let mut ada = sample_repo();
+        ada.commit.author = Some("Ada Lovelace".to_string());
+        ada.commit.author_email = Some("ada@example.com".to_string());
+        let mut unborn = sample_repo();
+        unborn.commit.author = None;
+
+        let columns = [ListColumn::Author];
+        let out = format_repos_list(
+            &[&ada, &unborn],
+            &[],
+            &StatusPrefixes::default(),
+            None,
+            &columns,
+            None,
+        );
+        let (_, color) = author_initials("Ada Lovelace", Some("ada@example.com"));
+        assert!(out.contains(&"AL".with(color).to_string()), "{}", out);
+        let lines: Vec<Vec<String>> = strip_ansi_codes(&out)
+            .lines()
+            .map(|line| line.split_whitespace().map(str::to_string).collect())
+            .collect();
+        let branch = lines[0].iter().position(|c| c == "main").unwrap();
+        assert_eq!(lines[0][branch + 1], "AL");
+        assert_eq!(lines[1][branch + 1], "-");
+    }
+
+    #[test]
+    fn test_porcelain_output() {
+        let mut clean = RepoInfo::stub("clean", "/work/clean");
+        clean.sync.has_upstream = true;
+        let mut dirty = RepoInfo::stub("dirty", "/work/my projects/dirty");
+        dirty.basic.branch = "feature/x".to_string();
+        dirty.sync.has_upstream = true;
+        dirty.working.is_dirty = true;
+        dirty.working.staged = 1;
+        dirty.working.modified = 2;
+        dirty.working.untracked = 3;
+        let mut conflicted = RepoInfo::stub("conflicted", "/work/conflicted");
+        conflicted.sync.has_upstream = true;
+        conflicted.working.conflicts = 4;
+        conflicted.sync.ahead = 5;
+        conflicted.sync.behind = 6;
+        let mut unpushed = RepoInfo::stub("unpushed", "/work/unpushed");
+        unpushed.sync.has_upstream = true;
+        unpushed.sync.ahead = 2;
+        let local = RepoInfo::stub("local", "/work/local");
+        let mut detached = RepoInfo::stub("detached", "/work/detached");
+        detached.basic.branch = "HEAD".to_string();
+        detached.sync.has_upstream = true;
+        detached.sync.behind = 1;
+        let mut unknown = RepoInfo::stub("unknown", "/work/unknown");
+        unknown.basic.branch = "?".to_string();
+        unknown.sync.has_upstream = true;
+
+        let out = format_repos_porcelain(&[
+            &clean,
+            &dirty,
+            &conflicted,
+            &unpushed,
+            &local,
+            &detached,
+            &unknown,
+        ]);
+        assert_eq!(
+            out,
+            ". 0 0 0 0 0 0 main /work/clean\n\
+             D 1 2 3 0 0 0 feature/x /work/my projects/dirty\n\
+             C 0 0 0 4 5 6 main /work/conflicted\n\
+             P 0 0 0 0 2 0 main /work/unpushed\n\
+             N 0 0 0 0 0 0 main /work/local\n\
+             U 0 0 0 0 0 1 HEAD /work/detached\n\
+             . 0 0 0 0 0 0 - /work/unknown\n"
+        );
+    }
+
+    #[test]
+    fn test_porcelain_untracked_only() {
+        let mut repo = RepoInfo::stub("app", "/work/app");
+        repo.sync.has_upstream = true;
+        repo.working.untracked = 1;
+        repo.working.is_dirty = true;
+        // Untracked files count as dirty under the default dirty_includes
+        assert_eq!(
+            format_repos_porcelain(&[&repo]),
+            "D 0 0 1 0 0 0 main /work/app\n"
+        );
+        assert_eq!(format_repos_porcelain(&[]), "");
+    }
+
+    #[test]
+    fn test_compact_remotes_column() {
+        let mut repo = sample_repo();
+        assert_eq!(repo.to_compact_view(&[]).remotes, "no remote");
+
+        repo.remote.count = 2;
+        assert_eq!(repo.to_compact_view(&[]).remotes, "2 remotes");
+    }
+
+    #[test]
+    fn test_status_prefix_before_name() {
+        let mut dirty = sample_repo();
+        dirty.basic.name = "dirty".to_string();
+        dirty.working.is_dirty = true;
+        let mut clean = sample_repo();
+        clean.working = Default::default();
+        clean.sync.ahead = 0;
+
+        let prefixes: StatusPrefixes = toml::from_str("dirty = \"* \"").unwrap();
+        let out = strip_ansi_codes(&format_repos_list(
+            &[&dirty, &clean],
+            &[],
+            &prefixes,
+            None,
+            &[],
+            None,
+        ));
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].starts_with("* dirty  dirty"), "{}", lines[0]);
+        // Names stay aligned when only some statuses have a prefix
+        assert!(lines[1].starts_with("sample   clean"), "{}", lines[1]);
     }
 }
@@ -1,3 +1,13 @@
+mod branches;
+mod diff;
+mod keys;
 mod list;
+mod scan;
+mod watch;
 
-pub use list::list_repos;
+pub use branches::summarize_repo_branches;
+pub use diff::diff_repos;
+pub use keys::print_keys;
+pub use list::{ListOptions, collect_repos, list_repos};
+pub use scan::explain_scan;
+pub use watch::watch_dirty;
@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use crossterm::style::{Color, Stylize};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+use crate::cli::output::write_output;
+use crate::config::AppConfig;
+use crate::core::scanner::{ScanDecision, ScanEvent, ScanTrace};
+use crate::core::{ScanOutcome, scan_directories_traced};
+
+/// Scan for repositories without reading git info
+///
+/// With `explain`, every directory the scanner entered or skipped is printed,
+/// with the reason it was skipped, instead of only the repository paths.
+pub async fn explain_scan(config: &AppConfig, explain: bool) -> Result<()> {
+    // A dry run leaves the checkpoint file alone
+    let mut config = config.clone();
+    config.internal.scan_checkpoint = None;
+    config.internal.resume_scan = false;
+
+    let events: Arc<Mutex<Vec<ScanEvent>>> = Arc::default();
+    let trace: Option<ScanTrace> = explain.then(|| {
+        let sink = events.clone();
+        Arc::new(move |event| {
+            if let Ok(mut events) = sink.lock() {
+                events.push(event);
+            }
+        }) as ScanTrace
+    });
+    let outcome = scan_directories_traced(&config.main.scan_dirs, &config, trace)
+        .await
+        .context("Failed to scan directories")?;
+
+    let output = match explain {
+        true => {
+            let mut events = events.lock().map(|e| e.clone()).unwrap_or_default();
+            events.sort_by(|a, b| a.path.cmp(&b.path));
+            format_scan_trace(&events, &outcome)
+        }
+        false => outcome
+            .paths
+            .iter()
+            .map(|path| format!("{}\n", path.display()))
+            .collect(),
+    };
+    write_output(&output, None)
+}
+
+/// Format the traced decisions, one directory per line, and a summary
+fn format_scan_trace(events: &[ScanEvent], outcome: &ScanOutcome) -> String {
+    let mut out = String::new();
+    for event in events {
+        let path = event.path.display().to_string();
+        let _ = match event.decision {
+            ScanDecision::Entered => writeln!(out, "{}  {}", "entered".with(Color::DarkGrey), path),
+            ScanDecision::Repo => writeln!(out, "{}     {}", "repo".with(Color::Green), path),
+            ScanDecision::Skipped(reason) => writeln!(
+                out,
+                "{}  {}  {}",
+                "skipped".with(Color::Yellow),
+                path,
+                format!("({})", reason.label()).with(Color::DarkGrey)
+            ),
+        };
+    }
+    let _ = writeln!(
+        out,
+        "{} repos found, {} directories read{}",
+        outcome.paths.len(),
+        outcome.dirs_visited,
+        match outcome.truncated {
+            true => " (scan timed out)",
+            false => "",
+        }
+    );
+    out
+}
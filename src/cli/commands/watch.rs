@@ -0,0 +1,203 @@
+use anyhow::{Context, Result};
+use crossterm::style::{Color, Stylize};
+use std::io::Write as _;
+use std::path::PathBuf;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::Command;
+use tracing::{debug, warn};
+
+use crate::config::{AppConfig, NotifyBackend};
+use crate::core::{
+    self, RepoInfo, RepoInfoOptions,
+    watch::{Health, HealthTracker, Transition},
+};
+
+/// Title of desktop notifications
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const NOTIFICATION_TITLE: &str = "reponest";
+
+/// Keep checking repositories and announce those that turn dirty or conflicted
+///
+/// The set of repositories is found once at startup. Runs until Ctrl-C.
+pub async fn watch_dirty(config: AppConfig) -> Result<()> {
+    let scan = core::scan_directories(&config.main.scan_dirs, &config)
+        .await
+        .context("Failed to scan directories")?;
+    if scan.truncated {
+        eprintln!(
+            "{}",
+            "Warning: scan timed out, not all repos are watched".with(Color::Yellow)
+        );
+    }
+
+    let options = RepoInfoOptions::from_config(&config);
+    let watch = &config.main.watch;
+    let mut notifier = Notifier::new(watch.notify, config.main.safe_mode);
+    let mut tracker = HealthTracker::default();
+    tracker.update(&load_repos(&scan.paths, options).await?);
+
+    eprintln!(
+        "Watching {} repos every {}, press Ctrl-C to stop",
+        scan.paths.len(),
+        core::duration::format_duration(watch.interval)
+    );
+
+    // Created once, so that Ctrl-C is also seen while repos are being checked
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = tokio::time::sleep(watch.interval) => {}
+        }
+
+        let repos = tokio::select! {
+            _ = &mut ctrl_c => break,
+            repos = load_repos(&scan.paths, options) => repos?,
+        };
+        for transition in tracker.update(&repos) {
+            notifier.notify(&transition);
+        }
+    }
+
+    Ok(())
+}
+
+/// Gather repository info on the blocking thread pool
+async fn load_repos(paths: &[PathBuf], options: RepoInfoOptions) -> Result<Vec<RepoInfo>> {
+    let paths = paths.to_vec();
+    tokio::task::spawn_blocking(move || core::get_repos_info_parallel(&paths, options))
+        .await
+        .context("Failed to gather repository info")
+}
+
+/// Announces transitions with the configured backend
+struct Notifier {
+    backend: NotifyBackend,
+}
+
+impl Notifier {
+    /// Create a notifier, using the bell instead of desktop notifications in safe mode
+    fn new(backend: NotifyBackend, safe_mode: bool) -> Self {
+        let backend = match backend {
+            NotifyBackend::Desktop if safe_mode => {
+                debug!("Safe mode: using the terminal bell instead of desktop notifications");
+                NotifyBackend::Bell
+            }
+            backend => backend,
+        };
+        Self { backend }
+    }
+
+    /// Print a transition and announce it
+    ///
+    /// If a desktop notification cannot be shown, the bell is used from then on.
+    fn notify(&mut self, transition: &Transition) {
+        let message = transition_message(transition);
+        let color = match transition.to {
+            Health::Conflict => Color::Red,
+            _ => Color::Yellow,
+        };
+        println!(
+            "{}  {}",
+            message.as_str().with(color).bold(),
+            transition.path.display().to_string().with(Color::DarkGrey)
+        );
+
+        match self.backend {
+            NotifyBackend::Desktop => {
+                if let Err(e) = send_desktop_notification(&message) {
+                    warn!(
+                        "Desktop notification failed: {}. Using the terminal bell",
+                        e
+                    );
+                    self.backend = NotifyBackend::Bell;
+                    ring_bell();
+                }
+            }
+            NotifyBackend::Bell => ring_bell(),
+            NotifyBackend::Log => {}
+        }
+    }
+}
+
+/// Describe a transition in one line
+fn transition_message(transition: &Transition) -> String {
+    format!(
+        "{} is now {} (was {})",
+        transition.name, transition.to, transition.from
+    )
+}
+
+/// Ring the terminal bell
+fn ring_bell() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+/// Show a desktop notification with the platform's notification command
+fn send_desktop_notification(message: &str) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        let mut command = Command::new("notify-send");
+        command.arg(NOTIFICATION_TITLE).arg(message);
+        command
+    };
+
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification \"{}\" with title \"{}\"",
+            escape(message),
+            escape(NOTIFICATION_TITLE)
+        ));
+        command
+    };
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        let _ = message;
+        return Err("not supported on this platform".to_string());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    match command.output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!(
+            "{:?} exited with {}",
+            command.get_program(),
+            output.status
+        )),
+        Err(e) => Err(format!("could not run {:?}: {}", command.get_program(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transition_message() {
+        let transition = Transition {
+            path: PathBuf::from("/tmp/app"),
+            name: "app".to_string(),
+            from: Health::Clean,
+            to: Health::Dirty,
+        };
+        assert_eq!(
+            transition_message(&transition),
+            "app is now dirty (was clean)"
+        );
+    }
+
+    #[test]
+    fn test_safe_mode_avoids_desktop_notifications() {
+        let notifier = Notifier::new(NotifyBackend::Desktop, true);
+        assert_eq!(notifier.backend, NotifyBackend::Bell);
+        let notifier = Notifier::new(NotifyBackend::Log, true);
+        assert_eq!(notifier.backend, NotifyBackend::Log);
+    }
+}
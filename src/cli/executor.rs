@@ -1,19 +1,118 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 
-use crate::cli::commands;
-use crate::cli::{CliArgs, CliSubCommands};
+use crate::cli::commands::{self, ListOptions};
+use crate::cli::{CliArgs, CliSubCommands, ListStyle, SortKey};
 use crate::config::AppConfig;
+use crate::core::RepoInfo;
 
 /// Execute CLI command based on the subcommand
 pub async fn execute_cli_command(args: &CliArgs, config: AppConfig) -> Result<()> {
     let command = args.command.as_ref().context("No CLI command provided")?;
 
     match command {
-        CliSubCommands::List { detail, json } => {
-            commands::list_repos(config, *detail, *json, args.dirty, args.conflict)
+        CliSubCommands::List { .. } => {
+            commands::list_repos(config, list_options(args, command)?)
                 .await
                 .context("Failed to execute list command")?;
         }
+        CliSubCommands::Diff { old, with } => {
+            commands::diff_repos(&config, old, with.as_deref())
+                .await
+                .context("Failed to execute diff command")?;
+        }
+        CliSubCommands::Scan { explain } => {
+            commands::explain_scan(&config, *explain)
+                .await
+                .context("Failed to execute scan command")?;
+        }
+        CliSubCommands::Branches { json } => {
+            commands::summarize_repo_branches(&config, *json)
+                .await
+                .context("Failed to execute branches command")?;
+        }
+        CliSubCommands::Keys { json } => {
+            commands::print_keys(&config.ui.keybindings, *json)
+                .context("Failed to execute keys command")?;
+        }
     }
     Ok(())
 }
+
+/// Check whether the command hands its results over to the TUI
+pub fn is_interactive(args: &CliArgs) -> bool {
+    matches!(
+        args.command,
+        Some(CliSubCommands::List {
+            interactive: true,
+            ..
+        })
+    )
+}
+
+/// Collect the repositories of `list --interactive`, with the filter flags applied
+pub async fn collect_interactive_repos(
+    args: &CliArgs,
+    config: &AppConfig,
+) -> Result<(Vec<RepoInfo>, Vec<&'static str>)> {
+    let command = args.command.as_ref().context("No CLI command provided")?;
+    let opts = list_options(args, command)?;
+    let repos = commands::collect_repos(config, &opts)
+        .await
+        .context("Failed to collect repositories")?;
+    Ok((repos, opts.active_filters()))
+}
+
+/// Options of the list command from the command line
+fn list_options(args: &CliArgs, command: &CliSubCommands) -> Result<ListOptions> {
+    let CliSubCommands::List {
+        detail,
+        json,
+        compact,
+        tree,
+        paths_only,
+        porcelain,
+        output,
+        no_nested,
+        null,
+        out,
+        stale,
+        sort,
+        sort_edited,
+        profile_timing,
+        ..
+    } = command
+    else {
+        bail!("Not a list command");
+    };
+    Ok(ListOptions {
+        detail: *detail,
+        json: *json,
+        compact: *compact,
+        tree: *tree,
+        paths_only: *paths_only,
+        porcelain: *porcelain,
+        table: *output == Some(ListStyle::Table),
+        grid: *output == Some(ListStyle::Grid),
+        no_nested: *no_nested,
+        null: *null,
+        dirty_filter: args.dirty,
+        conflict_filter: args.conflict,
+        no_remote_filter: args.no_remote,
+        locked_filter: args.locked,
+        detached_filter: args.detached,
+        anomalies_filter: args.anomalies,
+        unpushed_tags_filter: args.unpushed_tags,
+        unmerged_filter: args.unmerged,
+        lfs_locks_filter: args.lfs_locks,
+        assume_unchanged_filter: args.assume_unchanged,
+        tag_filter: args.tag.clone(),
+        redact_json: args.redact,
+        out: out.clone(),
+        stale: *stale,
+        sort: match sort_edited {
+            true => Some(SortKey::Edited),
+            false => *sort,
+        },
+        profile_timing: *profile_timing,
+    })
+}
@@ -1,6 +1,8 @@
 mod args;
 mod commands;
 mod executor;
+mod output;
 
-pub use args::{CliArgs, CliSubCommands};
-pub use executor::execute_cli_command;
+pub use args::{CliArgs, CliSubCommands, ListStyle, LogFormat, SortKey};
+pub use commands::watch_dirty;
+pub use executor::{collect_interactive_repos, execute_cli_command, is_interactive};
@@ -0,0 +1,120 @@
+//! This module writes command output to stdout or to a file.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// Write output to the given file, or to stdout if no file is given
+///
+/// Files get plain text, without terminal styling.
+pub fn write_output(content: &str, out: Option<&Path>) -> Result<()> {
+    match out {
+        Some(path) => write_atomic(path, &strip_ansi_codes(content)),
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            stdout
+                .write_all(content.as_bytes())
+                .and_then(|()| stdout.flush())
+                .context("Failed to write to stdout")
+        }
+    }
+}
+
+/// Write a file atomically, so readers never see partial content
+///
+/// The content is written to a temporary file next to the target, which is
+/// then renamed over it. Missing parent directories are created.
+fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create directory {:?}", dir))?;
+
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("Invalid output path {:?}", path))?;
+    let tmp_path = dir.join(format!(
+        ".{}.tmp-{}",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = fs::File::create(&tmp_path)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp_path, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result.with_context(|| format!("Failed to write output to {:?}", path))
+}
+
+/// Remove ANSI escape sequences (colors, bold, ...) from text
+pub(crate) fn strip_ansi_codes(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip "ESC [" parameters up to the final byte in '@'..='~'
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        plain.push(c);
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_json() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("reports").join("status.json");
+
+        write_atomic(&path, r#"[{"name":"repo"}]"#).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(parsed[0]["name"], "repo");
+
+        // Only the target file is left behind
+        let entries: Vec<_> = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec!["status.json"]);
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_existing_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("status.txt");
+        fs::write(&path, "old content that is longer").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_strip_ansi_codes() {
+        use crossterm::style::{Color, Stylize};
+
+        let styled = format!("{}  {}", "repo".with(Color::Cyan).bold(), "clean".green());
+        assert_eq!(strip_ansi_codes(&styled), "repo  clean");
+        assert_eq!(strip_ansi_codes("├── plain"), "├── plain");
+    }
+}
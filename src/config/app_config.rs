@@ -2,12 +2,23 @@
 
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::cli::CliArgs;
+use crate::core::exclude::ExcludeMatcher;
+use crate::core::grouping::GroupBy;
+use crate::core::heatmap;
+use crate::core::open::DEFAULT_TMUX_COMMAND;
+use crate::core::status::StatusRule;
 
-use super::{KeyBindings, Theme};
+use super::overrides::set_config_value;
+use super::theme::register_config_themes;
+use super::{
+    ColorSupport, DetailConfig, HealthConfig, HighlightMode, KeyBindings, LocalConfig,
+    StatusPrefixes, Theme, WatchConfig,
+};
 
 /// Non-hidden directories to exclude from scanning
 /// We ignore hidden directories (starting with .) by default in the scanner
@@ -42,6 +53,7 @@ const EXCLUDE_DIR_PATTERN: &[&str] = &[
 pub struct AppConfig {
     pub main: MainConfig,
     pub ui: UIConfig,
+    pub health: HealthConfig,
     pub internal: InternalConfig,
 }
 
@@ -52,6 +64,186 @@ pub struct MainConfig {
     pub scan_dirs: Vec<String>,
     /// Maximum scan depth (0 means unlimited)
     pub max_depth: usize,
+    /// Only check the scan roots and their direct subdirectories, without recursing
+    #[serde(default)]
+    pub flat: bool,
+    /// Disable external commands and write operations
+    #[serde(default)]
+    pub safe_mode: bool,
+    /// Read conflicted files to count conflict hunks (slower)
+    #[serde(default)]
+    pub conflict_hunks: bool,
+    /// Diff tracked files against the index to count unstaged hunks (slower)
+    #[serde(default)]
+    pub unstaged_hunks: bool,
+    /// Count distinct authors over the last commits (walks history, slower)
+    #[serde(default)]
+    pub count_contributors: bool,
+    /// Branch that ahead/behind counts are relative to
+    #[serde(default)]
+    pub compare_against: CompareAgainst,
+    /// Leave submodules out of the repository list
+    #[serde(default)]
+    pub hide_submodules: bool,
+    /// Load the working tree status of a repo only when it is selected
+    #[serde(default)]
+    pub lazy_info: bool,
+    /// Repos whose last commit is older than this are shown as stale
+    #[serde(default, with = "crate::core::duration::option")]
+    pub stale_threshold: Option<Duration>,
+    /// Look for ignored files and tracked files matched by .gitignore (slower)
+    #[serde(default)]
+    pub scan_anomalies: bool,
+    /// Count local tags not pushed to any remote (enumerates all tags)
+    #[serde(default)]
+    pub count_unpushed_tags: bool,
+    /// Count local branches merged into neither their upstream nor the default branch
+    #[serde(default)]
+    pub count_unmerged_branches: bool,
+    /// Count Git LFS locks held locally (runs git-lfs in repos using LFS)
+    #[serde(default)]
+    pub count_lfs_locks: bool,
+    /// Count files flagged assume-unchanged or skip-worktree (reads the whole index)
+    #[serde(default)]
+    pub count_assume_unchanged: bool,
+    /// How the last modification time of the working tree is found
+    #[serde(default)]
+    pub workdir_mtime: WorkdirMtime,
+    /// Do not descend into directories with more entries than this, unless they are repos
+    #[serde(default, alias = "auto_exclude_entry_threshold")]
+    pub skip_dirs_with_more_than: Option<usize>,
+    /// Directory names never skipped for their number of entries
+    #[serde(default)]
+    pub auto_exclude_keep: Vec<String>,
+    /// Categories of uncommitted changes that make a repo dirty
+    #[serde(default = "default_dirty_includes")]
+    pub dirty_includes: Vec<ChangeCategory>,
+    /// Gitignore-style patterns of directories to skip, relative to each scan root
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Patterns of the only directory names to descend into (empty means all)
+    #[serde(default)]
+    pub include_dirs: Vec<String>,
+    /// Descend into directories reached through symbolic links
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    /// TOML file mapping repo names or paths to tags
+    #[serde(default)]
+    pub tags_file: Option<String>,
+    /// Directory repos are moved to by the archive action
+    #[serde(default)]
+    pub archive_dir: Option<String>,
+    /// File the TUI saves scan results to, and shows them from at startup
+    #[serde(default)]
+    pub results_cache: Option<String>,
+    /// Repos the TUI gathers information of at once while loading (default: CPU threads)
+    #[serde(default)]
+    pub jobs: Option<usize>,
+    /// Command opening a repo inside tmux, `{path}` is replaced by the repo path
+    #[serde(default = "default_tmux_command")]
+    pub tmux_command: String,
+    /// Command opening a terminal at a repo, `{path}` is replaced by the repo path
+    /// (None means the platform's default terminal)
+    #[serde(default)]
+    pub terminal_command: Option<String>,
+    /// Watch mode settings
+    #[serde(default)]
+    pub watch: WatchConfig,
+}
+
+/// Branch that ahead/behind counts are relative to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareAgainst {
+    /// Remote-tracking branch of the current branch
+    #[default]
+    Upstream,
+    /// Remote-tracking ref of the remote's default branch (e.g. origin/main)
+    #[value(name = "default_branch")]
+    DefaultBranch,
+}
+
+/// How the last modification time of a working tree is found
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkdirMtime {
+    /// Not computed
+    #[default]
+    Off,
+    /// Modification time of the repository directory (cheap approximation)
+    Dir,
+    /// Latest modification time of tracked and changed files (reads every file's metadata)
+    Files,
+}
+
+/// Category of uncommitted changes in a working tree
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeCategory {
+    /// Changes added to the index
+    Staged,
+    /// Changes to tracked files not added to the index
+    Modified,
+    /// Files not tracked by git nor ignored
+    Untracked,
+    /// Files with merge conflicts
+    Conflicts,
+}
+
+impl ChangeCategory {
+    /// All categories
+    pub const ALL: [Self; 4] = [
+        Self::Staged,
+        Self::Modified,
+        Self::Untracked,
+        Self::Conflicts,
+    ];
+}
+
+/// Which directory `--from-repo-root` scans, relative to the enclosing repository
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RepoRootScope {
+    /// Parent directory of the repository, to scan its sibling projects
+    #[default]
+    Parent,
+    /// Top-level directory of the repository itself
+    Root,
+}
+
+/// Optional column of the compact list output
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListColumn {
+    /// Remote the current branch tracks, or `-` if it has no upstream
+    TrackingRemote,
+    /// Number of uncommitted changes (staged, modified, untracked and conflicted files)
+    Changes,
+    /// Initials of the last commit's author, colored by their email
+    Author,
+    /// Health score and its letter grade (e.g. `62 C`)
+    Health,
+}
+
+/// Layout of the TUI repository list
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ListLayout {
+    /// One repo per row, next to the details of the selected one
+    #[default]
+    List,
+    /// Dense grid of repo names over the full width, colored by status
+    Grid,
+}
+
+/// What repo names are colored by
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorBy {
+    /// The repo status (dirty, clean, in sync, ...)
+    #[default]
+    Status,
+    /// Age of the last commit, from fresh (green) to stale (red)
+    Age,
 }
 
 /// UI section of the configuration
@@ -59,6 +251,51 @@ pub struct MainConfig {
 pub struct UIConfig {
     /// TUI theme
     pub theme: Theme,
+    /// Highlight style of the selected row
+    #[serde(default)]
+    pub highlight: HighlightMode,
+    /// Colors the terminal supports; RGB theme colors are mapped down to them
+    #[serde(default)]
+    pub color_support: ColorSupport,
+    /// Show a bar of repo counts by status above the TUI lists
+    #[serde(default)]
+    pub health_bar: bool,
+    /// Show totals of pending work across all repos above the TUI keyhints
+    #[serde(default)]
+    pub totals_bar: bool,
+    /// Group repos of the TUI list under collapsible headers
+    #[serde(default)]
+    pub group_by: GroupBy,
+    /// Layout of the TUI repo list
+    #[serde(default)]
+    pub list_layout: ListLayout,
+    /// Color repo names by status or by age of the last commit
+    #[serde(default)]
+    pub color_by: ColorBy,
+    /// Repos (by name or path) always listed first, in this order
+    #[serde(default)]
+    pub pinned: Vec<String>,
+    /// Mask the host of remote URLs in detail views (e.g. for screenshots)
+    #[serde(default)]
+    pub redact_remotes: bool,
+    /// Use ASCII characters instead of box-drawing ones and arrows
+    #[serde(default)]
+    pub ascii_symbols: bool,
+    /// Symbol before the selected row of TUI lists, instead of the default arrow
+    #[serde(default)]
+    pub selection_symbol: Option<String>,
+    /// Custom status rules, tried in order before the built-in ones
+    #[serde(default)]
+    pub status_rules: Vec<StatusRule>,
+    /// Text shown before repo names in the compact list and the TUI list, by status
+    #[serde(default)]
+    pub status_prefixes: StatusPrefixes,
+    /// Optional columns of the compact `list` output, shown after the branch
+    #[serde(default)]
+    pub list_columns: Vec<ListColumn>,
+    /// Detail view layout
+    #[serde(default)]
+    pub detail: DetailConfig,
     /// Key bindings
     #[serde(default)]
     pub keybindings: KeyBindings,
@@ -69,56 +306,210 @@ pub struct UIConfig {
 pub struct InternalConfig {
     /// Directories to exclude from scanning
     pub exclude_dirs: Vec<String>,
-    /// UI refresh interval in milliseconds
-    pub refresh_interval: u64,
+    /// Interval in milliseconds at which background tasks poll for scan results
+    ///
+    /// Input handling does not wait on this, see `tui::input::INPUT_POLL_TIMEOUT`.
+    pub scan_poll_interval: u64,
     /// Path to file where current working directory should be written on exit
     pub cwd_file: Option<String>,
+    /// Maximum wall-clock time for scanning directories (None means unlimited)
+    pub scan_timeout: Option<Duration>,
+    /// File where scan progress is saved (None means no checkpointing)
+    pub scan_checkpoint: Option<PathBuf>,
+    /// Skip subtrees the checkpoint records as fully scanned
+    pub resume_scan: bool,
+    /// Show only repos carrying this tag in the TUI
+    pub tag_filter: Option<String>,
+    /// The command line or a local config changed which repos the scan finds
+    ///
+    /// Set for scan directories, depth, exclude patterns and the like that do
+    /// not come from the user config.
+    pub scan_scope_overridden: bool,
+}
+
+/// Scan directories without a home directory (the current directory)
+const NO_HOME_SCAN_DIR: &str = ".";
+
+/// Default scan directories: the home directory, or the current directory without one
+fn default_scan_dirs(home: Option<&Path>) -> Vec<String> {
+    match home {
+        Some(home) => vec![home.to_string_lossy().to_string()],
+        None => {
+            debug!(
+                "Could not determine home directory, using current directory as default scan directory"
+            );
+            vec![NO_HOME_SCAN_DIR.to_string()]
+        }
+    }
+}
+
+fn default_dirty_includes() -> Vec<ChangeCategory> {
+    ChangeCategory::ALL.to_vec()
+}
+
+fn default_tmux_command() -> String {
+    DEFAULT_TMUX_COMMAND.to_string()
 }
 
 impl Default for MainConfig {
     fn default() -> Self {
         Self {
-            scan_dirs: vec![dirs::home_dir()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| {
-                    debug!("Could not determine home directory, using current directory as default scan directory");
-                    ".".to_string()
-                })],
+            scan_dirs: default_scan_dirs(dirs::home_dir().as_deref()),
             max_depth: 5,
+            flat: false,
+            safe_mode: false,
+            conflict_hunks: false,
+            unstaged_hunks: false,
+            count_contributors: false,
+            compare_against: CompareAgainst::default(),
+            hide_submodules: false,
+            lazy_info: false,
+            stale_threshold: None,
+            scan_anomalies: false,
+            count_unpushed_tags: false,
+            count_unmerged_branches: false,
+            count_lfs_locks: false,
+            count_assume_unchanged: false,
+            workdir_mtime: WorkdirMtime::default(),
+            skip_dirs_with_more_than: None,
+            auto_exclude_keep: Vec::new(),
+            dirty_includes: default_dirty_includes(),
+            exclude: Vec::new(),
+            include_dirs: Vec::new(),
+            follow_symlinks: false,
+            tags_file: None,
+            archive_dir: None,
+            results_cache: None,
+            jobs: None,
+            tmux_command: default_tmux_command(),
+            terminal_command: None,
+            watch: WatchConfig::default(),
         }
     }
 }
 
+impl UIConfig {
+    /// Symbol before the selected row of TUI lists
+    ///
+    /// The configured `selection_symbol` if any, or else an arrow, drawn with
+    /// ASCII characters under `ascii_symbols`.
+    pub fn highlight_symbol(&self) -> &str {
+        match (&self.selection_symbol, self.ascii_symbols) {
+            (Some(symbol), _) => symbol,
+            (None, true) => "> ",
+            (None, false) => "▶ ",
+        }
+    }
+}
+
+impl MainConfig {
+    /// Number of repos processed at once, at least one
+    pub fn jobs_limit(&self) -> usize {
+        self.jobs.unwrap_or_else(rayon::current_num_threads).max(1)
+    }
+
+    /// Age at which repo names are fully stale under `color_by = "age"`
+    pub fn heatmap_horizon(&self) -> Duration {
+        self.stale_threshold.unwrap_or(heatmap::DEFAULT_HORIZON)
+    }
+}
+
 impl Default for InternalConfig {
     fn default() -> Self {
         Self {
             exclude_dirs: EXCLUDE_DIR_PATTERN.iter().map(|s| s.to_string()).collect(),
-            refresh_interval: 100,
+            scan_poll_interval: 100,
             cwd_file: None,
+            scan_timeout: None,
+            scan_checkpoint: None,
+            resume_scan: false,
+            tag_filter: None,
+            scan_scope_overridden: false,
         }
     }
 }
 
+impl AppConfig {
+    /// File of the results cache, if configured and the scan finds all repos
+    ///
+    /// A scan whose scope was changed on the command line or by a local config,
+    /// or one filtered by tag, finds other repos than the configured scan, which
+    /// must not replace the full cache.
+    pub fn results_cache_file(&self) -> Option<&Path> {
+        let partial = self.internal.scan_scope_overridden || self.internal.tag_filter.is_some();
+        match partial {
+            true => None,
+            false => self.main.results_cache.as_deref().map(Path::new),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Compile the exclude and include patterns for matching directory names
+    pub fn exclude_matcher(&self) -> ExcludeMatcher {
+        ExcludeMatcher::new(&self.internal.exclude_dirs).with_includes(&self.main.include_dirs)
+    }
+}
+
 #[derive(Serialize, Deserialize, Default)]
 #[serde(default)]
 struct AppConfigUserFields {
     main: MainConfig,
     ui: UIConfig,
+    health: HealthConfig,
 }
 
 impl AppConfig {
     /// Create app configuration with layered priority system:
-    /// CLI args (highest) -> Config file -> Default values (lowest)
-    pub fn from_layers(cli_args: &CliArgs) -> Self {
+    /// `--set` overrides (highest) -> CLI args -> Local project config -> User config file
+    /// -> Default values (lowest)
+    ///
+    /// Fails if `--from-repo-root` is given outside of a git repository, if
+    /// a `--set` override names an unknown setting or has an invalid value, if
+    /// a `main.exclude` pattern is invalid, or if a path starts with `~` and the
+    /// home directory is unknown.
+    pub fn from_layers(cli_args: &CliArgs) -> Result<Self, String> {
         let mut config = Self::default();
         if let Some(file_config) = Self::load_from_file(cli_args.config.as_deref()) {
-            config.merge_file_config(file_config);
+            config.merge_file_config(file_config)?;
+        }
+        if let Some(local_config) =
+            Self::local_config_start(cli_args)?.and_then(|start| LocalConfig::discover(&start))
+        {
+            local_config.merge_into(&mut config);
         }
-        config.apply_cli_overrides(cli_args);
+        config.apply_cli_overrides(cli_args)?;
+
+        if let Some(scope) = cli_args.from_repo_root {
+            let cwd = std::env::current_dir()
+                .map_err(|e| format!("Failed to get current directory: {}", e))?;
+            let root = scan_root_from_repo(&cwd, scope)?;
+            debug!("CLI override: scan_dirs = [{:?}] (from repo root)", root);
+            config.main.scan_dirs = vec![root.to_string_lossy().to_string()];
+            config.internal.scan_scope_overridden = true;
+        }
+
+        for (key, value) in &cli_args.set {
+            debug!("CLI override: {} = {}", key, value);
+            set_config_value(&mut config, key, value)?;
+        }
+
+        // Checked here, since scans compile the patterns again for each root
+        ExcludeMatcher::new(&[]).with_gitignore(Path::new("/"), &config.main.exclude)?;
 
         debug!("Final scan directories: {:?}", config.main.scan_dirs);
 
-        config
+        Ok(config)
+    }
+
+    /// Directory where the search for a local project config starts
+    ///
+    /// This is the first scan root given on the command line, or else the current directory.
+    fn local_config_start(args: &CliArgs) -> Result<Option<PathBuf>, String> {
+        match args.path.first() {
+            Some(path) => Ok(Some(PathBuf::from(expand_tilde_in_path(path)?))),
+            None => Ok(std::env::current_dir().ok()),
+        }
     }
 
     /// Get list of paths to search for configuration file (in priority order)
@@ -140,19 +531,29 @@ impl AppConfig {
 
         // Check for CLI --config argument first (highest priority)
         if let Some(config_path) = cli_config_path {
-            let expanded_path = PathBuf::from(expand_tilde_in_path(config_path));
-            debug!("Using config path from CLI argument: {:?}", expanded_path);
-            paths.push(expanded_path);
+            match expand_tilde_in_path(config_path) {
+                Ok(expanded) => {
+                    let expanded_path = PathBuf::from(expanded);
+                    debug!("Using config path from CLI argument: {:?}", expanded_path);
+                    paths.push(expanded_path);
+                }
+                Err(e) => warn!("Ignoring --config: {}", e),
+            }
         }
 
         // Check for REPONEST_CONFIG environment variable
         if let Ok(config_path) = std::env::var("REPONEST_CONFIG") {
-            let expanded_path = PathBuf::from(expand_tilde_in_path(&config_path));
-            debug!(
-                "Using config path from REPONEST_CONFIG: {:?}",
-                expanded_path
-            );
-            paths.push(expanded_path);
+            match expand_tilde_in_path(&config_path) {
+                Ok(expanded) => {
+                    let expanded_path = PathBuf::from(expanded);
+                    debug!(
+                        "Using config path from REPONEST_CONFIG: {:?}",
+                        expanded_path
+                    );
+                    paths.push(expanded_path);
+                }
+                Err(e) => warn!("Ignoring REPONEST_CONFIG: {}", e),
+            }
         }
 
         if let Some(dir) = dirs::config_dir() {
@@ -178,7 +579,10 @@ impl AppConfig {
             if config_path.exists() {
                 debug!("Loading config from: {:?}", config_path);
                 match fs::read_to_string(config_path) {
-                    Ok(content) => match toml::from_str::<AppConfigUserFields>(&content) {
+                    // Custom themes are registered first, so that ui.theme can select them
+                    Ok(content) => match register_config_themes(&content)
+                        .and_then(|_| toml::from_str(&content).map_err(|e| e.to_string()))
+                    {
                         Ok(config) => {
                             debug!("Successfully loaded config from file");
                             return Some(config);
@@ -207,29 +611,147 @@ impl AppConfig {
     }
 
     /// Merge user configuration loaded from file
-    fn merge_file_config(&mut self, mut file_config: AppConfigUserFields) {
+    ///
+    /// Fails if a path starts with `~` and the home directory is unknown.
+    fn merge_file_config(&mut self, mut file_config: AppConfigUserFields) -> Result<(), String> {
         // Expand ~ in scan_dirs paths
         file_config.main.scan_dirs = file_config
             .main
             .scan_dirs
             .iter()
             .map(|p| expand_tilde_in_path(p))
-            .collect();
+            .collect::<Result<_, _>>()?;
+        file_config.main.tags_file = file_config
+            .main
+            .tags_file
+            .as_deref()
+            .map(expand_tilde_in_path)
+            .transpose()?;
+        file_config.main.archive_dir = file_config
+            .main
+            .archive_dir
+            .as_deref()
+            .map(expand_tilde_in_path)
+            .transpose()?;
+        file_config.main.results_cache = file_config
+            .main
+            .results_cache
+            .as_deref()
+            .map(expand_tilde_in_path)
+            .transpose()?;
+        file_config.ui.pinned = file_config
+            .ui
+            .pinned
+            .iter()
+            .map(|p| expand_tilde_in_path(p))
+            .collect::<Result<_, _>>()?;
 
         self.main = file_config.main;
         self.ui = file_config.ui;
+        self.health = file_config.health;
+        Ok(())
     }
 
     /// Apply CLI argument overrides to configuration
-    fn apply_cli_overrides(&mut self, args: &CliArgs) {
-        if let Some(ref path) = args.path {
-            debug!("CLI override: scan_dirs = [{}]", path);
-            self.main.scan_dirs = vec![path.clone()];
+    fn apply_cli_overrides(&mut self, args: &CliArgs) -> Result<(), String> {
+        if !args.path.is_empty() {
+            debug!("CLI override: scan_dirs = {:?}", args.path);
+            self.main.scan_dirs = args.path.clone();
+            self.internal.scan_scope_overridden = true;
         }
 
         if let Some(depth) = args.max_depth {
             debug!("CLI override: max_depth = {}", depth);
             self.main.max_depth = depth;
+            self.internal.scan_scope_overridden = true;
+        }
+
+        if args.flat {
+            debug!("CLI override: flat = true");
+            self.main.flat = true;
+            self.internal.scan_scope_overridden = true;
+        }
+
+        if args.safe {
+            debug!("CLI override: safe_mode = true");
+            self.main.safe_mode = true;
+        }
+
+        if args.conflict_hunks {
+            debug!("CLI override: conflict_hunks = true");
+            self.main.conflict_hunks = true;
+        }
+        if let Some(jobs) = args.jobs {
+            debug!("CLI override: jobs = {}", jobs);
+            self.main.jobs = Some(jobs);
+        }
+        if args.unstaged_hunks {
+            debug!("CLI override: unstaged_hunks = true");
+            self.main.unstaged_hunks = true;
+        }
+        if args.contributors {
+            debug!("CLI override: count_contributors = true");
+            self.main.count_contributors = true;
+        }
+
+        if let Some(compare_against) = args.compare_against {
+            debug!("CLI override: compare_against = {:?}", compare_against);
+            self.main.compare_against = compare_against;
+        }
+        if let Some(mode) = args.workdir_mtime {
+            debug!("CLI override: workdir_mtime = {:?}", mode);
+            self.main.workdir_mtime = mode;
+        }
+
+        if let Some(ref tags_file) = args.tags_file {
+            debug!("CLI override: tags_file = {}", tags_file);
+            self.main.tags_file = Some(expand_tilde_in_path(tags_file)?);
+        }
+
+        if let Some(ref tag) = args.tag {
+            debug!("CLI override: tag_filter = {}", tag);
+            self.internal.tag_filter = Some(tag.clone());
+        }
+
+        if args.anomalies {
+            debug!("CLI override: scan_anomalies = true");
+            self.main.scan_anomalies = true;
+        }
+        if args.unpushed_tags {
+            debug!("CLI override: count_unpushed_tags = true");
+            self.main.count_unpushed_tags = true;
+        }
+        if args.unmerged {
+            debug!("CLI override: count_unmerged_branches = true");
+            self.main.count_unmerged_branches = true;
+        }
+        if args.lfs_locks {
+            debug!("CLI override: count_lfs_locks = true");
+            self.main.count_lfs_locks = true;
+        }
+        if args.assume_unchanged {
+            debug!("CLI override: count_assume_unchanged = true");
+            self.main.count_assume_unchanged = true;
+        }
+
+        if let Some(notify) = args.notify {
+            debug!("CLI override: watch.notify = {:?}", notify);
+            self.main.watch.notify = notify;
+        }
+
+        if args.lazy_info {
+            debug!("CLI override: lazy_info = true");
+            self.main.lazy_info = true;
+        }
+
+        if let Some(color_by) = args.color_by {
+            debug!("CLI override: color_by = {:?}", color_by);
+            self.ui.color_by = color_by;
+        }
+
+        if args.redact {
+            debug!("CLI override: redact_remotes = true");
+            self.ui.redact_remotes = true;
         }
 
         if let Some(ref theme_str) = args.theme {
@@ -244,37 +766,93 @@ impl AppConfig {
             }
         }
 
+        if let Some(timeout) = args.timeout {
+            debug!("CLI override: scan_timeout = {:?}", timeout);
+            self.internal.scan_timeout = Some(timeout);
+        }
+
+        if let Some(ref checkpoint) = args.checkpoint {
+            debug!("CLI override: scan_checkpoint = {:?}", checkpoint);
+            self.internal.scan_checkpoint = Some(checkpoint.clone());
+        }
+
+        if args.resume {
+            debug!("CLI override: resume_scan = true");
+            self.internal.resume_scan = true;
+        }
+
         if let Some(ref cwd_file) = args.cwd_file {
             debug!("CLI override: cwd_file = {}", cwd_file);
             self.internal.cwd_file = Some(cwd_file.clone());
         }
+        Ok(())
     }
 
     /// Print user-configurable fields in JSON format
+    ///
+    /// A note on stderr tells when scan_dirs fell back to the current directory
+    /// because the home directory is unknown.
     pub fn print(&self) {
+        if let Some(note) = self.home_fallback_note(dirs::home_dir().as_deref()) {
+            eprintln!("{}", note);
+        }
         let user_fields = AppConfigUserFields {
             main: self.main.clone(),
             ui: self.ui.clone(),
+            health: self.health.clone(),
         };
         match serde_json::to_string_pretty(&user_fields) {
             Ok(json) => println!("{}", json),
             Err(e) => eprintln!("Failed to serialize configuration: {}", e),
         }
     }
+
+    /// Note shown when scan_dirs is the fallback for an unknown home directory
+    fn home_fallback_note(&self, home: Option<&Path>) -> Option<String> {
+        (home.is_none() && self.main.scan_dirs == [NO_HOME_SCAN_DIR]).then(|| {
+            format!(
+                "Note: home directory is unknown (is $HOME set?), scan_dirs defaults to the current directory \"{}\"",
+                NO_HOME_SCAN_DIR
+            )
+        })
+    }
+}
+
+/// Derive a scan root from the repository enclosing `start`
+fn scan_root_from_repo(start: &Path, scope: RepoRootScope) -> Result<PathBuf, String> {
+    let repo = git2::Repository::discover(start).map_err(|_| {
+        format!(
+            "--from-repo-root: {} is not inside a git repository",
+            start.display()
+        )
+    })?;
+    // Bare repositories have no working directory, the repository is the root
+    let root: PathBuf = repo.workdir().unwrap_or(repo.path()).components().collect();
+    match scope {
+        RepoRootScope::Root => Ok(root),
+        RepoRootScope::Parent => Ok(root.parent().map(Path::to_path_buf).unwrap_or(root)),
+    }
 }
 
 /// Expand ~ in path to home directory
-fn expand_tilde_in_path(path: &str) -> String {
-    if path.starts_with("~/") {
-        if let Some(home) = dirs::home_dir() {
-            return path.replacen("~", &home.to_string_lossy(), 1);
-        }
-    } else if path == "~"
-        && let Some(home) = dirs::home_dir()
-    {
-        return home.to_string_lossy().to_string();
+///
+/// Fails if the path starts with `~` and the home directory is unknown.
+pub(crate) fn expand_tilde_in_path(path: &str) -> Result<String, String> {
+    expand_tilde_with_home(path, dirs::home_dir().as_deref())
+}
+
+/// Expand ~ in path to `home`
+fn expand_tilde_with_home(path: &str, home: Option<&Path>) -> Result<String, String> {
+    if path != "~" && !path.starts_with("~/") {
+        return Ok(path.to_string());
+    }
+    match home {
+        Some(home) => Ok(path.replacen('~', &home.to_string_lossy(), 1)),
+        None => Err(format!(
+            "Cannot expand '~' in \"{}\": home directory is unknown (is $HOME set?)",
+            path
+        )),
     }
-    path.to_string()
 }
 
 #[cfg(test)]
@@ -288,19 +866,46 @@ mod tests {
         let home_str = home.to_string_lossy();
 
         // Test ~/path
-        let result = expand_tilde_in_path("~/test/path");
+        let result = expand_tilde_in_path("~/test/path").unwrap();
         assert!(result.starts_with(&*home_str));
         assert!(result.ends_with("test/path"));
 
         // Test ~
-        let result = expand_tilde_in_path("~");
+        let result = expand_tilde_in_path("~").unwrap();
         assert_eq!(result, home_str);
 
         // Test no tilde
-        let result = expand_tilde_in_path("/absolute/path");
+        let result = expand_tilde_in_path("/absolute/path").unwrap();
         assert_eq!(result, "/absolute/path");
     }
 
+    #[test]
+    fn test_no_home_directory() {
+        // dirs::home_dir() falls back to the passwd database when $HOME is
+        // unset, so a missing home directory is passed in explicitly
+        let err = expand_tilde_with_home("~/code", None).unwrap_err();
+        assert!(err.contains("\"~/code\""), "{}", err);
+        assert!(err.contains("home directory is unknown"), "{}", err);
+        assert!(expand_tilde_with_home("~", None).is_err());
+        assert_eq!(
+            expand_tilde_with_home("/abs/~/path", None).unwrap(),
+            "/abs/~/path"
+        );
+
+        assert_eq!(default_scan_dirs(None), vec!["."]);
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = default_scan_dirs(None);
+        assert!(
+            config
+                .home_fallback_note(None)
+                .unwrap()
+                .contains("current directory")
+        );
+        assert_eq!(config.home_fallback_note(Some(Path::new("/home/me"))), None);
+        config.main.scan_dirs = vec!["/work".to_string()];
+        assert_eq!(config.home_fallback_note(None), None);
+    }
+
     #[test]
     fn test_cli_config_priority() {
         // Use platform-appropriate paths for testing
@@ -413,6 +1018,176 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_overrides_local_config() {
+        use clap::Parser;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join(crate::config::LOCAL_CONFIG_FILE),
+            "[main]\nmax_depth = 2\nexclude_dirs = [\"vendor\"]\n",
+        )
+        .unwrap();
+        let root_str = root.to_str().unwrap();
+
+        let args = CliArgs::parse_from(["reponest", root_str]);
+        let config = AppConfig::from_layers(&args).unwrap();
+        assert_eq!(config.main.max_depth, 2);
+        assert!(config.internal.exclude_dirs.contains(&"vendor".to_string()));
+
+        let args = CliArgs::parse_from(["reponest", "--max-depth", "9", root_str]);
+        let config = AppConfig::from_layers(&args).unwrap();
+        assert_eq!(config.main.max_depth, 9);
+        assert_eq!(config.main.scan_dirs, vec![root_str.to_string()]);
+    }
+
+    #[test]
+    fn test_config_file_selects_custom_theme() {
+        use clap::Parser;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = temp_dir.path().join("config.toml");
+        fs::write(
+            &file,
+            "[ui]\ntheme = \"test-ocean\"\n\n[themes.test-ocean]\nrepo_name = \"#0080ff\"\n",
+        )
+        .unwrap();
+
+        let args = CliArgs::parse_from(["reponest", "--config", file.to_str().unwrap()]);
+        let config = AppConfig::from_layers(&args).unwrap();
+        assert_eq!(config.ui.theme.name(), "test-ocean");
+        assert_eq!(
+            config.ui.theme.colors().repo_name,
+            ratatui::style::Color::Rgb(0, 128, 255)
+        );
+    }
+
+    #[test]
+    fn test_set_overrides_are_applied_last() {
+        use clap::Parser;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join(crate::config::LOCAL_CONFIG_FILE),
+            "[main]\nmax_depth = 2\n",
+        )
+        .unwrap();
+        let root_str = root.to_str().unwrap();
+
+        let args = CliArgs::parse_from([
+            "reponest",
+            "list",
+            "--max-depth",
+            "9",
+            "--set",
+            "main.max_depth=3",
+            "--set",
+            "ui.theme=dark",
+            root_str,
+        ]);
+        let config = AppConfig::from_layers(&args).unwrap();
+        assert_eq!(config.main.max_depth, 3);
+        assert_eq!(config.ui.theme.name(), "dark");
+
+        let args = CliArgs::parse_from(["reponest", "--set", "main.depth=3", root_str]);
+        let err = AppConfig::from_layers(&args).unwrap_err();
+        assert!(err.contains("Unknown setting 'main.depth'"), "{}", err);
+
+        let args = CliArgs::parse_from(["reponest", "--set", "main.exclude=[\"{a\"]", root_str]);
+        let err = AppConfig::from_layers(&args).unwrap_err();
+        assert!(err.contains("Invalid exclude pattern"), "{}", err);
+        assert!(CliArgs::try_parse_from(["reponest", "--set", "main.max_depth"]).is_err());
+    }
+
+    #[test]
+    fn test_positional_paths_override_scan_dirs() {
+        use clap::Parser;
+
+        let mut config = AppConfig::default();
+        let args = CliArgs::parse_from(["reponest", "list", "/work", "/personal"]);
+        config.apply_cli_overrides(&args).unwrap();
+        assert_eq!(config.main.scan_dirs, vec!["/work", "/personal"]);
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec!["/from/config".to_string()];
+        let args = CliArgs::parse_from(["reponest", "list"]);
+        config.apply_cli_overrides(&args).unwrap();
+        assert_eq!(config.main.scan_dirs, vec!["/from/config"]);
+
+        let args = CliArgs::parse_from(["reponest", "/single"]);
+        config.apply_cli_overrides(&args).unwrap();
+        assert_eq!(config.main.scan_dirs, vec!["/single"]);
+    }
+
+    #[test]
+    fn test_results_cache_only_for_full_scans() {
+        use clap::Parser;
+
+        let mut config = AppConfig::default();
+        config.main.results_cache = Some("/cache/results.json".to_string());
+        assert_eq!(
+            config.results_cache_file(),
+            Some(Path::new("/cache/results.json"))
+        );
+
+        let mut tagged = config.clone();
+        tagged.internal.tag_filter = Some("work".to_string());
+        assert_eq!(tagged.results_cache_file(), None);
+
+        for args in [
+            vec!["reponest", "/single"],
+            vec!["reponest", "--max-depth", "2"],
+            vec!["reponest", "--flat"],
+        ] {
+            let mut overridden = config.clone();
+            let args = CliArgs::parse_from(args);
+            overridden.apply_cli_overrides(&args).unwrap();
+            assert_eq!(overridden.results_cache_file(), None);
+        }
+
+        for (key, value) in [
+            ("main.max_depth", "2"),
+            ("main.hide_submodules", "true"),
+            ("main.exclude", "build"),
+            ("main.include_dirs", "app-*"),
+        ] {
+            let mut overridden = config.clone();
+            set_config_value(&mut overridden, key, value).unwrap();
+            assert_eq!(overridden.results_cache_file(), None, "{}", key);
+        }
+
+        // Settings that do not change which repos are found keep the cache
+        set_config_value(&mut config, "main.safe_mode", "true").unwrap();
+        assert!(config.results_cache_file().is_some());
+    }
+
+    #[test]
+    fn test_scan_root_from_repo() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join("workspace").join("app");
+        git2::Repository::init(&repo_path).unwrap();
+        let nested = repo_path.join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+
+        let root = scan_root_from_repo(&nested, RepoRootScope::Root).unwrap();
+        assert_eq!(
+            root.canonicalize().unwrap(),
+            repo_path.canonicalize().unwrap()
+        );
+        let parent = scan_root_from_repo(&nested, RepoRootScope::Parent).unwrap();
+        assert_eq!(
+            parent.canonicalize().unwrap(),
+            temp_dir.path().join("workspace").canonicalize().unwrap()
+        );
+
+        let outside = temp_dir.path().join("not-a-repo");
+        fs::create_dir_all(&outside).unwrap();
+        let err = scan_root_from_repo(&outside, RepoRootScope::Root).unwrap_err();
+        assert!(err.contains("not inside a git repository"));
+    }
+
     #[test]
     fn test_default_config() {
         let config = AppConfig::default();
@@ -0,0 +1,258 @@
+//! Terminal color support and fallback of theme colors
+//!
+//! Themes may use RGB colors, which terminals without truecolor support render
+//! poorly. Such colors are mapped to the nearest color the terminal can show.
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use std::env;
+
+use super::ColorScheme;
+
+/// Colors the terminal can show, as configured
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ColorSupport {
+    /// Detect from the COLORTERM and TERM environment variables
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    /// 24-bit RGB colors
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    /// The xterm 256-color palette
+    #[serde(rename = "256")]
+    Ansi256,
+    /// The 16 basic ANSI colors
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+impl ColorSupport {
+    /// Resolve `Auto` by looking at the environment
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => Self::detect(
+                env::var("COLORTERM").ok().as_deref(),
+                env::var("TERM").ok().as_deref(),
+            ),
+            support => support,
+        }
+    }
+
+    /// Map a single color to what the terminal supports
+    pub fn apply(self, color: Color) -> Color {
+        match self.resolve() {
+            Self::Ansi256 => to_ansi256(color),
+            Self::Ansi16 => to_ansi16(color),
+            _ => color,
+        }
+    }
+
+    /// Detect color support from the values of COLORTERM and TERM
+    ///
+    /// Terminals that advertise nothing are assumed to have 16 colors.
+    fn detect(colorterm: Option<&str>, term: Option<&str>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return Self::TrueColor;
+        }
+        match term {
+            Some(term) if term.contains("truecolor") || term.contains("direct") => Self::TrueColor,
+            Some(term) if term.contains("256color") => Self::Ansi256,
+            _ => Self::Ansi16,
+        }
+    }
+}
+
+impl ColorScheme {
+    /// Map the colors of this scheme to what the terminal supports
+    pub fn for_support(self, support: ColorSupport) -> Self {
+        match support.resolve() {
+            ColorSupport::Ansi256 => self.map(to_ansi256),
+            ColorSupport::Ansi16 => self.degrade_to_16(),
+            _ => self,
+        }
+    }
+
+    /// Replace RGB colors with the nearest of the 16 basic ANSI colors
+    pub fn degrade_to_16(self) -> Self {
+        self.map(to_ansi16)
+    }
+
+    /// Apply `f` to every color of the scheme
+    fn map(self, f: fn(Color) -> Color) -> Self {
+        Self {
+            border: f(self.border),
+            highlight_bg: f(self.highlight_bg),
+            text_primary: f(self.text_primary),
+            text_secondary: f(self.text_secondary),
+            text_muted: f(self.text_muted),
+            status_clean: f(self.status_clean),
+            status_dirty: f(self.status_dirty),
+            status_conflict: f(self.status_conflict),
+            status_sync: f(self.status_sync),
+            key_action: f(self.key_action),
+            key_warning: f(self.key_warning),
+            key_danger: f(self.key_danger),
+            repo_name: f(self.repo_name),
+            branch_name: f(self.branch_name),
+            commit_ahead: f(self.commit_ahead),
+            commit_behind: f(self.commit_behind),
+            section_remote: f(self.section_remote),
+            section_commit: f(self.section_commit),
+            section_stash: f(self.section_stash),
+        }
+    }
+}
+
+/// Map an RGB color to the nearest basic ANSI color, keeping other colors
+///
+/// Unsaturated colors become black, dark gray, gray or white by lightness.
+/// Others get the ANSI color of their hue, in the bright variant if light.
+fn to_ansi16(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+    let (r, g, b) = (f32::from(r), f32::from(g), f32::from(b));
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0 / 255.0;
+    let chroma = max - min;
+
+    if chroma < 48.0 {
+        return match lightness {
+            l if l < 0.15 => Color::Black,
+            l if l < 0.55 => Color::DarkGray,
+            l if l < 0.92 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let bright = lightness >= 0.5;
+    match hue {
+        h if !(30.0..330.0).contains(&h) => pick(bright, Color::LightRed, Color::Red),
+        h if h < 90.0 => pick(bright, Color::LightYellow, Color::Yellow),
+        h if h < 150.0 => pick(bright, Color::LightGreen, Color::Green),
+        h if h < 195.0 => pick(bright, Color::LightCyan, Color::Cyan),
+        h if h < 270.0 => pick(bright, Color::LightBlue, Color::Blue),
+        _ => pick(bright, Color::LightMagenta, Color::Magenta),
+    }
+}
+
+fn pick(bright: bool, light: Color, normal: Color) -> Color {
+    if bright { light } else { normal }
+}
+
+/// Channel levels of the 6x6x6 color cube of the 256-color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Map an RGB color to the nearest color of the 256-color palette, keeping other colors
+///
+/// Both the color cube and the grayscale ramp are considered.
+fn to_ansi256(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+
+    let nearest_level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| CUBE_LEVELS[i].abs_diff(v))
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    // Grayscale ramp: 232..=255 are 8, 18, ..., 238
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_step;
+    let gray = (gray_level, gray_level, gray_level);
+
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| u32::from(a.abs_diff(b)).pow(2);
+        d(cr, r) + d(cg, g) + d(cb, b)
+    };
+    if distance(gray) < distance(cube) {
+        Color::Indexed(232 + gray_step)
+    } else {
+        Color::Indexed(cube_index as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi16_known_colors() {
+        assert_eq!(to_ansi16(Color::Rgb(0, 0, 0)), Color::Black);
+        assert_eq!(to_ansi16(Color::Rgb(255, 255, 255)), Color::White);
+        assert_eq!(to_ansi16(Color::Rgb(128, 128, 128)), Color::DarkGray);
+        assert_eq!(to_ansi16(Color::Rgb(205, 205, 205)), Color::Gray);
+        assert_eq!(to_ansi16(Color::Rgb(205, 0, 0)), Color::Red);
+        assert_eq!(to_ansi16(Color::Rgb(255, 80, 80)), Color::LightRed);
+        assert_eq!(to_ansi16(Color::Rgb(0, 130, 50)), Color::Green);
+        assert_eq!(to_ansi16(Color::Rgb(0, 100, 180)), Color::Blue);
+        assert_eq!(to_ansi16(Color::Rgb(90, 180, 230)), Color::LightBlue);
+        assert_eq!(to_ansi16(Color::Rgb(230, 190, 90)), Color::LightYellow);
+        assert_eq!(to_ansi16(Color::Rgb(170, 50, 170)), Color::Magenta);
+        assert_eq!(to_ansi16(Color::Rgb(0, 180, 180)), Color::Cyan);
+        // Named colors are kept
+        assert_eq!(to_ansi16(Color::Cyan), Color::Cyan);
+    }
+
+    #[test]
+    fn test_ansi256_known_colors() {
+        assert_eq!(to_ansi256(Color::Rgb(0, 0, 0)), Color::Indexed(16));
+        assert_eq!(to_ansi256(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(to_ansi256(Color::Rgb(95, 135, 175)), Color::Indexed(67));
+        assert_eq!(to_ansi256(Color::Rgb(80, 80, 80)), Color::Indexed(239));
+        assert_eq!(to_ansi256(Color::Green), Color::Green);
+    }
+
+    #[test]
+    fn test_degrade_dark_theme_to_16() {
+        let colors = ColorScheme::dark().degrade_to_16();
+        assert_eq!(colors.status_clean, Color::LightGreen);
+        assert_eq!(colors.status_conflict, Color::LightRed);
+        assert_eq!(colors.highlight_bg, Color::DarkGray);
+        assert_eq!(colors.text_primary, Color::Gray);
+        assert!(
+            [colors.border, colors.repo_name, colors.section_stash]
+                .iter()
+                .all(|c| !matches!(c, Color::Rgb(..)))
+        );
+    }
+
+    #[test]
+    fn test_detect_color_support() {
+        let detect = ColorSupport::detect;
+        assert_eq!(
+            detect(Some("truecolor"), Some("xterm")),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(detect(None, Some("xterm-direct")), ColorSupport::TrueColor);
+        assert_eq!(detect(None, Some("xterm-256color")), ColorSupport::Ansi256);
+        assert_eq!(detect(None, Some("linux")), ColorSupport::Ansi16);
+        assert_eq!(detect(None, None), ColorSupport::Ansi16);
+    }
+
+    #[test]
+    fn test_color_support_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            colors: ColorSupport,
+        }
+        let parse = |s: &str| toml::from_str::<Wrapper>(s).unwrap().colors;
+        assert_eq!(parse(r#"colors = "16""#), ColorSupport::Ansi16);
+        assert_eq!(parse(r#"colors = "256""#), ColorSupport::Ansi256);
+        assert_eq!(parse(r#"colors = "auto""#), ColorSupport::Auto);
+    }
+}
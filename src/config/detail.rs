@@ -0,0 +1,130 @@
+//! Detail view configuration
+
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::warn;
+
+/// A section of the repository detail view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailSection {
+    Basic,
+    Sync,
+    Working,
+    Anomalies,
+    Stash,
+    Remote,
+    Commit,
+    Files,
+}
+
+impl DetailSection {
+    /// Section ids as written in the config file
+    const REGISTRY: [(&'static str, Self); 8] = [
+        ("basic", Self::Basic),
+        ("sync", Self::Sync),
+        ("working", Self::Working),
+        ("anomalies", Self::Anomalies),
+        ("stash", Self::Stash),
+        ("remote", Self::Remote),
+        ("commit", Self::Commit),
+        ("files", Self::Files),
+    ];
+
+    /// Look up a section by its id
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::REGISTRY
+            .iter()
+            .find(|(name, _)| *name == id)
+            .map(|(_, section)| *section)
+    }
+}
+
+/// How the file list of the detail view is laid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileLayout {
+    /// One list, conflicts first, then staged, modified and untracked files
+    #[default]
+    Sorted,
+    /// One list per category, each under its own header
+    Grouped,
+}
+
+/// How the lines of the detail view are laid out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailLayout {
+    /// One `Label: value` line per field
+    #[default]
+    Stacked,
+    /// Labels aligned in a left column, values wrapped in the right one
+    Columns,
+}
+
+/// Detail view configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetailConfig {
+    /// Sections to show, in order (None keeps the built-in layout)
+    #[serde(default, deserialize_with = "deserialize_sections")]
+    pub sections: Option<Vec<DetailSection>>,
+    /// Layout of the file list
+    #[serde(default)]
+    pub file_layout: FileLayout,
+    /// Layout of the label and value of each line
+    #[serde(default)]
+    pub layout: DetailLayout,
+}
+
+/// Deserialize section ids, skipping unknown ones with a warning
+fn deserialize_sections<'de, D: Deserializer<'de>>(
+    d: D,
+) -> Result<Option<Vec<DetailSection>>, D::Error> {
+    let ids = Option::<Vec<String>>::deserialize(d)?;
+    Ok(ids.map(|ids| {
+        ids.iter()
+            .filter_map(|id| {
+                let section = DetailSection::from_id(id);
+                if section.is_none() {
+                    warn!("Unknown detail section '{}', skipping it", id);
+                }
+                section
+            })
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_from_toml() {
+        let config: DetailConfig =
+            toml::from_str(r#"sections = ["working", "bogus", "basic"]"#).unwrap();
+        assert_eq!(
+            config.sections,
+            Some(vec![DetailSection::Working, DetailSection::Basic])
+        );
+
+        let config: DetailConfig = toml::from_str("").unwrap();
+        assert_eq!(config.sections, None);
+    }
+
+    #[test]
+    fn test_file_layout_from_toml() {
+        let config: DetailConfig = toml::from_str("").unwrap();
+        assert_eq!(config.file_layout, FileLayout::Sorted);
+
+        let config: DetailConfig = toml::from_str(r#"file_layout = "grouped""#).unwrap();
+        assert_eq!(config.file_layout, FileLayout::Grouped);
+    }
+
+    #[test]
+    fn test_layout_from_toml() {
+        let config: DetailConfig = toml::from_str("").unwrap();
+        assert_eq!(config.layout, DetailLayout::Stacked);
+
+        let config: DetailConfig = toml::from_str(r#"layout = "columns""#).unwrap();
+        assert_eq!(config.layout, DetailLayout::Columns);
+    }
+}
@@ -0,0 +1,52 @@
+//! Health score configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Weights of the health score, see `core::health`
+///
+/// Weights are relative: each one is the share of the score a factor can take
+/// away at worst, out of the sum of all weights.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthConfig {
+    /// Weight of uncommitted changes and conflicts
+    pub dirty: u32,
+    /// Weight of commits ahead of or behind the remote
+    pub sync: u32,
+    /// Weight of stashes
+    pub stashes: u32,
+    /// Weight of the age of the last commit
+    pub stale: u32,
+    /// Age of the last commit at which the staleness penalty is full
+    #[serde(with = "crate::core::duration")]
+    pub stale_after: Duration,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            dirty: 30,
+            sync: 30,
+            stashes: 10,
+            stale: 30,
+            stale_after: Duration::from_secs(365 * 86400),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_from_toml() {
+        let config: HealthConfig = toml::from_str("dirty = 50\nstale_after = \"90d\"").unwrap();
+        assert_eq!(config.dirty, 50);
+        assert_eq!(config.sync, 30);
+        assert_eq!(config.stale_after, Duration::from_secs(90 * 86400));
+
+        let config: HealthConfig = toml::from_str("").unwrap();
+        assert_eq!(config, HealthConfig::default());
+    }
+}
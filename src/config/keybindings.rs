@@ -15,6 +15,20 @@ pub struct KeyBindings {
     pub back: Vec<String>,
     pub cd: Vec<String>,
     pub open: Vec<String>,
+    pub branches: Vec<String>,
+    pub delete_branch: Vec<String>,
+    pub force_delete_branch: Vec<String>,
+    pub next_dirty: Vec<String>,
+    pub prev_dirty: Vec<String>,
+    pub next_conflict: Vec<String>,
+    pub git_command: Vec<String>,
+    pub archive: Vec<String>,
+    pub force_archive: Vec<String>,
+    pub tmux: Vec<String>,
+    pub terminal: Vec<String>,
+    pub toggle_group: Vec<String>,
+    pub toggle_clean: Vec<String>,
+    pub reload_config: Vec<String>,
 }
 
 impl Default for KeyBindings {
@@ -29,13 +43,60 @@ impl Default for KeyBindings {
             back: vec!["Esc".to_string()],
             cd: vec!["o".to_string()],
             open: vec!["O".to_string(), "Enter".to_string()],
+            branches: vec!["b".to_string()],
+            delete_branch: vec!["d".to_string()],
+            force_delete_branch: vec!["D".to_string()],
+            next_dirty: vec!["n".to_string()],
+            prev_dirty: vec!["N".to_string()],
+            next_conflict: vec!["c".to_string()],
+            git_command: vec!["g".to_string()],
+            archive: vec!["a".to_string()],
+            force_archive: vec!["A".to_string()],
+            tmux: vec!["t".to_string()],
+            terminal: vec!["T".to_string()],
+            toggle_group: vec!["z".to_string(), "Space".to_string()],
+            toggle_clean: vec!["H".to_string()],
+            reload_config: vec!["R".to_string()],
         }
     }
 }
 
 impl KeyBindings {
+    /// Names of all actions, in the order they are listed
+    pub const ACTIONS: [&'static str; 23] = [
+        "quit",
+        "move_up",
+        "move_down",
+        "move_left",
+        "move_right",
+        "details",
+        "back",
+        "cd",
+        "open",
+        "branches",
+        "delete_branch",
+        "force_delete_branch",
+        "next_dirty",
+        "prev_dirty",
+        "next_conflict",
+        "git_command",
+        "archive",
+        "force_archive",
+        "tmux",
+        "terminal",
+        "toggle_group",
+        "toggle_clean",
+        "reload_config",
+    ];
+
     /// Check if a key matches any binding for the given action
     pub fn matches(&self, action: &str, key: &str) -> bool {
+        self.keys(action)
+            .is_some_and(|bindings| bindings.iter().any(|b| b == key))
+    }
+
+    /// Get the keys bound to an action, or None if there is no such action
+    pub fn keys(&self, action: &str) -> Option<&[String]> {
         let bindings = match action {
             "quit" => &self.quit,
             "move_up" => &self.move_up,
@@ -46,8 +107,22 @@ impl KeyBindings {
             "back" => &self.back,
             "cd" => &self.cd,
             "open" => &self.open,
-            _ => return false,
+            "branches" => &self.branches,
+            "delete_branch" => &self.delete_branch,
+            "force_delete_branch" => &self.force_delete_branch,
+            "next_dirty" => &self.next_dirty,
+            "prev_dirty" => &self.prev_dirty,
+            "next_conflict" => &self.next_conflict,
+            "git_command" => &self.git_command,
+            "archive" => &self.archive,
+            "force_archive" => &self.force_archive,
+            "tmux" => &self.tmux,
+            "terminal" => &self.terminal,
+            "toggle_group" => &self.toggle_group,
+            "toggle_clean" => &self.toggle_clean,
+            "reload_config" => &self.reload_config,
+            _ => return None,
         };
-        bindings.iter().any(|b| b == key)
+        Some(bindings)
     }
 }
@@ -0,0 +1,182 @@
+//! Project-local configuration (`.reponest.toml`)
+//!
+//! A local config sits in a workspace directory and tweaks how that subtree is
+//! scanned. It is layered above the user config and below CLI arguments.
+
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::AppConfig;
+
+/// File name of the project-local configuration
+pub const LOCAL_CONFIG_FILE: &str = ".reponest.toml";
+
+/// Settings a project-local config may override (unset fields keep their value)
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LocalConfig {
+    main: LocalMainConfig,
+}
+
+/// Main section of the project-local configuration
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LocalMainConfig {
+    /// Maximum scan depth (0 means unlimited)
+    max_depth: Option<usize>,
+    /// Directory patterns to exclude in addition to the built-in ones
+    exclude_dirs: Vec<String>,
+    /// Patterns of the only directories to scan, replacing those of the user config
+    include_dirs: Vec<String>,
+    /// Leave submodules out of the repository list
+    hide_submodules: Option<bool>,
+    /// Repos whose last commit is older than this are shown as stale
+    #[serde(with = "crate::core::duration::option")]
+    stale_threshold: Option<Duration>,
+}
+
+impl LocalConfig {
+    /// Find the nearest local config file in `start` or one of its ancestors
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(LOCAL_CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Load a local config file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+    }
+
+    /// Find and load the nearest local config, warning if it cannot be loaded
+    pub fn discover(start: &Path) -> Option<Self> {
+        let path = Self::find(start)?;
+        debug!("Loading local config from: {:?}", path);
+        match Self::load(&path) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                warn!("{}. Ignoring local config", e);
+                None
+            }
+        }
+    }
+
+    /// Apply the settings this local config sets on top of `config`
+    ///
+    /// Settings that change which repos are found mark the scan scope as overridden.
+    pub fn merge_into(self, config: &mut AppConfig) {
+        let main = self.main;
+        if main.max_depth.is_some()
+            || !main.exclude_dirs.is_empty()
+            || !main.include_dirs.is_empty()
+            || main.hide_submodules.is_some()
+        {
+            config.internal.scan_scope_overridden = true;
+        }
+        if let Some(depth) = main.max_depth {
+            debug!("Local override: max_depth = {}", depth);
+            config.main.max_depth = depth;
+        }
+        if !main.exclude_dirs.is_empty() {
+            debug!("Local override: exclude_dirs += {:?}", main.exclude_dirs);
+            config.internal.exclude_dirs.extend(main.exclude_dirs);
+        }
+        if !main.include_dirs.is_empty() {
+            debug!("Local override: include_dirs = {:?}", main.include_dirs);
+            config.main.include_dirs = main.include_dirs;
+        }
+        if let Some(hide) = main.hide_submodules {
+            debug!("Local override: hide_submodules = {}", hide);
+            config.main.hide_submodules = hide;
+        }
+        if let Some(threshold) = main.stale_threshold {
+            debug!("Local override: stale_threshold = {:?}", threshold);
+            config.main.stale_threshold = Some(threshold);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::scanner::scan_directory;
+    use tempfile::TempDir;
+
+    fn create_git_repo(path: &Path) {
+        fs::create_dir_all(path.join(".git")).unwrap();
+        fs::write(path.join(".git/config"), "[core]").unwrap();
+    }
+
+    #[test]
+    fn test_find_nearest_local_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let nested = root.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(LocalConfig::find(&nested), None);
+
+        fs::write(root.join(LOCAL_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            LocalConfig::find(&nested),
+            Some(root.join(LOCAL_CONFIG_FILE))
+        );
+
+        fs::write(root.join("a").join(LOCAL_CONFIG_FILE), "").unwrap();
+        assert_eq!(
+            LocalConfig::find(&nested),
+            Some(root.join("a").join(LOCAL_CONFIG_FILE))
+        );
+    }
+
+    #[test]
+    fn test_merge_keeps_unset_fields() {
+        let mut config = AppConfig::default();
+        config.main.max_depth = 7;
+        config.main.hide_submodules = true;
+        let excludes = config.internal.exclude_dirs.len();
+
+        let local: LocalConfig = toml::from_str("[main]\nexclude_dirs = [\"vendor\"]").unwrap();
+        local.merge_into(&mut config);
+
+        assert_eq!(config.main.max_depth, 7);
+        assert!(config.main.hide_submodules);
+        assert_eq!(config.internal.exclude_dirs.len(), excludes + 1);
+        assert!(config.internal.exclude_dirs.contains(&"vendor".to_string()));
+        assert!(config.internal.scan_scope_overridden);
+
+        let mut config = AppConfig::default();
+        let local: LocalConfig = toml::from_str("[main]\nstale_threshold = \"30d\"").unwrap();
+        local.merge_into(&mut config);
+        assert!(!config.internal.scan_scope_overridden);
+    }
+
+    #[tokio::test]
+    async fn test_local_config_changes_scan() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        create_git_repo(&root.join("app"));
+        create_git_repo(&root.join("vendor").join("lib"));
+        create_git_repo(&root.join("deep").join("er").join("repo"));
+        let root_str = root.to_str().unwrap();
+
+        let config = AppConfig::default();
+        assert_eq!(scan_directory(root_str, &config).await.unwrap().len(), 3);
+
+        fs::write(
+            root.join(LOCAL_CONFIG_FILE),
+            "[main]\nmax_depth = 2\nexclude_dirs = [\"vendor\"]\n",
+        )
+        .unwrap();
+        let mut config = AppConfig::default();
+        LocalConfig::discover(root).unwrap().merge_into(&mut config);
+
+        let found = scan_directory(root_str, &config).await.unwrap();
+        assert_eq!(found, vec![root.join("app")]);
+    }
+}
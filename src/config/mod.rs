@@ -1,7 +1,25 @@
 mod app_config;
+mod color_support;
+mod detail;
+mod health;
 mod keybindings;
+mod local;
+mod overrides;
+mod prefixes;
 mod theme;
+mod watch;
 
-pub use app_config::AppConfig;
+pub(crate) use app_config::expand_tilde_in_path;
+pub use app_config::{
+    AppConfig, ChangeCategory, ColorBy, CompareAgainst, ListColumn, ListLayout, RepoRootScope,
+    UIConfig, WorkdirMtime,
+};
+pub use color_support::ColorSupport;
+pub use detail::{DetailConfig, DetailLayout, DetailSection, FileLayout};
+pub use health::HealthConfig;
 pub use keybindings::KeyBindings;
-pub use theme::{ColorScheme, Theme};
+pub use local::{LOCAL_CONFIG_FILE, LocalConfig};
+pub use overrides::parse_assignment;
+pub use prefixes::StatusPrefixes;
+pub use theme::{ColorScheme, HighlightMode, Theme, ThemeRegistry, register_theme, theme_names};
+pub use watch::{NotifyBackend, WatchConfig};
@@ -0,0 +1,259 @@
+//! Overrides of single settings given on the command line (`--set key=value`)
+
+use serde::de::DeserializeOwned;
+
+use super::AppConfig;
+use super::app_config::expand_tilde_in_path;
+use crate::core::duration::parse_duration;
+
+/// Settings that can be overridden with `--set`
+pub const OVERRIDE_KEYS: [&str; 42] = [
+    "main.max_depth",
+    "main.flat",
+    "main.safe_mode",
+    "main.conflict_hunks",
+    "main.unstaged_hunks",
+    "main.count_contributors",
+    "main.compare_against",
+    "main.hide_submodules",
+    "main.lazy_info",
+    "main.stale_threshold",
+    "main.exclude",
+    "main.include_dirs",
+    "main.scan_anomalies",
+    "main.count_unpushed_tags",
+    "main.count_unmerged_branches",
+    "main.count_lfs_locks",
+    "main.count_assume_unchanged",
+    "main.workdir_mtime",
+    "main.skip_dirs_with_more_than",
+    "main.auto_exclude_entry_threshold",
+    "main.follow_symlinks",
+    "main.archive_dir",
+    "main.results_cache",
+    "main.jobs",
+    "main.tmux_command",
+    "main.terminal_command",
+    "ui.theme",
+    "ui.highlight",
+    "ui.color_support",
+    "ui.health_bar",
+    "ui.totals_bar",
+    "ui.redact_remotes",
+    "ui.ascii_symbols",
+    "ui.selection_symbol",
+    "ui.group_by",
+    "ui.list_layout",
+    "ui.color_by",
+    "health.dirty",
+    "health.sync",
+    "health.stashes",
+    "health.stale",
+    "health.stale_after",
+];
+
+/// Settings that change which repos a scan finds
+const SCAN_SCOPE_KEYS: [&str; 8] = [
+    "main.max_depth",
+    "main.flat",
+    "main.hide_submodules",
+    "main.exclude",
+    "main.include_dirs",
+    "main.skip_dirs_with_more_than",
+    "main.auto_exclude_entry_threshold",
+    "main.follow_symlinks",
+];
+
+/// Parse a `key=value` assignment given to `--set`
+pub fn parse_assignment(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.trim().to_string()))
+        }
+        _ => Err(format!(
+            "expected KEY=VALUE (e.g. main.max_depth=3), got '{}'",
+            s
+        )),
+    }
+}
+
+/// Set the setting at `key` (e.g. `main.max_depth`) from its text value
+///
+/// Values are read as in the config file, except that strings need no quotes.
+pub fn set_config_value(config: &mut AppConfig, key: &str, value: &str) -> Result<(), String> {
+    let (main, ui, health) = (&mut config.main, &mut config.ui, &mut config.health);
+    match key {
+        "main.max_depth" => main.max_depth = parse_value(key, value)?,
+        "main.flat" => main.flat = parse_value(key, value)?,
+        "main.safe_mode" => main.safe_mode = parse_value(key, value)?,
+        "main.conflict_hunks" => main.conflict_hunks = parse_value(key, value)?,
+        "main.unstaged_hunks" => main.unstaged_hunks = parse_value(key, value)?,
+        "main.count_contributors" => main.count_contributors = parse_value(key, value)?,
+        "main.compare_against" => main.compare_against = parse_value(key, value)?,
+        "main.hide_submodules" => main.hide_submodules = parse_value(key, value)?,
+        "main.lazy_info" => main.lazy_info = parse_value(key, value)?,
+        "main.stale_threshold" => {
+            let threshold = parse_duration(value)
+                .map_err(|e| format!("Invalid value '{}' for {}: {}", value, key, e))?;
+            main.stale_threshold = Some(threshold);
+        }
+        // A single pattern may be given without the brackets of a list
+        "main.exclude" => match value.starts_with('[') {
+            true => main.exclude = parse_value(key, value)?,
+            false => main.exclude = vec![value.to_string()],
+        },
+        "main.include_dirs" => match value.starts_with('[') {
+            true => main.include_dirs = parse_value(key, value)?,
+            false => main.include_dirs = vec![value.to_string()],
+        },
+        "main.scan_anomalies" => main.scan_anomalies = parse_value(key, value)?,
+        "main.count_unpushed_tags" => main.count_unpushed_tags = parse_value(key, value)?,
+        "main.count_unmerged_branches" => main.count_unmerged_branches = parse_value(key, value)?,
+        "main.count_lfs_locks" => main.count_lfs_locks = parse_value(key, value)?,
+        "main.count_assume_unchanged" => main.count_assume_unchanged = parse_value(key, value)?,
+        "main.workdir_mtime" => main.workdir_mtime = parse_value(key, value)?,
+        "main.skip_dirs_with_more_than" | "main.auto_exclude_entry_threshold" => {
+            main.skip_dirs_with_more_than = Some(parse_value(key, value)?);
+        }
+        "main.follow_symlinks" => main.follow_symlinks = parse_value(key, value)?,
+        "main.archive_dir" => main.archive_dir = Some(expand_tilde_in_path(value)?),
+        "main.results_cache" => main.results_cache = Some(expand_tilde_in_path(value)?),
+        "main.jobs" => main.jobs = Some(parse_value(key, value)?),
+        "main.tmux_command" => main.tmux_command = parse_value(key, value)?,
+        "main.terminal_command" => main.terminal_command = Some(parse_value(key, value)?),
+        "ui.theme" => ui.theme = value.parse()?,
+        "ui.highlight" => ui.highlight = parse_value(key, value)?,
+        "ui.color_support" => ui.color_support = parse_value(key, value)?,
+        "ui.health_bar" => ui.health_bar = parse_value(key, value)?,
+        "ui.totals_bar" => ui.totals_bar = parse_value(key, value)?,
+        "ui.redact_remotes" => ui.redact_remotes = parse_value(key, value)?,
+        "ui.ascii_symbols" => ui.ascii_symbols = parse_value(key, value)?,
+        "ui.selection_symbol" => ui.selection_symbol = Some(parse_value(key, value)?),
+        "ui.group_by" => ui.group_by = parse_value(key, value)?,
+        "ui.list_layout" => ui.list_layout = parse_value(key, value)?,
+        "ui.color_by" => ui.color_by = parse_value(key, value)?,
+        "health.dirty" => health.dirty = parse_value(key, value)?,
+        "health.sync" => health.sync = parse_value(key, value)?,
+        "health.stashes" => health.stashes = parse_value(key, value)?,
+        "health.stale" => health.stale = parse_value(key, value)?,
+        "health.stale_after" => {
+            health.stale_after = parse_duration(value)
+                .map_err(|e| format!("Invalid value '{}' for {}: {}", value, key, e))?;
+        }
+        _ => {
+            return Err(format!(
+                "Unknown setting '{}' for --set. Settings: {}",
+                key,
+                OVERRIDE_KEYS.join(", ")
+            ));
+        }
+    }
+    if SCAN_SCOPE_KEYS.contains(&key) {
+        config.internal.scan_scope_overridden = true;
+    }
+    Ok(())
+}
+
+/// Parse a value as a TOML value, or as a bare string if it is not one
+fn parse_value<T: DeserializeOwned>(key: &str, value: &str) -> Result<T, String> {
+    let parsed = toml::from_str::<toml::Table>(&format!("value = {}", value))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(value.to_string()));
+    T::deserialize(parsed).map_err(|e| format!("Invalid value '{}' for {}: {}", value, key, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HighlightMode;
+    use crate::core::grouping::GroupBy;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_assignment() {
+        assert_eq!(
+            parse_assignment("main.max_depth=3").unwrap(),
+            ("main.max_depth".to_string(), "3".to_string())
+        );
+        // Only the first '=' separates the key
+        assert_eq!(
+            parse_assignment("main.tmux_command=tmux new-window -e A=1").unwrap(),
+            (
+                "main.tmux_command".to_string(),
+                "tmux new-window -e A=1".to_string()
+            )
+        );
+        assert!(parse_assignment("main.max_depth").is_err());
+        assert!(parse_assignment("=3").is_err());
+    }
+
+    #[test]
+    fn test_set_config_value() {
+        let mut config = AppConfig::default();
+        set_config_value(&mut config, "main.max_depth", "3").unwrap();
+        set_config_value(&mut config, "main.safe_mode", "true").unwrap();
+        set_config_value(&mut config, "main.stale_threshold", "90d").unwrap();
+        set_config_value(&mut config, "main.jobs", "2").unwrap();
+        set_config_value(&mut config, "ui.highlight", "reverse").unwrap();
+        set_config_value(&mut config, "ui.group_by", "\"host\"").unwrap();
+        set_config_value(&mut config, "ui.theme", "dark").unwrap();
+        set_config_value(&mut config, "health.stale", "0").unwrap();
+        set_config_value(&mut config, "main.exclude", "[\"/build\", \"out-*\"]").unwrap();
+
+        assert_eq!(config.main.max_depth, 3);
+        assert!(config.main.safe_mode);
+        assert_eq!(
+            config.main.stale_threshold,
+            Some(Duration::from_secs(90 * 86400))
+        );
+        assert_eq!(config.main.jobs, Some(2));
+        assert_eq!(config.ui.highlight, HighlightMode::Reverse);
+        assert_eq!(config.ui.group_by, GroupBy::Host);
+        assert_eq!(config.ui.theme.name(), "dark");
+        assert_eq!(config.health.stale, 0);
+        assert_eq!(config.main.exclude, ["/build", "out-*"]);
+        set_config_value(&mut config, "main.exclude", "node_modules").unwrap();
+        assert_eq!(config.main.exclude, ["node_modules"]);
+        set_config_value(
+            &mut config,
+            "main.include_dirs",
+            "[\"service-*\", \"lib-*\"]",
+        )
+        .unwrap();
+        assert_eq!(config.main.include_dirs, ["service-*", "lib-*"]);
+        set_config_value(&mut config, "main.include_dirs", "app-*").unwrap();
+        assert_eq!(config.main.include_dirs, ["app-*"]);
+        assert!(config.internal.scan_scope_overridden);
+    }
+
+    #[test]
+    fn test_set_config_value_rejects_bad_input() {
+        let mut config = AppConfig::default();
+        let err = set_config_value(&mut config, "main.max_dept", "3").unwrap_err();
+        assert!(err.contains("Unknown setting 'main.max_dept'"), "{}", err);
+        let err = set_config_value(&mut config, "main.max_depth", "deep").unwrap_err();
+        assert!(
+            err.contains("Invalid value 'deep' for main.max_depth"),
+            "{}",
+            err
+        );
+        assert!(set_config_value(&mut config, "main.flat", "yes").is_err());
+        assert!(set_config_value(&mut config, "ui.highlight", "blink").is_err());
+        assert!(set_config_value(&mut config, "ui.theme", "no-such-theme").is_err());
+        assert!(set_config_value(&mut config, "main.stale_threshold", "soon").is_err());
+        assert_eq!(config.main.max_depth, AppConfig::default().main.max_depth);
+    }
+
+    #[test]
+    fn test_every_override_key_is_settable() {
+        // Values valid for every setting's type are not available, so only
+        // the key lookup is checked: errors must not be about unknown keys
+        for key in OVERRIDE_KEYS {
+            let mut config = AppConfig::default();
+            if let Err(e) = set_config_value(&mut config, key, "1") {
+                assert!(!e.starts_with("Unknown setting"), "{}", e);
+            }
+        }
+    }
+}
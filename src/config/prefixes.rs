@@ -0,0 +1,80 @@
+//! Status prefixes configuration
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Text shown before a repository name in the compact list and the TUI list,
+/// chosen by the repository's status
+///
+/// Statuses without a prefix show the bare name, so glyphs that need a
+/// patched font (e.g. Nerd Fonts) are only used when configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusPrefixes {
+    #[serde(deserialize_with = "deserialize_prefix")]
+    pub clean: Option<String>,
+    #[serde(deserialize_with = "deserialize_prefix")]
+    pub dirty: Option<String>,
+    #[serde(deserialize_with = "deserialize_prefix")]
+    pub conflict: Option<String>,
+    #[serde(deserialize_with = "deserialize_prefix")]
+    pub unpushed: Option<String>,
+    #[serde(deserialize_with = "deserialize_prefix")]
+    pub unpulled: Option<String>,
+}
+
+impl StatusPrefixes {
+    /// Prefix of a status label, empty if none is configured
+    pub fn prefix_for(&self, label: &str) -> &str {
+        let prefix = match label {
+            "clean" => &self.clean,
+            "dirty" => &self.dirty,
+            "conflict" => &self.conflict,
+            "unpushed" => &self.unpushed,
+            "unpulled" => &self.unpulled,
+            _ => return "",
+        };
+        prefix.as_deref().unwrap_or_default()
+    }
+
+    /// Repository name with the prefix of its status label
+    pub fn apply(&self, label: &str, name: &str) -> String {
+        format!("{}{}", self.prefix_for(label), name)
+    }
+}
+
+/// Deserialize a prefix, refusing empty strings
+fn deserialize_prefix<'de, D: Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    let prefix = String::deserialize(d)?;
+    if prefix.is_empty() {
+        return Err(D::Error::custom(
+            "status prefix must not be empty, leave it out to show no prefix",
+        ));
+    }
+    Ok(Some(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefixes_from_toml() {
+        let prefixes: StatusPrefixes =
+            toml::from_str("dirty = \"● \"\nconflict = \"[!] \"").unwrap();
+        assert_eq!(prefixes.prefix_for("dirty"), "● ");
+        assert_eq!(prefixes.apply("conflict", "app"), "[!] app");
+        // Unset and unknown statuses have no prefix
+        assert_eq!(prefixes.apply("clean", "app"), "app");
+        assert_eq!(prefixes.prefix_for("no upstream"), "");
+
+        assert_eq!(
+            toml::from_str::<StatusPrefixes>("").unwrap(),
+            StatusPrefixes::default()
+        );
+        let prefixes: StatusPrefixes = toml::from_str("dirty = \"● \"").unwrap();
+        assert_eq!(toml::to_string(&prefixes).unwrap(), "dirty = \"● \"\n");
+        let err = toml::from_str::<StatusPrefixes>("clean = \"\"").unwrap_err();
+        assert!(err.to_string().contains("must not be empty"));
+    }
+}
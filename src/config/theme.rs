@@ -1,29 +1,159 @@
 //! Theme system for TUI color schemes
 
 use ratatui::style::Color;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::str::FromStr;
+use std::sync::{LazyLock, RwLock};
+use tracing::warn;
 
-/// Available themes
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-#[derive(Default)]
-pub enum Theme {
-    #[default]
-    Default,
-    Dark,
-    Light,
+/// Name of the theme used when none is configured
+const DEFAULT_THEME: &str = "default";
+
+/// Global theme registry, seeded with the built-in themes on first use
+static REGISTRY: LazyLock<RwLock<ThemeRegistry>> =
+    LazyLock::new(|| RwLock::new(ThemeRegistry::with_builtins()));
+
+/// Registry mapping theme names to their color schemes
+#[derive(Debug, Clone, Default)]
+pub struct ThemeRegistry {
+    themes: HashMap<String, ColorScheme>,
+}
+
+impl ThemeRegistry {
+    /// Create a registry containing only the built-in themes
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::default();
+        registry.register(DEFAULT_THEME, ColorScheme::default());
+        registry.register("dark", ColorScheme::dark());
+        registry.register("light", ColorScheme::light());
+        registry
+    }
+
+    /// Register a color scheme under the given name (case-insensitive)
+    ///
+    /// Registering an existing name replaces its color scheme.
+    pub fn register(&mut self, name: &str, colors: ColorScheme) {
+        self.themes.insert(name.to_lowercase(), colors);
+    }
+
+    /// Look up the color scheme registered under the given name
+    pub fn get(&self, name: &str) -> Option<ColorScheme> {
+        self.themes.get(&name.to_lowercase()).copied()
+    }
+
+    /// Check if a theme with the given name is registered
+    pub fn contains(&self, name: &str) -> bool {
+        self.themes.contains_key(&name.to_lowercase())
+    }
+
+    /// Get all registered theme names in sorted order
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.themes.keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+/// Register a custom theme in the global registry so it can be selected by name
+pub fn register_theme(name: &str, colors: ColorScheme) {
+    REGISTRY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(name, colors);
+}
+
+/// Register the themes defined in the `[themes]` section of a config file
+///
+/// This runs before the rest of the file is read, so that `ui.theme` can
+/// select one of them. Fails if a theme has an unknown base, an unknown color
+/// name or an invalid color.
+pub fn register_config_themes(content: &str) -> Result<(), String> {
+    #[derive(Deserialize, Default)]
+    #[serde(default)]
+    struct ThemesSection {
+        themes: BTreeMap<String, ThemePalette>,
+    }
+
+    let section: ThemesSection = toml::from_str(content).map_err(|e| e.to_string())?;
+    for (name, palette) in section.themes {
+        let colors = palette
+            .color_scheme()
+            .map_err(|e| format!("Invalid theme '{}': {}", name, e))?;
+        register_theme(&name, colors);
+    }
+    Ok(())
+}
+
+/// Custom theme from a `[themes.<name>]` section of the config file
+///
+/// Colors that are not given are taken from the `base` theme, the default
+/// theme if unset. Colors are names such as `"cyan"`, `"#rrggbb"` or an
+/// index into the 256-color palette.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ThemePalette {
+    #[serde(default)]
+    base: Option<String>,
+    #[serde(flatten)]
+    colors: BTreeMap<String, String>,
+}
+
+impl ThemePalette {
+    /// Color scheme of this theme, on top of its base theme
+    pub fn color_scheme(&self) -> Result<ColorScheme, String> {
+        let base = self.base.as_deref().unwrap_or(DEFAULT_THEME);
+        let mut scheme = REGISTRY
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(base)
+            .ok_or_else(|| format!("unknown base theme '{}'", base))?;
+        for (field, value) in &self.colors {
+            let color = value
+                .parse::<Color>()
+                .map_err(|_| format!("invalid color '{}' for {}", value, field))?;
+            scheme.set(field, color)?;
+        }
+        Ok(scheme)
+    }
+}
+
+/// Get all theme names in the global registry in sorted order
+pub fn theme_names() -> Vec<String> {
+    REGISTRY.read().unwrap_or_else(|e| e.into_inner()).names()
+}
+
+/// Theme selected by name from the theme registry
+///
+/// Only registered names are accepted, whether parsed or read from the config file.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Theme(String);
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self(DEFAULT_THEME.to_string())
+    }
 }
 
 impl Theme {
+    /// Get the theme name
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
     /// Get the color scheme for this theme
+    ///
+    /// Falls back to the default color scheme if the theme is not registered.
     pub fn colors(&self) -> ColorScheme {
-        match self {
-            Self::Default => ColorScheme::default(),
-            Self::Dark => ColorScheme::dark(),
-            Self::Light => ColorScheme::light(),
-        }
+        REGISTRY
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&self.0)
+            .unwrap_or_else(|| {
+                warn!("Unknown theme '{}', using default theme", self.0);
+                ColorScheme::default()
+            })
     }
 }
 
@@ -31,28 +161,44 @@ impl FromStr for Theme {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "default" => Ok(Self::Default),
-            "dark" => Ok(Self::Dark),
-            "light" => Ok(Self::Light),
-            _ => Err(format!(
-                "Invalid theme '{}'. Valid options: default, dark, light",
-                s
-            )),
+        let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+        if registry.contains(s) {
+            Ok(Self(s.to_lowercase()))
+        } else {
+            Err(format!(
+                "Invalid theme '{}'. Valid options: {}",
+                s,
+                registry.names().join(", ")
+            ))
         }
     }
 }
 
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
 impl fmt::Display for Theme {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Default => write!(f, "default"),
-            Self::Dark => write!(f, "dark"),
-            Self::Light => write!(f, "light"),
-        }
+        write!(f, "{}", self.0)
     }
 }
 
+/// How the selected row in the repository list is highlighted
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HighlightMode {
+    /// Highlight background color from the color scheme
+    #[default]
+    Background,
+    /// Reverse video, visible regardless of theme contrast
+    Reverse,
+}
+
 /// Color scheme for the TUI
 #[derive(Debug, Clone, Copy)]
 pub struct ColorScheme {
@@ -122,6 +268,34 @@ impl Default for ColorScheme {
 }
 
 impl ColorScheme {
+    /// Set the color with the given field name, as used in custom themes
+    pub fn set(&mut self, field: &str, color: Color) -> Result<(), String> {
+        let slot = match field {
+            "border" => &mut self.border,
+            "highlight_bg" => &mut self.highlight_bg,
+            "text_primary" => &mut self.text_primary,
+            "text_secondary" => &mut self.text_secondary,
+            "text_muted" => &mut self.text_muted,
+            "status_clean" => &mut self.status_clean,
+            "status_dirty" => &mut self.status_dirty,
+            "status_conflict" => &mut self.status_conflict,
+            "status_sync" => &mut self.status_sync,
+            "key_action" => &mut self.key_action,
+            "key_warning" => &mut self.key_warning,
+            "key_danger" => &mut self.key_danger,
+            "repo_name" => &mut self.repo_name,
+            "branch_name" => &mut self.branch_name,
+            "commit_ahead" => &mut self.commit_ahead,
+            "commit_behind" => &mut self.commit_behind,
+            "section_remote" => &mut self.section_remote,
+            "section_commit" => &mut self.section_commit,
+            "section_stash" => &mut self.section_stash,
+            _ => return Err(format!("unknown color '{}'", field)),
+        };
+        *slot = color;
+        Ok(())
+    }
+
     /// Dark theme
     pub fn dark() -> Self {
         Self {
@@ -190,3 +364,85 @@ impl ColorScheme {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_themes_registered() {
+        let registry = ThemeRegistry::with_builtins();
+        assert_eq!(registry.names(), vec!["dark", "default", "light"]);
+        assert!(registry.contains("DARK"));
+    }
+
+    #[test]
+    fn test_parse_builtin_theme() {
+        let theme: Theme = "Light".parse().unwrap();
+        assert_eq!(theme.name(), "light");
+        assert_eq!(theme.to_string(), "light");
+    }
+
+    #[test]
+    fn test_register_and_resolve_custom_theme() {
+        assert!("test-custom".parse::<Theme>().is_err());
+        let custom = ColorScheme {
+            border: Color::Rgb(1, 2, 3),
+            ..ColorScheme::default()
+        };
+        register_theme("Test-Custom", custom);
+
+        let theme: Theme = "test-custom".parse().unwrap();
+        assert_eq!(theme.colors().border, Color::Rgb(1, 2, 3));
+        assert!(theme_names().contains(&"test-custom".to_string()));
+    }
+
+    #[test]
+    fn test_register_config_themes() {
+        let content = "[ui]\ntheme = \"test-config\"\n\n\
+                       [themes.test-config]\nbase = \"dark\"\nrepo_name = \"#ff0000\"\nborder = \"cyan\"\n";
+        register_config_themes(content).unwrap();
+
+        let colors = "Test-Config".parse::<Theme>().unwrap().colors();
+        assert_eq!(colors.repo_name, Color::Rgb(255, 0, 0));
+        assert_eq!(colors.border, Color::Cyan);
+        assert_eq!(colors.status_clean, ColorScheme::dark().status_clean);
+
+        for (theme, error) in [
+            ("base = \"nope\"", "unknown base theme 'nope'"),
+            ("repo_nam = \"red\"", "unknown color 'repo_nam'"),
+            ("repo_name = \"reddish\"", "invalid color 'reddish'"),
+        ] {
+            let content = format!("[themes.test-broken]\n{}\n", theme);
+            let err = register_config_themes(&content).unwrap_err();
+            assert!(err.contains(error), "{}", err);
+        }
+        assert!("test-broken".parse::<Theme>().is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_theme() {
+        let err = "no-such-theme".parse::<Theme>().unwrap_err();
+        assert!(err.contains("no-such-theme"));
+        assert!(err.contains("default"));
+    }
+
+    #[test]
+    fn test_deserialize_theme() {
+        #[derive(Deserialize)]
+        struct Ui {
+            theme: Theme,
+        }
+
+        let ui: Ui = toml::from_str("theme = \"Dark\"").unwrap();
+        assert_eq!(ui.theme.name(), "dark");
+        let err = toml::from_str::<Ui>("theme = \"nope\"").err().unwrap();
+        assert!(err.to_string().contains("Invalid theme 'nope'"), "{}", err);
+    }
+
+    #[test]
+    fn test_unknown_theme_falls_back_to_default_colors() {
+        let theme = Theme("no-such-theme".to_string());
+        assert_eq!(theme.colors().border, ColorScheme::default().border);
+    }
+}
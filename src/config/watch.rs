@@ -0,0 +1,53 @@
+//! Watch mode configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How transitions into dirty or conflict are announced in watch mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyBackend {
+    /// Desktop notification, falling back to the terminal bell
+    #[default]
+    Desktop,
+    /// Terminal bell
+    Bell,
+    /// Only print the transition
+    Log,
+}
+
+/// Watch mode configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    /// Time between two status checks
+    #[serde(with = "crate::core::duration")]
+    pub interval: Duration,
+    /// How transitions are announced
+    pub notify: NotifyBackend,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            notify: NotifyBackend::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watch_from_toml() {
+        let config: WatchConfig = toml::from_str("interval = \"10s\"\nnotify = \"bell\"").unwrap();
+        assert_eq!(config.interval, Duration::from_secs(10));
+        assert_eq!(config.notify, NotifyBackend::Bell);
+
+        let config: WatchConfig = toml::from_str("").unwrap();
+        assert_eq!(config.interval, Duration::from_secs(2));
+        assert_eq!(config.notify, NotifyBackend::Desktop);
+    }
+}
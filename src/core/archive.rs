@@ -0,0 +1,236 @@
+//! This module moves repositories out of the way into an archive directory.
+//!
+//! Only repositories whose work is safely on a remote are archived: a clean
+//! working tree, an upstream, and no commits ahead of it. A forced archive
+//! skips these checks.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::repo_info::RepoInfo;
+
+/// Check whether a repository may be archived
+///
+/// Without `force`, a repository must be clean, have an upstream and have
+/// nothing unpushed. Its status must also be fully loaded.
+pub fn check_archivable(repo: &RepoInfo, force: bool) -> Result<(), String> {
+    if force {
+        return Ok(());
+    }
+    let name = &repo.basic.name;
+    if repo.partial {
+        return Err(format!("Status of '{}' is still loading", name));
+    }
+    if repo.working.is_dirty || repo.working.conflicts > 0 {
+        return Err(format!("'{}' has uncommitted changes", name));
+    }
+    if !repo.sync.has_upstream {
+        return Err(format!("'{}' has no upstream to be pushed to", name));
+    }
+    if repo.sync.ahead > 0 {
+        return Err(format!(
+            "'{}' has {} unpushed commits",
+            name, repo.sync.ahead
+        ));
+    }
+    Ok(())
+}
+
+/// Path a repository is moved to in the archive directory
+///
+/// Fails if something already exists there.
+pub fn archive_destination(archive_dir: &Path, repo_path: &Path) -> Result<PathBuf, String> {
+    let dir_name = repo_path
+        .file_name()
+        .ok_or_else(|| format!("Cannot archive {:?}", repo_path))?;
+    let destination = archive_dir.join(dir_name);
+    if destination.exists() {
+        return Err(format!("{:?} already exists", destination));
+    }
+    Ok(destination)
+}
+
+/// Move a repository into the archive directory, returning its new path
+///
+/// The archive directory is created if needed.
+pub fn archive_repo(repo_path: &Path, archive_dir: &Path) -> Result<PathBuf, String> {
+    if archive_dir.starts_with(repo_path) {
+        return Err(format!(
+            "Archive directory {:?} is inside the repo",
+            archive_dir
+        ));
+    }
+    fs::create_dir_all(archive_dir)
+        .map_err(|e| format!("Failed to create {:?}: {}", archive_dir, e))?;
+    let destination = archive_destination(archive_dir, repo_path)?;
+    move_dir(repo_path, &destination, |from, to| fs::rename(from, to))
+        .map_err(|e| format!("Failed to move {:?}: {}", repo_path, e))?;
+    Ok(destination)
+}
+
+/// Move a directory with `rename`, copying it and removing the original if
+/// `rename` fails because the destination is on another filesystem
+fn move_dir(
+    from: &Path,
+    to: &Path,
+    rename: impl Fn(&Path, &Path) -> io::Result<()>,
+) -> io::Result<()> {
+    match rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_dir(from, to) {
+                // Leave the original untouched and drop the partial copy
+                let _ = fs::remove_dir_all(to);
+                return Err(e);
+            }
+            fs::remove_dir_all(from)
+        }
+        result => result,
+    }
+}
+
+/// Copy a directory recursively, keeping symbolic links as links
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    fs::set_permissions(to, fs::metadata(from)?.permissions())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clean repository that is pushed to its upstream
+    fn pushed(name: &str) -> RepoInfo {
+        let mut repo = RepoInfo::stub(name, &format!("/work/{}", name));
+        repo.sync.has_upstream = true;
+        repo
+    }
+
+    /// Create a directory tree standing in for a repository
+    fn create_repo_dir(path: &Path) {
+        fs::create_dir_all(path.join(".git").join("refs")).unwrap();
+        fs::write(path.join(".git").join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        fs::write(path.join("README.md"), "# Test\n").unwrap();
+    }
+
+    #[test]
+    fn test_archivable_checks() {
+        assert!(check_archivable(&pushed("app"), false).is_ok());
+
+        let mut dirty = pushed("app");
+        dirty.working.is_dirty = true;
+        let mut no_upstream = pushed("app");
+        no_upstream.sync.has_upstream = false;
+        let mut unpushed = pushed("app");
+        unpushed.sync.ahead = 2;
+        let mut loading = pushed("app");
+        loading.partial = true;
+
+        for (repo, reason) in [
+            (&dirty, "uncommitted changes"),
+            (&no_upstream, "no upstream"),
+            (&unpushed, "2 unpushed commits"),
+            (&loading, "still loading"),
+        ] {
+            assert!(check_archivable(repo, false).unwrap_err().contains(reason));
+            assert!(check_archivable(repo, true).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_archive_repo_moves_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("work").join("app");
+        create_repo_dir(&repo_path);
+        let archive_dir = temp_dir.path().join("archive");
+
+        let moved = archive_repo(&repo_path, &archive_dir).unwrap();
+        assert_eq!(moved, archive_dir.join("app"));
+        assert!(!repo_path.exists());
+        assert!(moved.join(".git").join("HEAD").is_file());
+
+        // An existing directory of the same name is never overwritten
+        create_repo_dir(&repo_path);
+        assert!(
+            archive_repo(&repo_path, &archive_dir)
+                .unwrap_err()
+                .contains("already exists")
+        );
+        assert!(repo_path.exists());
+    }
+
+    #[test]
+    fn test_archive_dir_inside_repo_is_refused() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("app");
+        create_repo_dir(&repo_path);
+
+        assert!(archive_repo(&repo_path, &repo_path.join("archive")).is_err());
+        assert!(repo_path.join("README.md").exists());
+    }
+
+    #[test]
+    fn test_move_falls_back_to_copy_across_devices() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let from = temp_dir.path().join("app");
+        create_repo_dir(&from);
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("README.md", from.join("link")).unwrap();
+        let to = temp_dir.path().join("archived");
+
+        let cross_device = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::CrossesDevices));
+        move_dir(&from, &to, cross_device).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(
+            fs::read_to_string(to.join(".git").join("HEAD")).unwrap(),
+            "ref: refs/heads/main\n"
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            fs::read_link(to.join("link")).unwrap(),
+            Path::new("README.md")
+        );
+    }
+
+    #[test]
+    fn test_move_keeps_original_on_other_errors() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let from = temp_dir.path().join("app");
+        create_repo_dir(&from);
+        let to = temp_dir.path().join("archived");
+
+        let denied = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(move_dir(&from, &to, denied).is_err());
+        assert!(from.join("README.md").exists());
+        assert!(!to.exists());
+
+        // A failed copy removes the partial destination
+        let missing = temp_dir.path().join("missing");
+        let cross_device = |_: &Path, _: &Path| Err(io::Error::from(io::ErrorKind::CrossesDevices));
+        assert!(move_dir(&missing, &to, cross_device).is_err());
+        assert!(!to.exists());
+    }
+}
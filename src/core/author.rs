@@ -0,0 +1,98 @@
+//! This module derives short, colored author labels from commit authors.
+//!
+//! The color is picked from a hash of the author email, so an author keeps the
+//! same color across repositories and runs.
+
+use ratatui::style::Color;
+
+/// Colors author initials are shown in
+///
+/// Only basic ANSI colors are used, so they stay distinct whatever colors the
+/// terminal supports.
+const AUTHOR_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Initials shown for an author without a usable name
+const UNKNOWN_INITIALS: &str = "?";
+
+/// Get one or two uppercase initials of an author name
+///
+/// The first letters of the first and last words are used, so "Ada King
+/// Lovelace" gives "AL" and "ada" gives "A".
+pub fn initials(name: &str) -> String {
+    let mut words = name
+        .split(|c: char| c.is_whitespace() || matches!(c, '.' | '_' | '-'))
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()));
+    let Some(first) = words.next() else {
+        return UNKNOWN_INITIALS.to_string();
+    };
+    first
+        .to_uppercase()
+        .chain(words.next_back().into_iter().flat_map(char::to_uppercase))
+        .collect()
+}
+
+/// Get the initials of an author and a color derived from their email
+///
+/// Emails are compared case-insensitively. Without an email, the color is
+/// derived from the name.
+pub fn initials_and_color(name: &str, email: Option<&str>) -> (String, Color) {
+    let key = match email.map(str::trim) {
+        Some(email) if !email.is_empty() => email.to_lowercase(),
+        _ => name.trim().to_lowercase(),
+    };
+    let color = AUTHOR_COLORS[(fnv1a(key.as_bytes()) % AUTHOR_COLORS.len() as u64) as usize];
+    (initials(name), color)
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and Rust versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(initials("Ada Lovelace"), "AL");
+        assert_eq!(initials("Ada King Lovelace"), "AL");
+        assert_eq!(initials("linus"), "L");
+        assert_eq!(initials("jean-luc picard"), "JP");
+        assert_eq!(initials("  émile  zola "), "ÉZ");
+        assert_eq!(initials(""), "?");
+        assert_eq!(initials("---"), "?");
+    }
+
+    #[test]
+    fn test_color_is_stable_per_email() {
+        let (initials, color) = initials_and_color("Ada Lovelace", Some("ada@example.com"));
+        assert_eq!(initials, "AL");
+        // The color depends on the email only, not on the name or its case
+        for name in ["Ada", "A. Lovelace", "someone else"] {
+            let (_, same) = initials_and_color(name, Some(" ADA@example.com"));
+            assert_eq!(same, color);
+        }
+        assert_eq!(fnv1a(b"ada@example.com"), 0x2668_11ad_9b55_c572);
+
+        // Different emails spread over the palette
+        let colors: std::collections::HashSet<String> = (0..20)
+            .map(|i| format!("dev{}@example.com", i))
+            .map(|email| format!("{:?}", initials_and_color("Dev", Some(&email)).1))
+            .collect();
+        assert!(colors.len() > 1);
+
+        // Without an email, the name decides
+        let (_, by_name) = initials_and_color("Ada Lovelace", None);
+        assert_eq!(by_name, initials_and_color("ada lovelace", Some("")).1);
+    }
+}
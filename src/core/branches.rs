@@ -0,0 +1,283 @@
+//! This module provides local branch listing, safe branch deletion and a
+//! summary of the branches checked out across repositories.
+
+use git2::{BranchType, Oid, Repository};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::repo_info::RepoInfo;
+
+/// A local branch of a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalBranch {
+    pub name: String,
+    /// Whether the branch is currently checked out
+    pub is_head: bool,
+    /// Whether the branch is fully merged into its upstream or another branch
+    pub is_merged: bool,
+}
+
+/// List the local branches of the repository at `path`, sorted by name
+pub fn list_local_branches(path: &Path) -> Result<Vec<LocalBranch>, String> {
+    let repo = open(path)?;
+    let branches = repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| format!("Failed to list branches: {}", e))?;
+
+    let mut result = Vec::new();
+    for (branch, _) in branches.flatten() {
+        let Ok(Some(name)) = branch.name() else {
+            continue;
+        };
+        result.push(LocalBranch {
+            name: name.to_string(),
+            is_head: branch.is_head(),
+            is_merged: is_branch_merged(&repo, name),
+        });
+    }
+    result.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(result)
+}
+
+/// Check whether a local branch may be deleted
+///
+/// The current branch is never deletable. An unmerged branch is only
+/// deletable when `force` is set.
+pub fn check_branch_deletable(repo: &Repository, name: &str, force: bool) -> Result<(), String> {
+    let branch = repo
+        .find_branch(name, BranchType::Local)
+        .map_err(|e| format!("Branch '{}' not found: {}", name, e))?;
+
+    if branch.is_head() {
+        return Err(format!("Cannot delete the current branch '{}'", name));
+    }
+    if !force && !is_branch_merged(repo, name) {
+        return Err(format!("Branch '{}' is not fully merged", name));
+    }
+    Ok(())
+}
+
+/// Delete a local branch of the repository at `path`
+pub fn delete_local_branch(path: &Path, name: &str, force: bool) -> Result<(), String> {
+    let repo = open(path)?;
+    check_branch_deletable(&repo, name, force)?;
+
+    repo.find_branch(name, BranchType::Local)
+        .and_then(|mut branch| branch.delete())
+        .map_err(|e| format!("Failed to delete branch '{}': {}", name, e))
+}
+
+/// Check whether a branch's tip is reachable from its upstream or any other local branch
+fn is_branch_merged(repo: &Repository, name: &str) -> bool {
+    let Ok(branch) = repo.find_branch(name, BranchType::Local) else {
+        return false;
+    };
+    let Some(tip) = branch.get().target() else {
+        return false;
+    };
+
+    let upstream_tip = branch.upstream().ok().and_then(|u| u.get().target());
+    let other_tips = repo
+        .branches(Some(BranchType::Local))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|(b, _)| b.name().ok().flatten() != Some(name))
+        .filter_map(|(b, _)| b.get().target());
+
+    upstream_tip
+        .into_iter()
+        .chain(other_tips)
+        .any(|target| contains_commit(repo, target, tip))
+}
+
+/// Check whether `commit` is `target` or one of its ancestors
+pub(crate) fn contains_commit(repo: &Repository, target: Oid, commit: Oid) -> bool {
+    target == commit || repo.graph_descendant_of(target, commit).unwrap_or(false)
+}
+
+/// What the HEAD of a repository points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeadKind {
+    /// A branch with commits
+    Branch,
+    /// A commit rather than a branch
+    Detached,
+    /// A branch without commits yet
+    Unborn,
+    /// HEAD could not be read
+    Unknown,
+}
+
+/// Repositories sharing what they have checked out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BranchCount {
+    pub kind: HeadKind,
+    /// Name of the branch, only set for `HeadKind::Branch`
+    pub branch: Option<String>,
+    pub count: usize,
+    pub repos: Vec<PathBuf>,
+}
+
+/// Count the repositories on each branch
+///
+/// Detached, unborn and unreadable HEADs each form a single bucket, whatever
+/// they point to. Buckets are ordered by count, largest first.
+pub fn summarize_branches(repos: &[RepoInfo]) -> Vec<BranchCount> {
+    let mut buckets: BTreeMap<(HeadKind, Option<&str>), Vec<PathBuf>> = BTreeMap::new();
+    for repo in repos {
+        let basic = &repo.basic;
+        let key = match basic.branch.as_str() {
+            _ if basic.is_unborn => (HeadKind::Unborn, None),
+            "HEAD" => (HeadKind::Detached, None),
+            "?" => (HeadKind::Unknown, None),
+            name => (HeadKind::Branch, Some(name)),
+        };
+        buckets.entry(key).or_default().push(basic.path.clone());
+    }
+
+    let mut counts: Vec<BranchCount> = buckets
+        .into_iter()
+        .map(|((kind, branch), repos)| BranchCount {
+            kind,
+            branch: branch.map(str::to_string),
+            count: repos.len(),
+            repos,
+        })
+        .collect();
+    // Stable, so ties keep branches first and names in order
+    counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+    counts
+}
+
+/// Open the repository at `path`
+fn open(path: &Path) -> Result<Repository, String> {
+    Repository::open(path).map_err(|e| format!("Failed to open repo at {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use std::fs;
+
+    /// Create a repository on `main` with one commit
+    fn create_test_repo(path: &Path) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        commit_file(&repo, "README.md", "# Test\n");
+        repo
+    }
+
+    /// Write a file and commit it on top of HEAD
+    fn commit_file(repo: &Repository, filename: &str, content: &str) {
+        fs::write(repo.workdir().unwrap().join(filename), content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(filename)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, "commit", &tree, &parents)
+            .unwrap();
+    }
+
+    /// Create a branch at HEAD
+    fn create_branch(repo: &Repository, name: &str) {
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch(name, &head, false).unwrap();
+    }
+
+    #[test]
+    fn test_merged_branch_is_deletable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = create_test_repo(temp_dir.path());
+        create_branch(&repo, "merged");
+        commit_file(&repo, "later.txt", "main moved on");
+
+        assert!(is_branch_merged(&repo, "merged"));
+        assert!(check_branch_deletable(&repo, "merged", false).is_ok());
+    }
+
+    #[test]
+    fn test_unmerged_branch_requires_force() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = create_test_repo(temp_dir.path());
+        create_branch(&repo, "feature");
+        repo.set_head("refs/heads/feature").unwrap();
+        commit_file(&repo, "feature.txt", "work in progress");
+        repo.set_head("refs/heads/main").unwrap();
+
+        assert!(!is_branch_merged(&repo, "feature"));
+        assert!(check_branch_deletable(&repo, "feature", false).is_err());
+        assert!(check_branch_deletable(&repo, "feature", true).is_ok());
+    }
+
+    #[test]
+    fn test_current_branch_is_never_deletable() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = create_test_repo(temp_dir.path());
+        create_branch(&repo, "other");
+
+        assert!(check_branch_deletable(&repo, "main", false).is_err());
+        assert!(check_branch_deletable(&repo, "main", true).is_err());
+    }
+
+    #[test]
+    fn test_list_and_delete_branch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = create_test_repo(temp_dir.path());
+        create_branch(&repo, "done");
+
+        let branches = list_local_branches(temp_dir.path()).unwrap();
+        let names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["done", "main"]);
+        assert!(branches[1].is_head);
+
+        delete_local_branch(temp_dir.path(), "done", false).unwrap();
+        let branches = list_local_branches(temp_dir.path()).unwrap();
+        assert_eq!(branches.len(), 1);
+    }
+
+    #[test]
+    fn test_summarize_branches() {
+        let mut unborn = RepoInfo::stub_on_branch("new", "main");
+        unborn.basic.is_unborn = true;
+        let repos = vec![
+            RepoInfo::stub_on_branch("a", "main"),
+            RepoInfo::stub_on_branch("b", "feature/login"),
+            RepoInfo::stub_on_branch("c", "main"),
+            RepoInfo::stub_on_branch("d", "HEAD"),
+            RepoInfo::stub_on_branch("e", "master"),
+            RepoInfo::stub_on_branch("f", "main"),
+            RepoInfo::stub_on_branch("g", "HEAD"),
+            unborn,
+            RepoInfo::stub_on_branch("h", "?"),
+        ];
+
+        let counts = summarize_branches(&repos);
+        let summary: Vec<(HeadKind, Option<&str>, usize)> = counts
+            .iter()
+            .map(|c| (c.kind, c.branch.as_deref(), c.count))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (HeadKind::Branch, Some("main"), 3),
+                (HeadKind::Detached, None, 2),
+                (HeadKind::Branch, Some("feature/login"), 1),
+                (HeadKind::Branch, Some("master"), 1),
+                (HeadKind::Unborn, None, 1),
+                (HeadKind::Unknown, None, 1),
+            ]
+        );
+
+        assert_eq!(
+            counts[0].repos,
+            ["/work/a", "/work/c", "/work/f"].map(PathBuf::from)
+        );
+        assert!(summarize_branches(&[]).is_empty());
+    }
+}
@@ -0,0 +1,136 @@
+//! This module persists scan progress, so an interrupted scan can be resumed.
+//!
+//! Progress is tracked per subtree, where a subtree is a first-level child
+//! directory of a scan root. Once a subtree has been fully scanned, it is
+//! recorded with the repositories found in it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Scan progress, saved to a file after each completed subtree
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ScanCheckpoint {
+    /// File the checkpoint is saved to
+    #[serde(skip)]
+    file: PathBuf,
+    /// Whether the whole scan finished
+    #[serde(default)]
+    complete: bool,
+    /// Fully scanned subtrees and the repositories found in them
+    #[serde(default)]
+    subtrees: BTreeMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl ScanCheckpoint {
+    /// Create an empty checkpoint that is saved to `file`
+    pub fn new(file: &Path) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            ..Default::default()
+        }
+    }
+
+    /// Load the checkpoint of an interrupted scan from `file`
+    ///
+    /// A missing file, or the checkpoint of a scan that finished, gives an
+    /// empty checkpoint, so the scan starts over.
+    pub fn resume(file: &Path) -> Result<Self, String> {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new(file)),
+            Err(e) => return Err(format!("Failed to read checkpoint {:?}: {}", file, e)),
+        };
+        let checkpoint: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid checkpoint {:?}: {}", file, e))?;
+        if checkpoint.complete {
+            return Ok(Self::new(file));
+        }
+        Ok(Self {
+            file: file.to_path_buf(),
+            ..checkpoint
+        })
+    }
+
+    /// Get the repositories of a subtree, if it was already fully scanned
+    pub fn completed_repos(&self, subtree: &Path) -> Option<&[PathBuf]> {
+        self.subtrees.get(subtree).map(Vec::as_slice)
+    }
+
+    /// Record a fully scanned subtree and save the checkpoint
+    pub fn mark_complete(&mut self, subtree: PathBuf, repos: Vec<PathBuf>) -> Result<(), String> {
+        self.subtrees.insert(subtree, repos);
+        self.save()
+    }
+
+    /// Record that the whole scan finished and save the checkpoint
+    pub fn finish(&mut self) -> Result<(), String> {
+        self.complete = true;
+        self.save()
+    }
+
+    /// Save the checkpoint atomically, so an interrupt never leaves a partial file
+    fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        let mut tmp_name = self.file.clone().into_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_name);
+
+        if let Some(dir) = self.file.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::File::create(&tmp_path)
+            .and_then(|mut file| file.write_all(content.as_bytes()))
+            .and_then(|()| fs::rename(&tmp_path, &self.file))
+            .map_err(|e| format!("Failed to save checkpoint {:?}: {}", self.file, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("scan.json");
+
+        let mut checkpoint = ScanCheckpoint::new(&file);
+        checkpoint
+            .mark_complete(
+                PathBuf::from("/root/a"),
+                vec![PathBuf::from("/root/a/repo")],
+            )
+            .unwrap();
+
+        let resumed = ScanCheckpoint::resume(&file).unwrap();
+        assert_eq!(
+            resumed.completed_repos(Path::new("/root/a")),
+            Some(&[PathBuf::from("/root/a/repo")][..])
+        );
+        assert_eq!(resumed.completed_repos(Path::new("/root/b")), None);
+    }
+
+    #[test]
+    fn test_finished_checkpoint_starts_over() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("scan.json");
+
+        let mut checkpoint = ScanCheckpoint::new(&file);
+        checkpoint
+            .mark_complete(PathBuf::from("/root/a"), Vec::new())
+            .unwrap();
+        checkpoint.finish().unwrap();
+
+        let resumed = ScanCheckpoint::resume(&file).unwrap();
+        assert_eq!(resumed.completed_repos(Path::new("/root/a")), None);
+
+        // A missing file also starts over
+        let missing = ScanCheckpoint::resume(&temp_dir.path().join("missing.json")).unwrap();
+        assert!(missing.subtrees.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+//! This module parses and formats durations such as "500ms", "10s" or "180d".
+
+use serde::{Deserialize, Deserializer, Serializer};
+use std::time::Duration;
+
+/// Units in seconds, from the largest to the smallest
+const UNITS: [(&str, u64); 5] = [
+    ("w", 7 * 86400),
+    ("d", 86400),
+    ("h", 3600),
+    ("m", 60),
+    ("s", 1),
+];
+
+/// Parse a duration such as "500ms", "10s", "2m", "1h", "180d" or "2w"
+///
+/// Plain numbers are seconds.
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", s))?;
+
+    if unit == "ms" {
+        return Ok(Duration::from_millis(value));
+    }
+    let unit = if unit.is_empty() { "s" } else { unit };
+    let (_, secs) = UNITS.iter().find(|(name, _)| *name == unit).ok_or(format!(
+        "invalid duration unit '{}' (expected ms, s, m, h, d or w)",
+        unit
+    ))?;
+    value
+        .checked_mul(*secs)
+        .map(Duration::from_secs)
+        .ok_or(format!("duration '{}' is too large", s))
+}
+
+/// Format a duration with the largest unit that represents it exactly
+pub fn format_duration(duration: Duration) -> String {
+    if duration.subsec_nanos() != 0 {
+        return format!("{}ms", duration.as_millis());
+    }
+    let secs = duration.as_secs();
+    let (unit, size) = UNITS
+        .iter()
+        .find(|(_, size)| secs > 0 && secs.is_multiple_of(*size))
+        .unwrap_or(&("s", 1));
+    format!("{}{}", secs / size, unit)
+}
+
+/// Serialize a duration as a string, e.g. "180d"
+pub fn serialize<S: Serializer>(value: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format_duration(*value))
+}
+
+/// Deserialize a duration written as a string, e.g. "180d"
+pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+    parse_duration(&String::deserialize(d)?).map_err(serde::de::Error::custom)
+}
+
+/// Serde support for optional durations written as strings, e.g. "180d"
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => s.serialize_some(&format_duration(*duration)),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Duration>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|s| parse_duration(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("10"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("180d"), Ok(Duration::from_secs(180 * 86400)));
+        assert_eq!(parse_duration("2w"), Ok(Duration::from_secs(14 * 86400)));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("s").is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(180 * 86400)), "180d");
+        assert_eq!(format_duration(Duration::from_secs(14 * 86400)), "2w");
+        assert_eq!(format_duration(Duration::from_secs(90)), "90s");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1500ms");
+        assert_eq!(format_duration(Duration::ZERO), "0s");
+    }
+}
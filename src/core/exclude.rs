@@ -0,0 +1,354 @@
+//! This module decides which directories the scanner skips.
+//!
+//! Exclude patterns are compiled once per scan so that checking a directory
+//! name against the literal patterns is a single hash lookup.
+//!
+//! Gitignore-style patterns from `main.exclude` match paths relative to the
+//! scan root instead of names, and are compiled once per scan root.
+//!
+//! Include patterns restrict scanning to directories with matching names.
+//! They use the same syntax as exclude patterns, and exclusion wins.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// A pattern with a single `*` wildcard
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Wildcard {
+    /// `*`
+    Any,
+    /// `prefix*`
+    Prefix(String),
+    /// `*suffix`
+    Suffix(String),
+    /// `prefix*suffix`
+    PrefixSuffix(String, String),
+}
+
+impl Wildcard {
+    /// Parse a pattern, or None if it is matched literally
+    ///
+    /// Patterns with more than one `*` are matched literally.
+    fn parse(pattern: &str) -> Option<Self> {
+        let (prefix, suffix) = pattern.split_once('*')?;
+        if suffix.contains('*') {
+            return None;
+        }
+        Some(match (prefix.is_empty(), suffix.is_empty()) {
+            (true, true) => Self::Any,
+            (false, true) => Self::Prefix(prefix.to_string()),
+            (true, false) => Self::Suffix(suffix.to_string()),
+            (false, false) => Self::PrefixSuffix(prefix.to_string(), suffix.to_string()),
+        })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Prefix(prefix) => name.starts_with(prefix.as_str()),
+            Self::Suffix(suffix) => name.ends_with(suffix.as_str()),
+            Self::PrefixSuffix(prefix, suffix) => {
+                name.len() >= prefix.len() + suffix.len()
+                    && name.starts_with(prefix.as_str())
+                    && name.ends_with(suffix.as_str())
+            }
+        }
+    }
+}
+
+/// Compiled set of directory name patterns
+#[derive(Debug, Clone, Default)]
+struct NamePatterns {
+    literals: HashSet<String>,
+    wildcards: Vec<Wildcard>,
+}
+
+impl NamePatterns {
+    fn new(patterns: &[String]) -> Self {
+        let mut names = Self::default();
+        for pattern in patterns {
+            match Wildcard::parse(pattern) {
+                Some(wildcard) => {
+                    if !names.wildcards.contains(&wildcard) {
+                        names.wildcards.push(wildcard);
+                    }
+                }
+                None => {
+                    names.literals.insert(pattern.clone());
+                }
+            }
+        }
+        names
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.literals.contains(name) || self.wildcards.iter().any(|w| w.matches(name))
+    }
+}
+
+/// Compiled set of directory exclude patterns
+///
+/// Hidden directories (starting with `.`) are always excluded.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludeMatcher {
+    excludes: NamePatterns,
+    /// Names of the only directories to descend into, if any are given
+    includes: Option<NamePatterns>,
+    /// Gitignore-style patterns, anchored at the scan root
+    gitignore: Option<Gitignore>,
+}
+
+impl ExcludeMatcher {
+    /// Compile exclude patterns
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            excludes: NamePatterns::new(patterns),
+            ..Self::default()
+        }
+    }
+
+    /// Restrict scanning to directories matching one of `patterns`, unless empty
+    pub fn with_includes(mut self, patterns: &[String]) -> Self {
+        self.includes = (!patterns.is_empty()).then(|| NamePatterns::new(patterns));
+        self
+    }
+
+    /// Add gitignore-style patterns matching directories below `root`
+    ///
+    /// Patterns follow gitignore syntax: a leading `/` anchors a pattern at
+    /// `root`, a trailing `/` is implied as only directories are matched, and
+    /// `!` re-includes a directory excluded by an earlier pattern.
+    pub fn with_gitignore(mut self, root: &Path, patterns: &[String]) -> Result<Self, String> {
+        if patterns.is_empty() {
+            return Ok(self);
+        }
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in patterns {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| format!("Invalid exclude pattern {:?}: {}", pattern, e))?;
+        }
+        let gitignore = builder
+            .build()
+            .map_err(|e| format!("Invalid exclude patterns: {}", e))?;
+        self.gitignore = Some(gitignore);
+        Ok(self)
+    }
+
+    /// Check if a directory should be excluded from scanning
+    #[inline]
+    pub fn is_excluded(&self, dir_name: &str) -> bool {
+        dir_name.starts_with('.') || self.excludes.matches(dir_name)
+    }
+
+    /// Check if a directory matches the include patterns, or none are given
+    pub fn is_included(&self, dir_name: &str) -> bool {
+        self.includes
+            .as_ref()
+            .is_none_or(|includes| includes.matches(dir_name))
+    }
+
+    /// Check if the directory at `path` matches the gitignore-style patterns
+    pub fn is_path_excluded(&self, path: &Path) -> bool {
+        self.gitignore
+            .as_ref()
+            .is_some_and(|gitignore| gitignore.matched(path, true).is_ignore())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference implementation: match a name against a single pattern
+    fn matches_wildcard(name: &str, pattern: &str) -> bool {
+        if !pattern.contains('*') {
+            return name == pattern;
+        }
+
+        let parts: Vec<&str> = pattern.split('*').collect();
+        match parts.len() {
+            2 => {
+                let (prefix, suffix) = (parts[0], parts[1]);
+                name.starts_with(prefix)
+                    && name.ends_with(suffix)
+                    && name.len() >= prefix.len() + suffix.len()
+            }
+            _ => name == pattern,
+        }
+    }
+
+    fn is_match(name: &str, pattern: &str) -> bool {
+        ExcludeMatcher::new(&[pattern.to_string()]).is_excluded(name)
+    }
+
+    #[test]
+    fn test_matches_wildcard_exact() {
+        assert!(is_match("node_modules", "node_modules"));
+        assert!(!is_match("node_modules", "target"));
+    }
+
+    #[test]
+    fn test_matches_wildcard_prefix() {
+        assert!(is_match("test_file", "test*"));
+        assert!(is_match("test", "test*"));
+        assert!(!is_match("other", "test*"));
+    }
+
+    #[test]
+    fn test_matches_wildcard_suffix() {
+        assert!(is_match("file.txt", "*.txt"));
+        assert!(is_match(".txt", "*.txt"));
+        assert!(!is_match("file.rs", "*.txt"));
+    }
+
+    #[test]
+    fn test_matches_wildcard_prefix_suffix() {
+        assert!(is_match("test_file.txt", "test*.txt"));
+        assert!(is_match("test.txt", "test*.txt"));
+        assert!(!is_match("other_file.txt", "test*.txt"));
+        assert!(!is_match("test", "test*.txt"));
+        // Prefix and suffix must not overlap
+        assert!(!is_match("aba", "ab*ba"));
+    }
+
+    #[test]
+    fn test_matches_wildcard_star_only() {
+        assert!(is_match("anything", "*"));
+        assert!(is_match("", "*"));
+    }
+
+    #[test]
+    fn test_multiple_stars_match_literally() {
+        assert!(is_match("a*b*c", "a*b*c"));
+        assert!(!is_match("axbxc", "a*b*c"));
+    }
+
+    #[test]
+    fn test_is_excluded_hidden_dirs() {
+        let matcher = ExcludeMatcher::new(&[]);
+        assert!(matcher.is_excluded(".hidden"));
+        assert!(matcher.is_excluded(".git"));
+        assert!(!matcher.is_excluded("normal"));
+    }
+
+    #[test]
+    fn test_is_excluded_with_patterns() {
+        let matcher = ExcludeMatcher::new(&[
+            "node_modules".to_string(),
+            "target".to_string(),
+            "*.tmp".to_string(),
+        ]);
+
+        assert!(matcher.is_excluded("node_modules"));
+        assert!(matcher.is_excluded("target"));
+        assert!(matcher.is_excluded("file.tmp"));
+        assert!(!matcher.is_excluded("src"));
+    }
+
+    #[test]
+    fn test_include_patterns() {
+        let matcher = ExcludeMatcher::new(&[]);
+        assert!(matcher.is_included("anything"));
+
+        let matcher = ExcludeMatcher::new(&[])
+            .with_includes(&["service-*".to_string(), "shared".to_string()]);
+        assert!(matcher.is_included("service-api"));
+        assert!(matcher.is_included("shared"));
+        assert!(!matcher.is_included("docs"));
+        assert!(!matcher.is_included("my-service-api"));
+    }
+
+    #[test]
+    fn test_gitignore_patterns() {
+        let root = Path::new("/src");
+        let patterns = [
+            "/build/",
+            "**/node_modules/",
+            "out*",
+            "!out-keep",
+            "docs/*/",
+        ]
+        .map(String::from);
+        let matcher = ExcludeMatcher::new(&[])
+            .with_gitignore(root, &patterns)
+            .unwrap();
+        let excluded = |path: &str| matcher.is_path_excluded(&root.join(path));
+
+        // Anchored patterns only match at the scan root
+        assert!(excluded("build"));
+        assert!(!excluded("app/build"));
+        // Unanchored patterns match at any depth
+        assert!(excluded("node_modules"));
+        assert!(excluded("web/app/node_modules"));
+        assert!(excluded("app/out-debug"));
+        // Negated patterns re-include what earlier ones excluded
+        assert!(!excluded("out-keep"));
+        assert!(!excluded("app/out-keep"));
+        // Patterns with a slash match relative to the root
+        assert!(excluded("docs/api"));
+        assert!(!excluded("docs"));
+        assert!(!excluded("src"));
+
+        assert!(!ExcludeMatcher::new(&[]).is_path_excluded(&root.join("build")));
+        let invalid = ExcludeMatcher::new(&[]).with_gitignore(root, &["{a".to_string()]);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_matches_reference_implementation() {
+        let patterns: Vec<String> = [
+            "node_modules",
+            "target",
+            "*log",
+            "*logs",
+            "tmp*",
+            "pre*suf",
+            "*",
+            "a*b*c",
+            "",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let names = [
+            "",
+            "src",
+            "node_modules",
+            "node_module",
+            "target",
+            "targets",
+            "log",
+            "changelog",
+            "logs",
+            "tmp",
+            "tmpdir",
+            "pre_suf",
+            "presuf",
+            "presu",
+            "a*b*c",
+            "abc",
+            ".hidden",
+        ];
+
+        for count in 0..=patterns.len() {
+            let subset = &patterns[..count];
+            let without_any: Vec<String> = subset.iter().filter(|p| *p != "*").cloned().collect();
+            for set in [subset, &without_any[..]] {
+                let matcher = ExcludeMatcher::new(set);
+                for name in names {
+                    let expected =
+                        name.starts_with('.') || set.iter().any(|p| matches_wildcard(name, p));
+                    assert_eq!(
+                        matcher.is_excluded(name),
+                        expected,
+                        "name {:?} with patterns {:?}",
+                        name,
+                        set
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! This module runs user-entered git commands that only read a repository.
+//!
+//! Subcommands are checked against an allowlist, and options that write files
+//! or spawn other programs are refused, so that nothing typed into the prompt
+//! can modify a repository.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Subcommands that never modify the repository
+pub const READ_ONLY_SUBCOMMANDS: [&str; 13] = [
+    "blame",
+    "cat-file",
+    "describe",
+    "diff",
+    "grep",
+    "log",
+    "ls-files",
+    "ls-tree",
+    "rev-parse",
+    "shortlog",
+    "show",
+    "show-ref",
+    "status",
+];
+
+/// Options that write to files or run other programs, refused with any subcommand
+///
+/// Long options are also refused when abbreviated, since git accepts any
+/// unambiguous prefix, and short ones anywhere in a cluster such as `-iO`.
+/// `--filters` runs the clean and smudge filters the repository configures;
+/// refusing its prefixes also refuses `cat-file --batch --filter`.
+/// `--show-signature` runs the configured `gpg.program`.
+const REFUSED_OPTIONS: [&str; 7] = [
+    "--output",
+    "--open-files-in-pager",
+    "--ext-diff",
+    "--textconv",
+    "--filters",
+    "--show-signature",
+    "-O",
+];
+
+/// Options that abbreviate a refused one but that git matches exactly
+const EXACT_OPTIONS: [&str; 1] = ["--text"];
+
+/// Longest time a command may run before it is killed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Split the prompt input into arguments and check that they only read
+///
+/// Arguments are separated by whitespace and may be quoted with `'` or `"`.
+pub fn parse_git_command(input: &str) -> Result<Vec<String>, String> {
+    let args = split_args(input)?;
+    let Some(subcommand) = args.first() else {
+        return Err("No git command given".to_string());
+    };
+    if !READ_ONLY_SUBCOMMANDS.contains(&subcommand.as_str()) {
+        return Err(format!(
+            "'git {}' is not allowed, only read-only commands can be run",
+            subcommand
+        ));
+    }
+    if let Some(option) = args[1..].iter().find(|arg| is_refused_option(arg)) {
+        return Err(format!("Option '{}' is not allowed", option));
+    }
+    Ok(args)
+}
+
+fn is_refused_option(arg: &str) -> bool {
+    let option = arg.split('=').next().unwrap_or_default();
+    if option == "--" || EXACT_OPTIONS.contains(&option) {
+        return false;
+    }
+    REFUSED_OPTIONS.iter().any(|refused| {
+        match (option.strip_prefix("--"), refused.strip_prefix("--")) {
+            (Some(_), Some(_)) => refused.starts_with(option),
+            (None, None) => option.starts_with('-') && option[1..].contains(&refused[1..]),
+            _ => false,
+        }
+    })
+}
+
+/// Options added after the subcommand so that no program configured by the
+/// repository runs, such as an external diff tool or a textconv driver
+fn guard_options(subcommand: &str) -> &'static [&'static str] {
+    match subcommand {
+        "diff" => &["--no-ext-diff", "--no-textconv"],
+        "log" | "show" => &["--no-ext-diff", "--no-textconv", "--no-show-signature"],
+        "blame" => &["--no-textconv"],
+        _ => &[],
+    }
+}
+
+/// Split a command line on whitespace, honoring single and double quotes
+fn split_args(input: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote: Option<char> = None;
+
+    for c in input.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.get_or_insert_default().push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.get_or_insert_default();
+            }
+            (None, c) if c.is_whitespace() => args.extend(current.take()),
+            (None, c) => current.get_or_insert_default().push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err("Unclosed quote in git command".to_string());
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+/// Build the command running git with `args` in the repository at `repo_path`
+///
+/// The pager, colors and credential prompts are disabled since the output is
+/// captured rather than shown in a terminal. Optional locks are disabled too,
+/// so that `status` does not refresh the index. `args` start with the subcommand.
+pub fn build_git_command(repo_path: &Path, args: &[String]) -> Command {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo_path)
+        .args(["--no-pager", "-c", "color.ui=never"]);
+    if let Some((subcommand, rest)) = args.split_first() {
+        command
+            .arg(subcommand)
+            .args(guard_options(subcommand))
+            .args(rest);
+    }
+    command
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    command
+}
+
+/// Run a read-only git command in a repository and return its output
+///
+/// Standard error is appended to the output; a failing command returns it as the error.
+pub async fn run_git_command(repo_path: &Path, input: &str) -> Result<String, String> {
+    let args = parse_git_command(input)?;
+    let output = tokio::time::timeout(
+        COMMAND_TIMEOUT,
+        build_git_command(repo_path, &args).output(),
+    )
+    .await
+    .map_err(|_| format!("git {} timed out", args[0]))?
+    .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        return Err(match stderr.trim() {
+            "" => format!("git {} failed with {}", args[0], output.status),
+            message => message.to_string(),
+        });
+    }
+    Ok(format!("{}{}", stdout, stderr))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_permitted_commands() {
+        assert_eq!(
+            parse_git_command("log --oneline -10").unwrap(),
+            vec!["log", "--oneline", "-10"]
+        );
+        assert_eq!(
+            parse_git_command("  status -sb ").unwrap(),
+            vec!["status", "-sb"]
+        );
+        assert_eq!(
+            parse_git_command("log --grep \"fix bug\" --author='A B'").unwrap(),
+            vec!["log", "--grep", "fix bug", "--author=A B"]
+        );
+        assert_eq!(parse_git_command("show ''").unwrap(), vec!["show", ""]);
+    }
+
+    #[test]
+    fn test_rejected_commands() {
+        for input in [
+            "push",
+            "reset --hard",
+            "clean -fdx",
+            "checkout main",
+            "-c core.pager=sh status",
+            "--no-pager log",
+        ] {
+            assert!(
+                parse_git_command(input)
+                    .unwrap_err()
+                    .contains("not allowed"),
+                "{} was permitted",
+                input
+            );
+        }
+        assert!(parse_git_command("").is_err());
+        assert!(parse_git_command("log --grep 'open").is_err());
+    }
+
+    #[test]
+    fn test_rejected_options() {
+        for input in [
+            "diff --output=patch.diff",
+            "log --output patch.diff",
+            "grep -Ovim foo",
+            "grep -iOvim foo",
+            "grep --open-files-in-pager foo",
+            "grep --open-fi=vim foo",
+            "diff --out=patch.diff",
+            "diff --ext-diff",
+            "show --textconv HEAD",
+            "cat-file --filters HEAD:README.md",
+            "cat-file --filt HEAD:README.md",
+            "cat-file --batch --filters",
+            "cat-file --batch --filter",
+            "cat-file --batch=%(objectname) --filters",
+            "log --show-signature",
+            "show --show-sig HEAD",
+        ] {
+            assert!(parse_git_command(input).is_err(), "{} was permitted", input);
+        }
+        // Options that only share a prefix are fine
+        assert!(parse_git_command("log --output-indicator-new=+").is_ok());
+        assert!(parse_git_command("diff --text").is_ok());
+        assert!(parse_git_command("diff -- file").is_ok());
+        assert!(parse_git_command("log --first-parent").is_ok());
+        assert!(parse_git_command("cat-file --batch").is_ok());
+    }
+
+    #[test]
+    fn test_build_git_command() {
+        let args = parse_git_command("status -sb").unwrap();
+        let command = build_git_command(Path::new("/work/app"), &args);
+        let std_command = command.as_std();
+
+        assert_eq!(std_command.get_program(), "git");
+        let args: Vec<_> = std_command.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "-C",
+                "/work/app",
+                "--no-pager",
+                "-c",
+                "color.ui=never",
+                "status",
+                "-sb"
+            ]
+        );
+        let envs: Vec<_> = std_command.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new("GIT_OPTIONAL_LOCKS"), Some(OsStr::new("0")))));
+
+        let args = parse_git_command("log -1").unwrap();
+        let command = build_git_command(Path::new("/work/app"), &args);
+        let args: Vec<_> = command.as_std().get_args().skip(5).collect();
+        assert_eq!(
+            args,
+            [
+                "log",
+                "--no-ext-diff",
+                "--no-textconv",
+                "--no-show-signature",
+                "-1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_guards_diff_programs() {
+        let args = parse_git_command("diff HEAD").unwrap();
+        let command = build_git_command(Path::new("/work/app"), &args);
+        let args: Vec<_> = command.as_std().get_args().skip(5).collect();
+        assert_eq!(args, ["diff", "--no-ext-diff", "--no-textconv", "HEAD"]);
+    }
+
+    #[tokio::test]
+    async fn test_run_git_command() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(temp_dir.path()).unwrap();
+
+        let output = run_git_command(temp_dir.path(), "rev-parse --git-dir")
+            .await
+            .unwrap();
+        assert_eq!(output.trim(), ".git");
+        // Logging an unborn branch fails
+        assert!(run_git_command(temp_dir.path(), "log").await.is_err());
+        assert!(run_git_command(temp_dir.path(), "push").await.is_err());
+    }
+}
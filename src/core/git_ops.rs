@@ -5,35 +5,94 @@
 //! https://github.com/gitui-org/gitui/tree/master/asyncgit
 
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
-use super::repo_info::RepoInfo;
+use super::profile::RepoTiming;
+use super::repo_info::{RepoInfo, RepoInfoOptions};
 use super::worker::Worker;
 
 /// Gather repository information in parallel using rayon
 ///
 /// This is the fastest way to process repositories synchronously.
-pub fn get_repos_info_parallel(paths: &[PathBuf]) -> Vec<RepoInfo> {
+pub fn get_repos_info_parallel(paths: &[PathBuf], options: RepoInfoOptions) -> Vec<RepoInfo> {
     paths
         .par_iter()
-        .filter_map(|path| RepoInfo::from_path(path.clone()).ok())
+        .filter_map(|path| RepoInfo::from_path_with_options(path.clone(), options).ok())
         .collect()
 }
 
+/// Gather repository information in parallel, recording how long each repository took
+///
+/// Timings are returned for every path, including those that failed to open.
+pub fn get_repos_info_parallel_timed(
+    paths: &[PathBuf],
+    options: RepoInfoOptions,
+) -> (Vec<RepoInfo>, Vec<RepoTiming>) {
+    let results: Vec<(Option<RepoInfo>, RepoTiming)> = paths
+        .par_iter()
+        .map(|path| {
+            let start = Instant::now();
+            let info = RepoInfo::from_path_with_options(path.clone(), options).ok();
+            let timing = RepoTiming {
+                path: path.clone(),
+                elapsed: start.elapsed(),
+            };
+            (info, timing)
+        })
+        .collect();
+    let (infos, timings): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+    (infos.into_iter().flatten().collect(), timings)
+}
+
+/// Append repositories whose path is not in `seen`, recording their paths
+///
+/// `seen` must hold the paths of the repositories already in `repos`, which
+/// makes each check O(1) instead of a scan of the whole list. Cached entries
+/// are not in `seen`, and a repository with the path of one replaces it in place.
+pub fn append_unique_repos(
+    repos: &mut Vec<RepoInfo>,
+    seen: &mut HashSet<PathBuf>,
+    batch: impl IntoIterator<Item = RepoInfo>,
+) {
+    let cached: HashMap<PathBuf, usize> = repos
+        .iter()
+        .enumerate()
+        .filter(|(_, repo)| repo.cached)
+        .map(|(idx, repo)| (repo.basic.path.clone(), idx))
+        .collect();
+    for repo in batch {
+        if !seen.insert(repo.basic.path.clone()) {
+            continue;
+        }
+        match cached.get(&repo.basic.path) {
+            Some(&idx) => repos[idx] = repo,
+            None => repos.push(repo),
+        }
+    }
+}
+
 /// Worker for extracting repository information
 pub type RepoInfoWorker = Worker<PathBuf, RepoInfo>;
 
 impl RepoInfoWorker {
     /// Create a new repository information worker
-    pub fn for_repo_info() -> Self {
-        Self::new(RepoInfo::from_path)
+    pub fn for_repo_info(options: RepoInfoOptions) -> Self {
+        Self::new(move |path| RepoInfo::from_path_with_options(path, options))
+    }
+
+    /// Create a repository information worker that can be reset for later refreshes
+    pub fn reusable_for_repo_info(options: RepoInfoOptions) -> Self {
+        Self::reusable(move |path| RepoInfo::from_path_with_options(path, options))
     }
 
     /// Submit multiple repository paths to the worker
     ///
     /// This is a non-blocking batch operation. All paths are queued immediately,
-    /// and results can be polled later using `poll_results()`.
+    /// and results can be polled later using `poll_results()`. The batch is
+    /// closed afterwards; a reusable worker takes more after `reset()`.
     pub fn submit_repos(self: &Arc<Self>, paths: &[PathBuf]) {
         for path in paths {
             let _ = self.submit(path.clone());
@@ -41,3 +100,76 @@ impl RepoInfoWorker {
         self.finish_submitting();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_unique_repos() {
+        let mut repos = Vec::new();
+        let mut seen = HashSet::new();
+
+        let batch = (0..1000).map(|i| RepoInfo::stub("repo", &format!("/repos/{}", i % 400)));
+        append_unique_repos(&mut repos, &mut seen, batch);
+        assert_eq!(repos.len(), 400);
+
+        // Later batches only add paths not seen before
+        let batch = (350..450).map(|i| RepoInfo::stub("repo", &format!("/repos/{}", i)));
+        append_unique_repos(&mut repos, &mut seen, batch);
+        assert_eq!(repos.len(), 450);
+        assert_eq!(seen.len(), 450);
+        assert_eq!(repos[0].basic.path, PathBuf::from("/repos/0"));
+        assert_eq!(repos[449].basic.path, PathBuf::from("/repos/449"));
+    }
+
+    #[test]
+    fn test_append_replaces_cached_repos() {
+        let mut repos: Vec<RepoInfo> = ["/repos/a", "/repos/b"]
+            .iter()
+            .map(|path| RepoInfo {
+                cached: true,
+                ..RepoInfo::stub("old", path)
+            })
+            .collect();
+        let mut seen = HashSet::new();
+
+        let batch = ["/repos/c", "/repos/b"].map(|path| RepoInfo::stub("new", path));
+        append_unique_repos(&mut repos, &mut seen, batch);
+        let entries: Vec<_> = repos
+            .iter()
+            .map(|r| {
+                (
+                    r.basic.path.to_str().unwrap(),
+                    r.basic.name.as_str(),
+                    r.cached,
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("/repos/a", "old", true),
+                ("/repos/b", "new", false),
+                ("/repos/c", "new", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_timed_records_every_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("repo");
+        git2::Repository::init(&repo_path).unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        let paths = vec![repo_path.clone(), missing.clone()];
+        let (repos, timings) = get_repos_info_parallel_timed(&paths, RepoInfoOptions::default());
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].basic.path, repo_path);
+
+        let mut timed: Vec<&PathBuf> = timings.iter().map(|t| &t.path).collect();
+        timed.sort();
+        assert_eq!(timed, [&missing, &repo_path]);
+    }
+}
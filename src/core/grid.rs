@@ -0,0 +1,132 @@
+//! This module lays out repositories as a dense grid of names, for an
+//! overview of many repositories at once, and moves a cursor around in it.
+//!
+//! Cells are filled row by row and all have the width of the widest name,
+//! capped so that one long name does not leave room for a single column.
+
+/// Widest a grid cell gets; longer names are truncated
+pub const MAX_CELL_WIDTH: usize = 24;
+
+/// Spaces between the cells of a row
+pub const CELL_GAP: usize = 2;
+
+/// Dimensions of a grid fitted to the available width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridLayout {
+    /// Cells per row, at least one
+    pub columns: usize,
+    /// Display width of each cell, gaps excluded
+    pub cell_width: usize,
+}
+
+impl GridLayout {
+    /// Fit cells for names of the given display widths into `width` columns
+    pub fn fit(name_widths: impl IntoIterator<Item = usize>, width: usize) -> Self {
+        let cell_width = name_widths
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+            .clamp(1, MAX_CELL_WIDTH)
+            .min(width.max(1));
+        let columns = ((width + CELL_GAP) / (cell_width + CELL_GAP)).max(1);
+        Self {
+            columns,
+            cell_width,
+        }
+    }
+
+    /// Number of rows needed for `count` cells
+    pub fn rows(&self, count: usize) -> usize {
+        count.div_ceil(self.columns)
+    }
+}
+
+/// Direction of a cursor move in the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridMove {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Position reached by moving from `position` in a grid of `count` cells
+///
+/// Moves stop at the edges of the grid. Moving down onto a last row that is
+/// too short to have a cell below lands on its last cell.
+pub fn grid_step(position: usize, count: usize, columns: usize, direction: GridMove) -> usize {
+    let columns = columns.max(1);
+    if count == 0 {
+        return 0;
+    }
+    let position = position.min(count - 1);
+    match direction {
+        GridMove::Up => position.checked_sub(columns).unwrap_or(position),
+        GridMove::Down if position + columns < count => position + columns,
+        GridMove::Down if position / columns < (count - 1) / columns => count - 1,
+        GridMove::Down => position,
+        GridMove::Left if !position.is_multiple_of(columns) => position - 1,
+        GridMove::Right if position % columns < columns - 1 && position + 1 < count => position + 1,
+        GridMove::Left | GridMove::Right => position,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_layout_fit() {
+        // Cells of 5 with gaps of 2 fit 3 times into 19 columns, not into 18
+        assert_eq!(
+            GridLayout::fit([3, 5, 4], 19),
+            GridLayout {
+                columns: 3,
+                cell_width: 5
+            }
+        );
+        assert_eq!(GridLayout::fit([3, 5, 4], 18).columns, 2);
+
+        // Long names are capped, and a narrow area still has one column
+        assert_eq!(GridLayout::fit([60], 80).cell_width, MAX_CELL_WIDTH);
+        assert_eq!(GridLayout::fit([60], 80).columns, 3);
+        assert_eq!(
+            GridLayout::fit([10], 4),
+            GridLayout {
+                columns: 1,
+                cell_width: 4
+            }
+        );
+        assert_eq!(GridLayout::fit([], 10).columns, 4);
+
+        let layout = GridLayout::fit([5], 19);
+        assert_eq!(layout.rows(0), 0);
+        assert_eq!(layout.rows(3), 1);
+        assert_eq!(layout.rows(7), 3);
+    }
+
+    #[test]
+    fn test_grid_step() {
+        // 0 1 2
+        // 3 4 5
+        // 6 7
+        let step = |position, direction| grid_step(position, 8, 3, direction);
+        assert_eq!(step(4, GridMove::Up), 1);
+        assert_eq!(step(1, GridMove::Up), 1);
+        assert_eq!(step(1, GridMove::Down), 4);
+        assert_eq!(step(4, GridMove::Down), 7);
+        // No cell below 5, so the short last row's last cell is reached
+        assert_eq!(step(5, GridMove::Down), 7);
+        assert_eq!(step(7, GridMove::Down), 7);
+
+        assert_eq!(step(4, GridMove::Left), 3);
+        assert_eq!(step(3, GridMove::Left), 3);
+        assert_eq!(step(4, GridMove::Right), 5);
+        assert_eq!(step(5, GridMove::Right), 5);
+        assert_eq!(step(7, GridMove::Right), 7);
+
+        assert_eq!(grid_step(0, 0, 3, GridMove::Down), 0);
+        assert_eq!(grid_step(2, 5, 1, GridMove::Right), 2);
+        assert_eq!(grid_step(2, 5, 1, GridMove::Down), 3);
+    }
+}
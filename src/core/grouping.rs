@@ -0,0 +1,98 @@
+//! This module groups repositories by parent directory or remote host.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::core::RepoInfo;
+use crate::core::remote_url::host_from_url;
+
+/// Group of repositories without a remote, when grouping by host
+pub const NO_REMOTE_GROUP: &str = "(no remote)";
+
+/// Group of repositories whose remote is a local path, when grouping by host
+pub const LOCAL_REMOTE_GROUP: &str = "(local)";
+
+/// How repositories are grouped in the TUI list
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// A flat list
+    #[default]
+    None,
+    /// Parent directory of the repository
+    Directory,
+    /// Host of the remote URL
+    Host,
+}
+
+/// Key of the group a repository belongs to, or None when not grouping
+pub fn group_key(repo: &RepoInfo, group_by: GroupBy) -> Option<String> {
+    match group_by {
+        GroupBy::None => None,
+        GroupBy::Directory => Some(
+            repo.basic
+                .path
+                .parent()
+                .map(|parent| parent.display().to_string())
+                .unwrap_or_default(),
+        ),
+        GroupBy::Host => Some(match repo.remote.url.as_deref() {
+            Some(url) => host_from_url(url).unwrap_or_else(|| LOCAL_REMOTE_GROUP.to_string()),
+            None => NO_REMOTE_GROUP.to_string(),
+        }),
+    }
+}
+
+/// Group repository indices by key
+///
+/// Groups are sorted by key, and repositories keep their order in the list.
+/// Returns no groups when not grouping.
+pub fn group_repos(repos: &[RepoInfo], group_by: GroupBy) -> Vec<(String, Vec<usize>)> {
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (idx, repo) in repos.iter().enumerate() {
+        if let Some(key) = group_key(repo, group_by) {
+            groups.entry(key).or_default().push(idx);
+        }
+    }
+    groups.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_remote(name: &str, path: &str, url: Option<&str>) -> RepoInfo {
+        let mut repo = RepoInfo::stub(name, path);
+        repo.remote.url = url.map(str::to_string);
+        repo
+    }
+
+    #[test]
+    fn test_group_repos() {
+        let repos = vec![
+            with_remote("web", "/work/web", Some("git@github.com:me/web.git")),
+            with_remote("dots", "/home/dots", None),
+            with_remote("api", "/work/api", Some("https://gitlab.com/me/api")),
+            with_remote("lib", "/home/lib", Some("https://github.com/me/lib")),
+            with_remote("mirror", "/work/mirror", Some("/srv/git/mirror.git")),
+        ];
+
+        assert_eq!(
+            group_repos(&repos, GroupBy::Directory),
+            [
+                ("/home".to_string(), vec![1, 3]),
+                ("/work".to_string(), vec![0, 2, 4]),
+            ]
+        );
+        assert_eq!(
+            group_repos(&repos, GroupBy::Host),
+            [
+                (LOCAL_REMOTE_GROUP.to_string(), vec![4]),
+                (NO_REMOTE_GROUP.to_string(), vec![1]),
+                ("github.com".to_string(), vec![0, 3]),
+                ("gitlab.com".to_string(), vec![2]),
+            ]
+        );
+        assert!(group_repos(&repos, GroupBy::None).is_empty());
+    }
+}
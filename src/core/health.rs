@@ -0,0 +1,179 @@
+//! This module computes a single health score per repository.
+//!
+//! The score goes from 100 (nothing to do) down to 0. Each factor gives a
+//! penalty between 0 and 1, and the score is
+//!
+//! ```text
+//! 100 * (1 - sum(weight * penalty) / sum(weight))
+//! ```
+//!
+//! rounded to the nearest integer, with the weights from `[health]`:
+//!
+//! - dirty: uncommitted changes over 10, capped at 1; any conflict gives 1
+//! - sync: commits ahead plus behind over 10, capped at 1
+//! - stashes: stashes over 5, capped at 1
+//! - stale: age of the last commit over `stale_after`, capped at 1
+
+use std::time::SystemTime;
+
+use super::RepoInfo;
+use crate::config::HealthConfig;
+
+/// Uncommitted changes giving the full dirty penalty
+const DIRTY_CAP: usize = 10;
+
+/// Commits ahead plus behind giving the full sync penalty
+const SYNC_CAP: usize = 10;
+
+/// Stashes giving the full stash penalty
+const STASH_CAP: usize = 5;
+
+/// Compute the health score of a repository, from 0 (worst) to 100 (best)
+///
+/// With all weights at zero, every repository scores 100.
+pub fn health_score(repo: &RepoInfo, weights: &HealthConfig, now: SystemTime) -> u8 {
+    let dirty = match repo.working.conflicts {
+        0 => ratio(repo.working.total_changes(), DIRTY_CAP),
+        _ => 1.0,
+    };
+    let sync = ratio(repo.sync.ahead + repo.sync.behind, SYNC_CAP);
+    let stashes = ratio(repo.stash.count, STASH_CAP);
+    let stale = match repo
+        .commit
+        .time
+        .and_then(|time| now.duration_since(time).ok())
+    {
+        Some(age) if !weights.stale_after.is_zero() => {
+            (age.as_secs_f64() / weights.stale_after.as_secs_f64()).min(1.0)
+        }
+        _ => 0.0,
+    };
+
+    let factors = [
+        (weights.dirty, dirty),
+        (weights.sync, sync),
+        (weights.stashes, stashes),
+        (weights.stale, stale),
+    ];
+    let total: f64 = factors.iter().map(|&(weight, _)| f64::from(weight)).sum();
+    if total == 0.0 {
+        return 100;
+    }
+    let penalty: f64 = factors
+        .iter()
+        .map(|&(weight, penalty)| f64::from(weight) * penalty)
+        .sum();
+    (100.0 * (1.0 - penalty / total)).round() as u8
+}
+
+/// Letter grade of a health score: A (90+), B (75+), C (50+), D (25+) or F
+pub fn health_grade(score: u8) -> char {
+    match score {
+        90.. => 'A',
+        75.. => 'B',
+        50.. => 'C',
+        25.. => 'D',
+        _ => 'F',
+    }
+}
+
+/// Compute the health score of every repository
+pub fn apply_health(repos: &mut [RepoInfo], weights: &HealthConfig, now: SystemTime) {
+    for repo in repos {
+        repo.health = Some(health_score(repo, weights, now));
+    }
+}
+
+/// `count / cap`, capped at 1
+fn ratio(count: usize, cap: usize) -> f64 {
+    count.min(cap) as f64 / cap as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const DAY: u64 = 86400;
+
+    /// Repository with 5 changes, 2 commits ahead, 1 stash and a commit 180 days old
+    fn neglected_repo(now: SystemTime) -> RepoInfo {
+        let mut repo = RepoInfo::stub("app", "/repos/app");
+        repo.working.modified = 3;
+        repo.working.untracked = 2;
+        repo.sync.ahead = 2;
+        repo.stash.count = 1;
+        repo.commit.time = Some(now - Duration::from_secs(180 * DAY));
+        repo
+    }
+
+    #[test]
+    fn test_health_score() {
+        let now = SystemTime::now();
+        let weights = HealthConfig {
+            stale_after: Duration::from_secs(360 * DAY),
+            ..Default::default()
+        };
+
+        let mut clean = RepoInfo::stub("clean", "/repos/clean");
+        clean.commit.time = Some(now);
+        assert_eq!(health_score(&clean, &weights, now), 100);
+
+        // 30 * 0.5 + 30 * 0.2 + 10 * 0.2 + 30 * 0.5 = 38 points lost
+        let repo = neglected_repo(now);
+        assert_eq!(health_score(&repo, &weights, now), 62);
+
+        let mut worst = repo.clone();
+        worst.working.conflicts = 1;
+        worst.sync.behind = 20;
+        worst.stash.count = 9;
+        worst.commit.time = Some(now - Duration::from_secs(1000 * DAY));
+        assert_eq!(health_score(&worst, &weights, now), 0);
+
+        // Repositories without commits are not stale
+        let unborn = RepoInfo::stub("new", "/repos/new");
+        assert_eq!(health_score(&unborn, &weights, now), 100);
+    }
+
+    #[test]
+    fn test_weights_change_score() {
+        let now = SystemTime::now();
+        let repo = neglected_repo(now);
+        let default = health_score(&repo, &HealthConfig::default(), now);
+
+        // Only changes count: half of the dirty cap loses half of the score
+        let dirty_only = HealthConfig {
+            dirty: 1,
+            sync: 0,
+            stashes: 0,
+            stale: 0,
+            ..Default::default()
+        };
+        assert_eq!(health_score(&repo, &dirty_only, now), 50);
+
+        let ignore_age = HealthConfig {
+            stale: 0,
+            ..Default::default()
+        };
+        assert!(health_score(&repo, &ignore_age, now) > default);
+
+        let nothing = HealthConfig {
+            dirty: 0,
+            sync: 0,
+            stashes: 0,
+            stale: 0,
+            ..Default::default()
+        };
+        assert_eq!(health_score(&repo, &nothing, now), 100);
+    }
+
+    #[test]
+    fn test_health_grade() {
+        assert_eq!(health_grade(100), 'A');
+        assert_eq!(health_grade(90), 'A');
+        assert_eq!(health_grade(89), 'B');
+        assert_eq!(health_grade(62), 'C');
+        assert_eq!(health_grade(25), 'D');
+        assert_eq!(health_grade(0), 'F');
+    }
+}
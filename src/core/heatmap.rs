@@ -0,0 +1,71 @@
+//! This module maps the age of a repository's last commit to a color, for
+//! coloring repo names as a heatmap from fresh to stale instead of by status.
+//!
+//! Ages run from green through yellow to red over a horizon; repos whose last
+//! commit is at least that old are all fully red.
+
+use ratatui::style::Color;
+use std::time::{Duration, SystemTime};
+
+/// Age at which repos become fully stale when no stale threshold is configured
+pub const DEFAULT_HORIZON: Duration = Duration::from_secs(180 * 24 * 60 * 60);
+
+/// Color of a repo whose last commit is `age` old, on a gradient over `horizon`
+pub fn age_color(age: Duration, horizon: Duration) -> Color {
+    let t = match horizon.is_zero() {
+        true => 1.0,
+        false => (age.as_secs_f64() / horizon.as_secs_f64()).min(1.0),
+    };
+    // Green to yellow in the first half, yellow to red in the second
+    let red = (t * 2.0).min(1.0);
+    let green = ((1.0 - t) * 2.0).min(1.0);
+    Color::Rgb(
+        (red * 255.0).round() as u8,
+        (green * 255.0).round() as u8,
+        0,
+    )
+}
+
+/// Color of a repo by the time of its last commit, or None without a commit
+///
+/// Commit times in the future count as fresh.
+pub fn commit_time_color(time: Option<SystemTime>, horizon: Duration) -> Option<Color> {
+    let age = SystemTime::now()
+        .duration_since(time?)
+        .unwrap_or(Duration::ZERO);
+    Some(age_color(age, horizon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn test_age_color_gradient() {
+        let horizon = 100 * DAY;
+        assert_eq!(age_color(Duration::ZERO, horizon), Color::Rgb(0, 255, 0));
+        assert_eq!(age_color(25 * DAY, horizon), Color::Rgb(128, 255, 0));
+        assert_eq!(age_color(50 * DAY, horizon), Color::Rgb(255, 255, 0));
+        assert_eq!(age_color(75 * DAY, horizon), Color::Rgb(255, 128, 0));
+        assert_eq!(age_color(100 * DAY, horizon), Color::Rgb(255, 0, 0));
+        // Past the horizon stays at the stale end
+        assert_eq!(age_color(1000 * DAY, horizon), Color::Rgb(255, 0, 0));
+        assert_eq!(age_color(DAY, Duration::ZERO), Color::Rgb(255, 0, 0));
+    }
+
+    #[test]
+    fn test_commit_time_color() {
+        let now = SystemTime::now();
+        assert_eq!(commit_time_color(None, DEFAULT_HORIZON), None);
+        assert_eq!(
+            commit_time_color(Some(now + DAY), DEFAULT_HORIZON),
+            Some(Color::Rgb(0, 255, 0))
+        );
+        assert_eq!(
+            commit_time_color(Some(now - 365 * DAY), DEFAULT_HORIZON),
+            Some(Color::Rgb(255, 0, 0))
+        );
+    }
+}
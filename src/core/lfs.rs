@@ -0,0 +1,68 @@
+//! This module finds Git LFS file locks held by the local user.
+//!
+//! Locks are read with `git lfs locks --local --json`, which lists the locks
+//! cached in the repository without contacting the LFS server.
+
+use serde::de::IgnoredAny;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Count the LFS locks held locally in the repository at `workdir`
+///
+/// Repositories whose git dir has no `lfs` directory never used LFS and have
+/// no locks, so git-lfs is not run for them. Returns None if git-lfs is not
+/// installed or fails.
+pub fn count_local_locks(workdir: &Path, git_dir: &Path) -> Option<usize> {
+    if !git_dir.join("lfs").is_dir() {
+        return Some(0);
+    }
+    let output = Command::new("git")
+        .args(["lfs", "locks", "--local", "--json"])
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_locks_json(&String::from_utf8_lossy(&output.stdout)).ok()
+}
+
+/// Count the locks in the JSON output of `git lfs locks --json`
+pub fn parse_locks_json(output: &str) -> Result<usize, String> {
+    // Older git-lfs versions print nothing instead of an empty list
+    if output.trim().is_empty() {
+        return Ok(0);
+    }
+    serde_json::from_str::<Vec<IgnoredAny>>(output)
+        .map(|locks| locks.len())
+        .map_err(|e| format!("Invalid git lfs locks output: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Output of `git lfs locks --local --json` with two locks
+    const LOCKS_FIXTURE: &str = r#"[
+        {"id":"101","path":"art/hero.psd","owner":{"name":"ada"},"locked_at":"2024-05-01T10:00:00Z"},
+        {"id":"102","path":"levels/intro.umap","owner":{"name":"ada"},"locked_at":"2024-05-02T09:30:00Z"}
+    ]"#;
+
+    #[test]
+    fn test_parse_locks_json() {
+        assert_eq!(parse_locks_json(LOCKS_FIXTURE), Ok(2));
+        assert_eq!(parse_locks_json("[]\n"), Ok(0));
+        assert_eq!(parse_locks_json(""), Ok(0));
+        assert!(parse_locks_json("Error: not a git-lfs repository").is_err());
+    }
+
+    #[test]
+    fn test_no_locks_without_lfs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        // git-lfs is not run, so this holds whether or not it is installed
+        assert_eq!(count_local_locks(temp_dir.path(), repo.path()), Some(0));
+    }
+}
@@ -1,8 +1,34 @@
+pub mod archive;
+pub mod author;
+pub mod branches;
+mod checkpoint;
+pub mod duration;
+pub mod exclude;
+pub mod git_command;
 pub mod git_ops;
+pub mod grid;
+pub mod grouping;
+pub mod health;
+pub mod heatmap;
+pub mod lfs;
+pub mod open;
+pub mod profile;
+pub mod remote_url;
 pub mod repo_info;
+pub mod results_cache;
+pub mod safety;
 pub mod scanner;
+pub mod snapshot;
+pub mod sorting;
+pub mod status;
+pub mod tags;
+pub mod text;
+pub mod tree;
+pub mod watch;
 mod worker;
 
-pub use git_ops::{RepoInfoWorker, get_repos_info_parallel};
-pub use repo_info::RepoInfo;
-pub use scanner::scan_directories;
+pub use git_ops::{
+    RepoInfoWorker, append_unique_repos, get_repos_info_parallel, get_repos_info_parallel_timed,
+};
+pub use repo_info::{RepoInfo, RepoInfoOptions};
+pub use scanner::{ScanOutcome, scan_directories, scan_directories_traced};
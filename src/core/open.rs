@@ -0,0 +1,164 @@
+//! This module builds the commands that open a repository outside of reponest.
+//!
+//! Inside tmux, a repository is opened in a new tmux window (or pane) from a
+//! configurable command template. Elsewhere, it is opened in the file manager.
+//! A terminal emulator can also be opened at the repository, from a command
+//! template defaulting to the platform's terminal.
+
+use std::ffi::OsStr;
+use std::path::Path;
+use std::process::Command;
+
+/// Placeholder replaced by the repository path in command templates
+pub const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Tmux command run unless configured otherwise
+pub const DEFAULT_TMUX_COMMAND: &str = "tmux new-window -c {path}";
+
+/// Check whether reponest runs inside tmux, from the value of `$TMUX`
+pub fn in_tmux(tmux_env: Option<&OsStr>) -> bool {
+    tmux_env.is_some_and(|value| !value.is_empty())
+}
+
+/// Terminal command run on the platform `os` (as in `std::env::consts::OS`) unless configured otherwise
+pub fn default_terminal_command(os: &str) -> &'static str {
+    match os {
+        "macos" => "open -a Terminal {path}",
+        "windows" => "wt -d {path}",
+        _ => "gnome-terminal --working-directory={path}",
+    }
+}
+
+/// Build the command opening `path` in tmux from a command template
+///
+/// The template is split on whitespace, then each `{path}` is replaced by the
+/// path, so paths with spaces stay a single argument.
+pub fn tmux_command(template: &str, path: &Path) -> Result<Command, String> {
+    template_command(template, path, "tmux")
+}
+
+/// Build the command opening a terminal emulator at `path` from a command template
+///
+/// Without a template, the platform's default terminal command is used.
+pub fn terminal_command(template: Option<&str>, path: &Path) -> Result<Command, String> {
+    let template = template.unwrap_or(default_terminal_command(std::env::consts::OS));
+    template_command(template, path, "terminal")
+}
+
+/// Build a command from a template, replacing each `{path}` in its arguments
+fn template_command(template: &str, path: &Path, what: &str) -> Result<Command, String> {
+    let mut args = template.split_whitespace();
+    let program = args
+        .next()
+        .ok_or_else(|| format!("The {} command is empty", what))?;
+    let mut command = Command::new(program);
+    for arg in args {
+        match arg.contains(PATH_PLACEHOLDER) {
+            true => command.arg(arg.replace(PATH_PLACEHOLDER, &path.to_string_lossy())),
+            false => command.arg(arg),
+        };
+    }
+    Ok(command)
+}
+
+/// Build the command opening `path` in the platform's file manager
+pub fn file_manager_command(path: &Path) -> Command {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    let mut command = Command::new(program);
+    command.arg(path);
+    command
+}
+
+/// Build the command opening `path`: in tmux when inside it, else in the file manager
+pub fn open_repo_command(
+    path: &Path,
+    tmux_template: &str,
+    tmux_env: Option<&OsStr>,
+) -> Result<Command, String> {
+    match in_tmux(tmux_env) {
+        true => tmux_command(tmux_template, path),
+        false => Ok(file_manager_command(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &Command) -> Vec<&OsStr> {
+        command.get_args().collect()
+    }
+
+    #[test]
+    fn test_in_tmux() {
+        assert!(in_tmux(Some(OsStr::new("/tmp/tmux-1000/default,123,0"))));
+        assert!(!in_tmux(Some(OsStr::new(""))));
+        assert!(!in_tmux(None));
+    }
+
+    #[test]
+    fn test_tmux_command() {
+        let path = Path::new("/work/my app");
+        let tmux = Some(OsStr::new("/tmp/tmux-1000/default,123,0"));
+
+        let command = open_repo_command(path, DEFAULT_TMUX_COMMAND, tmux).unwrap();
+        assert_eq!(command.get_program(), "tmux");
+        assert_eq!(args(&command), ["new-window", "-c", "/work/my app"]);
+
+        let command = open_repo_command(path, "tmux split-window -h -c {path}", tmux).unwrap();
+        assert_eq!(args(&command), ["split-window", "-h", "-c", "/work/my app"]);
+
+        let command = tmux_command("tmux new-window -n repo -c={path}", path).unwrap();
+        assert_eq!(
+            args(&command),
+            ["new-window", "-n", "repo", "-c=/work/my app"]
+        );
+
+        assert!(open_repo_command(path, "  ", tmux).is_err());
+    }
+
+    #[test]
+    fn test_terminal_command() {
+        let path = Path::new("/work/my app");
+        let expected: [(&str, &str, &[&str]); 3] = [
+            (
+                "linux",
+                "gnome-terminal",
+                &["--working-directory=/work/my app"],
+            ),
+            ("macos", "open", &["-a", "Terminal", "/work/my app"]),
+            ("windows", "wt", &["-d", "/work/my app"]),
+        ];
+        for (os, program, expected_args) in expected {
+            let command = terminal_command(Some(default_terminal_command(os)), path).unwrap();
+            assert_eq!(command.get_program(), program);
+            assert_eq!(args(&command), expected_args);
+        }
+
+        let command = terminal_command(Some("kitty --directory {path}"), path).unwrap();
+        assert_eq!(command.get_program(), "kitty");
+        assert_eq!(args(&command), ["--directory", "/work/my app"]);
+
+        let command = terminal_command(None, path).unwrap();
+        assert!(
+            args(&command)
+                .iter()
+                .any(|arg| arg.to_string_lossy().contains("my app"))
+        );
+        assert!(terminal_command(Some(""), path).is_err());
+    }
+
+    #[test]
+    fn test_file_manager_outside_tmux() {
+        let path = Path::new("/work/app");
+        let command = open_repo_command(path, DEFAULT_TMUX_COMMAND, None).unwrap();
+        assert_ne!(command.get_program(), "tmux");
+        assert_eq!(args(&command), ["/work/app"]);
+    }
+}
@@ -0,0 +1,120 @@
+//! This module records where the time of a scan went, for `list --profile-timing`.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Time spent gathering the information of a single repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoTiming {
+    pub path: PathBuf,
+    pub elapsed: Duration,
+}
+
+/// Breakdown of the time spent scanning and gathering repository information
+#[derive(Debug, Clone, Default)]
+pub struct ScanProfile {
+    /// Number of directories read by the scan
+    pub dirs_visited: usize,
+    /// Number of repositories found by the scan
+    pub repos_found: usize,
+    /// Time spent walking the scan directories
+    pub scan: Duration,
+    /// Time spent gathering git information of all repositories
+    pub git_info: Duration,
+    /// Processing time of each repository, including those that failed to open
+    pub repo_timings: Vec<RepoTiming>,
+}
+
+impl ScanProfile {
+    /// The `n` repositories that took longest to process, slowest first
+    pub fn slowest(&self, n: usize) -> Vec<&RepoTiming> {
+        let mut timings: Vec<&RepoTiming> = self.repo_timings.iter().collect();
+        timings.sort_by(|a, b| b.elapsed.cmp(&a.elapsed).then_with(|| a.path.cmp(&b.path)));
+        timings.truncate(n);
+        timings
+    }
+
+    /// Format the breakdown as a plain-text report listing the `n` slowest repositories
+    pub fn report(&self, n: usize) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Scan timing:");
+        let _ = writeln!(out, "  directories visited  {}", self.dirs_visited);
+        let _ = writeln!(out, "  repos found          {}", self.repos_found);
+        let _ = writeln!(out, "  scan                 {} ms", self.scan.as_millis());
+        let _ = writeln!(
+            out,
+            "  git info             {} ms",
+            self.git_info.as_millis()
+        );
+
+        let slowest = self.slowest(n);
+        if !slowest.is_empty() {
+            let _ = writeln!(out, "  slowest repos:");
+            for timing in slowest {
+                let _ = writeln!(
+                    out,
+                    "  {:>8} ms  {}",
+                    timing.elapsed.as_millis(),
+                    timing.path.display()
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(path: &str, ms: u64) -> RepoTiming {
+        RepoTiming {
+            path: PathBuf::from(path),
+            elapsed: Duration::from_millis(ms),
+        }
+    }
+
+    fn profile() -> ScanProfile {
+        ScanProfile {
+            dirs_visited: 120,
+            repos_found: 3,
+            scan: Duration::from_millis(45),
+            git_info: Duration::from_millis(310),
+            repo_timings: vec![
+                timing("/work/app", 20),
+                timing("/work/monorepo", 300),
+                timing("/work/lib", 20),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_slowest_repos() {
+        let profile = profile();
+        let slowest: Vec<&str> = profile
+            .slowest(2)
+            .iter()
+            .map(|t| t.path.to_str().unwrap())
+            .collect();
+        // Ties are ordered by path
+        assert_eq!(slowest, ["/work/monorepo", "/work/app"]);
+        assert_eq!(profile.slowest(10).len(), 3);
+        assert!(ScanProfile::default().slowest(5).is_empty());
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(
+            profile().report(1),
+            "Scan timing:\n\
+             \x20 directories visited  120\n\
+             \x20 repos found          3\n\
+             \x20 scan                 45 ms\n\
+             \x20 git info             310 ms\n\
+             \x20 slowest repos:\n\
+             \x20      300 ms  /work/monorepo\n"
+        );
+        assert!(!ScanProfile::default().report(5).contains("slowest"));
+    }
+}
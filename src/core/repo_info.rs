@@ -1,68 +1,227 @@
 //! This module contains all data structures for representing Git repository information.
 
-use git2::{Repository, StatusOptions};
-use serde::Serialize;
-use std::path::PathBuf;
+use git2::{
+    BranchType, ConfigLevel, IndexEntryExtendedFlag, IndexEntryFlag, Oid, Repository,
+    RepositoryState, StatusOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::branches::contains_commit;
+use super::lfs;
+use super::remote_url::repo_name_from_url;
+use super::status::DirtyCategories;
+use crate::config::{AppConfig, CompareAgainst, WorkdirMtime};
+
+/// Git config key holding a repository's display name
+pub const NAME_CONFIG_KEY: &str = "reponest.name";
+
+/// Most commits walked when counting contributors
+pub const CONTRIBUTOR_WALK_LIMIT: usize = 500;
+
+/// Branch names commonly used as a remote's default branch
+const DEFAULT_BRANCH_NAMES: [&str; 5] = ["main", "master", "trunk", "develop", "default"];
 
 /// Basic repository identification
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoBasicInfo {
     pub path: PathBuf,
+    /// Display name: `reponest.name` from the git config, else the remote's
+    /// repo name, else the path relative to the superproject, else the directory name
     pub name: String,
+    /// Name of the repository directory
+    pub dir_name: String,
     pub branch: String,
+    /// HEAD points to a branch without commits (e.g. right after `git init`)
+    #[serde(default)]
+    pub is_unborn: bool,
+    /// HEAD points to a commit instead of a branch (e.g. after checking out a tag)
+    #[serde(default)]
+    pub is_detached: bool,
+    /// Path of the repository this one is a registered submodule of
+    pub superproject: Option<PathBuf>,
+    /// Name of the main repository if this is a linked worktree
+    pub worktree_of: Option<String>,
+    /// Tags from the user's tags file
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 /// Repository sync status with remote
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RepoSyncStatus {
     pub ahead: usize,
     pub behind: usize,
+    /// Whether the current branch has a remote-tracking branch to compare against
+    pub has_upstream: bool,
+    /// The configured upstream branch no longer exists (e.g. deleted on the remote)
+    pub upstream_gone: bool,
+    /// The current branch tracks a former default branch missing from origin,
+    /// whose default branch (`origin/HEAD`) is now another one (e.g. master to main)
+    pub default_branch_changed: bool,
+    /// Which branch the counts are relative to
+    pub compare_against: CompareAgainst,
+    /// Remote-tracking branch the counts were computed against (e.g. "origin/main")
+    pub compare_ref: Option<String>,
+    /// Local tags whose commit is on no remote-tracking branch, if counted
+    pub unpushed_tags: Option<usize>,
+    /// Local branches merged into neither their upstream nor the default branch, if counted
+    pub unmerged_branches: Option<usize>,
 }
 
 /// Repository working directory status
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RepoWorkingStatus {
     pub is_dirty: bool,
     pub staged: usize,
     pub modified: usize,
     pub untracked: usize,
     pub conflicts: usize,
+    /// Total conflict hunks across conflicted files, if counted
+    pub conflict_hunks: Option<usize>,
+    /// Hunks of tracked files changed in the working tree but not staged, if counted
+    pub unstaged_hunks: Option<usize>,
+    /// An `index.lock` file exists, e.g. left behind by a crashed git process
+    pub locked: bool,
+    /// Operation in progress (e.g. a rebase stopped at a conflict), if any
+    pub operation: Option<RepoOperation>,
+    /// Git LFS locks held locally, if counted
+    pub lfs_locks: Option<usize>,
+    /// Index entries flagged assume-unchanged or skip-worktree, if counted
+    ///
+    /// Git status does not report changes to these files.
+    pub assume_unchanged: Option<usize>,
+    /// When the working tree was last modified, if computed
+    pub workdir_mtime: Option<SystemTime>,
+}
+
+impl RepoWorkingStatus {
+    /// Number of uncommitted changes: staged, modified, untracked and conflicted files
+    pub fn total_changes(&self) -> usize {
+        self.staged + self.modified + self.untracked + self.conflicts
+    }
+}
+
+/// Multi-step git operation a repository is in the middle of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    /// Applying patches with `git am`
+    ApplyMailbox,
+}
+
+impl RepoOperation {
+    /// Human-readable name of the operation
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+            Self::Bisect => "bisect",
+            Self::ApplyMailbox => "git am",
+        }
+    }
+
+    /// Operation a repository state stands for, or None if the repository is idle
+    fn from_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(Self::Merge),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(Self::Revert),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+                Some(Self::CherryPick)
+            }
+            RepositoryState::Bisect => Some(Self::Bisect),
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Some(Self::Rebase),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+                Some(Self::ApplyMailbox)
+            }
+        }
+    }
 }
 
 /// Repository remote information
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RepoRemoteInfo {
     pub url: Option<String>,
+    /// Number of configured remotes
+    pub count: usize,
+    /// Time of the last fetch, or None if the repository was never fetched
+    pub last_fetch: Option<SystemTime>,
+    /// Remote the current branch tracks (e.g. "origin"), if it has an upstream
+    pub tracking_remote: Option<String>,
 }
 
 /// Repository commit information
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RepoCommitInfo {
     pub message: Option<String>,
     pub author: Option<String>,
+    /// Email of the author of the last commit
+    pub author_email: Option<String>,
+    /// Commit time of the last commit
+    pub time: Option<SystemTime>,
+    /// Distinct author emails over the last commits of HEAD, if counted
+    pub contributors: Option<usize>,
 }
 
 /// Repository stash information
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RepoStashInfo {
     pub count: usize,
 }
 
+/// Hygiene oddities involving ignore rules
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RepoAnomalies {
+    /// Ignored files or directories present in the working tree
+    pub ignored: usize,
+    /// Tracked files that are also matched by an ignore rule
+    pub tracked_ignored: usize,
+}
+
+impl RepoAnomalies {
+    /// Check whether any anomaly was found
+    pub fn has_any(&self) -> bool {
+        self.ignored > 0 || self.tracked_ignored > 0
+    }
+}
+
 /// File changes in the repository
-#[derive(Debug, Clone, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RepoFileChanges {
     pub changes: Vec<FileChange>,
 }
 
 /// Represents a change in a file within the repository
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileChange {
     pub path: String,
     pub status: FileChangeStatus,
+    /// Number of conflict marker groups in the file, if counted
+    pub conflict_hunks: Option<usize>,
 }
 
 /// Enum for the status of a file change
-#[derive(Debug, Clone, PartialEq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FileChangeStatus {
     Staged,
@@ -71,8 +230,71 @@ pub enum FileChangeStatus {
     Conflicted,
 }
 
+impl FileChangeStatus {
+    /// Human-readable name of the category
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Staged => "Staged",
+            Self::Modified => "Modified",
+            Self::Untracked => "Untracked",
+            Self::Conflicted => "Conflicted",
+        }
+    }
+}
+
+/// Options controlling how much work is done when gathering repository info
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RepoInfoOptions {
+    /// Read conflicted files to count their conflict hunks
+    pub count_conflict_hunks: bool,
+    /// Which branch ahead/behind counts are relative to
+    pub compare_against: CompareAgainst,
+    /// Skip the working tree status, the most expensive part to compute
+    pub skip_working_status: bool,
+    /// Look for ignored files and tracked files matched by ignore rules (slow)
+    pub scan_anomalies: bool,
+    /// How the last modification time of the working tree is found
+    pub workdir_mtime: WorkdirMtime,
+    /// Categories of changes that make the repository dirty
+    pub dirty_categories: DirtyCategories,
+    /// Count tags not pushed to any remote (enumerates all tags and remote refs)
+    pub count_unpushed_tags: bool,
+    /// Count local branches with unmerged work (a merge-base check per branch)
+    pub count_unmerged_branches: bool,
+    /// Diff the index against the working tree to count unstaged hunks
+    pub count_unstaged_hunks: bool,
+    /// Walk recent history to count distinct authors
+    pub count_contributors: bool,
+    /// Run git-lfs to count locally held LFS locks
+    pub count_lfs_locks: bool,
+    /// Read the whole index to count assume-unchanged and skip-worktree entries
+    pub count_assume_unchanged: bool,
+}
+
+impl RepoInfoOptions {
+    /// Options as set in the application configuration
+    ///
+    /// Safe mode turns off the options that run external commands.
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            count_conflict_hunks: config.main.conflict_hunks,
+            compare_against: config.main.compare_against,
+            skip_working_status: false,
+            scan_anomalies: config.main.scan_anomalies,
+            workdir_mtime: config.main.workdir_mtime,
+            dirty_categories: DirtyCategories::from_categories(&config.main.dirty_includes),
+            count_unpushed_tags: config.main.count_unpushed_tags,
+            count_unmerged_branches: config.main.count_unmerged_branches,
+            count_unstaged_hunks: config.main.unstaged_hunks,
+            count_contributors: config.main.count_contributors,
+            count_lfs_locks: config.main.count_lfs_locks && !config.main.safe_mode,
+            count_assume_unchanged: config.main.count_assume_unchanged,
+        }
+    }
+}
+
 /// Information about a Git repository
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoInfo {
     pub basic: RepoBasicInfo,
     pub sync: RepoSyncStatus,
@@ -81,6 +303,17 @@ pub struct RepoInfo {
     pub commit: RepoCommitInfo,
     pub stash: RepoStashInfo,
     pub files: RepoFileChanges,
+    /// Ignore rule anomalies, if scanned for
+    pub anomalies: Option<RepoAnomalies>,
+    /// The working tree status was skipped and is not known yet
+    #[serde(default)]
+    pub partial: bool,
+    /// Health score from 0 to 100, if computed (see `core::health`)
+    #[serde(default)]
+    pub health: Option<u8>,
+    /// Loaded from the results cache and not refreshed by a scan yet
+    #[serde(skip)]
+    pub cached: bool,
 }
 
 /// Statistics about file changes in the repository
@@ -90,17 +323,95 @@ struct FileChangeStatistic {
 }
 
 impl RepoInfo {
+    /// Check whether this repository carries `tag`
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.basic.tags.iter().any(|t| t == tag)
+    }
+
+    /// Replace the git information with freshly loaded `info`, keeping the tags
+    pub fn reload_from(&mut self, mut info: Self) {
+        info.basic.tags = std::mem::take(&mut self.basic.tags);
+        *self = info;
+    }
+
+    /// Check whether this repository is a submodule of another repository
+    pub fn is_submodule(&self) -> bool {
+        self.basic.superproject.is_some()
+    }
+
+    /// Check whether the last commit is older than `threshold`
+    ///
+    /// Repositories without commits are never stale.
+    pub fn is_stale(&self, threshold: Duration, now: SystemTime) -> bool {
+        self.commit
+            .time
+            .is_some_and(|time| now.duration_since(time).is_ok_and(|age| age > threshold))
+    }
+
     /// Create a RepoInfo from a repository path
     pub fn from_path(path: PathBuf) -> Result<Self, String> {
+        Self::from_path_with_options(path, RepoInfoOptions::default())
+    }
+
+    /// Create a RepoInfo from a repository path with the given options
+    pub fn from_path_with_options(path: PathBuf, options: RepoInfoOptions) -> Result<Self, String> {
         let mut repo = Repository::open(&path)
             .map_err(|e| format!("Failed to open repo at {:?}: {}", path, e))?;
 
-        let basic = Self::get_basic_info(&repo, path)?;
-        let sync = Self::get_sync_status(&repo);
-        let change_stat = Self::get_file_changes(&repo)?;
         let remote = Self::get_remote_info(&repo);
-        let commit = Self::get_commit_info(&repo);
-        let stash = Self::get_stash_info(&mut repo);
+        let basic = Self::get_basic_info(&repo, path, remote.url.as_deref())?;
+        let mut change_stat = if options.skip_working_status {
+            // The index lock and the operation are cheap enough to check even without the status
+            FileChangeStatistic {
+                working: RepoWorkingStatus {
+                    locked: Self::is_index_locked(&repo),
+                    operation: RepoOperation::from_state(repo.state()),
+                    ..Default::default()
+                },
+                files: RepoFileChanges::default(),
+            }
+        } else {
+            Self::get_file_changes(&repo, options)?
+        };
+        change_stat.working.workdir_mtime =
+            Self::get_workdir_mtime(&repo, options, &change_stat.files);
+        if options.count_lfs_locks
+            && let Some(workdir) = repo.workdir()
+        {
+            change_stat.working.lfs_locks = lfs::count_local_locks(workdir, repo.commondir());
+        }
+        if options.count_assume_unchanged {
+            change_stat.working.assume_unchanged = Self::count_assume_unchanged(&repo).ok();
+        }
+        let anomalies = if options.scan_anomalies && !options.skip_working_status {
+            Some(Self::get_anomalies(&repo)?)
+        } else {
+            None
+        };
+
+        // Without commits there is nothing to compare, commit or stash
+        let (mut sync, mut commit, stash) = if basic.is_unborn {
+            let sync = RepoSyncStatus {
+                compare_against: options.compare_against,
+                ..Default::default()
+            };
+            (sync, RepoCommitInfo::default(), RepoStashInfo::default())
+        } else {
+            (
+                Self::get_sync_status(&repo, options.compare_against),
+                Self::get_commit_info(&repo),
+                Self::get_stash_info(&mut repo),
+            )
+        };
+        if options.count_unpushed_tags {
+            sync.unpushed_tags = Self::count_unpushed_tags(&repo).ok();
+        }
+        if options.count_unmerged_branches {
+            sync.unmerged_branches = Self::count_unmerged_branches(&repo).ok();
+        }
+        if options.count_contributors && !basic.is_unborn {
+            commit.contributors = Self::count_contributors(&repo).ok();
+        }
 
         Ok(Self {
             basic,
@@ -110,57 +421,397 @@ impl RepoInfo {
             commit,
             stash,
             files: change_stat.files,
+            anomalies,
+            partial: options.skip_working_status,
+            health: None,
+            cached: false,
         })
     }
 
     /// Get basic repository information
-    fn get_basic_info(repo: &Repository, path: PathBuf) -> Result<RepoBasicInfo, String> {
-        let name = path
+    fn get_basic_info(
+        repo: &Repository,
+        path: PathBuf,
+        remote_url: Option<&str>,
+    ) -> Result<RepoBasicInfo, String> {
+        let dir_name = path
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("?")
             .to_string();
-        let branch = match repo.head() {
-            Ok(head) => head.shorthand().unwrap_or("?").to_string(),
-            Err(_) => "?".to_string(),
+        let (branch, is_unborn) = match repo.head() {
+            Ok(head) => (head.shorthand().unwrap_or("?").to_string(), false),
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => (
+                Self::get_unborn_branch_name(repo).unwrap_or("?".to_string()),
+                true,
+            ),
+            Err(_) => ("?".to_string(), false),
+        };
+
+        let superproject = Self::find_superproject(&path);
+        let worktree_of = Self::get_worktree_main_name(repo, remote_url);
+        let name = Self::get_configured_name(repo)
+            .or_else(|| remote_url.and_then(repo_name_from_url))
+            .or_else(|| {
+                let relative = path.strip_prefix(superproject.as_ref()?).ok()?;
+                Some(relative.to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| dir_name.clone());
+
+        Ok(RepoBasicInfo {
+            path,
+            name,
+            dir_name,
+            branch,
+            is_unborn,
+            is_detached: repo.head_detached().unwrap_or(false),
+            superproject,
+            worktree_of,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Get the display name set with `git config reponest.name`, if any
+    ///
+    /// Only the repository's own config is read, as a name set globally would
+    /// give every repository the same name.
+    fn get_configured_name(repo: &Repository) -> Option<String> {
+        let config = repo.config().ok()?.open_level(ConfigLevel::Local).ok()?;
+        let name = config.get_string(NAME_CONFIG_KEY).ok()?;
+        let name = name.trim();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// Get when the working tree was last modified, as configured in `options`
+    ///
+    /// Walking the files is skipped along with the working tree status.
+    fn get_workdir_mtime(
+        repo: &Repository,
+        options: RepoInfoOptions,
+        files: &RepoFileChanges,
+    ) -> Option<SystemTime> {
+        let workdir = repo.workdir()?;
+        let mtime = |path: &Path| fs::symlink_metadata(path).and_then(|m| m.modified()).ok();
+        match options.workdir_mtime {
+            WorkdirMtime::Off => None,
+            WorkdirMtime::Dir => mtime(workdir),
+            WorkdirMtime::Files if options.skip_working_status => None,
+            WorkdirMtime::Files => {
+                let index = repo.index().ok()?;
+                let tracked = index
+                    .iter()
+                    .map(|entry| workdir.join(String::from_utf8_lossy(&entry.path).as_ref()));
+                let untracked = files
+                    .changes
+                    .iter()
+                    .filter(|c| c.status == FileChangeStatus::Untracked)
+                    .map(|c| workdir.join(&c.path));
+                tracked.chain(untracked).filter_map(|p| mtime(&p)).max()
+            }
+        }
+    }
+
+    /// Check whether the index is locked by an `index.lock` file
+    ///
+    /// Linked worktrees have their own index, so the lock is looked up in the
+    /// worktree's git dir.
+    fn is_index_locked(repo: &Repository) -> bool {
+        repo.path().join("index.lock").exists()
+    }
+
+    /// Get the name of the main repository of a linked worktree
+    ///
+    /// The main repository is found through the common dir that the worktree
+    /// shares with it. Like other repos, it is named after its remote if it has one.
+    fn get_worktree_main_name(repo: &Repository, remote_url: Option<&str>) -> Option<String> {
+        if !repo.is_worktree() {
+            return None;
+        }
+        if let Some(name) = remote_url.and_then(repo_name_from_url) {
+            return Some(name);
+        }
+
+        // The common dir is the main repo's .git dir, or the repo itself if bare
+        let common_dir = repo.commondir();
+        let main_dir = match common_dir.file_name() {
+            Some(name) if name == ".git" => common_dir.parent()?,
+            _ => common_dir,
         };
+        let name = main_dir.file_name()?.to_string_lossy();
+        Some(name.strip_suffix(".git").unwrap_or(&name).to_string())
+    }
+
+    /// Find the closest enclosing repository that registers `path` as a submodule
+    ///
+    /// Only the nearest enclosing repository is checked, as that is the one
+    /// whose `.gitmodules` can list it.
+    fn find_superproject(path: &Path) -> Option<PathBuf> {
+        let parent = path
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.join(".git").exists())?;
+        if !parent.join(".gitmodules").is_file() {
+            return None;
+        }
+
+        let relative = path.strip_prefix(parent).ok()?;
+        let parent_repo = Repository::open(parent).ok()?;
+        let submodules = parent_repo.submodules().ok()?;
+        submodules
+            .iter()
+            .any(|sm| sm.path() == relative)
+            .then(|| parent.to_path_buf())
+    }
 
-        Ok(RepoBasicInfo { path, name, branch })
+    /// Get the name of the branch an unborn HEAD points to
+    fn get_unborn_branch_name(repo: &Repository) -> Option<String> {
+        let head = repo.find_reference("HEAD").ok()?;
+        let target = head.symbolic_target()?;
+        Some(target.trim_start_matches("refs/heads/").to_string())
     }
 
     /// Get repository sync status with remote
-    fn get_sync_status(repo: &Repository) -> RepoSyncStatus {
-        let (ahead, behind) = Self::get_ahead_behind(repo).unwrap_or((0, 0));
-        RepoSyncStatus { ahead, behind }
+    fn get_sync_status(repo: &Repository, compare_against: CompareAgainst) -> RepoSyncStatus {
+        let compare_ref = match compare_against {
+            CompareAgainst::Upstream => Self::get_upstream_ref(repo),
+            CompareAgainst::DefaultBranch => Self::get_default_branch_ref(repo),
+        };
+        let ahead_behind = compare_ref
+            .as_deref()
+            .and_then(|name| Self::get_ahead_behind(repo, name).ok());
+        let (ahead, behind) = ahead_behind.unwrap_or((0, 0));
+        let upstream_gone = Self::is_upstream_gone(repo);
+        RepoSyncStatus {
+            ahead,
+            behind,
+            has_upstream: ahead_behind.is_some(),
+            upstream_gone,
+            default_branch_changed: Self::is_default_branch_changed(repo),
+            compare_against,
+            compare_ref: compare_ref
+                .filter(|_| ahead_behind.is_some())
+                .map(|name| name.trim_start_matches("refs/remotes/").to_string()),
+            unpushed_tags: None,
+            unmerged_branches: None,
+        }
     }
 
-    /// Get ahead/behind counts with respect to the upstream
-    fn get_ahead_behind(repo: &Repository) -> Result<(usize, usize), git2::Error> {
-        let head = repo.head()?;
-        let local_oid = head
-            .target()
-            .ok_or_else(|| git2::Error::from_str("HEAD has no target"))?;
+    /// Count local tags whose commit is not reachable from any remote-tracking branch
+    ///
+    /// Like ahead/behind counts, this is only as accurate as the last fetch. A
+    /// tag on a commit that is on a remote but was not pushed itself is not counted.
+    fn count_unpushed_tags(repo: &Repository) -> Result<usize, git2::Error> {
+        let mut tag_commits = Vec::new();
+        for name in repo.tag_names(None)?.iter().flatten() {
+            let reference = repo.find_reference(&format!("refs/tags/{}", name))?;
+            // Tags of trees or blobs have no commit to push
+            if let Ok(commit) = reference.peel_to_commit() {
+                tag_commits.push(commit.id());
+            }
+        }
+        if tag_commits.is_empty() {
+            return Ok(0);
+        }
 
-        let branch_name = head
-            .shorthand()
-            .ok_or_else(|| git2::Error::from_str("No branch name"))?;
+        // Commits reachable from a tag but from no remote-tracking branch
+        let mut walk = repo.revwalk()?;
+        for reference in repo.references_glob("refs/remotes/*")?.flatten() {
+            if let Ok(commit) = reference.peel_to_commit() {
+                walk.hide(commit.id())?;
+            }
+        }
+        for &commit in &tag_commits {
+            walk.push(commit)?;
+        }
+        let unpushed: HashSet<Oid> = walk.flatten().collect();
+        Ok(tag_commits
+            .iter()
+            .filter(|commit| unpushed.contains(commit))
+            .count())
+    }
 
-        let upstream_name = format!("refs/remotes/origin/{}", branch_name);
-        let upstream = match repo.find_reference(&upstream_name) {
-            Ok(r) => r,
-            Err(_) => return Ok((0, 0)),
+    /// Count local branches whose tip is on neither their upstream nor the default branch
+    ///
+    /// The default branch is the remote's, or else a local `main` or `master`.
+    /// Like ahead/behind counts, this is only as accurate as the last fetch.
+    fn count_unmerged_branches(repo: &Repository) -> Result<usize, git2::Error> {
+        let default_tip = Self::get_default_branch_ref(repo)
+            .or_else(|| {
+                ["refs/heads/main", "refs/heads/master"]
+                    .into_iter()
+                    .find(|name| repo.find_reference(name).is_ok())
+                    .map(str::to_string)
+            })
+            .and_then(|name| repo.find_reference(&name).ok()?.target());
+
+        let mut count = 0;
+        for (branch, _) in repo.branches(Some(BranchType::Local))?.flatten() {
+            let Some(tip) = branch.get().target() else {
+                continue;
+            };
+            let upstream_tip = branch.upstream().ok().and_then(|u| u.get().target());
+            if !upstream_tip
+                .into_iter()
+                .chain(default_tip)
+                .any(|target| contains_commit(repo, target, tip))
+            {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Count distinct author emails over the last commits reachable from HEAD
+    ///
+    /// At most `CONTRIBUTOR_WALK_LIMIT` commits are walked, so long histories
+    /// only count their recent authors. Emails are compared case-insensitively.
+    fn count_contributors(repo: &Repository) -> Result<usize, git2::Error> {
+        let mut walk = repo.revwalk()?;
+        walk.push_head()?;
+        let mut emails = HashSet::new();
+        for oid in walk.take(CONTRIBUTOR_WALK_LIMIT) {
+            let commit = repo.find_commit(oid?)?;
+            if let Some(email) = commit.author().email() {
+                emails.insert(email.to_lowercase());
+            }
+        }
+        Ok(emails.len())
+    }
+
+    /// Count index entries flagged assume-unchanged or skip-worktree
+    ///
+    /// Both flags make git ignore changes to the file in the working tree, set
+    /// with `git update-index --assume-unchanged` or `--skip-worktree`.
+    fn count_assume_unchanged(repo: &Repository) -> Result<usize, git2::Error> {
+        let index = repo.index()?;
+        Ok(index
+            .iter()
+            .filter(|entry| {
+                IndexEntryFlag::from_bits_truncate(entry.flags).is_valid()
+                    || IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended)
+                        .is_skip_worktree()
+            })
+            .count())
+    }
+
+    /// Count hunks of the diff between the index and the working tree
+    ///
+    /// Untracked files are left out; a partially staged file counts only the
+    /// hunks that remain unstaged.
+    fn count_unstaged_hunks(repo: &Repository) -> Result<usize, git2::Error> {
+        let diff = repo.diff_index_to_workdir(None, None)?;
+        let mut hunks = 0;
+        diff.foreach(
+            &mut |_, _| true,
+            None,
+            Some(&mut |_, _| {
+                hunks += 1;
+                true
+            }),
+            None,
+        )?;
+        Ok(hunks)
+    }
+
+    /// Check if the current branch has an upstream configured whose
+    /// remote-tracking ref is missing
+    fn is_upstream_gone(repo: &Repository) -> bool {
+        let Ok(head) = repo.head() else {
+            return false;
+        };
+        let Some(head_name) = head.name().filter(|_| head.is_branch()) else {
+            return false;
+        };
+
+        // The upstream name is resolved from config, even if the ref itself is missing
+        match repo.branch_upstream_name(head_name) {
+            Ok(upstream_name) => upstream_name
+                .as_str()
+                .is_some_and(|name| repo.find_reference(name).is_err()),
+            Err(_) => false,
+        }
+    }
+
+    /// Check if the current branch tracks a former default branch of origin
+    ///
+    /// This holds when `origin/HEAD` points to an existing branch while the
+    /// branch the current one tracks (its upstream, or else the origin branch
+    /// of the same name) is missing and has a common default branch name. Other
+    /// branches missing from origin, like merged feature branches, do not count.
+    fn is_default_branch_changed(repo: &Repository) -> bool {
+        let default_exists = repo
+            .find_reference("refs/remotes/origin/HEAD")
+            .ok()
+            .and_then(|remote_head| remote_head.symbolic_target().map(str::to_string))
+            .is_some_and(|target| repo.find_reference(&target).is_ok());
+        if !default_exists {
+            return false;
+        }
+        let Ok(head) = repo.head() else {
+            return false;
+        };
+        let Some(head_name) = head.name().filter(|_| head.is_branch()) else {
+            return false;
         };
+        let tracked = match repo.branch_upstream_name(head_name) {
+            Ok(upstream_name) => upstream_name.as_str().map(str::to_string),
+            Err(_) => head
+                .shorthand()
+                .map(|branch| format!("refs/remotes/origin/{}", branch)),
+        };
+        tracked.is_some_and(|tracked| {
+            tracked
+                .strip_prefix("refs/remotes/origin/")
+                .is_some_and(|branch| DEFAULT_BRANCH_NAMES.contains(&branch))
+                && repo.find_reference(&tracked).is_err()
+        })
+    }
+
+    /// Get the remote-tracking ref of the current branch, if it exists
+    fn get_upstream_ref(repo: &Repository) -> Option<String> {
+        let head = repo.head().ok()?;
+        let branch_name = head.shorthand()?;
+        let upstream_name = format!("refs/remotes/origin/{}", branch_name);
+        repo.find_reference(&upstream_name).ok()?;
+        Some(upstream_name)
+    }
 
-        let upstream_oid = upstream
+    /// Get the remote-tracking ref of the remote's default branch
+    ///
+    /// Uses `origin/HEAD` if set (e.g. by clone), falling back to
+    /// `origin/main` and then `origin/master`.
+    fn get_default_branch_ref(repo: &Repository) -> Option<String> {
+        if let Ok(remote_head) = repo.find_reference("refs/remotes/origin/HEAD")
+            && let Some(target) = remote_head.symbolic_target()
+            && repo.find_reference(target).is_ok()
+        {
+            return Some(target.to_string());
+        }
+
+        ["refs/remotes/origin/main", "refs/remotes/origin/master"]
+            .into_iter()
+            .find(|name| repo.find_reference(name).is_ok())
+            .map(str::to_string)
+    }
+
+    /// Get ahead/behind counts of HEAD with respect to the given ref
+    fn get_ahead_behind(repo: &Repository, ref_name: &str) -> Result<(usize, usize), git2::Error> {
+        let local_oid = repo
+            .head()?
             .target()
-            .ok_or_else(|| git2::Error::from_str("Upstream has no target"))?;
+            .ok_or_else(|| git2::Error::from_str("HEAD has no target"))?;
+        let base_oid = repo.find_reference(ref_name)?.peel_to_commit()?.id();
 
-        let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid)?;
-        Ok((ahead, behind))
+        // Counts are relative to the common ancestor of both sides
+        repo.merge_base(local_oid, base_oid)?;
+        repo.graph_ahead_behind(local_oid, base_oid)
     }
 
     /// Get file change statistics for the repository
-    fn get_file_changes(repo: &Repository) -> Result<FileChangeStatistic, String> {
+    fn get_file_changes(
+        repo: &Repository,
+        options: RepoInfoOptions,
+    ) -> Result<FileChangeStatistic, String> {
         let mut status_opts = StatusOptions::new();
         status_opts
             .show(git2::StatusShow::IndexAndWorkdir)
@@ -170,8 +821,6 @@ impl RepoInfo {
             .statuses(Some(&mut status_opts))
             .map_err(|e| format!("Failed to get statuses: {}", e))?;
 
-        let is_dirty = statuses.iter().any(|s| s.status() != git2::Status::CURRENT);
-
         let mut staged = 0;
         let mut modified = 0;
         let mut untracked = 0;
@@ -187,80 +836,165 @@ impl RepoInfo {
                 file_changes.push(FileChange {
                     path: file_path,
                     status: FileChangeStatus::Conflicted,
+                    conflict_hunks: None,
                 });
             } else if status.is_index_new()
                 || status.is_index_modified()
                 || status.is_index_deleted()
+                || status.is_index_renamed()
+                || status.is_index_typechange()
             {
                 staged += 1;
                 file_changes.push(FileChange {
                     path: file_path,
                     status: FileChangeStatus::Staged,
+                    conflict_hunks: None,
                 });
-            } else if status.is_wt_modified() || status.is_wt_deleted() {
+            } else if status.is_wt_modified()
+                || status.is_wt_deleted()
+                || status.is_wt_renamed()
+                || status.is_wt_typechange()
+            {
                 modified += 1;
                 file_changes.push(FileChange {
                     path: file_path,
                     status: FileChangeStatus::Modified,
+                    conflict_hunks: None,
                 });
             } else if status.is_wt_new() {
                 untracked += 1;
                 file_changes.push(FileChange {
                     path: file_path,
                     status: FileChangeStatus::Untracked,
+                    conflict_hunks: None,
                 });
             }
         }
 
-        Ok(FileChangeStatistic {
-            working: RepoWorkingStatus {
-                is_dirty,
-                staged,
-                modified,
-                untracked,
-                conflicts,
+        let mut conflict_hunks = None;
+        if options.count_conflict_hunks
+            && let Some(workdir) = repo.workdir()
+        {
+            let mut total = 0;
+            for change in file_changes
+                .iter_mut()
+                .filter(|c| c.status == FileChangeStatus::Conflicted)
+            {
+                change.conflict_hunks = count_conflict_hunks_in_file(&workdir.join(&change.path));
+                total += change.conflict_hunks.unwrap_or(0);
+            }
+            conflict_hunks = Some(total);
+        }
+
+        let mut working = RepoWorkingStatus {
+            is_dirty: false,
+            staged,
+            modified,
+            untracked,
+            conflicts,
+            conflict_hunks,
+            unstaged_hunks: match options.count_unstaged_hunks {
+                true => Self::count_unstaged_hunks(repo).ok(),
+                false => None,
             },
+            locked: Self::is_index_locked(repo),
+            operation: RepoOperation::from_state(repo.state()),
+            lfs_locks: None,
+            assume_unchanged: None,
+            // Depends on the file changes, so it is filled in afterwards
+            workdir_mtime: None,
+        };
+        working.is_dirty = options.dirty_categories.is_dirty(&working);
+
+        Ok(FileChangeStatistic {
+            working,
             files: RepoFileChanges {
                 changes: file_changes,
             },
         })
     }
 
+    /// Count ignored files present in the tree and tracked files matched by ignore rules
+    ///
+    /// Ignored directories are counted once, without looking inside them.
+    fn get_anomalies(repo: &Repository) -> Result<RepoAnomalies, String> {
+        let mut status_opts = StatusOptions::new();
+        status_opts
+            .show(git2::StatusShow::Workdir)
+            .include_untracked(false)
+            .include_ignored(true)
+            .recurse_ignored_dirs(false);
+
+        let statuses = repo
+            .statuses(Some(&mut status_opts))
+            .map_err(|e| format!("Failed to get statuses: {}", e))?;
+        let ignored = statuses.iter().filter(|s| s.status().is_ignored()).count();
+
+        let index = repo
+            .index()
+            .map_err(|e| format!("Failed to read index: {}", e))?;
+        let tracked_ignored = index
+            .iter()
+            .filter_map(|entry| String::from_utf8(entry.path).ok())
+            .filter(|path| repo.is_path_ignored(path).unwrap_or(false))
+            .count();
+
+        Ok(RepoAnomalies {
+            ignored,
+            tracked_ignored,
+        })
+    }
+
     /// Get remote repository information
     fn get_remote_info(repo: &Repository) -> RepoRemoteInfo {
-        // Try to get remote from current branch's upstream
-        let remote_name = repo
-            .head()
+        RepoRemoteInfo {
+            url: Self::get_remote_url(repo),
+            count: repo.remotes().map(|remotes| remotes.len()).unwrap_or(0),
+            last_fetch: Self::get_last_fetch(repo),
+            tracking_remote: Self::get_tracking_remote(repo),
+        }
+    }
+
+    /// Get the time of the last fetch from the modification time of FETCH_HEAD
+    ///
+    /// Git writes FETCH_HEAD to the common directory, shared by all worktrees.
+    fn get_last_fetch(repo: &Repository) -> Option<SystemTime> {
+        std::fs::metadata(repo.commondir().join("FETCH_HEAD"))
+            .and_then(|m| m.modified())
             .ok()
-            .and_then(|head| {
-                let branch_name = head.shorthand()?;
-                let branch = repo
-                    .find_branch(branch_name, git2::BranchType::Local)
-                    .ok()?;
-                branch.upstream().ok()?.name().ok()?.map(|s| s.to_string())
-            })
-            .and_then(|upstream_name| {
-                // Extract remote name from upstream (e.g., "origin/main" -> "origin")
-                upstream_name.split('/').next().map(|s| s.to_string())
-            });
+    }
+
+    /// Get the name of the remote the current branch's upstream belongs to
+    ///
+    /// The remote is read from the branch config rather than split off the
+    /// upstream name, since both remote and branch names may contain slashes.
+    fn get_tracking_remote(repo: &Repository) -> Option<String> {
+        let head = repo.head().ok()?;
+        if !head.is_branch() {
+            return None;
+        }
+        let remote = repo.branch_upstream_remote(head.name()?).ok()?;
+        remote.as_str().map(str::to_string)
+    }
+
+    /// Get the URL of the most relevant remote
+    fn get_remote_url(repo: &Repository) -> Option<String> {
+        // Try to get remote from current branch's upstream
+        let remote_name = Self::get_tracking_remote(repo);
 
         // If we found a remote from upstream, use it
         if let Some(name) = remote_name
             && let Ok(remote) = repo.find_remote(&name)
             && let Some(url) = remote.url()
         {
-            return RepoRemoteInfo {
-                url: Some(url.to_string()),
-            };
+            return Some(url.to_string());
         }
 
         // Fallback to "origin"
         if let Ok(remote) = repo.find_remote("origin")
             && let Some(url) = remote.url()
         {
-            return RepoRemoteInfo {
-                url: Some(url.to_string()),
-            };
+            return Some(url.to_string());
         }
 
         // Fallback to first available remote
@@ -270,14 +1004,12 @@ impl RepoInfo {
                     && let Ok(remote) = repo.find_remote(name)
                     && let Some(url) = remote.url()
                 {
-                    return RepoRemoteInfo {
-                        url: Some(url.to_string()),
-                    };
+                    return Some(url.to_string());
                 }
             }
         }
 
-        RepoRemoteInfo { url: None }
+        None
     }
 
     /// Get the last commit information
@@ -288,9 +1020,20 @@ impl RepoInfo {
                     let message = commit
                         .message()
                         .map(|m| m.lines().next().unwrap_or("").to_string());
-                    let author = Some(commit.author().name().unwrap_or("Unknown").to_string());
-
-                    RepoCommitInfo { message, author }
+                    let signature = commit.author();
+                    let author = Some(signature.name().unwrap_or("Unknown").to_string());
+                    let author_email = signature.email().map(str::to_string);
+                    let time = u64::try_from(commit.time().seconds())
+                        .ok()
+                        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+
+                    RepoCommitInfo {
+                        message,
+                        author,
+                        author_email,
+                        time,
+                        contributors: None,
+                    }
                 } else {
                     RepoCommitInfo::default()
                 }
@@ -311,9 +1054,82 @@ impl RepoInfo {
     }
 }
 
+/// Count conflict hunks in a file
+///
+/// Returns None if the file cannot be read or looks binary.
+fn count_conflict_hunks_in_file(path: &Path) -> Option<usize> {
+    let content = std::fs::read(path).ok()?;
+    if content.iter().take(8000).any(|&b| b == 0) {
+        return None;
+    }
+    Some(count_conflict_hunks(&String::from_utf8_lossy(&content)))
+}
+
+/// Count complete `<<<<<<<` / `=======` / `>>>>>>>` marker groups in text
+fn count_conflict_hunks(content: &str) -> usize {
+    #[derive(PartialEq)]
+    enum Marker {
+        None,
+        Ours,
+        Theirs,
+    }
+
+    let mut state = Marker::None;
+    let mut hunks = 0;
+    for line in content.lines() {
+        if line.starts_with("<<<<<<<") {
+            state = Marker::Ours;
+        } else if line.starts_with("=======") && state == Marker::Ours {
+            state = Marker::Theirs;
+        } else if line.starts_with(">>>>>>>") && state == Marker::Theirs {
+            hunks += 1;
+            state = Marker::None;
+        }
+    }
+    hunks
+}
+
+#[cfg(test)]
+impl RepoInfo {
+    /// Create a clean repository info for tests without opening a repository
+    pub(crate) fn stub(name: &str, path: &str) -> Self {
+        Self {
+            basic: RepoBasicInfo {
+                path: PathBuf::from(path),
+                name: name.to_string(),
+                dir_name: name.to_string(),
+                branch: "main".to_string(),
+                is_unborn: false,
+                is_detached: false,
+                superproject: None,
+                worktree_of: None,
+                tags: Vec::new(),
+            },
+            sync: RepoSyncStatus::default(),
+            working: RepoWorkingStatus::default(),
+            remote: RepoRemoteInfo::default(),
+            commit: RepoCommitInfo::default(),
+            stash: RepoStashInfo::default(),
+            files: RepoFileChanges::default(),
+            anomalies: None,
+            partial: false,
+            health: None,
+            cached: false,
+        }
+    }
+
+    /// Create a clean repository info under `/work` with `branch` checked out
+    pub(crate) fn stub_on_branch(name: &str, branch: &str) -> Self {
+        let mut repo = Self::stub(name, &format!("/work/{}", name));
+        repo.basic.branch = branch.to_string();
+        repo
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ChangeCategory;
     use git2::{Repository, Signature};
     use std::fs;
     use std::path::Path;
@@ -375,6 +1191,20 @@ mod tests {
         assert_eq!(info.stash.count, 0);
     }
 
+    #[test]
+    fn test_total_changes() {
+        let working = RepoWorkingStatus {
+            staged: 2,
+            modified: 3,
+            untracked: 4,
+            conflicts: 1,
+            conflict_hunks: Some(6),
+            ..Default::default()
+        };
+        assert_eq!(working.total_changes(), 10);
+        assert_eq!(RepoWorkingStatus::default().total_changes(), 0);
+    }
+
     #[test]
     fn test_repo_info_with_untracked_files() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -403,6 +1233,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_safe_mode_skips_lfs_locks() {
+        let mut config = AppConfig::default();
+        config.main.count_lfs_locks = true;
+        assert!(RepoInfoOptions::from_config(&config).count_lfs_locks);
+
+        config.main.safe_mode = true;
+        assert!(!RepoInfoOptions::from_config(&config).count_lfs_locks);
+    }
+
+    #[test]
+    fn test_untracked_files_excluded_from_dirty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        create_file(repo_path, "build.out", "artifact");
+
+        let mut config = AppConfig::default();
+        config.main.dirty_includes = vec![
+            ChangeCategory::Staged,
+            ChangeCategory::Modified,
+            ChangeCategory::Conflicts,
+        ];
+        let options = RepoInfoOptions::from_config(&config);
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+
+        // The untracked file is still counted, but the repo is clean
+        assert!(!info.working.is_dirty);
+        assert_eq!(info.working.untracked, 1);
+        assert_eq!(
+            crate::core::status::overall_status(&info, &[]).label,
+            "no upstream"
+        );
+
+        // Staging it makes the repo dirty
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("build.out")).unwrap();
+        index.write().unwrap();
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert!(info.working.is_dirty);
+    }
+
     #[test]
     fn test_repo_info_with_staged_files() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -539,6 +1411,10 @@ mod tests {
         // Should have initial commit info
         assert_eq!(info.commit.message, Some("Initial commit".to_string()));
         assert_eq!(info.commit.author, Some("Test User".to_string()));
+        assert_eq!(
+            info.commit.author_email.as_deref(),
+            Some("test@example.com")
+        );
     }
 
     #[test]
@@ -567,6 +1443,7 @@ mod tests {
             info.remote.url,
             Some("https://github.com/test/repo.git".to_string())
         );
+        assert_eq!(info.remote.count, 1);
     }
 
     #[test]
@@ -579,6 +1456,7 @@ mod tests {
 
         // Should have no remote info
         assert_eq!(info.remote.url, None);
+        assert_eq!(info.remote.count, 0);
     }
 
     #[test]
@@ -602,6 +1480,7 @@ mod tests {
             info.remote.url,
             Some("https://github.com/origin/repo.git".to_string())
         );
+        assert_eq!(info.remote.count, 3);
     }
 
     #[test]
@@ -658,6 +1537,63 @@ mod tests {
             info.remote.url,
             Some("https://github.com/upstream/repo.git".to_string())
         );
+        assert_eq!(info.remote.tracking_remote.as_deref(), Some("upstream"));
+    }
+
+    #[test]
+    fn test_tracking_remote_with_slashes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        repo.remote("origin", "https://github.com/origin/repo.git")
+            .unwrap();
+        repo.remote("team/fork", "https://github.com/team/fork.git")
+            .unwrap();
+
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut branch = repo.branch("feature/foo", &commit, false).unwrap();
+        repo.set_head("refs/heads/feature/foo").unwrap();
+
+        // Slashes in the branch name
+        repo.reference(
+            "refs/remotes/origin/feature/foo",
+            commit.id(),
+            false,
+            "create upstream branch",
+        )
+        .unwrap();
+        branch.set_upstream(Some("origin/feature/foo")).unwrap();
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.basic.branch, "feature/foo");
+        assert_eq!(info.remote.tracking_remote.as_deref(), Some("origin"));
+
+        // Slashes in the remote name too
+        repo.reference(
+            "refs/remotes/team/fork/feature/foo",
+            commit.id(),
+            false,
+            "create upstream branch",
+        )
+        .unwrap();
+        branch.set_upstream(Some("team/fork/feature/foo")).unwrap();
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.remote.tracking_remote.as_deref(), Some("team/fork"));
+        assert_eq!(
+            info.remote.url,
+            Some("https://github.com/team/fork.git".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tracking_remote_untracked_branch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo = create_test_repo(temp_dir.path());
+        repo.remote("origin", "https://github.com/origin/repo.git")
+            .unwrap();
+
+        let info = RepoInfo::from_path(temp_dir.path().to_path_buf()).unwrap();
+        assert_eq!(info.remote.count, 1);
+        assert_eq!(info.remote.tracking_remote, None);
     }
 
     #[test]
@@ -680,6 +1616,7 @@ mod tests {
         let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
 
         // Should be in sync
+        assert!(info.sync.has_upstream);
         assert_eq!(info.sync.ahead, 0);
         assert_eq!(info.sync.behind, 0);
     }
@@ -880,4 +1817,863 @@ mod tests {
         // Should have 2 stashes
         assert_eq!(info.stash.count, 2);
     }
+
+    #[test]
+    fn test_last_fetch_never_fetched() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert!(info.remote.last_fetch.is_none());
+    }
+
+    #[test]
+    fn test_last_fetch_from_fetch_head() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+
+        let before = std::time::SystemTime::now() - std::time::Duration::from_secs(1);
+        create_file(repo_path, ".git/FETCH_HEAD", "");
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        let last_fetch = info.remote.last_fetch.expect("last fetch should be read");
+        assert!(last_fetch >= before);
+    }
+
+    #[test]
+    fn test_last_fetch_of_worktree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let main_path = temp_dir.path().join("main-repo");
+        let repo = create_test_repo(&main_path);
+        let worktree_path = temp_dir.path().join("feature-wt");
+        repo.worktree("feature-wt", &worktree_path, None).unwrap();
+
+        let info = RepoInfo::from_path(worktree_path.clone()).unwrap();
+        assert!(info.remote.last_fetch.is_none());
+
+        // A fetch from any worktree writes FETCH_HEAD to the main .git directory
+        create_file(&main_path, ".git/FETCH_HEAD", "");
+        let info = RepoInfo::from_path(worktree_path).unwrap();
+        assert!(info.remote.last_fetch.is_some());
+    }
+
+    #[test]
+    fn test_upstream_gone() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+
+        // Track origin/main, then delete the remote-tracking ref
+        repo.remote("origin", "https://github.com/test/repo.git")
+            .unwrap();
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+        let mut tracking_ref = repo
+            .reference("refs/remotes/origin/main", commit.id(), false, "track")
+            .unwrap();
+        let mut branch = repo.find_branch("main", git2::BranchType::Local).unwrap();
+        branch.set_upstream(Some("origin/main")).unwrap();
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.sync.upstream_gone);
+
+        tracking_ref.delete().unwrap();
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(info.sync.upstream_gone);
+        assert_eq!(info.sync.ahead, 0);
+        assert_eq!(info.sync.behind, 0);
+    }
+
+    #[test]
+    fn test_default_branch_changed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("master", &commit, false).unwrap();
+        repo.set_head("refs/heads/master").unwrap();
+        repo.remote("origin", "https://github.com/test/repo.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/master", commit.id(), false, "fetch")
+            .unwrap();
+        let mut branch = repo.find_branch("master", git2::BranchType::Local).unwrap();
+        branch.set_upstream(Some("origin/master")).unwrap();
+
+        // Without origin/HEAD, there is no default branch to compare against
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.sync.default_branch_changed);
+
+        repo.reference("refs/remotes/origin/main", commit.id(), false, "fetch")
+            .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/main",
+            false,
+            "fetch",
+        )
+        .unwrap();
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.sync.default_branch_changed);
+
+        // origin renamed master to main, and the fetch pruned origin/master
+        repo.find_reference("refs/remotes/origin/master")
+            .unwrap()
+            .delete()
+            .unwrap();
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(info.sync.default_branch_changed);
+        assert!(info.sync.upstream_gone);
+
+        // A feature branch deleted on the remote is just gone
+        repo.branch("feature", &commit, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.sync.default_branch_changed);
+    }
+
+    #[test]
+    fn test_upstream_not_configured_is_not_gone() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert!(!info.sync.upstream_gone);
+    }
+
+    #[test]
+    fn test_sync_status_no_upstream() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert!(!info.sync.has_upstream);
+        assert_eq!(info.sync.ahead, 0);
+        assert_eq!(info.sync.behind, 0);
+    }
+
+    /// Commit all files in the working directory on top of HEAD
+    fn commit_all(repo: &Repository, message: &str) -> git2::Oid {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+            .unwrap()
+    }
+
+    /// Create a repository with one conflicted file containing two conflict hunks
+    fn create_conflicted_repo(repo_path: &Path) {
+        let repo = create_test_repo(repo_path);
+        let base = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        create_file(repo_path, "file.txt", base);
+        let base_oid = commit_all(&repo, "base");
+
+        // Diverge on a side branch, changing the first and last lines
+        repo.branch("other", &repo.find_commit(base_oid).unwrap(), false)
+            .unwrap();
+        repo.set_head("refs/heads/other").unwrap();
+        create_file(
+            repo_path,
+            "file.txt",
+            &base.replace('a', "A1").replace('j', "J1"),
+        );
+        let other_oid = commit_all(&repo, "other");
+
+        // Change the same lines differently on main, then merge
+        repo.set_head("refs/heads/main").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        create_file(
+            repo_path,
+            "file.txt",
+            &base.replace('a', "A2").replace('j', "J2"),
+        );
+        commit_all(&repo, "main");
+
+        let theirs = repo.find_annotated_commit(other_oid).unwrap();
+        repo.merge(&[&theirs], None, None).unwrap();
+    }
+
+    #[test]
+    fn test_count_conflict_hunks_in_text() {
+        let content = "x\n<<<<<<< ours\na\n=======\nb\n>>>>>>> theirs\ny\n\
+                       <<<<<<< ours\nc\n=======\nd\n>>>>>>> theirs\n";
+        assert_eq!(count_conflict_hunks(content), 2);
+        assert_eq!(count_conflict_hunks("no markers\n=======\n"), 0);
+        // An unterminated group is not counted
+        assert_eq!(count_conflict_hunks("<<<<<<< ours\na\n=======\nb\n"), 0);
+    }
+
+    #[test]
+    fn test_conflict_hunks_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        create_conflicted_repo(repo_path);
+
+        let options = RepoInfoOptions {
+            count_conflict_hunks: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+
+        assert_eq!(info.working.conflicts, 1);
+        assert_eq!(info.working.conflict_hunks, Some(2));
+        let change = &info.files.changes[0];
+        assert_eq!(change.status, FileChangeStatus::Conflicted);
+        assert_eq!(change.conflict_hunks, Some(2));
+    }
+
+    #[test]
+    fn test_conflict_hunks_not_counted_by_default() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        create_conflicted_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert_eq!(info.working.conflicts, 1);
+        assert_eq!(info.working.conflict_hunks, None);
+        assert_eq!(info.files.changes[0].conflict_hunks, None);
+    }
+
+    #[test]
+    fn test_conflict_hunks_binary_or_missing_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let binary = temp_dir.path().join("blob.bin");
+        fs::write(&binary, b"<<<<<<<\n\0\n=======\n>>>>>>>\n").unwrap();
+
+        assert_eq!(count_conflict_hunks_in_file(&binary), None);
+        assert_eq!(
+            count_conflict_hunks_in_file(&temp_dir.path().join("missing")),
+            None
+        );
+    }
+
+    /// Create a repository on a feature branch two commits ahead of and one
+    /// commit behind `origin/main`
+    fn create_feature_branch_repo(repo_path: &Path) -> Repository {
+        let repo = create_test_repo(repo_path);
+        let initial = repo.head().unwrap().peel_to_commit().unwrap();
+
+        // origin/main moved on by one commit
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+        let remote_oid = repo
+            .commit(
+                None,
+                &sig,
+                &sig,
+                "remote",
+                &initial.tree().unwrap(),
+                &[&initial],
+            )
+            .unwrap();
+        repo.reference("refs/remotes/origin/main", remote_oid, false, "test")
+            .unwrap();
+
+        repo.branch("feature", &initial, false).unwrap();
+        drop(initial);
+        repo.set_head("refs/heads/feature").unwrap();
+        create_file(repo_path, "one.txt", "1");
+        commit_all(&repo, "one");
+        create_file(repo_path, "two.txt", "2");
+        commit_all(&repo, "two");
+        repo
+    }
+
+    #[test]
+    fn test_sync_status_against_default_branch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_feature_branch_repo(repo_path);
+
+        let options = RepoInfoOptions {
+            compare_against: CompareAgainst::DefaultBranch,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+
+        assert!(info.sync.has_upstream);
+        assert_eq!(info.sync.ahead, 2);
+        assert_eq!(info.sync.behind, 1);
+        assert_eq!(info.sync.compare_ref.as_deref(), Some("origin/main"));
+    }
+
+    #[test]
+    fn test_sync_status_against_upstream_ignores_default_branch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_feature_branch_repo(repo_path);
+
+        // The feature branch was never pushed, so there is nothing to compare against
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert!(!info.sync.has_upstream);
+        assert_eq!(info.sync.compare_ref, None);
+    }
+
+    #[test]
+    fn test_default_branch_from_remote_head() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_feature_branch_repo(repo_path);
+
+        // origin/HEAD takes precedence over the main/master fallback
+        let head_oid = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/trunk", head_oid, false, "test")
+            .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/trunk",
+            false,
+            "test",
+        )
+        .unwrap();
+
+        assert_eq!(
+            RepoInfo::get_default_branch_ref(&repo).as_deref(),
+            Some("refs/remotes/origin/trunk")
+        );
+    }
+
+    #[test]
+    fn test_unborn_repo() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        Repository::init(repo_path).unwrap();
+        create_file(repo_path, "new.txt", "not committed yet");
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert!(info.basic.is_unborn);
+        assert_eq!(info.basic.branch, "main");
+        assert!(!info.sync.has_upstream);
+        assert!(info.commit.message.is_none());
+        assert_eq!(info.stash.count, 0);
+        assert_eq!(info.working.untracked, 1);
+    }
+
+    #[test]
+    fn test_repo_with_commit_is_not_unborn() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+
+        assert!(!info.basic.is_unborn);
+    }
+
+    #[test]
+    fn test_detached_head() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.basic.is_detached);
+
+        let head = repo.head().unwrap().target().unwrap();
+        repo.set_head_detached(head).unwrap();
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(info.basic.is_detached);
+        assert_eq!(info.basic.branch, "HEAD");
+    }
+
+    /// Create a superproject at `super_path` with a submodule at `libs/sub`
+    fn create_superproject_with_submodule(super_path: &Path, source_path: &Path) {
+        let _source = create_test_repo(source_path);
+        let superproject = create_test_repo(super_path);
+
+        let url = source_path.to_str().unwrap();
+        let mut submodule = superproject
+            .submodule(url, Path::new("libs/sub"), true)
+            .unwrap();
+        submodule.clone(None).unwrap();
+        submodule.add_finalize().unwrap();
+        commit_all(&superproject, "add submodule");
+    }
+
+    #[test]
+    fn test_submodule_superproject() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let super_path = temp_dir.path().join("super");
+        create_superproject_with_submodule(&super_path, &temp_dir.path().join("source"));
+
+        let sub = RepoInfo::from_path(super_path.join("libs/sub")).unwrap();
+        assert!(sub.is_submodule());
+        assert_eq!(
+            sub.basic.superproject.as_deref(),
+            Some(super_path.as_path())
+        );
+
+        let parent = RepoInfo::from_path(super_path).unwrap();
+        assert!(!parent.is_submodule());
+    }
+
+    #[test]
+    fn test_nested_repo_is_not_submodule() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let parent_path = temp_dir.path();
+        let _parent = create_test_repo(parent_path);
+        let nested_path = parent_path.join("vendor").join("nested");
+        let _nested = create_test_repo(&nested_path);
+
+        let info = RepoInfo::from_path(nested_path).unwrap();
+        assert!(!info.is_submodule());
+    }
+
+    #[test]
+    fn test_name_from_remote_url() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("checkout");
+        let repo = create_test_repo(&repo_path);
+        repo.remote("origin", "git@github.com:owner/reponest.git")
+            .unwrap();
+
+        let info = RepoInfo::from_path(repo_path).unwrap();
+        assert_eq!(info.basic.name, "reponest");
+        assert_eq!(info.basic.dir_name, "checkout");
+    }
+
+    #[test]
+    fn test_name_from_git_config() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("checkout");
+        let repo = create_test_repo(&repo_path);
+        repo.remote("origin", "git@github.com:owner/reponest.git")
+            .unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str(NAME_CONFIG_KEY, "My Service").unwrap();
+
+        // The configured name wins over the remote's repo name
+        let info = RepoInfo::from_path(repo_path.clone()).unwrap();
+        assert_eq!(info.basic.name, "My Service");
+        assert_eq!(info.basic.dir_name, "checkout");
+
+        config.set_str(NAME_CONFIG_KEY, "  ").unwrap();
+        let info = RepoInfo::from_path(repo_path).unwrap();
+        assert_eq!(info.basic.name, "reponest");
+    }
+
+    #[test]
+    fn test_name_falls_back_to_dir_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("v2");
+        let _repo = create_test_repo(&repo_path);
+
+        let info = RepoInfo::from_path(repo_path).unwrap();
+        assert_eq!(info.basic.name, "v2");
+        assert_eq!(info.basic.dir_name, "v2");
+    }
+
+    #[test]
+    fn test_submodule_name() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let super_path = temp_dir.path().join("super");
+        create_superproject_with_submodule(&super_path, &temp_dir.path().join("source"));
+        let sub_path = super_path.join("libs/sub");
+
+        // The remote points at the source repo
+        let info = RepoInfo::from_path(sub_path.clone()).unwrap();
+        assert_eq!(info.basic.name, "source");
+
+        // Without a remote, the path within the superproject is used
+        Repository::open(&sub_path)
+            .unwrap()
+            .remote_delete("origin")
+            .unwrap();
+        let info = RepoInfo::from_path(sub_path).unwrap();
+        assert_eq!(Path::new(&info.basic.name), Path::new("libs/sub"));
+        assert_eq!(info.basic.dir_name, "sub");
+    }
+
+    #[test]
+    fn test_stale_classification() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let threshold = Duration::from_secs(180 * 86400);
+        let now = SystemTime::now();
+
+        // Recent commit
+        let recent_path = temp_dir.path().join("recent");
+        let _recent = create_test_repo(&recent_path);
+        let recent = RepoInfo::from_path(recent_path).unwrap();
+        assert!(recent.commit.time.is_some());
+        assert!(!recent.is_stale(threshold, now));
+
+        // Commit from a year ago
+        let old_path = temp_dir.path().join("old");
+        let old_repo = create_test_repo(&old_path);
+        let year_ago = now - Duration::from_secs(365 * 86400);
+        let secs = year_ago
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let sig = Signature::new(
+            "Test User",
+            "test@example.com",
+            &git2::Time::new(secs as i64, 0),
+        )
+        .unwrap();
+        let head = old_repo.head().unwrap().peel_to_commit().unwrap();
+        old_repo
+            .commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                "Old commit",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+        let old = RepoInfo::from_path(old_path).unwrap();
+        assert!(old.is_stale(threshold, now));
+        assert!(!old.is_stale(Duration::from_secs(400 * 86400), now));
+
+        // Without commits, a repo is not stale
+        let empty_path = temp_dir.path().join("empty");
+        Repository::init(&empty_path).unwrap();
+        let empty = RepoInfo::from_path(empty_path).unwrap();
+        assert!(!empty.is_stale(Duration::ZERO, now));
+    }
+
+    #[test]
+    fn test_skip_working_status() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+        create_file(repo_path, "untracked.txt", "content");
+
+        let options = RepoInfoOptions {
+            skip_working_status: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+
+        assert!(info.partial);
+        assert_eq!(info.working.untracked, 0);
+        assert!(info.commit.message.is_some());
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.partial);
+        assert_eq!(info.working.untracked, 1);
+    }
+
+    #[test]
+    fn test_anomalies_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+
+        // A tracked file that a later ignore rule also matches
+        create_file(repo_path, "debug.log", "tracked");
+        commit_all(&repo, "track log");
+        create_file(repo_path, ".gitignore", "*.log\nbuild/\n");
+        create_file(repo_path, "error.log", "ignored");
+        fs::create_dir(repo_path.join("build")).unwrap();
+        create_file(repo_path, "build/out.bin", "ignored");
+
+        let options = RepoInfoOptions {
+            scan_anomalies: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+
+        let anomalies = info.anomalies.unwrap();
+        assert_eq!(anomalies.ignored, 2);
+        assert_eq!(anomalies.tracked_ignored, 1);
+        assert!(anomalies.has_any());
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.anomalies, None);
+    }
+
+    #[test]
+    fn test_unpushed_tags_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        let sig = Signature::now("Test User", "test@example.com").unwrap();
+
+        // v1 is on the remote-tracking branch, v2 and v3 only exist locally
+        let pushed = repo.head().unwrap().target().unwrap();
+        repo.reference("refs/remotes/origin/main", pushed, false, "fetch")
+            .unwrap();
+        repo.tag_lightweight("v1", &repo.find_object(pushed, None).unwrap(), false)
+            .unwrap();
+        create_file(repo_path, "release.txt", "1.1");
+        let local = commit_all(&repo, "release");
+        let local = repo.find_object(local, None).unwrap();
+        repo.tag_lightweight("v2", &local, false).unwrap();
+        repo.tag("v3", &local, &sig, "annotated", false).unwrap();
+
+        let options = RepoInfoOptions {
+            count_unpushed_tags: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.sync.unpushed_tags, Some(2));
+
+        // Once the remote-tracking branch has the commit, nothing is unpushed
+        repo.reference("refs/remotes/origin/main", local.id(), true, "fetch")
+            .unwrap();
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.sync.unpushed_tags, Some(0));
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.sync.unpushed_tags, None);
+    }
+
+    #[test]
+    fn test_unmerged_branches_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        let head = repo.head().unwrap();
+        let main_ref = head.name().unwrap().to_string();
+
+        // "merged" is behind main, "feature" has a commit main lacks
+        let base = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("merged", &base, false).unwrap();
+        repo.branch("feature", &base, false).unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+        create_file(repo_path, "feature.txt", "work in progress");
+        commit_all(&repo, "feature work");
+        repo.set_head(&main_ref).unwrap();
+        create_file(repo_path, "later.txt", "main moved on");
+        commit_all(&repo, "main work");
+
+        let options = RepoInfoOptions {
+            count_unmerged_branches: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.sync.unmerged_branches, Some(1));
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.sync.unmerged_branches, None);
+    }
+
+    #[test]
+    fn test_contributors_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        commit_all(&repo, "second");
+        // Another author, and the first one again with a differently cased email
+        for (name, email) in [("Ada", "ada@example.com"), ("Test", "TEST@example.com")] {
+            let sig = Signature::now(name, email).unwrap();
+            let tree = repo.head().unwrap().peel_to_tree().unwrap();
+            let parent = repo.head().unwrap().peel_to_commit().unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "change", &tree, &[&parent])
+                .unwrap();
+        }
+
+        let options = RepoInfoOptions {
+            count_contributors: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.commit.contributors, Some(2));
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.commit.contributors, None);
+
+        // Without commits there is nobody to count
+        let empty_path = temp_dir.path().join("empty");
+        Repository::init(&empty_path).unwrap();
+        let info = RepoInfo::from_path_with_options(empty_path, options).unwrap();
+        assert_eq!(info.commit.contributors, None);
+    }
+
+    #[test]
+    fn test_unstaged_hunks_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        let lines: Vec<String> = (1..=20).map(|i| format!("line {}\n", i)).collect();
+        create_file(repo_path, "notes.txt", &lines.concat());
+        commit_all(&repo, "add notes");
+
+        // Change two distant lines, then stage only the first change
+        let mut changed = lines.clone();
+        changed[1] = "line two\n".to_string();
+        let mut index = repo.index().unwrap();
+        let mut entry = index.get_path(Path::new("notes.txt"), 0).unwrap();
+        let staged = changed.concat();
+        entry.file_size = staged.len() as u32;
+        index.add_frombuffer(&entry, staged.as_bytes()).unwrap();
+        index.write().unwrap();
+        changed[17] = "line eighteen\n".to_string();
+        create_file(repo_path, "notes.txt", &changed.concat());
+
+        let options = RepoInfoOptions {
+            count_unstaged_hunks: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.working.staged, 1);
+        assert_eq!(info.working.unstaged_hunks, Some(1));
+
+        // Both changes are unstaged once the index is reset
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.reset(head.as_object(), git2::ResetType::Mixed, None)
+            .unwrap();
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.working.unstaged_hunks, Some(2));
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.working.unstaged_hunks, None);
+    }
+
+    #[test]
+    fn test_assume_unchanged_counted_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        create_file(repo_path, "settings.local", "debug = false");
+        create_file(repo_path, "README.md", "readme");
+        commit_all(&repo, "add files");
+
+        // Same as `git update-index --assume-unchanged settings.local`
+        let mut index = repo.index().unwrap();
+        let mut entry = index.get_path(Path::new("settings.local"), 0).unwrap();
+        entry.flags |= IndexEntryFlag::VALID.bits();
+        index.add(&entry).unwrap();
+        index.write().unwrap();
+        create_file(repo_path, "settings.local", "debug = true");
+
+        let options = RepoInfoOptions {
+            count_assume_unchanged: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.working.assume_unchanged, Some(1));
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.working.assume_unchanged, None);
+    }
+
+    #[test]
+    fn test_worktree_of_main_repo() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let main_path = temp_dir.path().join("main-repo");
+        let repo = create_test_repo(&main_path);
+        let worktree_path = temp_dir.path().join("feature-wt");
+        repo.worktree("feature-wt", &worktree_path, None).unwrap();
+
+        let info = RepoInfo::from_path(worktree_path).unwrap();
+        assert_eq!(info.basic.worktree_of.as_deref(), Some("main-repo"));
+        assert_eq!(info.basic.branch, "feature-wt");
+
+        let main = RepoInfo::from_path(main_path).unwrap();
+        assert_eq!(main.basic.worktree_of, None);
+    }
+
+    #[test]
+    fn test_index_lock_detected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let _repo = create_test_repo(repo_path);
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(!info.working.locked);
+
+        create_file(repo_path, ".git/index.lock", "");
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert!(info.working.locked);
+
+        // The lock is reported even when the working tree status is skipped
+        let options = RepoInfoOptions {
+            skip_working_status: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert!(info.working.locked);
+    }
+
+    #[test]
+    fn test_operation_in_progress_detected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        let head = repo.head().unwrap().target().unwrap().to_string();
+
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.working.operation, None);
+
+        create_file(repo_path, ".git/MERGE_HEAD", &head);
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        assert_eq!(info.working.operation, Some(RepoOperation::Merge));
+        fs::remove_file(repo_path.join(".git/MERGE_HEAD")).unwrap();
+
+        // An interactive rebase keeps its todo list in rebase-merge
+        fs::create_dir(repo_path.join(".git/rebase-merge")).unwrap();
+        create_file(
+            repo_path,
+            ".git/rebase-merge/git-rebase-todo",
+            "pick 1234 msg\n",
+        );
+        create_file(repo_path, ".git/rebase-merge/interactive", "");
+        let options = RepoInfoOptions {
+            skip_working_status: true,
+            ..Default::default()
+        };
+        let info = RepoInfo::from_path_with_options(repo_path.to_path_buf(), options).unwrap();
+        assert_eq!(info.working.operation, Some(RepoOperation::Rebase));
+    }
+
+    /// Set the modification time of a file or directory
+    fn set_mtime(path: &Path, time: SystemTime) {
+        fs::File::open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn test_workdir_mtime_modes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path();
+        let repo = create_test_repo(repo_path);
+        fs::create_dir(repo_path.join("src")).unwrap();
+        create_file(repo_path, "src/lib.rs", "tracked");
+        create_file(repo_path, "README.md", "tracked");
+        commit_all(&repo, "add files");
+
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        for path in ["src/lib.rs", "README.md", "src", ""] {
+            set_mtime(&repo_path.join(path), past);
+        }
+        let with_mode = |workdir_mtime| {
+            let options = RepoInfoOptions {
+                workdir_mtime,
+                ..Default::default()
+            };
+            RepoInfo::from_path_with_options(repo_path.to_path_buf(), options)
+                .unwrap()
+                .working
+                .workdir_mtime
+        };
+
+        assert_eq!(with_mode(WorkdirMtime::Off), None);
+
+        // Editing a file in a subdirectory leaves the repo directory untouched
+        let edited = SystemTime::now();
+        create_file(repo_path, "src/lib.rs", "edited");
+        set_mtime(&repo_path.join("src/lib.rs"), edited);
+
+        let dir_mtime = fs::metadata(repo_path).unwrap().modified().unwrap();
+        assert_eq!(with_mode(WorkdirMtime::Dir), Some(dir_mtime));
+        assert_eq!(with_mode(WorkdirMtime::Files), Some(edited));
+        assert!(dir_mtime < edited);
+    }
 }
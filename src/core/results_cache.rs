@@ -0,0 +1,70 @@
+//! This module saves the repositories found by the last TUI scan, so the next
+//! start can show them right away while a new scan refreshes them.
+//!
+//! The cache is the JSON list written by `list --json`. Loaded repositories
+//! are marked `cached` until the scan replaces them.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::repo_info::RepoInfo;
+
+/// Load the cached repositories, or none if the cache does not exist yet
+pub fn load_cached_repos(file: &Path) -> Result<Vec<RepoInfo>, String> {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read results cache {:?}: {}", file, e)),
+    };
+    let mut repos: Vec<RepoInfo> = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid results cache {:?}: {}", file, e))?;
+    for repo in &mut repos {
+        repo.cached = true;
+    }
+    Ok(repos)
+}
+
+/// Save the repositories to the cache atomically, so a crash never leaves a partial file
+pub fn save_cached_repos(file: &Path, repos: &[RepoInfo]) -> Result<(), String> {
+    let content = serde_json::to_string(repos).map_err(|e| e.to_string())?;
+    let mut tmp_name = file.to_path_buf().into_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_name);
+
+    if let Some(dir) = file.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::File::create(&tmp_path)
+        .and_then(|mut f| f.write_all(content.as_bytes()))
+        .and_then(|()| fs::rename(&tmp_path, file))
+        .map_err(|e| format!("Failed to save results cache {:?}: {}", file, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("cache").join("results.json");
+        assert!(load_cached_repos(&file).unwrap().is_empty());
+
+        let repos = [
+            RepoInfo::stub("api", "/repos/api"),
+            RepoInfo::stub("web", "/repos/web"),
+        ];
+        save_cached_repos(&file, &repos).unwrap();
+
+        let loaded = load_cached_repos(&file).unwrap();
+        let names: Vec<_> = loaded.iter().map(|r| r.basic.name.as_str()).collect();
+        assert_eq!(names, ["api", "web"]);
+        assert!(loaded.iter().all(|r| r.cached));
+
+        fs::write(&file, "not json").unwrap();
+        assert!(load_cached_repos(&file).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! This module decides whether write actions may touch a repository.
+//!
+//! Writing to a repository in the middle of a merge, rebase or similar
+//! operation, or while another git process holds the index lock, can lose
+//! work. Every write action checks [`can_perform_write`] first.
+
+use super::repo_info::RepoInfo;
+
+/// Check whether a write action may be performed on a repository
+///
+/// Refuses repositories in the middle of an operation, with unresolved
+/// conflicts, or with a locked index. Checks specific to an action (e.g.
+/// whether there is unpushed work) are left to the action.
+pub fn can_perform_write(repo: &RepoInfo) -> Result<(), String> {
+    let name = &repo.basic.name;
+    if let Some(operation) = repo.working.operation {
+        return Err(format!(
+            "'{}' is in the middle of a {}, finish or abort it first",
+            name,
+            operation.label()
+        ));
+    }
+    if repo.working.conflicts > 0 {
+        return Err(format!(
+            "'{}' has unresolved conflicts, resolve them first",
+            name
+        ));
+    }
+    if repo.working.locked {
+        return Err(format!(
+            "The index of '{}' is locked (index.lock exists), another git process may be running",
+            name
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::repo_info::RepoOperation;
+
+    #[test]
+    fn test_can_perform_write() {
+        let mut repo = RepoInfo::stub("app", "/work/app");
+        assert!(can_perform_write(&repo).is_ok());
+        // Uncommitted changes alone are for each action to judge
+        repo.working.is_dirty = true;
+        assert!(can_perform_write(&repo).is_ok());
+
+        repo.working.operation = Some(RepoOperation::Rebase);
+        assert_eq!(
+            can_perform_write(&repo).unwrap_err(),
+            "'app' is in the middle of a rebase, finish or abort it first"
+        );
+
+        repo.working.operation = None;
+        repo.working.conflicts = 2;
+        assert!(can_perform_write(&repo).unwrap_err().contains("conflicts"));
+
+        repo.working.conflicts = 0;
+        repo.working.locked = true;
+        assert!(can_perform_write(&repo).unwrap_err().contains("index.lock"));
+    }
+}
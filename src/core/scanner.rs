@@ -1,112 +1,686 @@
 //! This module provides asynchronous directory traversal to discover Git repositories.
+//!
+//! Directories are read concurrently by a bounded set of tokio tasks, while the
+//! scan loop keeps a queue of directories that are still to be visited.
+//!
+//! In flat mode, only the scan root and its direct subdirectories are checked,
+//! without reading the subdirectories.
+//!
+//! With a checkpoint file configured, each first-level subtree of a scan root
+//! is recorded once it is fully scanned, and a resumed scan skips it.
+//!
+//! A scan trace receives the traversal decisions, for `reponest scan --explain`.
+//!
+//! When following symbolic links, the real path of every directory is tracked
+//! so that no directory is read twice, which also stops symlink cycles.
 
 use anyhow::Result;
-use std::path::PathBuf;
-
+use std::collections::{HashSet, VecDeque};
+use std::fs::FileType;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use super::checkpoint::ScanCheckpoint;
+use super::exclude::ExcludeMatcher;
 use crate::config::AppConfig;
 
+/// Maximum number of directories read concurrently during a scan
+const MAX_CONCURRENT_READS: usize = 32;
+
+/// Heuristic skipping directories that look like the output of some tool
+///
+/// A directory with more entries than `max_entries` is not descended into,
+/// unless it is a Git repository or its name is in `keep`. The scan root is
+/// always read, so it is listed without the heuristic.
+#[derive(Debug, Default)]
+struct AutoExclude {
+    max_entries: Option<usize>,
+    keep: Vec<String>,
+}
+
+impl AutoExclude {
+    fn from_config(cfg: &AppConfig) -> Self {
+        Self {
+            max_entries: cfg.main.skip_dirs_with_more_than,
+            keep: cfg.main.auto_exclude_keep.clone(),
+        }
+    }
+
+    /// Check whether the directory at `path`, which is not a repository, is skipped
+    fn skips(&self, path: &Path, entries: usize) -> bool {
+        let Some(max) = self.max_entries else {
+            return false;
+        };
+        entries > max
+            && !path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| self.keep.iter().any(|keep| keep == name))
+    }
+}
+
+/// Why the scanner did not descend into a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Name starts with `.`
+    Hidden,
+    /// Name matches an exclude pattern
+    NamedExclude,
+    /// Path matches a gitignore-style pattern of `main.exclude`
+    ExcludePattern,
+    /// Name matches none of the include patterns
+    NotIncluded,
+    /// Deeper than `max_depth`
+    MaxDepth,
+    /// More entries than `skip_dirs_with_more_than`
+    TooManyEntries,
+    /// Reading the directory failed
+    Unreadable,
+    /// Symbolic link to a directory that was already visited
+    AlreadyVisited,
+}
+
+impl SkipReason {
+    /// Reason of an excluded directory name
+    fn of_excluded(name: &str) -> Self {
+        match name.starts_with('.') {
+            true => Self::Hidden,
+            false => Self::NamedExclude,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Hidden => "hidden",
+            Self::NamedExclude => "named exclude",
+            Self::ExcludePattern => "exclude pattern",
+            Self::NotIncluded => "not included",
+            Self::MaxDepth => "max depth",
+            Self::TooManyEntries => "too many entries",
+            Self::Unreadable => "unreadable",
+            Self::AlreadyVisited => "already visited",
+        }
+    }
+}
+
+/// Decision of the scanner about a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanDecision {
+    /// The directory was read
+    Entered,
+    /// The directory was read and is a Git repository
+    Repo,
+    /// The directory was not descended into
+    Skipped(SkipReason),
+}
+
+/// Decision about a directory, reported to a scan trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanEvent {
+    pub path: PathBuf,
+    pub decision: ScanDecision,
+}
+
+/// Callback receiving the decisions of a scan, from any of the scan tasks
+pub type ScanTrace = Arc<dyn Fn(ScanEvent) + Send + Sync>;
+
+/// Report a decision to the trace, if any
+fn report(trace: Option<&ScanTrace>, path: &Path, decision: ScanDecision) {
+    if let Some(trace) = trace {
+        trace(ScanEvent {
+            path: path.to_path_buf(),
+            decision,
+        });
+    }
+}
+
+/// Entries found when reading a single directory
+#[derive(Default)]
+struct DirListing {
+    /// Git repositories found directly in the directory
+    repos: Vec<PathBuf>,
+    /// Subdirectories that should be scanned next
+    subdirs: Vec<PathBuf>,
+    /// Subdirectories reached through a symbolic link
+    links: HashSet<PathBuf>,
+}
+
+/// Real paths of the directories visited by a scan following symbolic links
+struct VisitedDirs(HashSet<PathBuf>);
+
+impl VisitedDirs {
+    /// Start tracking from the scan root at the real path `root_real`
+    fn new(root_real: PathBuf) -> Self {
+        Self(HashSet::from([root_real]))
+    }
+
+    /// Keep the subdirectories of a listing not visited yet, paired with their real paths
+    ///
+    /// Only links are resolved: other directories are below the real path of
+    /// their parent, `parent_real`.
+    async fn visit(
+        &mut self,
+        subdirs: Vec<PathBuf>,
+        links: &HashSet<PathBuf>,
+        parent_real: &Path,
+        trace: Option<&ScanTrace>,
+    ) -> Vec<(PathBuf, PathBuf)> {
+        let mut unvisited = Vec::new();
+        for dir in subdirs {
+            let real = match links.contains(&dir) {
+                true => tokio::fs::canonicalize(&dir).await.ok(),
+                false => dir.file_name().map(|name| parent_real.join(name)),
+            };
+            match real {
+                Some(real) if self.0.insert(real.clone()) => unvisited.push((dir, real)),
+                _ => {
+                    debug!("Skipping directory already visited: {:?}", dir);
+                    report(
+                        trace,
+                        &dir,
+                        ScanDecision::Skipped(SkipReason::AlreadyVisited),
+                    );
+                }
+            }
+        }
+        unvisited
+    }
+}
+
+/// Subdirectories to scan next, with their real paths if directories are tracked
+async fn next_dirs(
+    subdirs: Vec<PathBuf>,
+    links: &HashSet<PathBuf>,
+    parent_real: Option<&Path>,
+    visited: Option<&mut VisitedDirs>,
+    trace: Option<&ScanTrace>,
+) -> Vec<(PathBuf, Option<PathBuf>)> {
+    match (visited, parent_real) {
+        (Some(visited), Some(parent_real)) => visited
+            .visit(subdirs, links, parent_real, trace)
+            .await
+            .into_iter()
+            .map(|(dir, real)| (dir, Some(real)))
+            .collect(),
+        _ => subdirs.into_iter().map(|dir| (dir, None)).collect(),
+    }
+}
+
+/// Result of scanning directories for Git repositories
+#[derive(Debug, Default)]
+pub struct ScanOutcome {
+    /// Paths of the discovered repositories
+    pub paths: Vec<PathBuf>,
+    /// Whether the scan stopped early because its time budget ran out
+    pub truncated: bool,
+    /// Number of directories read, including the scan roots
+    pub dirs_visited: usize,
+}
+
 /// Scan a single directory for Git repositories
+///
+/// Results are sorted by path, so the output does not depend on the order in
+/// which concurrent directory reads complete.
 pub async fn scan_directory(base_path: &str, cfg: &AppConfig) -> Result<Vec<PathBuf>> {
-    let base = PathBuf::from(base_path);
-    let mut paths = Vec::new();
-    scan_recursive(base, cfg, 0, &mut paths).await?;
-    Ok(paths)
+    let deadline = cfg.internal.scan_timeout.map(|t| Instant::now() + t);
+    let excludes = exclude_matcher(base_path, cfg)?;
+    let outcome = scan_directory_until(base_path, cfg, excludes, deadline, None, None).await?;
+    Ok(outcome.paths)
 }
 
 /// Scan multiple directories for Git repositories
-pub async fn scan_directories(base_paths: &[String], cfg: &AppConfig) -> Result<Vec<PathBuf>> {
-    let mut all_paths = Vec::new();
+///
+/// The scan timeout is a budget shared by all directories. Once it runs out,
+/// the repositories found so far are returned and the outcome is marked truncated.
+///
+/// If a checkpoint file is configured, progress is saved to it, and with
+/// `resume_scan` the subtrees it records as complete are not scanned again.
+pub async fn scan_directories(base_paths: &[String], cfg: &AppConfig) -> Result<ScanOutcome> {
+    scan_directories_traced(base_paths, cfg, None).await
+}
+
+/// Scan multiple directories like `scan_directories`, reporting decisions to `trace`
+pub async fn scan_directories_traced(
+    base_paths: &[String],
+    cfg: &AppConfig,
+    trace: Option<ScanTrace>,
+) -> Result<ScanOutcome> {
+    let deadline = cfg.internal.scan_timeout.map(|t| Instant::now() + t);
+    let mut checkpoint = match cfg.internal.scan_checkpoint.as_deref() {
+        Some(file) if cfg.internal.resume_scan => {
+            Some(ScanCheckpoint::resume(file).map_err(anyhow::Error::msg)?)
+        }
+        Some(file) => Some(ScanCheckpoint::new(file)),
+        None => None,
+    };
+    if cfg.main.flat && checkpoint.is_some() {
+        warn!("Scan checkpoints are not used by flat scans");
+    }
+
+    let mut all = ScanOutcome::default();
     for base in base_paths {
-        if let Ok(mut paths) = scan_directory(base, cfg).await {
-            all_paths.append(&mut paths);
+        // Invalid exclude patterns fail the scan, unlike an unreadable root
+        let excludes = exclude_matcher(base, cfg)?;
+        let scanned = scan_directory_until(
+            base,
+            cfg,
+            excludes,
+            deadline,
+            checkpoint.as_mut(),
+            trace.as_ref(),
+        )
+        .await;
+        match scanned {
+            Ok(mut outcome) => {
+                all.paths.append(&mut outcome.paths);
+                all.dirs_visited += outcome.dirs_visited;
+                if outcome.truncated {
+                    all.truncated = true;
+                    break;
+                }
+            }
+            Err(_) => report(
+                trace.as_ref(),
+                Path::new(base),
+                ScanDecision::Skipped(SkipReason::Unreadable),
+            ),
         }
     }
-    Ok(all_paths)
+    if !all.truncated
+        && let Some(ref mut checkpoint) = checkpoint
+        && let Err(e) = checkpoint.finish()
+    {
+        warn!("{}", e);
+    }
+    if all.truncated {
+        warn!(
+            found = all.paths.len(),
+            "Scan timed out, results are incomplete"
+        );
+    }
+    Ok(all)
 }
 
-/// Recursively traverse directory tree to find Git repositories
-fn scan_recursive<'a>(
-    path: PathBuf,
-    cfg: &'a AppConfig,
-    depth: usize,
-    paths: &'a mut Vec<PathBuf>,
-) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
-    Box::pin(async move {
-        if cfg.main.max_depth > 0 && depth >= cfg.main.max_depth {
-            return Ok(());
-        }
+/// Progress of a first-level subtree of the scan root
+struct Subtree {
+    root: PathBuf,
+    /// Directories of the subtree that are queued or being read
+    pending: usize,
+    /// Repositories found in the subtree so far
+    repos: Vec<PathBuf>,
+}
 
-        let mut entries = tokio::fs::read_dir(&path).await?;
+/// Scan a single directory for Git repositories, stopping at the deadline if given
+///
+/// Completed subtrees are recorded in the checkpoint, if one is given.
+async fn scan_directory_until(
+    base_path: &str,
+    cfg: &AppConfig,
+    excludes: Arc<ExcludeMatcher>,
+    deadline: Option<Instant>,
+    mut checkpoint: Option<&mut ScanCheckpoint>,
+    trace: Option<&ScanTrace>,
+) -> Result<ScanOutcome> {
+    if cfg.main.flat {
+        return scan_flat(base_path, cfg, excludes, deadline, trace).await;
+    }
 
-        while let Some(entry) = entries.next_entry().await? {
-            let entry_path = entry.path();
-            if !entry_path.is_dir() {
+    let max_depth = cfg.main.max_depth;
+    let within_depth = |depth: usize| max_depth == 0 || depth < max_depth;
+
+    // Errors reading the base directory are reported, errors below it are skipped
+    let auto_exclude = Arc::new(AutoExclude::from_config(cfg));
+    let follow_symlinks = cfg.main.follow_symlinks;
+    let listing = read_dir_listing(
+        PathBuf::from(base_path),
+        excludes.clone(),
+        Arc::default(),
+        follow_symlinks,
+        trace.cloned(),
+    )
+    .await?;
+    let mut paths = listing.repos;
+    let mut subtrees = Vec::new();
+    // Only with symbolic links followed can a directory be reached twice
+    let base_real = match follow_symlinks {
+        true => Some(
+            tokio::fs::canonicalize(base_path)
+                .await
+                .unwrap_or_else(|_| PathBuf::from(base_path)),
+        ),
+        false => None,
+    };
+    let mut visited = base_real.clone().map(VisitedDirs::new);
+    // Each queued directory carries its real path if tracked, and the index of its subtree
+    let mut queue: VecDeque<(PathBuf, Option<PathBuf>, usize, usize)> = VecDeque::new();
+    if !within_depth(1) {
+        for dir in &listing.subdirs {
+            report(trace, dir, ScanDecision::Skipped(SkipReason::MaxDepth));
+        }
+    } else {
+        let dirs = next_dirs(
+            listing.subdirs,
+            &listing.links,
+            base_real.as_deref(),
+            visited.as_mut(),
+            trace,
+        )
+        .await;
+        for (dir, real) in dirs {
+            if let Some(repos) = checkpoint.as_ref().and_then(|c| c.completed_repos(&dir)) {
+                debug!("Skipping subtree completed in checkpoint: {:?}", dir);
+                paths.extend_from_slice(repos);
                 continue;
             }
+            queue.push_back((dir.clone(), real, 1, subtrees.len()));
+            subtrees.push(Subtree {
+                root: dir,
+                pending: 1,
+                repos: Vec::new(),
+            });
+        }
+    }
 
-            let file_name = entry_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("");
-
-            // If we find a .git directory, record the parent as a Git repository.
-            // After that, we will continue scanning other directories, thus finding nested repos.
-            if file_name == ".git" {
-                if let Some(repo_path) = entry_path.parent() {
-                    paths.push(repo_path.to_path_buf());
+    let mut tasks = JoinSet::new();
+    let mut truncated = false;
+    let mut dirs_visited = 1;
+    loop {
+        let has_work = !queue.is_empty() || !tasks.is_empty();
+        if has_work && deadline.is_some_and(|d| Instant::now() >= d) {
+            truncated = true;
+            break; // in-flight reads are aborted when the join set is dropped
+        }
+
+        // Keep up to MAX_CONCURRENT_READS directory reads in flight
+        while tasks.len() < MAX_CONCURRENT_READS
+            && let Some((dir, real, depth, subtree)) = queue.pop_front()
+        {
+            let excludes = excludes.clone();
+            let auto_exclude = auto_exclude.clone();
+            let trace = trace.cloned();
+            tasks.spawn(async move {
+                let listing =
+                    read_dir_listing(dir.clone(), excludes, auto_exclude, follow_symlinks, trace)
+                        .await;
+                (dir, real, listing, depth, subtree)
+            });
+        }
+
+        let joined = match deadline {
+            Some(d) => {
+                let until = tokio::time::Instant::from_std(d);
+                match tokio::time::timeout_at(until, tasks.join_next()).await {
+                    Ok(joined) => joined,
+                    Err(_) => continue, // deadline reached, handled above
                 }
-                continue;
             }
-
-            if is_excluded(file_name, &cfg.internal.exclude_dirs) {
-                continue;
+            None => tasks.join_next().await,
+        };
+        let Some(joined) = joined else {
+            break; // queue drained and no reads in flight
+        };
+        let Ok((dir, real, listing, depth, idx)) = joined else {
+            continue;
+        };
+        let subtree = &mut subtrees[idx];
+        subtree.pending -= 1;
+        match listing {
+            Ok(listing) => {
+                dirs_visited += 1;
+                paths.extend_from_slice(&listing.repos);
+                subtree.repos.extend(listing.repos);
+                if within_depth(depth + 1) {
+                    let dirs = next_dirs(
+                        listing.subdirs,
+                        &listing.links,
+                        real.as_deref(),
+                        visited.as_mut(),
+                        trace,
+                    )
+                    .await;
+                    subtree.pending += dirs.len();
+                    queue.extend(
+                        dirs.into_iter()
+                            .map(|(dir, real)| (dir, real, depth + 1, idx)),
+                    );
+                } else {
+                    for dir in &listing.subdirs {
+                        report(trace, dir, ScanDecision::Skipped(SkipReason::MaxDepth));
+                    }
+                }
             }
-            let _ = scan_recursive(entry_path, cfg, depth + 1, paths).await;
+            Err(_) => report(trace, &dir, ScanDecision::Skipped(SkipReason::Unreadable)),
         }
 
-        Ok(())
+        if subtree.pending == 0
+            && let Some(checkpoint) = checkpoint.as_deref_mut()
+            && let Err(e) =
+                checkpoint.mark_complete(subtree.root.clone(), std::mem::take(&mut subtree.repos))
+        {
+            warn!("{}", e);
+        }
+    }
+
+    paths.sort();
+    Ok(ScanOutcome {
+        paths,
+        truncated,
+        dirs_visited,
     })
 }
 
-/// Check if a directory should be excluded from scanning
-#[inline]
-fn is_excluded(dir_name: &str, exclude_patterns: &[String]) -> bool {
-    // Skip all hidden directories
-    if dir_name.starts_with('.') {
-        return true;
+/// Scan the base directory and its direct subdirectories for Git repositories
+///
+/// Finds the same repositories as a scan with `max_depth = 2`, but instead of
+/// reading each subdirectory, only checks whether it has a `.git` entry.
+/// Checks still pending at the deadline, if given, are abandoned.
+async fn scan_flat(
+    base_path: &str,
+    cfg: &AppConfig,
+    excludes: Arc<ExcludeMatcher>,
+    deadline: Option<Instant>,
+    trace: Option<&ScanTrace>,
+) -> Result<ScanOutcome> {
+    let listing = read_dir_listing(
+        PathBuf::from(base_path),
+        excludes,
+        Arc::default(),
+        cfg.main.follow_symlinks,
+        trace.cloned(),
+    )
+    .await?;
+
+    let mut checks = JoinSet::new();
+    for dir in listing.subdirs {
+        checks.spawn(async move {
+            let is_repo = tokio::fs::symlink_metadata(dir.join(".git")).await.is_ok();
+            if is_repo { Ok(dir) } else { Err(dir) }
+        });
     }
+    let mut paths = listing.repos;
+    let mut truncated = false;
+    loop {
+        if !checks.is_empty() && deadline.is_some_and(|d| Instant::now() >= d) {
+            truncated = true;
+            break; // pending checks are aborted when the join set is dropped
+        }
+        let joined = match deadline {
+            Some(d) => {
+                let until = tokio::time::Instant::from_std(d);
+                match tokio::time::timeout_at(until, checks.join_next()).await {
+                    Ok(joined) => joined,
+                    Err(_) => continue, // deadline reached, handled above
+                }
+            }
+            None => checks.join_next().await,
+        };
+        let Some(joined) = joined else {
+            break;
+        };
+        match joined {
+            Ok(Ok(dir)) => {
+                report(trace, &dir, ScanDecision::Repo);
+                paths.push(dir);
+            }
+            // Subdirectories are only checked for a `.git` entry, not read
+            Ok(Err(dir)) => report(trace, &dir, ScanDecision::Skipped(SkipReason::MaxDepth)),
+            Err(_) => {}
+        }
+    }
+
+    paths.sort();
+    Ok(ScanOutcome {
+        paths,
+        truncated,
+        dirs_visited: 1,
+    })
+}
 
-    exclude_patterns
-        .iter()
-        .any(|pattern| matches_wildcard(dir_name, pattern))
+/// Compile the exclude patterns for a scan of `base_path`
+fn exclude_matcher(base_path: &str, cfg: &AppConfig) -> Result<Arc<ExcludeMatcher>> {
+    let excludes = cfg
+        .exclude_matcher()
+        .with_gitignore(Path::new(base_path), &cfg.main.exclude)
+        .map_err(anyhow::Error::msg)?;
+    Ok(Arc::new(excludes))
 }
 
-/// Match a name against a pattern with wildcard support
-#[inline]
-fn matches_wildcard(name: &str, pattern: &str) -> bool {
-    if !pattern.contains('*') {
-        return name == pattern;
-    }
-
-    let parts: Vec<&str> = pattern.split('*').collect();
-
-    match parts.len() {
-        1 => true, // pattern is just "*"
-        2 => {
-            let (prefix, suffix) = (parts[0], parts[1]);
-            match (prefix.is_empty(), suffix.is_empty()) {
-                (true, false) => name.ends_with(suffix),   // "*suffix"
-                (false, true) => name.starts_with(prefix), // "prefix*"
-                (false, false) => {
-                    // "prefix*suffix"
-                    name.starts_with(prefix)
-                        && name.ends_with(suffix)
-                        && name.len() >= prefix.len() + suffix.len()
-                }
-                (true, true) => true, // "*"
+/// Read a single directory, collecting Git repositories and subdirectories to descend into
+///
+/// A directory skipped by `auto_exclude` is not descended into, unless it is
+/// a Git repository itself.
+async fn read_dir_listing(
+    path: PathBuf,
+    excludes: Arc<ExcludeMatcher>,
+    auto_exclude: Arc<AutoExclude>,
+    follow_symlinks: bool,
+    trace: Option<ScanTrace>,
+) -> io::Result<DirListing> {
+    let trace = trace.as_ref();
+    let mut entries = tokio::fs::read_dir(&path).await?;
+    let mut dir_entries = Vec::new();
+    // An error partway through keeps the entries read so far
+    loop {
+        match entries.next_entry().await {
+            Ok(Some(entry)) => dir_entries.push(entry),
+            Ok(None) => break,
+            Err(e) => {
+                warn!(
+                    "Error reading directory {:?}, using the entries read so far: {}",
+                    path, e
+                );
+                break;
+            }
+        }
+    }
+
+    let mut listing = DirListing::default();
+    // If we find a .git directory, or a .git file (gitlink, used by submodules),
+    // record the directory as a Git repository.
+    // After that, we will continue scanning other directories, thus finding nested repos.
+    let is_repo = dir_entries.iter().any(|e| e.file_name() == ".git");
+    if is_repo {
+        listing.repos.push(path.clone());
+    }
+
+    if !is_repo && auto_exclude.skips(&path, dir_entries.len()) {
+        info!(
+            entries = dir_entries.len(),
+            "Auto-excluding directory with more than {} entries: {:?}",
+            auto_exclude.max_entries.unwrap_or_default(),
+            path
+        );
+        report(
+            trace,
+            &path,
+            ScanDecision::Skipped(SkipReason::TooManyEntries),
+        );
+        return Ok(listing);
+    }
+    let decision = match is_repo {
+        true => ScanDecision::Repo,
+        false => ScanDecision::Entered,
+    };
+    report(trace, &path, decision);
+
+    for entry in dir_entries {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_str().unwrap_or("");
+        if file_name == ".git" {
+            continue;
+        }
+        if excludes.is_excluded(file_name) {
+            // Excluded entries are only checked for being directories when traced
+            if trace.is_some()
+                && is_scannable_dir(&entry.path(), entry.file_type().await, follow_symlinks).await
+            {
+                let reason = SkipReason::of_excluded(file_name);
+                report(trace, &entry.path(), ScanDecision::Skipped(reason));
             }
+            continue;
+        }
+        let entry_path = entry.path();
+        let file_type = entry.file_type().await;
+        let is_link = file_type.as_ref().is_ok_and(|t| t.is_symlink());
+        if !is_scannable_dir(&entry_path, file_type, follow_symlinks).await {
+            continue;
+        }
+        if !excludes.is_included(file_name) {
+            report(
+                trace,
+                &entry_path,
+                ScanDecision::Skipped(SkipReason::NotIncluded),
+            );
+            continue;
+        }
+        if excludes.is_path_excluded(&entry_path) {
+            report(
+                trace,
+                &entry_path,
+                ScanDecision::Skipped(SkipReason::ExcludePattern),
+            );
+            continue;
         }
-        _ => name == pattern, // complex patterns fallback to exact match
+        if is_link {
+            listing.links.insert(entry_path.clone());
+        }
+        listing.subdirs.push(entry_path);
+    }
+
+    Ok(listing)
+}
+
+/// Check whether a directory entry is a directory to descend into
+///
+/// The file type comes from the directory entry, which needs no extra `stat` on
+/// most filesystems and does not follow symbolic links. Links are only resolved
+/// with `follow_symlinks`. If the entry has no file type, it is read with `lstat`.
+async fn is_scannable_dir(
+    path: &Path,
+    file_type: io::Result<FileType>,
+    follow_symlinks: bool,
+) -> bool {
+    let file_type = match file_type {
+        Ok(file_type) => file_type,
+        Err(_) => match tokio::fs::symlink_metadata(path).await {
+            Ok(metadata) => metadata.file_type(),
+            Err(e) => {
+                debug!("Skipping entry with unknown file type {:?}: {}", path, e);
+                return false;
+            }
+        },
+    };
+    if file_type.is_symlink() {
+        follow_symlinks
+            && tokio::fs::metadata(path)
+                .await
+                .is_ok_and(|metadata| metadata.is_dir())
+    } else {
+        file_type.is_dir()
     }
 }
 
@@ -128,6 +702,312 @@ mod tests {
         fs::create_dir_all(path).unwrap();
     }
 
+    /// Reference implementation: the original sequential depth-first traversal
+    fn scan_sequential<'a>(
+        path: PathBuf,
+        cfg: &'a AppConfig,
+        depth: usize,
+        paths: &'a mut Vec<PathBuf>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(async move {
+            if cfg.main.max_depth > 0 && depth >= cfg.main.max_depth {
+                return Ok(());
+            }
+
+            let mut entries = tokio::fs::read_dir(&path).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let entry_path = entry.path();
+                let file_name = entry_path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("");
+                if file_name == ".git" {
+                    paths.push(path.clone());
+                    continue;
+                }
+                if !entry_path.is_dir() {
+                    continue;
+                }
+                if cfg.exclude_matcher().is_excluded(file_name) {
+                    continue;
+                }
+                let _ = scan_sequential(entry_path, cfg, depth + 1, paths).await;
+            }
+            Ok(())
+        })
+    }
+
+    /// Assert the concurrent scan finds the same repos as the sequential one
+    async fn assert_matches_sequential(base: &std::path::Path, cfg: &AppConfig) {
+        let mut expected = Vec::new();
+        scan_sequential(base.to_path_buf(), cfg, 0, &mut expected)
+            .await
+            .unwrap();
+        expected.sort();
+
+        let result = scan_directory(base.to_str().unwrap(), cfg).await.unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[tokio::test]
+    async fn test_scan_matches_sequential_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        // Wide, deep and nested fixtures with excluded and hidden directories
+        for i in 0..40 {
+            create_git_repo(&base.join(format!("wide{}", i)));
+        }
+        let mut deep = base.join("deep");
+        for level in 0..8 {
+            deep = deep.join(format!("level{}", level));
+            create_git_repo(&deep);
+        }
+        create_git_repo(&base.join("outer"));
+        create_git_repo(&base.join("outer").join("inner"));
+        create_git_repo(&base.join("node_modules").join("pkg"));
+        create_git_repo(&base.join(".hidden").join("repo"));
+        create_dir(&base.join("empty").join("nested"));
+
+        for max_depth in [0, 1, 2, 3, 5, 10] {
+            let mut config = AppConfig::default();
+            config.main.max_depth = max_depth;
+            assert_matches_sequential(base, &config).await;
+        }
+
+        let mut config = AppConfig::default();
+        config.internal.exclude_dirs = vec!["wide1*".to_string(), "level3".to_string()];
+        assert_matches_sequential(base, &config).await;
+    }
+
+    #[tokio::test]
+    async fn test_skip_dirs_with_many_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        // A flat cache directory, with a repo hidden below it
+        let cache = base.join("downloads");
+        create_git_repo(&cache.join("nested").join("repo"));
+        // A repo of the same size, with a nested repo
+        let big_repo = base.join("big_repo");
+        create_git_repo(&big_repo);
+        create_git_repo(&big_repo.join("inner"));
+        for dir in [&cache, &big_repo] {
+            for i in 0..200 {
+                fs::write(dir.join(format!("file{}", i)), "").unwrap();
+            }
+        }
+        create_git_repo(&base.join("small"));
+
+        let mut config = AppConfig::default();
+        config.main.skip_dirs_with_more_than = Some(100);
+        let result = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![big_repo.clone(), big_repo.join("inner"), base.join("small")]
+        );
+
+        // Without a threshold everything is found
+        let result = scan_directory(base.to_str().unwrap(), &AppConfig::default())
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_auto_exclude_spares_scan_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        for i in 0..30 {
+            create_git_repo(&base.join(format!("repo{}", i)));
+        }
+
+        let mut config = AppConfig::default();
+        config.main.skip_dirs_with_more_than = Some(10);
+        let result = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 30);
+
+        config.main.flat = true;
+        let result = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_auto_exclude_keeps_listed_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+
+        // Two large directories without .git, each hiding a repo
+        for name in ["dist-xyz", "checkouts"] {
+            let dir = base.join(name);
+            create_git_repo(&dir.join("repo"));
+            for i in 0..50 {
+                fs::write(dir.join(format!("file{}", i)), "").unwrap();
+            }
+        }
+
+        let mut config = AppConfig {
+            main: toml::from_str(
+                "scan_dirs = []\nmax_depth = 5\nauto_exclude_entry_threshold = 20\nauto_exclude_keep = [\"checkouts\"]",
+            )
+            .unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(config.main.skip_dirs_with_more_than, Some(20));
+        let result = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(result, vec![base.join("checkouts").join("repo")]);
+
+        // Below the threshold, nothing is skipped
+        config.main.skip_dirs_with_more_than = Some(100);
+        let result = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+    }
+
+    /// Scan `base` and collect the traced decisions, sorted by path
+    async fn traced_scan(base: &std::path::Path, cfg: &AppConfig) -> Vec<ScanEvent> {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let trace: ScanTrace = Arc::new(move |event| sink.lock().unwrap().push(event));
+        let base = base.to_string_lossy().to_string();
+        scan_directories_traced(&[base], cfg, Some(trace))
+            .await
+            .unwrap();
+        let mut events = events.lock().unwrap().clone();
+        events.sort_by(|a, b| a.path.cmp(&b.path));
+        events
+    }
+
+    #[tokio::test]
+    async fn test_trace_records_skip_reasons() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_git_repo(&base.join("app"));
+        create_git_repo(&base.join("node_modules").join("pkg"));
+        create_dir(&base.join(".cache"));
+        create_git_repo(&base.join("a").join("b").join("c"));
+        fs::write(base.join("node_modules.txt"), "").unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.max_depth = 3;
+        let events = traced_scan(base, &config).await;
+        let decision = |path: PathBuf| {
+            events
+                .iter()
+                .find(|event| event.path == path)
+                .map(|event| event.decision)
+        };
+
+        assert_eq!(decision(base.to_path_buf()), Some(ScanDecision::Entered));
+        assert_eq!(decision(base.join("app")), Some(ScanDecision::Repo));
+        assert_eq!(
+            decision(base.join("node_modules")),
+            Some(ScanDecision::Skipped(SkipReason::NamedExclude))
+        );
+        assert_eq!(SkipReason::NamedExclude.label(), "named exclude");
+        assert_eq!(
+            decision(base.join(".cache")),
+            Some(ScanDecision::Skipped(SkipReason::Hidden))
+        );
+        assert_eq!(
+            decision(base.join("a").join("b")),
+            Some(ScanDecision::Entered)
+        );
+        assert_eq!(
+            decision(base.join("a").join("b").join("c")),
+            Some(ScanDecision::Skipped(SkipReason::MaxDepth))
+        );
+        assert_eq!(SkipReason::MaxDepth.label(), "max depth");
+        // Files are not traced, and nothing below a skipped directory is
+        assert_eq!(decision(base.join("node_modules.txt")), None);
+        assert_eq!(decision(base.join("node_modules").join("pkg")), None);
+        assert_eq!(events.len(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_gitignore_exclude_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_git_repo(&base.join("generated").join("repo"));
+        create_git_repo(&base.join("app").join("generated").join("repo"));
+        create_git_repo(&base.join("vendor-a").join("repo"));
+        create_git_repo(&base.join("vendor-keep").join("repo"));
+
+        let mut config = AppConfig::default();
+        config.main.exclude = ["/generated/", "vendor*/", "!vendor-keep/"]
+            .map(String::from)
+            .to_vec();
+        let result = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            vec![
+                base.join("app").join("generated").join("repo"),
+                base.join("vendor-keep").join("repo"),
+            ]
+        );
+
+        let events = traced_scan(base, &config).await;
+        let skipped = events
+            .iter()
+            .find(|event| event.path == base.join("vendor-a"))
+            .map(|event| event.decision);
+        assert_eq!(
+            skipped,
+            Some(ScanDecision::Skipped(SkipReason::ExcludePattern))
+        );
+
+        config.main.exclude = vec!["{a".to_string()];
+        assert!(
+            scan_directory(base.to_str().unwrap(), &config)
+                .await
+                .is_err()
+        );
+        // An invalid pattern fails the whole scan instead of skipping the root
+        let roots = [base.to_str().unwrap().to_string()];
+        assert!(scan_directories(&roots, &config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_counts_visited_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_git_repo(&base.join("a"));
+        create_dir(&base.join("b").join("c"));
+        create_git_repo(&base.join("node_modules").join("pkg"));
+
+        let base = base.to_str().unwrap().to_string();
+        let outcome = scan_directories(&[base], &AppConfig::default())
+            .await
+            .unwrap();
+        // The root, a, b and b/c; excluded and .git directories are not read
+        assert_eq!(outcome.dirs_visited, 4);
+        assert_eq!(outcome.paths.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scan_missing_base_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        let config = AppConfig::default();
+        assert!(
+            scan_directory(missing.to_str().unwrap(), &config)
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_scan_empty_directory() {
         let temp_dir = TempDir::new().unwrap();
@@ -196,6 +1076,61 @@ mod tests {
         assert_eq!(result.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_scan_finds_gitlink_repos() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // Submodules have a .git file pointing into the superproject's .git dir
+        let parent = temp_dir.path().join("parent");
+        create_git_repo(&parent);
+        let submodule = parent.join("libs").join("sub");
+        create_dir(&submodule);
+        fs::write(submodule.join(".git"), "gitdir: ../../.git/modules/sub").unwrap();
+
+        let config = AppConfig::default();
+        let result = scan_directory(temp_dir.path().to_str().unwrap(), &config)
+            .await
+            .unwrap();
+
+        assert_eq!(result, vec![parent, submodule]);
+    }
+
+    #[tokio::test]
+    async fn test_scan_flat_matches_depth_two() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_git_repo(&base.join("app"));
+        create_git_repo(&base.join("app").join("vendor"));
+        create_git_repo(&base.join("group").join("deep"));
+        create_git_repo(&base.join("node_modules"));
+        create_dir(&base.join("sub"));
+        fs::write(base.join("sub").join(".git"), "gitdir: ../.git/modules/sub").unwrap();
+        fs::write(base.join("notes.txt"), "").unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.flat = true;
+        let flat = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(flat, vec![base.join("app"), base.join("sub")]);
+
+        config.main.flat = false;
+        config.main.max_depth = 2;
+        let depth_two = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(flat, depth_two);
+
+        // The scan root itself counts too
+        create_git_repo(base);
+        config.main.flat = true;
+        let flat = scan_directory(base.to_str().unwrap(), &config)
+            .await
+            .unwrap();
+        assert_eq!(flat.first().map(PathBuf::as_path), Some(base));
+        assert_eq!(flat.len(), 3);
+    }
+
     #[tokio::test]
     async fn test_scan_with_max_depth() {
         let temp_dir = TempDir::new().unwrap();
@@ -287,6 +1222,43 @@ mod tests {
         assert!(result[0].ends_with("repo1"));
     }
 
+    #[tokio::test]
+    async fn test_scan_with_include_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path();
+        create_git_repo(&base.join("service-api"));
+        create_git_repo(&base.join("service-legacy"));
+        create_git_repo(&base.join("lib-core"));
+        create_git_repo(&base.join("docs"));
+        create_git_repo(&base.join("tools/service-cli"));
+        let scan = |config: AppConfig| async move {
+            let mut found: Vec<String> = scan_directory(base.to_str().unwrap(), &config)
+                .await
+                .unwrap()
+                .iter()
+                .map(|path| path.strip_prefix(base).unwrap().display().to_string())
+                .collect();
+            found.sort();
+            found
+        };
+
+        // Without include patterns, everything is scanned
+        let mut config = AppConfig::default();
+        assert_eq!(scan(config.clone()).await.len(), 5);
+
+        // Only matching directories are descended into, so tools/ is skipped
+        config.main.include_dirs = vec!["service-*".to_string()];
+        assert_eq!(
+            scan(config.clone()).await,
+            ["service-api", "service-legacy"]
+        );
+
+        // Exclusion wins over inclusion
+        config.main.include_dirs = vec!["service-*".to_string(), "lib-*".to_string()];
+        config.internal.exclude_dirs.push("*-legacy".to_string());
+        assert_eq!(scan(config).await, ["lib-core", "service-api"]);
+    }
+
     #[tokio::test]
     async fn test_scan_ignores_non_git_dirs() {
         let temp_dir = TempDir::new().unwrap();
@@ -327,63 +1299,8 @@ mod tests {
         let config = AppConfig::default();
         let result = scan_directories(&paths, &config).await.unwrap();
 
-        assert_eq!(result.len(), 3);
-    }
-
-    #[test]
-    fn test_matches_wildcard_exact() {
-        assert!(matches_wildcard("node_modules", "node_modules"));
-        assert!(!matches_wildcard("node_modules", "target"));
-    }
-
-    #[test]
-    fn test_matches_wildcard_prefix() {
-        assert!(matches_wildcard("test_file", "test*"));
-        assert!(matches_wildcard("test", "test*"));
-        assert!(!matches_wildcard("other", "test*"));
-    }
-
-    #[test]
-    fn test_matches_wildcard_suffix() {
-        assert!(matches_wildcard("file.txt", "*.txt"));
-        assert!(matches_wildcard(".txt", "*.txt"));
-        assert!(!matches_wildcard("file.rs", "*.txt"));
-    }
-
-    #[test]
-    fn test_matches_wildcard_prefix_suffix() {
-        assert!(matches_wildcard("test_file.txt", "test*.txt"));
-        assert!(matches_wildcard("test.txt", "test*.txt"));
-        assert!(!matches_wildcard("other_file.txt", "test*.txt"));
-        assert!(!matches_wildcard("test", "test*.txt"));
-    }
-
-    #[test]
-    fn test_matches_wildcard_star_only() {
-        assert!(matches_wildcard("anything", "*"));
-        assert!(matches_wildcard("", "*"));
-    }
-
-    #[test]
-    fn test_is_excluded_hidden_dirs() {
-        let patterns = vec![];
-        assert!(is_excluded(".hidden", &patterns));
-        assert!(is_excluded(".git", &patterns));
-        assert!(!is_excluded("normal", &patterns));
-    }
-
-    #[test]
-    fn test_is_excluded_with_patterns() {
-        let patterns = vec![
-            "node_modules".to_string(),
-            "target".to_string(),
-            "*.tmp".to_string(),
-        ];
-
-        assert!(is_excluded("node_modules", &patterns));
-        assert!(is_excluded("target", &patterns));
-        assert!(is_excluded("file.tmp", &patterns));
-        assert!(!is_excluded("src", &patterns));
+        assert_eq!(result.paths.len(), 3);
+        assert!(!result.truncated);
     }
 
     #[tokio::test]
@@ -459,4 +1376,228 @@ mod tests {
 
         assert_eq!(result.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_scan_timeout_returns_partial_results() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // The base is itself a repo, found before any subdirectory is read
+        let base = temp_dir.path().join("base");
+        create_git_repo(&base);
+        let mut nested = base.clone();
+        for level in 0..5 {
+            nested = nested.join(format!("level{}", level));
+            create_git_repo(&nested);
+        }
+
+        // A zero budget expires before any subdirectory read completes
+        let mut config = AppConfig::default();
+        config.main.max_depth = 0;
+        config.internal.scan_timeout = Some(std::time::Duration::ZERO);
+        let paths = vec![base.to_str().unwrap().to_string()];
+        let result = scan_directories(&paths, &config).await.unwrap();
+
+        assert!(result.truncated);
+        assert_eq!(result.paths, vec![base.clone()]);
+
+        // A generous budget finds everything
+        config.internal.scan_timeout = Some(std::time::Duration::from_secs(60));
+        let result = scan_directories(&paths, &config).await.unwrap();
+
+        assert!(!result.truncated);
+        assert_eq!(result.paths.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_flat_scan_timeout_returns_partial_results() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // The base is itself a repo, found while reading the base listing
+        let base = temp_dir.path().join("base");
+        create_git_repo(&base);
+        for name in ["repo1", "repo2", "repo3"] {
+            create_git_repo(&base.join(name));
+        }
+
+        // A zero budget expires before any subdirectory check completes
+        let mut config = AppConfig::default();
+        config.main.flat = true;
+        config.internal.scan_timeout = Some(std::time::Duration::ZERO);
+        let paths = vec![base.to_str().unwrap().to_string()];
+        let result = scan_directories(&paths, &config).await.unwrap();
+
+        assert!(result.truncated);
+        assert_eq!(result.paths, vec![base.clone()]);
+
+        // A generous budget finds everything
+        config.internal.scan_timeout = Some(std::time::Duration::from_secs(60));
+        let result = scan_directories(&paths, &config).await.unwrap();
+
+        assert!(!result.truncated);
+        assert_eq!(result.paths.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_scan_resume_skips_completed_subtrees() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        create_git_repo(&root.join("a").join("r1"));
+        create_git_repo(&root.join("b").join("r2"));
+        let file = temp_dir.path().join("scan.json");
+
+        // An interrupted scan finished subtree "a" but not "b"
+        let mut checkpoint = ScanCheckpoint::new(&file);
+        checkpoint
+            .mark_complete(root.join("a"), vec![root.join("a").join("r1")])
+            .unwrap();
+        // Created after "a" was scanned, so a resumed scan does not see it
+        create_git_repo(&root.join("a").join("r3"));
+
+        let mut config = AppConfig::default();
+        config.internal.scan_checkpoint = Some(file.clone());
+        config.internal.resume_scan = true;
+        let paths = vec![root.to_str().unwrap().to_string()];
+        let result = scan_directories(&paths, &config).await.unwrap();
+        assert_eq!(
+            result.paths,
+            vec![root.join("a").join("r1"), root.join("b").join("r2")]
+        );
+
+        // The resumed scan finished, so the next resume starts over
+        let result = scan_directories(&paths, &config).await.unwrap();
+        assert_eq!(result.paths.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_scan_records_completed_subtrees() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        create_git_repo(&root.join("a").join("r1"));
+        create_dir(&root.join("b"));
+        let file = temp_dir.path().join("scan.json");
+
+        let mut config = AppConfig::default();
+        config.internal.scan_checkpoint = Some(file.clone());
+        let paths = vec![root.to_str().unwrap().to_string()];
+        scan_directories(&paths, &config).await.unwrap();
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+        assert_eq!(saved["complete"], true);
+        let subtrees = saved["subtrees"].as_object().unwrap();
+        assert_eq!(subtrees.len(), 2);
+        assert_eq!(
+            subtrees[root.join("a").to_str().unwrap()],
+            serde_json::json!([root.join("a").join("r1")])
+        );
+    }
+
+    /// Classify every entry of `dir` by name, as the scan does
+    async fn classify(dir: &std::path::Path, follow_symlinks: bool) -> Vec<(String, bool)> {
+        let mut entries = tokio::fs::read_dir(dir).await.unwrap();
+        let mut classified = Vec::new();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            let is_dir =
+                is_scannable_dir(&entry.path(), entry.file_type().await, follow_symlinks).await;
+            classified.push((entry.file_name().to_string_lossy().to_string(), is_dir));
+        }
+        classified.sort();
+        classified
+    }
+
+    #[tokio::test]
+    async fn test_classify_dirs_and_files() {
+        let temp_dir = TempDir::new().unwrap();
+        create_dir(&temp_dir.path().join("dir"));
+        fs::write(temp_dir.path().join("file"), "").unwrap();
+
+        let expected = [("dir".to_string(), true), ("file".to_string(), false)];
+        assert_eq!(classify(temp_dir.path(), true).await, expected);
+        assert_eq!(classify(temp_dir.path(), false).await, expected);
+
+        // Without a file type from the entry, the path is read instead
+        let dir = temp_dir.path().join("dir");
+        let unknown = || Err(io::Error::other("no file type"));
+        assert!(is_scannable_dir(&dir, unknown(), false).await);
+        assert!(!is_scannable_dir(&temp_dir.path().join("file"), unknown(), false).await);
+        assert!(!is_scannable_dir(&temp_dir.path().join("missing"), unknown(), true).await);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_classify_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let targets = temp_dir.path().join("targets");
+        create_dir(&targets.join("dir"));
+        fs::write(targets.join("file"), "").unwrap();
+        let links = temp_dir.path().join("links");
+        create_dir(&links);
+        symlink(targets.join("dir"), links.join("to_dir")).unwrap();
+        symlink(targets.join("file"), links.join("to_file")).unwrap();
+        symlink(targets.join("missing"), links.join("dangling")).unwrap();
+
+        assert_eq!(
+            classify(&links, true).await,
+            [
+                ("dangling".to_string(), false),
+                ("to_dir".to_string(), true),
+                ("to_file".to_string(), false),
+            ]
+        );
+        assert!(classify(&links, false).await.iter().all(|(_, d)| !d));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_scan_follow_symlinks_setting() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = temp_dir.path().join("outside");
+        create_git_repo(&outside.join("repo"));
+        let root = temp_dir.path().join("root");
+        create_dir(&root);
+        std::os::unix::fs::symlink(&outside, root.join("linked")).unwrap();
+
+        // Symlinked directories are not followed by default
+        let base = root.to_str().unwrap();
+        let mut config = AppConfig::default();
+        assert!(scan_directory(base, &config).await.unwrap().is_empty());
+
+        config.main.follow_symlinks = true;
+        let found = scan_directory(base, &config).await.unwrap();
+        assert_eq!(found, [root.join("linked").join("repo")]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_scan_symlink_cycle_terminates() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("root");
+        create_git_repo(&root.join("home/repo"));
+        // Links back to an ancestor, to the directory itself, and to each other
+        symlink(&root, root.join("home/current")).unwrap();
+        symlink(root.join("home"), root.join("home/self")).unwrap();
+        symlink(root.join("b"), root.join("a")).unwrap();
+        symlink(root.join("a"), root.join("b")).unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.max_depth = 0;
+        config.main.follow_symlinks = true;
+        let scan = scan_directory(root.to_str().unwrap(), &config);
+        let found = tokio::time::timeout(std::time::Duration::from_secs(10), scan)
+            .await
+            .expect("scan should terminate")
+            .unwrap();
+        assert_eq!(found, [root.join("home/repo")]);
+
+        // The links into visited directories are reported as skipped
+        let events = traced_scan(&root, &config).await;
+        assert!(events.contains(&ScanEvent {
+            path: root.join("home/current"),
+            decision: ScanDecision::Skipped(SkipReason::AlreadyVisited),
+        }));
+    }
 }
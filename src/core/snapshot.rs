@@ -0,0 +1,233 @@
+//! This module compares two snapshots of repository status, as saved by
+//! `list --json`, to show what changed between them.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::repo_info::RepoInfo;
+use super::watch::Health;
+
+/// Load a snapshot written by `list --json`
+pub fn load_snapshot(path: &Path) -> Result<Vec<RepoInfo>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+}
+
+/// A change of a single repository between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoChange {
+    /// The repository is new in the later snapshot
+    Added,
+    /// The repository is gone from the later snapshot
+    Removed,
+    /// The working tree became clean, dirty or conflicted
+    Health { from: Health, to: Health },
+    /// The checked-out branch changed
+    Branch { from: String, to: String },
+    /// The last commit changed
+    NewCommit { message: Option<String> },
+    /// The number of commits ahead of the upstream changed
+    Ahead { from: usize, to: usize },
+    /// The number of commits behind the upstream changed
+    Behind { from: usize, to: usize },
+}
+
+impl fmt::Display for RepoChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added => f.write_str("appeared"),
+            Self::Removed => f.write_str("disappeared"),
+            Self::Health { from, to } => write!(f, "became {} (was {})", to, from),
+            Self::Branch { from, to } => write!(f, "switched branch {} -> {}", from, to),
+            Self::NewCommit { message: Some(m) } => write!(f, "new commit: {}", m),
+            Self::NewCommit { message: None } => f.write_str("new commit"),
+            Self::Ahead { from, to } => write!(f, "ahead {} -> {}", from, to),
+            Self::Behind { from, to } => write!(f, "behind {} -> {}", from, to),
+        }
+    }
+}
+
+/// Changes of one repository, identified by its path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoDiff {
+    pub path: PathBuf,
+    pub name: String,
+    pub changes: Vec<RepoChange>,
+}
+
+/// Compare two snapshots, matching repositories by path
+///
+/// Only repositories with changes are returned, ordered by path.
+pub fn diff_snapshots(old: &[RepoInfo], new: &[RepoInfo]) -> Vec<RepoDiff> {
+    let mut pairs: BTreeMap<&Path, (Option<&RepoInfo>, Option<&RepoInfo>)> = BTreeMap::new();
+    for repo in old {
+        pairs.entry(&repo.basic.path).or_default().0 = Some(repo);
+    }
+    for repo in new {
+        pairs.entry(&repo.basic.path).or_default().1 = Some(repo);
+    }
+
+    pairs
+        .into_iter()
+        .filter_map(|(path, pair)| {
+            let (changes, name) = match pair {
+                (Some(old), Some(new)) => (repo_changes(old, new), &new.basic.name),
+                (None, Some(new)) => (vec![RepoChange::Added], &new.basic.name),
+                (Some(old), None) => (vec![RepoChange::Removed], &old.basic.name),
+                (None, None) => return None,
+            };
+            (!changes.is_empty()).then(|| RepoDiff {
+                path: path.to_path_buf(),
+                name: name.clone(),
+                changes,
+            })
+        })
+        .collect()
+}
+
+/// List the changes of a repository present in both snapshots
+///
+/// The working tree health is not compared if either snapshot did not load it.
+fn repo_changes(old: &RepoInfo, new: &RepoInfo) -> Vec<RepoChange> {
+    let mut changes = Vec::new();
+
+    let (from, to) = (Health::of(old), Health::of(new));
+    if from != to && !old.partial && !new.partial {
+        changes.push(RepoChange::Health { from, to });
+    }
+    if old.basic.branch != new.basic.branch {
+        changes.push(RepoChange::Branch {
+            from: old.basic.branch.clone(),
+            to: new.basic.branch.clone(),
+        });
+    }
+    if new.commit.time.is_some()
+        && (old.commit.time, &old.commit.message) != (new.commit.time, &new.commit.message)
+    {
+        changes.push(RepoChange::NewCommit {
+            message: new.commit.message.clone(),
+        });
+    }
+    if old.sync.ahead != new.sync.ahead {
+        changes.push(RepoChange::Ahead {
+            from: old.sync.ahead,
+            to: new.sync.ahead,
+        });
+    }
+    if old.sync.behind != new.sync.behind {
+        changes.push(RepoChange::Behind {
+            from: old.sync.behind,
+            to: new.sync.behind,
+        });
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn repo(name: &str) -> RepoInfo {
+        let mut repo = RepoInfo::stub(name, &format!("/work/{}", name));
+        repo.commit.time = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_000));
+        repo.commit.message = Some("Initial commit".to_string());
+        repo
+    }
+
+    fn dirty(mut repo: RepoInfo) -> RepoInfo {
+        repo.working.is_dirty = true;
+        repo.working.modified = 1;
+        repo
+    }
+
+    #[test]
+    fn test_diff_detects_transitions() {
+        let old = vec![
+            dirty(repo("cleaned")),
+            repo("pushed"),
+            repo("removed"),
+            repo("same"),
+        ];
+
+        let mut pushed = repo("pushed");
+        pushed.sync.ahead = 2;
+        pushed.commit.time = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(2_000));
+        pushed.commit.message = Some("Add feature".to_string());
+        let new = vec![repo("added"), repo("cleaned"), pushed, repo("same")];
+
+        let diff = diff_snapshots(&old, &new);
+        let summary: Vec<(&str, &[RepoChange])> = diff
+            .iter()
+            .map(|d| (d.name.as_str(), d.changes.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("added", &[RepoChange::Added][..]),
+                (
+                    "cleaned",
+                    &[RepoChange::Health {
+                        from: Health::Dirty,
+                        to: Health::Clean
+                    }][..]
+                ),
+                (
+                    "pushed",
+                    &[
+                        RepoChange::NewCommit {
+                            message: Some("Add feature".to_string())
+                        },
+                        RepoChange::Ahead { from: 0, to: 2 },
+                    ][..]
+                ),
+                ("removed", &[RepoChange::Removed][..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partial_snapshot_skips_health() {
+        let old = vec![dirty(repo("app"))];
+        let mut loading = repo("app");
+        loading.partial = true;
+
+        assert!(diff_snapshots(&old, &[loading]).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("snapshot.json");
+        let repos = vec![dirty(repo("app")), repo("lib")];
+        fs::write(&path, serde_json::to_string(&repos).unwrap()).unwrap();
+
+        let loaded = load_snapshot(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert!(diff_snapshots(&repos, &loaded).is_empty());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(
+            load_snapshot(&path)
+                .unwrap_err()
+                .contains("Failed to parse")
+        );
+    }
+
+    #[test]
+    fn test_change_descriptions() {
+        let health = RepoChange::Health {
+            from: Health::Clean,
+            to: Health::Dirty,
+        };
+        assert_eq!(health.to_string(), "became dirty (was clean)");
+        assert_eq!(
+            RepoChange::Ahead { from: 0, to: 3 }.to_string(),
+            "ahead 0 -> 3"
+        );
+    }
+}
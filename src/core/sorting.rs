@@ -0,0 +1,231 @@
+//! This module provides ordering of repository lists and file changes for display.
+
+use std::cmp::Reverse;
+use std::path::Path;
+
+use super::repo_info::{FileChange, FileChangeStatus, RepoInfo};
+
+/// File change categories, in the order they are displayed
+pub const FILE_CHANGE_ORDER: [FileChangeStatus; 4] = [
+    FileChangeStatus::Conflicted,
+    FileChangeStatus::Staged,
+    FileChangeStatus::Modified,
+    FileChangeStatus::Untracked,
+];
+
+/// Move pinned repositories to the front, in the order they are pinned
+///
+/// A pin matches a repository by name, directory name or path. Unpinned repositories keep
+/// their relative order after the pinned ones.
+pub fn sort_pinned_first(repos: &mut [RepoInfo], pinned: &[String]) {
+    if pinned.is_empty() {
+        return;
+    }
+    repos.sort_by_key(|repo| pinned_rank(repo, pinned).unwrap_or(usize::MAX));
+}
+
+/// Sort repositories by when their working tree was last edited, most recent first
+///
+/// Repositories without a known edit time go last, in their current order.
+pub fn sort_recently_edited_first(repos: &mut [RepoInfo]) {
+    repos.sort_by_key(|repo| Reverse(repo.working.workdir_mtime));
+}
+
+/// Sort repositories by their number of uncommitted changes, most first
+///
+/// Repositories with the same number keep their current order.
+pub fn sort_most_changes_first(repos: &mut [RepoInfo]) {
+    repos.sort_by_key(|repo| Reverse(repo.working.total_changes()));
+}
+
+/// Sort repositories by health score, least healthy first
+///
+/// Repositories without a score go last; ties keep their current order.
+pub fn sort_least_healthy_first(repos: &mut [RepoInfo]) {
+    repos.sort_by_key(|repo| repo.health.unwrap_or(u8::MAX));
+}
+
+/// Get the position of the first pin matching the repository, if any
+fn pinned_rank(repo: &RepoInfo, pinned: &[String]) -> Option<usize> {
+    pinned.iter().position(|pin| {
+        repo.basic.name == *pin || repo.basic.dir_name == *pin || repo.basic.path == Path::new(pin)
+    })
+}
+
+/// Sort file changes by category (conflicts first), then alphabetically by path
+pub fn sort_file_changes(changes: &[FileChange]) -> Vec<&FileChange> {
+    let mut sorted: Vec<&FileChange> = changes.iter().collect();
+    sorted.sort_by(|a, b| {
+        category_rank(a.status)
+            .cmp(&category_rank(b.status))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    sorted
+}
+
+/// Split file changes into categories in display order, each sorted by path
+///
+/// Categories without changes are left out.
+pub fn group_file_changes(changes: &[FileChange]) -> Vec<(FileChangeStatus, Vec<&FileChange>)> {
+    let sorted = sort_file_changes(changes);
+    FILE_CHANGE_ORDER
+        .iter()
+        .map(|&status| {
+            let files: Vec<&FileChange> = sorted
+                .iter()
+                .copied()
+                .filter(|change| change.status == status)
+                .collect();
+            (status, files)
+        })
+        .filter(|(_, files)| !files.is_empty())
+        .collect()
+}
+
+/// Get the display position of a file change category
+fn category_rank(status: FileChangeStatus) -> usize {
+    FILE_CHANGE_ORDER
+        .iter()
+        .position(|&s| s == status)
+        .unwrap_or(FILE_CHANGE_ORDER.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a clean repository info with the given name under /repos
+    fn repo(name: &str) -> RepoInfo {
+        RepoInfo::stub(name, &format!("/repos/{}", name))
+    }
+
+    fn names(repos: &[RepoInfo]) -> Vec<&str> {
+        repos.iter().map(|r| r.basic.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_most_changes_first() {
+        let mut repos = vec![repo("clean"), repo("few"), repo("many"), repo("tie")];
+        repos[1].working.modified = 2;
+        repos[2].working.untracked = 5;
+        repos[2].working.staged = 1;
+        repos[3].working.conflicts = 2;
+
+        sort_most_changes_first(&mut repos);
+
+        assert_eq!(names(&repos), vec!["many", "few", "tie", "clean"]);
+    }
+
+    #[test]
+    fn test_least_healthy_first() {
+        let mut repos = vec![repo("unknown"), repo("ok"), repo("bad"), repo("tie")];
+        repos[1].health = Some(80);
+        repos[2].health = Some(20);
+        repos[3].health = Some(80);
+
+        sort_least_healthy_first(&mut repos);
+
+        assert_eq!(names(&repos), vec!["bad", "ok", "tie", "unknown"]);
+    }
+
+    #[test]
+    fn test_pinned_first_in_config_order() {
+        let mut repos = vec![repo("a"), repo("b"), repo("c"), repo("d"), repo("e")];
+        let pinned = vec!["d".to_string(), "/repos/b".to_string()];
+
+        sort_pinned_first(&mut repos, &pinned);
+
+        assert_eq!(names(&repos), vec!["d", "b", "a", "c", "e"]);
+    }
+
+    #[test]
+    fn test_no_pins_keeps_order() {
+        let mut repos = vec![repo("c"), repo("a"), repo("b")];
+
+        sort_pinned_first(&mut repos, &[]);
+        assert_eq!(names(&repos), vec!["c", "a", "b"]);
+
+        sort_pinned_first(&mut repos, &["missing".to_string()]);
+        assert_eq!(names(&repos), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_recently_edited_first() {
+        let now = std::time::SystemTime::now();
+        let mut repos = vec![repo("old"), repo("unknown"), repo("new"), repo("mid")];
+        repos[0].working.workdir_mtime = Some(now - std::time::Duration::from_secs(300));
+        repos[2].working.workdir_mtime = Some(now);
+        repos[3].working.workdir_mtime = Some(now - std::time::Duration::from_secs(60));
+
+        sort_recently_edited_first(&mut repos);
+        assert_eq!(names(&repos), vec!["new", "mid", "old", "unknown"]);
+    }
+
+    fn change(path: &str, status: FileChangeStatus) -> FileChange {
+        FileChange {
+            path: path.to_string(),
+            status,
+            conflict_hunks: None,
+        }
+    }
+
+    fn mixed_changes() -> Vec<FileChange> {
+        vec![
+            change("src/zeta.rs", FileChangeStatus::Modified),
+            change("notes.txt", FileChangeStatus::Untracked),
+            change("src/main.rs", FileChangeStatus::Conflicted),
+            change("Cargo.toml", FileChangeStatus::Staged),
+            change("src/alpha.rs", FileChangeStatus::Modified),
+            change("build.rs", FileChangeStatus::Conflicted),
+            change("README.md", FileChangeStatus::Staged),
+        ]
+    }
+
+    #[test]
+    fn test_sort_file_changes_by_category_then_path() {
+        let changes = mixed_changes();
+        let paths: Vec<&str> = sort_file_changes(&changes)
+            .iter()
+            .map(|c| c.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "build.rs",
+                "src/main.rs",
+                "Cargo.toml",
+                "README.md",
+                "src/alpha.rs",
+                "src/zeta.rs",
+                "notes.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_file_changes() {
+        let changes = mixed_changes();
+        let groups: Vec<(FileChangeStatus, Vec<&str>)> = group_file_changes(&changes)
+            .into_iter()
+            .map(|(status, files)| (status, files.iter().map(|c| c.path.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    FileChangeStatus::Conflicted,
+                    vec!["build.rs", "src/main.rs"]
+                ),
+                (FileChangeStatus::Staged, vec!["Cargo.toml", "README.md"]),
+                (
+                    FileChangeStatus::Modified,
+                    vec!["src/alpha.rs", "src/zeta.rs"]
+                ),
+                (FileChangeStatus::Untracked, vec!["notes.txt"]),
+            ]
+        );
+
+        let staged_only = vec![change("a", FileChangeStatus::Staged)];
+        assert_eq!(group_file_changes(&staged_only).len(), 1);
+    }
+}
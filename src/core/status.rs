@@ -0,0 +1,527 @@
+//! This module classifies repositories into a single overall status.
+//!
+//! Classification is driven by a prioritized list of rules. User rules from
+//! the config are tried first, then the built-in rules.
+
+use serde::{Deserialize, Serialize};
+
+use super::repo_info::{RepoInfo, RepoWorkingStatus};
+use crate::config::ChangeCategory;
+
+/// Categories of uncommitted changes that make a repository dirty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyCategories {
+    staged: bool,
+    modified: bool,
+    untracked: bool,
+    conflicts: bool,
+}
+
+impl Default for DirtyCategories {
+    /// Any uncommitted change makes a repository dirty
+    fn default() -> Self {
+        Self::from_categories(&ChangeCategory::ALL)
+    }
+}
+
+impl DirtyCategories {
+    /// Set of the given categories
+    pub fn from_categories(categories: &[ChangeCategory]) -> Self {
+        let has = |category| categories.contains(&category);
+        Self {
+            staged: has(ChangeCategory::Staged),
+            modified: has(ChangeCategory::Modified),
+            untracked: has(ChangeCategory::Untracked),
+            conflicts: has(ChangeCategory::Conflicts),
+        }
+    }
+
+    /// Check whether a working tree has changes in any of these categories
+    pub fn is_dirty(self, working: &RepoWorkingStatus) -> bool {
+        (self.staged && working.staged > 0)
+            || (self.modified && working.modified > 0)
+            || (self.untracked && working.untracked > 0)
+            || (self.conflicts && working.conflicts > 0)
+    }
+}
+
+/// A condition on a repository's state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Conflict,
+    Dirty,
+    Staged,
+    Modified,
+    Untracked,
+    /// Untracked files but no staged, modified or conflicted ones
+    UntrackedOnly,
+    Ahead,
+    Behind,
+    UpstreamGone,
+    NoUpstream,
+    HasStashes,
+    /// No commits yet
+    Unborn,
+    /// HEAD points to a commit instead of a branch
+    Detached,
+    /// The index is locked by a leftover `index.lock`
+    Locked,
+}
+
+impl Condition {
+    /// Check whether the repository meets this condition
+    pub fn matches(self, repo: &RepoInfo) -> bool {
+        let working = &repo.working;
+        match self {
+            Self::Conflict => working.conflicts > 0,
+            Self::Dirty => working.is_dirty,
+            Self::Staged => working.staged > 0,
+            Self::Modified => working.modified > 0,
+            Self::Untracked => working.untracked > 0,
+            Self::UntrackedOnly => {
+                working.untracked > 0
+                    && working.staged == 0
+                    && working.modified == 0
+                    && working.conflicts == 0
+            }
+            Self::Ahead => repo.sync.ahead > 0,
+            Self::Behind => repo.sync.behind > 0,
+            Self::UpstreamGone => repo.sync.upstream_gone,
+            Self::NoUpstream => !repo.sync.has_upstream,
+            Self::HasStashes => repo.stash.count > 0,
+            Self::Unborn => repo.basic.is_unborn,
+            Self::Detached => repo.basic.is_detached,
+            Self::Locked => working.locked,
+        }
+    }
+}
+
+/// Color of a status, resolved by the CLI and the TUI theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusColor {
+    Clean,
+    Dirty,
+    Conflict,
+    Sync,
+    Gone,
+    Muted,
+}
+
+/// A classification rule: if all conditions hold, the repo gets this status
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusRule {
+    /// Conditions that must all hold (an empty list always matches)
+    #[serde(default)]
+    pub when: Vec<Condition>,
+    pub label: String,
+    pub color: StatusColor,
+}
+
+impl StatusRule {
+    fn new(when: &[Condition], label: &str, color: StatusColor) -> Self {
+        Self {
+            when: when.to_vec(),
+            label: label.to_string(),
+            color,
+        }
+    }
+
+    /// Check whether all conditions of this rule hold for the repository
+    pub fn matches(&self, repo: &RepoInfo) -> bool {
+        self.when.iter().all(|c| c.matches(repo))
+    }
+}
+
+/// Overall status of a repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoStatus {
+    pub label: String,
+    pub color: StatusColor,
+}
+
+/// Built-in rules, in priority order, ending with a catch-all
+fn default_rules() -> Vec<StatusRule> {
+    use Condition::*;
+    vec![
+        StatusRule::new(&[Conflict], "conflict", StatusColor::Conflict),
+        StatusRule::new(&[Locked], "locked", StatusColor::Conflict),
+        StatusRule::new(&[Dirty], "dirty", StatusColor::Dirty),
+        StatusRule::new(&[Unborn], "empty", StatusColor::Muted),
+        StatusRule::new(&[Detached], "detached", StatusColor::Gone),
+        StatusRule::new(&[Ahead], "unpushed", StatusColor::Sync),
+        StatusRule::new(&[Behind], "unpulled", StatusColor::Sync),
+        StatusRule::new(&[UpstreamGone], "gone", StatusColor::Gone),
+        StatusRule::new(&[NoUpstream], "no upstream", StatusColor::Muted),
+        StatusRule::new(&[], "clean", StatusColor::Clean),
+    ]
+}
+
+/// Classify a repository using the first matching rule
+///
+/// `rules` are tried in order before the built-in rules. Repositories whose
+/// working tree status is not loaded yet are reported as loading.
+pub fn overall_status(repo: &RepoInfo, rules: &[StatusRule]) -> RepoStatus {
+    if repo.partial {
+        return RepoStatus {
+            label: "loading".to_string(),
+            color: StatusColor::Muted,
+        };
+    }
+
+    let rule = rules
+        .iter()
+        .find(|rule| rule.matches(repo))
+        .cloned()
+        .or_else(|| default_rules().into_iter().find(|rule| rule.matches(repo)))
+        .expect("built-in rules end with a catch-all");
+
+    RepoStatus {
+        label: rule.label,
+        color: rule.color,
+    }
+}
+
+/// Conditions with a `list --porcelain` code, in the priority of the built-in rules
+///
+/// The codes are part of a stable output format and must not change, so a
+/// detached HEAD, classified after the format was fixed, has no code of its own.
+const PORCELAIN_CODES: [(Condition, char); 8] = [
+    (Condition::Conflict, 'C'),
+    (Condition::Locked, 'L'),
+    (Condition::Dirty, 'D'),
+    (Condition::Unborn, 'E'),
+    (Condition::Ahead, 'P'),
+    (Condition::Behind, 'U'),
+    (Condition::UpstreamGone, 'G'),
+    (Condition::NoUpstream, 'N'),
+];
+
+/// Single-character code of a repository's status, for `list --porcelain`
+///
+/// Codes follow the built-in classification only, so that they do not depend
+/// on the configuration: the code of the first matching condition, or `.`.
+pub fn porcelain_code(repo: &RepoInfo) -> char {
+    if repo.partial {
+        return '?';
+    }
+    PORCELAIN_CODES
+        .iter()
+        .find(|(condition, _)| condition.matches(repo))
+        .map_or('.', |&(_, code)| code)
+}
+
+/// Number of repositories by overall status color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusCounts {
+    pub clean: usize,
+    pub dirty: usize,
+    pub conflict: usize,
+    /// Repositories out of sync, without upstream, loading or otherwise notable
+    pub other: usize,
+}
+
+impl StatusCounts {
+    /// Count repositories by the color of their overall status
+    pub fn from_repos(repos: &[RepoInfo], rules: &[StatusRule]) -> Self {
+        let mut counts = Self::default();
+        for repo in repos {
+            match overall_status(repo, rules).color {
+                StatusColor::Clean => counts.clean += 1,
+                StatusColor::Dirty => counts.dirty += 1,
+                StatusColor::Conflict => counts.conflict += 1,
+                _ => counts.other += 1,
+            }
+        }
+        counts
+    }
+
+    /// Number of repositories counted
+    pub fn total(&self) -> usize {
+        self.clean + self.dirty + self.conflict + self.other
+    }
+}
+
+/// Totals of pending work across repositories
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepoTotals {
+    pub repos: usize,
+    /// Uncommitted files, of repositories whose status is loaded
+    pub uncommitted: usize,
+    pub stashes: usize,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+impl RepoTotals {
+    /// Sum the pending work of all repositories
+    pub fn from_repos(repos: &[RepoInfo]) -> Self {
+        let mut totals = Self {
+            repos: repos.len(),
+            ..Self::default()
+        };
+        for repo in repos {
+            if !repo.partial {
+                totals.uncommitted += repo.working.total_changes();
+            }
+            totals.stashes += repo.stash.count;
+            totals.ahead += repo.sync.ahead;
+            totals.behind += repo.sync.behind;
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a clean repository info that has an upstream
+    fn clean_repo() -> RepoInfo {
+        let mut repo = RepoInfo::stub("repo", "/repos/repo");
+        repo.sync.has_upstream = true;
+        repo
+    }
+
+    #[test]
+    fn test_dirty_categories() {
+        let untracked_only = RepoWorkingStatus {
+            untracked: 3,
+            ..Default::default()
+        };
+        let modified = RepoWorkingStatus {
+            modified: 1,
+            untracked: 3,
+            ..Default::default()
+        };
+        let tracked_only = DirtyCategories::from_categories(&[
+            ChangeCategory::Staged,
+            ChangeCategory::Modified,
+            ChangeCategory::Conflicts,
+        ]);
+
+        assert!(DirtyCategories::default().is_dirty(&untracked_only));
+        assert!(!tracked_only.is_dirty(&untracked_only));
+        assert!(tracked_only.is_dirty(&modified));
+        assert!(!DirtyCategories::from_categories(&[]).is_dirty(&modified));
+        assert!(!DirtyCategories::default().is_dirty(&RepoWorkingStatus::default()));
+    }
+
+    #[test]
+    fn test_default_classification() {
+        let mut repo = clean_repo();
+        assert_eq!(overall_status(&repo, &[]).label, "clean");
+
+        repo.sync.ahead = 1;
+        assert_eq!(overall_status(&repo, &[]).label, "unpushed");
+
+        repo.basic.is_detached = true;
+        let status = overall_status(&repo, &[]);
+        assert_eq!(status.label, "detached");
+        assert_eq!(status.color, StatusColor::Gone);
+
+        repo.working.is_dirty = true;
+        repo.working.untracked = 1;
+        let status = overall_status(&repo, &[]);
+        assert_eq!(status.label, "dirty");
+        assert_eq!(status.color, StatusColor::Dirty);
+    }
+
+    #[test]
+    fn test_status_counts() {
+        let mut dirty = clean_repo();
+        dirty.working.is_dirty = true;
+        let mut conflict = clean_repo();
+        conflict.working.conflicts = 1;
+        let mut unpushed = clean_repo();
+        unpushed.sync.ahead = 2;
+        let mut loading = clean_repo();
+        loading.partial = true;
+        let repos = [
+            clean_repo(),
+            clean_repo(),
+            dirty,
+            conflict,
+            unpushed,
+            loading,
+        ];
+
+        let counts = StatusCounts::from_repos(&repos, &[]);
+        assert_eq!(
+            counts,
+            StatusCounts {
+                clean: 2,
+                dirty: 1,
+                conflict: 1,
+                other: 2,
+            }
+        );
+        assert_eq!(counts.total(), 6);
+        assert_eq!(StatusCounts::from_repos(&[], &[]).total(), 0);
+
+        // Custom rules change the classification
+        let rules = vec![StatusRule::new(
+            &[Condition::Ahead],
+            "ahead",
+            StatusColor::Dirty,
+        )];
+        assert_eq!(StatusCounts::from_repos(&repos, &rules).dirty, 2);
+    }
+
+    #[test]
+    fn test_repo_totals() {
+        let mut api = clean_repo();
+        api.working.modified = 2;
+        api.working.untracked = 1;
+        api.stash.count = 1;
+        api.sync.ahead = 3;
+        let mut web = clean_repo();
+        web.working.staged = 4;
+        web.sync.behind = 2;
+        web.stash.count = 2;
+        // Counts of a repo still loading are not known yet
+        let mut loading = clean_repo();
+        loading.partial = true;
+        loading.working.modified = 7;
+
+        assert_eq!(
+            RepoTotals::from_repos(&[api, web, loading, clean_repo()]),
+            RepoTotals {
+                repos: 4,
+                uncommitted: 7,
+                stashes: 3,
+                ahead: 3,
+                behind: 2,
+            }
+        );
+        assert_eq!(RepoTotals::from_repos(&[]), RepoTotals::default());
+    }
+
+    #[test]
+    fn test_porcelain_codes() {
+        let code = |change: fn(&mut RepoInfo)| {
+            let mut repo = clean_repo();
+            change(&mut repo);
+            porcelain_code(&repo)
+        };
+        assert_eq!(code(|_| {}), '.');
+        assert_eq!(code(|r| r.working.conflicts = 1), 'C');
+        assert_eq!(code(|r| r.working.locked = true), 'L');
+        assert_eq!(code(|r| r.working.is_dirty = true), 'D');
+        assert_eq!(code(|r| r.basic.is_unborn = true), 'E');
+        assert_eq!(code(|r| r.sync.ahead = 1), 'P');
+        assert_eq!(code(|r| r.sync.behind = 1), 'U');
+        assert_eq!(code(|r| r.sync.upstream_gone = true), 'G');
+        assert_eq!(code(|r| r.sync.has_upstream = false), 'N');
+        assert_eq!(code(|r| r.partial = true), '?');
+        // Detached HEAD has no code of its own
+        assert_eq!(code(|r| r.basic.is_detached = true), '.');
+        assert_eq!(
+            code(|r| {
+                r.basic.is_detached = true;
+                r.sync.has_upstream = false;
+            }),
+            'N'
+        );
+    }
+
+    #[test]
+    fn test_porcelain_codes_follow_default_rules() {
+        // Codes keep the priority of the built-in rules they were taken from
+        let rule_conditions: Vec<Condition> = default_rules()
+            .iter()
+            .filter_map(|rule| rule.when.first().copied())
+            .filter(|&condition| condition != Condition::Detached)
+            .collect();
+        let code_conditions: Vec<Condition> = PORCELAIN_CODES.iter().map(|&(c, _)| c).collect();
+        assert_eq!(code_conditions, rule_conditions);
+    }
+
+    #[test]
+    fn test_unborn_repo_is_empty() {
+        let mut repo = RepoInfo::stub("repo", "/repos/repo");
+        repo.basic.is_unborn = true;
+        assert_eq!(overall_status(&repo, &[]).label, "empty");
+    }
+
+    #[test]
+    fn test_locked_repo() {
+        let mut repo = clean_repo();
+        repo.working.locked = true;
+        repo.working.is_dirty = true;
+        let status = overall_status(&repo, &[]);
+        assert_eq!(status.label, "locked");
+        assert_eq!(status.color, StatusColor::Conflict);
+    }
+
+    #[test]
+    fn test_partial_repo_is_loading() {
+        let mut repo = clean_repo();
+        repo.partial = true;
+        let status = overall_status(&repo, &[]);
+        assert_eq!(status.label, "loading");
+        assert_eq!(status.color, StatusColor::Muted);
+    }
+
+    #[test]
+    fn test_custom_rule_flags_stashes() {
+        let mut repo = clean_repo();
+        repo.stash.count = 2;
+        let rules = [StatusRule::new(
+            &[Condition::HasStashes],
+            "stashed",
+            StatusColor::Dirty,
+        )];
+
+        assert_eq!(overall_status(&repo, &[]).label, "clean");
+        let status = overall_status(&repo, &rules);
+        assert_eq!(status.label, "stashed");
+        assert_eq!(status.color, StatusColor::Dirty);
+    }
+
+    #[test]
+    fn test_custom_rule_downgrades_untracked_only() {
+        let rules = [StatusRule::new(
+            &[Condition::UntrackedOnly],
+            "clean",
+            StatusColor::Clean,
+        )];
+
+        let mut repo = clean_repo();
+        repo.working.is_dirty = true;
+        repo.working.untracked = 3;
+        assert_eq!(overall_status(&repo, &rules).label, "clean");
+
+        // Modified files still fall through to the built-in dirty rule
+        repo.working.modified = 1;
+        assert_eq!(overall_status(&repo, &rules).label, "dirty");
+    }
+
+    #[test]
+    fn test_rules_from_toml() {
+        #[derive(Deserialize)]
+        struct Rules {
+            status_rules: Vec<StatusRule>,
+        }
+
+        let rules: Rules = toml::from_str(
+            r#"
+            [[status_rules]]
+            when = ["has_stashes", "no_upstream"]
+            label = "attention"
+            color = "conflict"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules.status_rules,
+            vec![StatusRule::new(
+                &[Condition::HasStashes, Condition::NoUpstream],
+                "attention",
+                StatusColor::Conflict,
+            )]
+        );
+    }
+}
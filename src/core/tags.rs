@@ -0,0 +1,165 @@
+//! This module attaches user-maintained tags to repositories.
+//!
+//! Tags come from a TOML metadata file, kept apart from the git data, that maps
+//! a repository name, directory name or path to a list of tags:
+//!
+//! ```toml
+//! "~/work/api" = ["work"]
+//! dotfiles = ["personal"]
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::repo_info::RepoInfo;
+use crate::config::{AppConfig, expand_tilde_in_path};
+
+/// Tags of repositories, keyed by repository name, directory name or path
+#[derive(Debug, Clone, Default)]
+pub struct TagStore {
+    entries: BTreeMap<String, Vec<String>>,
+}
+
+impl TagStore {
+    /// Load the tags file set in the configuration, or an empty store if none is set
+    pub fn from_config(config: &AppConfig) -> Result<Self, String> {
+        match config.main.tags_file {
+            Some(ref path) => Self::load(Path::new(path)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Load a tags file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+        Self::parse(&content).map_err(|e| format!("Failed to parse {:?}: {}", path, e))
+    }
+
+    /// Parse the content of a tags file, expanding `~` in paths
+    fn parse(content: &str) -> Result<Self, String> {
+        let entries: BTreeMap<String, Vec<String>> =
+            toml::from_str(content).map_err(|e| e.to_string())?;
+        Ok(Self {
+            entries: entries
+                .into_iter()
+                .map(|(key, tags)| Ok((expand_tilde_in_path(&key)?, tags)))
+                .collect::<Result<_, String>>()?,
+        })
+    }
+
+    /// Check whether no repository has tags
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the tags of a repository, sorted and without duplicates
+    ///
+    /// Tags of every entry matching the repository are combined.
+    pub fn tags_for(&self, repo: &RepoInfo) -> Vec<String> {
+        let basic = &repo.basic;
+        let mut tags: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(key, _)| {
+                basic.name == **key || basic.dir_name == **key || basic.path == Path::new(key)
+            })
+            .flat_map(|(_, tags)| tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Set the tags of each repository from this store
+    pub fn apply(&self, repos: &mut [RepoInfo]) {
+        if self.is_empty() {
+            return;
+        }
+        for repo in repos {
+            repo.basic.tags = self.tags_for(repo);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAGS: &str = r#"
+        api = ["work"]
+        "/home/me/work/api" = ["backend", "work"]
+        dotfiles = ["personal", "archived"]
+    "#;
+
+    #[test]
+    fn test_parse_tags_file() {
+        let store = TagStore::parse(TAGS).unwrap();
+        assert_eq!(store.entries.len(), 3);
+        assert_eq!(store.entries["dotfiles"], ["personal", "archived"]);
+
+        assert!(TagStore::parse("api = \"work\"").is_err());
+        assert!(TagStore::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tags_by_name_and_path() {
+        let store = TagStore::parse(TAGS).unwrap();
+
+        let api = RepoInfo::stub("api", "/home/me/work/api");
+        assert_eq!(store.tags_for(&api), ["backend", "work"]);
+
+        // Same name at another path only gets the name's tags
+        let other_api = RepoInfo::stub("api", "/tmp/api");
+        assert_eq!(store.tags_for(&other_api), ["work"]);
+
+        // The directory name matches too
+        let mut dotfiles = RepoInfo::stub("dots", "/home/me/dotfiles");
+        dotfiles.basic.dir_name = "dotfiles".to_string();
+        assert_eq!(store.tags_for(&dotfiles), ["archived", "personal"]);
+
+        assert!(
+            store
+                .tags_for(&RepoInfo::stub("web", "/home/me/web"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_apply_and_filter_by_tag() {
+        let store = TagStore::parse(TAGS).unwrap();
+        let mut repos = vec![
+            RepoInfo::stub("api", "/home/me/work/api"),
+            RepoInfo::stub("dotfiles", "/home/me/dotfiles"),
+            RepoInfo::stub("web", "/home/me/web"),
+        ];
+        store.apply(&mut repos);
+
+        let tagged = |tag: &str| -> Vec<&str> {
+            repos
+                .iter()
+                .filter(|r| r.has_tag(tag))
+                .map(|r| r.basic.name.as_str())
+                .collect()
+        };
+        assert_eq!(tagged("work"), ["api"]);
+        assert_eq!(tagged("personal"), ["dotfiles"]);
+        assert!(tagged("missing").is_empty());
+    }
+
+    #[test]
+    fn test_load_tags_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("tags.toml");
+        fs::write(&path, TAGS).unwrap();
+        assert_eq!(TagStore::load(&path).unwrap().entries.len(), 3);
+
+        fs::write(&path, "not toml [").unwrap();
+        assert!(
+            TagStore::load(&path)
+                .unwrap_err()
+                .contains("Failed to parse")
+        );
+    }
+}
@@ -0,0 +1,273 @@
+//! This module provides text helpers for fitting repository information into
+//! fixed-width terminal columns.
+
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Ellipsis appended to truncated text
+const ELLIPSIS: char = '…';
+
+/// Get the display width of a string in terminal columns
+#[inline]
+pub fn display_width(s: &str) -> usize {
+    s.width()
+}
+
+/// Truncate a string to fit within `max_width` terminal columns
+///
+/// If the string is wider than `max_width`, it is cut at a character boundary
+/// and an ellipsis is appended so that the result never exceeds `max_width`.
+pub fn truncate_to_width(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_width - 1; // reserve one column for the ellipsis
+    let mut result = String::new();
+    let mut width = 0;
+    for c in s.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width > budget {
+            break;
+        }
+        width += char_width;
+        result.push(c);
+    }
+    result.push(ELLIPSIS);
+    result
+}
+
+/// Truncate a string from the left to fit within `max_width` terminal columns
+///
+/// Keeps the trailing part, which is the meaningful one for paths, and puts
+/// an ellipsis in front so that the result never exceeds `max_width`.
+pub fn truncate_start_to_width(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_width - 1; // reserve one column for the ellipsis
+    let mut kept = Vec::new();
+    let mut width = 0;
+    for c in s.chars().rev() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width > budget {
+            break;
+        }
+        width += char_width;
+        kept.push(c);
+    }
+    std::iter::once(ELLIPSIS)
+        .chain(kept.into_iter().rev())
+        .collect()
+}
+
+/// Widest label the column layout of the detail view aligns
+pub const MAX_LABEL_WIDTH: usize = 24;
+
+/// Byte length of the `Label: ` prefix of a detail line, if it has one
+///
+/// The label runs up to the first `: ` and must be at most
+/// [`MAX_LABEL_WIDTH`] columns wide, so values containing `: ` are not split.
+pub fn label_prefix_len(line: &str) -> Option<usize> {
+    let end = line.find(": ")? + 2;
+    (!line.starts_with(' ') && line[..end].width() <= MAX_LABEL_WIDTH).then_some(end)
+}
+
+/// Byte ranges of the lines `text` wraps into at `width` columns
+///
+/// Lines break at the last space that fits, or inside words wider than a
+/// whole line. The spaces at a break belong to neither line.
+pub fn wrap_ranges(text: &str, width: usize) -> Vec<Range<usize>> {
+    let width = width.max(1);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    loop {
+        let mut used = 0;
+        let mut end = start;
+        let mut last_space = None;
+        for (i, c) in text[start..].char_indices() {
+            let char_width = c.width().unwrap_or(0);
+            // Always take one character so that every line makes progress
+            if used + char_width > width && end > start {
+                break;
+            }
+            if c == ' ' {
+                last_space = Some(start + i);
+            }
+            used += char_width;
+            end = start + i + c.len_utf8();
+        }
+        if end == text.len() {
+            ranges.push(start..start + text[start..end].trim_end_matches(' ').len());
+            return ranges;
+        }
+        let line_end = match last_space {
+            Some(space) if space > start => space,
+            _ => end,
+        };
+        ranges.push(start..start + text[start..line_end].trim_end_matches(' ').len());
+        start = text.len() - text[line_end..].trim_start_matches(' ').len();
+        if start == text.len() {
+            return ranges;
+        }
+    }
+}
+
+/// Format a point in time relative to now, e.g. "3 days ago"
+pub fn format_relative_time(time: SystemTime) -> String {
+    let elapsed = SystemTime::now()
+        .duration_since(time)
+        .unwrap_or(Duration::ZERO);
+    format_elapsed(elapsed)
+}
+
+/// Format an elapsed duration as a coarse human-readable age
+fn format_elapsed(elapsed: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const MONTH: u64 = 30 * DAY;
+    const YEAR: u64 = 365 * DAY;
+
+    let secs = elapsed.as_secs();
+    let (count, unit) = match secs {
+        s if s < MINUTE => return "just now".to_string(),
+        s if s < HOUR => (s / MINUTE, "minute"),
+        s if s < DAY => (s / HOUR, "hour"),
+        s if s < MONTH => (s / DAY, "day"),
+        s if s < YEAR => (s / MONTH, "month"),
+        s => (s / YEAR, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    format!("{} {}{} ago", count, unit, plural)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_fits() {
+        assert_eq!(truncate_to_width("main", 10), "main");
+        assert_eq!(truncate_to_width("main", 4), "main");
+        assert_eq!(truncate_to_width("", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate_to_width("feature/long-branch", 10), "feature/l…");
+        assert_eq!(truncate_to_width("abc", 1), "…");
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        // Each CJK character is two columns wide
+        let s = "修复了一个问题";
+        assert_eq!(display_width(s), 14);
+        assert_eq!(truncate_to_width(s, 14), s);
+        assert_eq!(truncate_to_width(s, 7), "修复了…");
+        assert_eq!(truncate_to_width(s, 6), "修复…");
+        assert!(display_width(&truncate_to_width(s, 6)) <= 6);
+
+        // Accented characters are one column wide but multiple bytes
+        assert_eq!(truncate_to_width("café résumé", 6), "café …");
+    }
+
+    /// Lines `text` wraps into at `width` columns
+    fn wrap(text: &str, width: usize) -> Vec<&str> {
+        wrap_ranges(text, width)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_ranges() {
+        assert_eq!(wrap("3 staged, 2 modified", 40), ["3 staged, 2 modified"]);
+        assert_eq!(
+            wrap("3 staged, 2 modified", 10),
+            ["3 staged,", "2 modified"]
+        );
+        assert_eq!(wrap("abc def", 3), ["abc", "def"]);
+        assert_eq!(wrap("", 10), [""]);
+
+        // Words wider than a line are broken, trailing spaces are dropped
+        assert_eq!(
+            wrap("origin/feature/long x  ", 8),
+            ["origin/f", "eature/l", "ong x"]
+        );
+        // Wide characters count two columns
+        assert_eq!(wrap("修复了一个问题", 6), ["修复了", "一个问", "题"]);
+        for line in wrap("fix the flaky scanner test on slow machines", 12) {
+            assert!(display_width(line) <= 12);
+        }
+    }
+
+    #[test]
+    fn test_label_prefix_len() {
+        assert_eq!(label_prefix_len("Branch: main"), Some(8));
+        assert_eq!(label_prefix_len("Message: fix: typo"), Some(9));
+        assert_eq!(label_prefix_len("  src/main.rs"), None);
+        assert_eq!(label_prefix_len("No remote"), None);
+        assert_eq!(
+            label_prefix_len("a very long sentence that is no label: x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_truncate_start() {
+        let path = "/home/user/projects/reponest";
+        assert_eq!(truncate_start_to_width(path, 40), path);
+        assert_eq!(truncate_start_to_width(path, path.len()), path);
+        assert_eq!(truncate_start_to_width(path, 10), "…/reponest");
+        assert_eq!(truncate_start_to_width(path, 1), "…");
+        assert_eq!(truncate_start_to_width(path, 0), "");
+    }
+
+    #[test]
+    fn test_truncate_start_multibyte() {
+        // Each CJK character is two columns wide
+        let path = "/home/用户/项目";
+        assert_eq!(display_width(path), 15);
+        assert_eq!(truncate_start_to_width(path, 15), path);
+        assert_eq!(truncate_start_to_width(path, 6), "…/项目");
+        // A wide character that does not fit is dropped whole
+        assert_eq!(truncate_start_to_width(path, 5), "…项目");
+        assert_eq!(truncate_start_to_width(path, 4), "…目");
+        assert!(display_width(&truncate_start_to_width(path, 4)) <= 4);
+
+        assert_eq!(truncate_start_to_width("/srv/café", 5), "…café");
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_secs(5)), "just now");
+        assert_eq!(format_elapsed(Duration::from_secs(60)), "1 minute ago");
+        assert_eq!(format_elapsed(Duration::from_secs(3 * 3600)), "3 hours ago");
+        assert_eq!(format_elapsed(Duration::from_secs(3 * 86400)), "3 days ago");
+        assert_eq!(
+            format_elapsed(Duration::from_secs(65 * 86400)),
+            "2 months ago"
+        );
+        assert_eq!(
+            format_elapsed(Duration::from_secs(400 * 86400)),
+            "1 year ago"
+        );
+    }
+
+    #[test]
+    fn test_format_relative_time_in_future() {
+        let future = SystemTime::now() + Duration::from_secs(3600);
+        assert_eq!(format_relative_time(future), "just now");
+    }
+}
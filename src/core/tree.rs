@@ -0,0 +1,130 @@
+//! This module arranges repositories into a tree following filesystem nesting.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// A repository in the nesting tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoTreeNode {
+    /// Index of the repository in the input slice
+    pub index: usize,
+    /// Repositories nested inside this one
+    pub children: Vec<Self>,
+}
+
+/// Build a forest of repositories from their paths
+///
+/// Each repository becomes a child of the closest repository containing it,
+/// even when intermediate directories are not repositories. Repositories
+/// without a containing repository are roots. Siblings are sorted by path.
+pub fn build_repo_tree<P: AsRef<Path>>(paths: &[P]) -> Vec<RepoTreeNode> {
+    let mut order: Vec<usize> = (0..paths.len()).collect();
+    order.sort_by(|&a, &b| paths[a].as_ref().cmp(paths[b].as_ref()));
+
+    // Closest containing repository of each repository
+    let parents: Vec<Option<usize>> = (0..paths.len())
+        .map(|i| {
+            let path = paths[i].as_ref();
+            (0..paths.len())
+                .filter(|&j| {
+                    let candidate = paths[j].as_ref();
+                    candidate != path && path.starts_with(candidate)
+                })
+                .max_by_key(|&j| paths[j].as_ref().components().count())
+        })
+        .collect();
+
+    fn build(index: usize, order: &[usize], parents: &[Option<usize>]) -> RepoTreeNode {
+        RepoTreeNode {
+            index,
+            children: order
+                .iter()
+                .filter(|&&i| parents[i] == Some(index))
+                .map(|&i| build(i, order, parents))
+                .collect(),
+        }
+    }
+
+    order
+        .iter()
+        .filter(|&&i| parents[i].is_none())
+        .map(|&i| build(i, &order, &parents))
+        .collect()
+}
+
+/// Drop repositories inside the working tree of another repository
+///
+/// Only the roots of the nesting tree are kept, in their original order.
+pub fn retain_top_level(paths: &mut Vec<PathBuf>) {
+    let all: HashSet<PathBuf> = paths.iter().cloned().collect();
+    paths.retain(|path| !path.ancestors().skip(1).any(|parent| all.contains(parent)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flatten a tree into (depth, index) pairs in display order
+    fn flatten(nodes: &[RepoTreeNode], depth: usize, out: &mut Vec<(usize, usize)>) {
+        for node in nodes {
+            out.push((depth, node.index));
+            flatten(&node.children, depth + 1, out);
+        }
+    }
+
+    #[test]
+    fn test_nested_repos() {
+        let paths = [
+            "/work/mono/vendor/lib-b",
+            "/work/mono",
+            "/work/other",
+            "/work/mono/vendor/lib-a",
+            "/work/mono/vendor/lib-a/deps/inner",
+        ];
+        let tree = build_repo_tree(&paths);
+
+        let mut flat = Vec::new();
+        flatten(&tree, 0, &mut flat);
+        assert_eq!(flat, vec![(0, 1), (1, 3), (2, 4), (1, 0), (0, 2)]);
+    }
+
+    #[test]
+    fn test_common_non_repo_ancestor() {
+        // Siblings under a plain directory are both roots
+        let paths = ["/src/group/b", "/src/group/a"];
+        let tree = build_repo_tree(&paths);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].index, 1);
+        assert_eq!(tree[1].index, 0);
+        assert!(tree.iter().all(|n| n.children.is_empty()));
+    }
+
+    #[test]
+    fn test_prefix_name_is_not_nesting() {
+        // "/src/app-old" starts with the string "/src/app" but is not inside it
+        let paths = ["/src/app", "/src/app-old"];
+        let tree = build_repo_tree(&paths);
+
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn test_retain_top_level() {
+        let mut paths: Vec<PathBuf> = [
+            "/work/mono/vendor/lib-b",
+            "/work/mono",
+            "/work/other",
+            "/work/mono/vendor/lib-a/deps/inner",
+            "/work/monorepo",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        retain_top_level(&mut paths);
+        assert_eq!(
+            paths,
+            ["/work/mono", "/work/other", "/work/monorepo"].map(PathBuf::from)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! This module detects repositories that turn dirty or conflicted between two
+//! status checks.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+
+use super::repo_info::RepoInfo;
+
+/// Working tree health of a repository, from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Health {
+    Clean,
+    Dirty,
+    Conflict,
+}
+
+impl Health {
+    /// Classify the working tree of a repository
+    pub fn of(repo: &RepoInfo) -> Self {
+        if repo.working.conflicts > 0 {
+            Self::Conflict
+        } else if repo.working.is_dirty {
+            Self::Dirty
+        } else {
+            Self::Clean
+        }
+    }
+}
+
+impl fmt::Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Clean => "clean",
+            Self::Dirty => "dirty",
+            Self::Conflict => "conflict",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A repository whose health got worse since the previous check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    pub path: PathBuf,
+    pub name: String,
+    pub from: Health,
+    pub to: Health,
+}
+
+/// Health of each watched repository, as of the last check
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    last: HashMap<PathBuf, Health>,
+}
+
+impl HealthTracker {
+    /// Record the current health of `repos`, returning those that got worse
+    ///
+    /// Repositories seen for the first time only set the baseline.
+    pub fn update(&mut self, repos: &[RepoInfo]) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+        for repo in repos.iter().filter(|r| !r.partial) {
+            let health = Health::of(repo);
+            let previous = self.last.insert(repo.basic.path.clone(), health);
+            if let Some(from) = previous
+                && health > from
+            {
+                transitions.push(Transition {
+                    path: repo.basic.path.clone(),
+                    name: repo.basic.name.clone(),
+                    from,
+                    to: health,
+                });
+            }
+        }
+        transitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_with(name: &str, dirty: bool, conflicts: usize) -> RepoInfo {
+        let mut repo = RepoInfo::stub(name, &format!("/tmp/{}", name));
+        repo.working.is_dirty = dirty || conflicts > 0;
+        repo.working.conflicts = conflicts;
+        repo
+    }
+
+    #[test]
+    fn test_transitions_into_dirty_or_conflict() {
+        let mut tracker = HealthTracker::default();
+        assert!(
+            tracker
+                .update(&[repo_with("a", false, 0), repo_with("b", true, 0)])
+                .is_empty()
+        );
+
+        // clean -> dirty fires, dirty -> dirty does not
+        let transitions = tracker.update(&[repo_with("a", true, 0), repo_with("b", true, 0)]);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].name, "a");
+        assert_eq!(transitions[0].from, Health::Clean);
+        assert_eq!(transitions[0].to, Health::Dirty);
+
+        // dirty -> conflict fires, dirty -> clean does not
+        let transitions = tracker.update(&[repo_with("a", false, 0), repo_with("b", true, 1)]);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].name, "b");
+        assert_eq!(transitions[0].to, Health::Conflict);
+
+        // Becoming dirty again after being clean fires again
+        let transitions = tracker.update(&[repo_with("a", true, 0), repo_with("b", true, 1)]);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].name, "a");
+    }
+
+    #[test]
+    fn test_new_repo_sets_baseline() {
+        let mut tracker = HealthTracker::default();
+        tracker.update(&[repo_with("a", false, 0)]);
+
+        let transitions = tracker.update(&[repo_with("a", false, 0), repo_with("new", true, 0)]);
+        assert!(transitions.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
 //! This module provides a reusable worker pattern for processing tasks
 //! in parallel with automatic shutdown when complete.
 //!
+//! A worker created with `Worker::reusable` does not shut down once its tasks
+//! are complete. After its results are polled, `reset` opens it for another
+//! batch, so repeated refreshes share one dispatcher thread.
+//!
 //! Example:
 //!
 //! ```ignore
@@ -30,6 +34,7 @@
 //! ```
 
 use crossbeam_channel::{Receiver, Sender, unbounded};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
@@ -50,6 +55,8 @@ where
     completed_tasks: Arc<AtomicUsize>,
     shutdown: Arc<AtomicBool>,
     submitting_finished: Arc<AtomicBool>,
+    /// Whether the worker shuts down once its tasks are complete
+    auto_shutdown: bool,
 }
 
 impl<I, O> Worker<I, O>
@@ -58,7 +65,26 @@ where
     O: Send + 'static,
 {
     /// Create a new worker with a custom processor function
+    ///
+    /// The worker shuts down once all its tasks are complete, so it processes a single batch.
     pub fn new<F>(processor: F) -> Self
+    where
+        F: Fn(I) -> Result<O, String> + Send + Sync + 'static,
+    {
+        Self::spawn(processor, true)
+    }
+
+    /// Create a worker that processes several batches, one after the other
+    ///
+    /// It keeps running until dropped; call `reset` between batches.
+    pub fn reusable<F>(processor: F) -> Self
+    where
+        F: Fn(I) -> Result<O, String> + Send + Sync + 'static,
+    {
+        Self::spawn(processor, false)
+    }
+
+    fn spawn<F>(processor: F, auto_shutdown: bool) -> Self
     where
         F: Fn(I) -> Result<O, String> + Send + Sync + 'static,
     {
@@ -93,7 +119,7 @@ where
                     }
                     Err(_) => {
                         // Check if all tasks are done
-                        if submitting_clone.load(Ordering::Relaxed) {
+                        if auto_shutdown && submitting_clone.load(Ordering::Relaxed) {
                             let pending = pending_clone.load(Ordering::Relaxed);
                             let completed = completed_clone.load(Ordering::Relaxed);
                             if pending > 0 && pending == completed {
@@ -115,6 +141,7 @@ where
             completed_tasks,
             shutdown,
             submitting_finished,
+            auto_shutdown,
         }
     }
 
@@ -135,6 +162,35 @@ where
         Ok(())
     }
 
+    /// Submit tasks from the front of `queue` until `limit` tasks are in flight
+    ///
+    /// Feeding a large batch this way, as earlier results come in, keeps the
+    /// thread pool from being flooded. Once `queue` is empty, submitting is
+    /// finished. Returns the number of tasks submitted.
+    pub fn submit_up_to(&self, queue: &mut VecDeque<I>, limit: usize) -> usize {
+        let room = limit.saturating_sub(self.in_flight());
+        let mut submitted = 0;
+        while submitted < room
+            && let Some(input) = queue.pop_front()
+        {
+            if self.submit(input).is_err() {
+                break;
+            }
+            submitted += 1;
+        }
+        if queue.is_empty() {
+            self.finish_submitting();
+        }
+        submitted
+    }
+
+    /// Number of submitted tasks not processed yet
+    pub fn in_flight(&self) -> usize {
+        let pending = self.pending_tasks.load(Ordering::Relaxed);
+        let completed = self.completed_tasks.load(Ordering::Relaxed);
+        pending.saturating_sub(completed)
+    }
+
     /// Mark that all tasks have been submitted
     ///
     /// After calling this, the worker will auto-shutdown when all tasks complete.
@@ -159,14 +215,36 @@ where
     ///
     /// Returns true when all submitted tasks have been processed.
     /// This will only return true after `finish_submitting()` has been called
-    /// and all pending tasks have completed.
+    /// and all pending tasks have completed. A batch without any task is
+    /// complete as soon as submitting is finished, so that waiting on a scan
+    /// that found no repositories ends.
     pub fn is_complete(&self) -> bool {
         if !self.submitting_finished.load(Ordering::Relaxed) {
             return false;
         }
         let pending = self.pending_tasks.load(Ordering::Relaxed);
         let completed = self.completed_tasks.load(Ordering::Relaxed);
-        pending > 0 && pending == completed
+        pending == completed
+    }
+
+    /// Open a reusable worker for the next batch of tasks
+    ///
+    /// Fails if the worker is not reusable, if the current batch is not complete,
+    /// or if some of its results were not polled yet.
+    pub fn reset(&self) -> Result<(), String> {
+        if self.auto_shutdown {
+            return Err("Only a reusable worker can be reset".to_string());
+        }
+        if !self.is_complete() {
+            return Err("Cannot reset before the current batch is complete".to_string());
+        }
+        if !self.result_rx.is_empty() {
+            return Err("Cannot reset before all results are polled".to_string());
+        }
+        self.pending_tasks.store(0, Ordering::Relaxed);
+        self.completed_tasks.store(0, Ordering::Relaxed);
+        self.submitting_finished.store(false, Ordering::Relaxed);
+        Ok(())
     }
 
     /// Gracefully shutdown the worker
@@ -187,3 +265,119 @@ where
         self.shutdown();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Poll until the current batch is complete, returning its results sorted
+    fn drain(worker: &Worker<i32, i32>) -> Vec<i32> {
+        let mut results = Vec::new();
+        loop {
+            let complete = worker.is_complete();
+            results.extend(worker.poll_results().into_iter().map(Result::unwrap));
+            if complete {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        results.sort();
+        results
+    }
+
+    #[test]
+    fn test_reusable_worker_runs_two_batches() {
+        let worker = Worker::reusable(|n: i32| Ok(n * 2));
+
+        for n in 1..=3 {
+            worker.submit(n).unwrap();
+        }
+        worker.finish_submitting();
+        assert_eq!(drain(&worker), [2, 4, 6]);
+
+        // The dispatcher outlives the first batch
+        std::thread::sleep(Duration::from_millis(150));
+        worker.reset().unwrap();
+        assert!(!worker.is_complete());
+        for n in 10..=11 {
+            worker.submit(n).unwrap();
+        }
+        worker.finish_submitting();
+        assert_eq!(drain(&worker), [20, 22]);
+    }
+
+    #[test]
+    fn test_reset_requires_drained_batch() {
+        let worker = Worker::reusable(|n: i32| Ok(n));
+        worker.submit(1).unwrap();
+        assert!(worker.reset().is_err()); // still submitting
+
+        worker.finish_submitting();
+        while !worker.is_complete() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(worker.reset().is_err()); // result not polled
+        assert_eq!(worker.poll_results(), [Ok(1)]);
+        assert!(worker.reset().is_ok());
+    }
+
+    #[test]
+    fn test_empty_batch_is_complete() {
+        let worker = Worker::new(|n: i32| Ok(n));
+        assert!(!worker.is_complete());
+        worker.submit_up_to(&mut VecDeque::new(), 4);
+        assert!(worker.is_complete());
+        assert!(worker.poll_results().is_empty());
+    }
+
+    #[test]
+    fn test_submit_up_to_limit() {
+        // Tasks block until released, so they stay in flight
+        let (release_tx, release_rx) = crossbeam_channel::unbounded::<()>();
+        let worker = Worker::new(move |n: i32| {
+            release_rx.recv().unwrap();
+            Ok(n)
+        });
+        let mut queue: VecDeque<i32> = (1..=5).collect();
+
+        assert_eq!(worker.submit_up_to(&mut queue, 2), 2);
+        assert_eq!(worker.in_flight(), 2);
+        assert_eq!(worker.submit_up_to(&mut queue, 2), 0);
+        assert_eq!(queue.len(), 3);
+
+        // Results of the first tasks arrive while the rest is still queued
+        release_tx.send(()).unwrap();
+        let mut results = Vec::new();
+        while results.is_empty() {
+            results.extend(worker.poll_results());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!worker.is_complete());
+        assert_eq!(worker.submit_up_to(&mut queue, 2), 1);
+        assert_eq!(queue.len(), 2);
+
+        for _ in 0..4 {
+            release_tx.send(()).unwrap();
+        }
+        while !queue.is_empty() {
+            worker.submit_up_to(&mut queue, 2);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        results.extend(worker.poll_results());
+        let mut results: Vec<i32> = results.into_iter().map(Result::unwrap).collect();
+        results.extend(drain(&worker));
+        results.sort();
+        assert_eq!(results, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_one_shot_worker_cannot_reset() {
+        let worker = Worker::new(|n: i32| Ok(n));
+        worker.submit(1).unwrap();
+        worker.finish_submitting();
+        assert_eq!(drain(&worker), [1]);
+
+        assert!(worker.reset().is_err());
+        assert!(worker.submit(2).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! This module sets up tracing output on stderr.
+//!
+//! Logs never go to stdout, which carries the command output.
+
+use tracing::Subscriber;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::cli::LogFormat;
+
+/// Set up logging based on RUST_LOG environment variable
+pub fn setup_logging(format: LogFormat) {
+    if std::env::var("RUST_LOG").is_ok() {
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"));
+        let _ = tracing::subscriber::set_global_default(build_subscriber(
+            format,
+            filter,
+            std::io::stderr,
+        ));
+    }
+}
+
+/// Build a subscriber that writes logs in the given format
+fn build_subscriber<W>(
+    format: LogFormat,
+    filter: EnvFilter,
+    writer: W,
+) -> Box<dyn Subscriber + Send + Sync>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(true)
+        .with_writer(writer);
+    match format {
+        LogFormat::Human => Box::new(builder.finish()),
+        LogFormat::Json => Box::new(builder.json().finish()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer that collects log output in memory
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_log_lines() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = build_subscriber(LogFormat::Json, EnvFilter::new("debug"), move || {
+            writer.clone()
+        });
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(found = 3, "Scan finished");
+            tracing::error!("Error processing repo");
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.is_object()));
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["fields"]["message"], "Scan finished");
+        assert_eq!(lines[0]["fields"]["found"], 3);
+        assert_eq!(lines[1]["level"], "ERROR");
+    }
+}
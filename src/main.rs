@@ -1,47 +1,56 @@
 mod cli;
 pub mod config;
 pub mod core;
+mod logging;
 mod tui;
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use cli::CliArgs;
 use config::AppConfig;
-use tracing_subscriber::EnvFilter;
-
-/// Set up logging based on RUST_LOG environment variable
-pub fn setup_logging() {
-    if std::env::var("RUST_LOG").is_ok() {
-        tracing_subscriber::fmt()
-            .with_env_filter(
-                EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug")),
-            )
-            .with_target(true)
-            .with_writer(std::io::stderr)
-            .init();
-    }
-}
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    setup_logging();
+    let cli_args = Arc::new(CliArgs::parse());
+    logging::setup_logging(cli_args.log_format);
 
-    let cli_args = CliArgs::parse();
-    let app_config = AppConfig::from_layers(&cli_args);
+    let app_config = AppConfig::from_layers(&cli_args).map_err(anyhow::Error::msg)?;
 
     if cli_args.print_config {
         app_config.print();
         return Ok(());
     }
 
+    let loader: tui::ConfigLoader = {
+        let cli_args = cli_args.clone();
+        Arc::new(move || AppConfig::from_layers(&cli_args))
+    };
+
     match &cli_args.command {
+        Some(_) if cli::is_interactive(&cli_args) => {
+            let (repos, filters) = cli::collect_interactive_repos(&cli_args, &app_config).await?;
+            tui::run_tui_app(
+                app_config,
+                None,
+                Some(tui::TuiSeed::new(repos, &filters)),
+                loader,
+            )
+            .await
+            .context("Failed to run TUI application")?;
+        }
         Some(_) => {
             cli::execute_cli_command(&cli_args, app_config)
                 .await
                 .context("Failed to execute CLI command")?;
         }
+        None if cli_args.watch_dirty => {
+            cli::watch_dirty(app_config)
+                .await
+                .context("Failed to watch repositories")?;
+        }
         None => {
-            tui::run_tui_app(app_config)
+            tui::run_tui_app(app_config, cli_args.repo.clone(), None, loader)
                 .await
                 .context("Failed to run TUI application")?;
         }
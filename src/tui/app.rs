@@ -5,10 +5,17 @@ use crossterm::{
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
+use std::panic::{self, PanicHookInfo};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
 
 use crate::config::AppConfig;
+use crate::core::RepoInfo;
+use crate::core::results_cache::load_cached_repos;
 use crate::tui::input;
-use crate::tui::state::AppState;
+use crate::tui::prefetch;
+use crate::tui::state::{AppState, ConfigLoader, StatusMessage};
 use crate::tui::task;
 use crate::tui::ui;
 
@@ -32,24 +39,160 @@ fn cleanup_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Re
     Ok(())
 }
 
+/// Restore the terminal without a handle to it, ignoring errors
+///
+/// Used when panicking, where the terminal may be in any state.
+fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, crossterm::cursor::Show);
+}
+
+type PanicHook = dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static;
+
+/// Wrap a panic hook so that `restore` runs before it on panics of `ui_thread`
+///
+/// Panics of other threads, such as background workers, leave the terminal
+/// alone, since the UI keeps drawing to it.
+fn restoring_hook(
+    restore: impl Fn() + Send + Sync + 'static,
+    ui_thread: ThreadId,
+    next: Arc<PanicHook>,
+) -> Box<PanicHook> {
+    Box::new(move |info| {
+        if thread::current().id() == ui_thread {
+            restore();
+        }
+        next(info);
+    })
+}
+
+/// Panic hook that restores the terminal while the TUI is running
+///
+/// The panic message is printed to the normal screen instead of the garbled
+/// alternate screen. The previous hook is reinstated when the guard is dropped.
+struct PanicHookGuard {
+    previous: Arc<PanicHook>,
+}
+
+impl PanicHookGuard {
+    /// Install the hook for panics of the current thread, which runs the UI
+    fn install(restore: impl Fn() + Send + Sync + 'static) -> Self {
+        let previous: Arc<PanicHook> = Arc::from(panic::take_hook());
+        panic::set_hook(restoring_hook(
+            restore,
+            thread::current().id(),
+            previous.clone(),
+        ));
+        Self { previous }
+    }
+}
+
+impl Drop for PanicHookGuard {
+    fn drop(&mut self) {
+        // The hook cannot be changed while unwinding from a panic
+        if thread::panicking() {
+            return;
+        }
+        let previous = self.previous.clone();
+        panic::set_hook(Box::new(move |info| previous(info)));
+    }
+}
+
+/// Repositories handed over to the TUI instead of scanning, e.g. by `list --interactive`
+pub struct TuiSeed {
+    repos: Vec<RepoInfo>,
+    /// Shown in the status line, so it is clear the list is filtered
+    status: String,
+}
+
+impl TuiSeed {
+    /// Seed the TUI with `repos`, which passed the given filter flags
+    pub fn new(repos: Vec<RepoInfo>, filters: &[&str]) -> Self {
+        let status = match filters {
+            [] => format!("{} repos from list", repos.len()),
+            _ => format!("{} repos matching {}", repos.len(), filters.join(" ")),
+        };
+        Self { repos, status }
+    }
+}
+
 /// Run the TUI application
-pub async fn run_tui_app(cfg: AppConfig) -> Result<()> {
+///
+/// If `initial_repo` is given, the TUI opens in detail view on the repository
+/// with that name once scanning completes. With a `seed`, its repositories
+/// are shown and no scan is done. The `loader` is run when the user asks to
+/// reload the config.
+pub async fn run_tui_app(
+    cfg: AppConfig,
+    initial_repo: Option<String>,
+    seed: Option<TuiSeed>,
+    loader: ConfigLoader,
+) -> Result<()> {
+    let _panic_guard = PanicHookGuard::install(restore_terminal);
     let mut terminal = setup_terminal()?;
 
-    let app_state = AppState::new(cfg.clone());
-    task::spawn_scan_repo_and_get_info_task(&app_state);
+    let app_state = init_state(cfg, initial_repo, seed)
+        .await
+        .with_config_loader(loader);
     let res = run_event_loop(&mut terminal, app_state).await;
 
     cleanup_terminal(&mut terminal)?;
     Ok(res?)
 }
 
+/// Create the TUI state and start its background tasks
+///
+/// Repositories come from the `seed` if given, or else from a background scan.
+/// Until the scan refreshes them, the results of the previous one are shown
+/// if a results cache is configured and the scan covers all configured roots.
+async fn init_state(
+    cfg: AppConfig,
+    initial_repo: Option<String>,
+    seed: Option<TuiSeed>,
+) -> AppState {
+    let app_state = AppState::new(cfg, initial_repo);
+    match seed {
+        Some(seed) => {
+            *app_state.repos.lock().await = seed.repos;
+            *app_state.scan_complete.lock().await = true;
+            app_state.set_status(StatusMessage::Info(seed.status)).await;
+        }
+        None => {
+            if let Some(file) = app_state.config.results_cache_file() {
+                load_results_cache(&app_state, file).await;
+            }
+            task::spawn_scan_repo_and_get_info_task(&app_state);
+        }
+    }
+    if app_state.config.main.lazy_info {
+        prefetch::spawn_prefetch_task(&app_state);
+    }
+    app_state
+}
+
+/// Show the repositories of the results cache, reporting a broken cache in the status line
+async fn load_results_cache(state: &AppState, file: &Path) {
+    match load_cached_repos(file) {
+        Ok(mut repos) => {
+            if let Some(ref tag) = state.config.internal.tag_filter {
+                repos.retain(|repo| repo.has_tag(tag));
+            }
+            *state.repos.lock().await = repos;
+        }
+        Err(e) => state.set_status(StatusMessage::Error(e)).await,
+    }
+}
+
 /// Main event loop for UI rendering and input handling
 async fn run_event_loop<B: ratatui::backend::Backend>(
     terminal: &mut Terminal<B>,
-    state: AppState,
+    mut state: AppState,
 ) -> io::Result<()> {
     loop {
+        if state.take_reload_request().await {
+            state.reload_config().await;
+        }
+
         // Render the UI
         terminal.draw(|f| {
             ui::render_ui(f, &state);
@@ -59,5 +202,133 @@ async fn run_event_loop<B: ratatui::backend::Backend>(
         if input::handle_input_events(&state).await? {
             return Ok(()); // exit requested
         }
+
+        // Exit with an error raised by a background task
+        if let Some(err) = state.take_exit_error().await {
+            return Err(io::Error::other(err));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::state::EmptyState;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_panic_hook_restores_terminal() {
+        // Panics of other tests run on other threads, so they are not counted
+        let restored = Arc::new(AtomicUsize::new(0));
+        let counter = restored.clone();
+        let guard = PanicHookGuard::install(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let result = panic::catch_unwind(|| panic!("render failed"));
+        assert!(result.is_err());
+        assert_eq!(restored.load(Ordering::SeqCst), 1);
+
+        // Panics of background threads leave the terminal to the UI
+        let worker = thread::spawn(|| panic!("worker failed"));
+        assert!(worker.join().is_err());
+        assert_eq!(restored.load(Ordering::SeqCst), 1);
+
+        // Once uninstalled, panics no longer touch the terminal
+        drop(guard);
+        let _ = panic::catch_unwind(|| panic!("after the TUI"));
+        assert_eq!(restored.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_seeded_state_skips_scan() {
+        let mut config = AppConfig::default();
+        // Nothing can be found here, so repos in the state come from the seed
+        config.main.scan_dirs = vec!["/nonexistent/reponest-test".to_string()];
+        let repos = vec![
+            RepoInfo::stub("api", "/tmp/api"),
+            RepoInfo::stub("web", "/tmp/web"),
+        ];
+
+        let seed = TuiSeed::new(repos, &["--dirty"]);
+        let state = init_state(config, None, Some(seed)).await;
+
+        let names: Vec<_> = state
+            .repos
+            .lock()
+            .await
+            .iter()
+            .map(|r| r.basic.name.clone())
+            .collect();
+        assert_eq!(names, ["api", "web"]);
+        assert_eq!(
+            *state.status.lock().await,
+            Some(StatusMessage::Info("2 repos matching --dirty".to_string()))
+        );
+        assert!(*state.scan_complete.lock().await);
+    }
+
+    #[tokio::test]
+    async fn test_cached_results_refreshed_by_scan() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("repos");
+        let fresh = root.join("fresh");
+        git2::Repository::init(&fresh).unwrap();
+        let gone = root.join("gone");
+
+        let cache = temp_dir.path().join("results.json");
+        let mut cached = RepoInfo::stub("fresh", fresh.to_str().unwrap());
+        cached.basic.branch = "cached-branch".to_string();
+        let cached = [cached, RepoInfo::stub("gone", gone.to_str().unwrap())];
+        crate::core::results_cache::save_cached_repos(&cache, &cached).unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![root.to_string_lossy().to_string()];
+        config.main.results_cache = Some(cache.to_string_lossy().to_string());
+        config.internal.scan_poll_interval = 1;
+        let state = init_state(config, None, None).await;
+
+        // The cached results are shown before the scan gets to run
+        {
+            let repos = state.repos.lock().await;
+            assert_eq!(repos.len(), 2);
+            assert!(repos.iter().all(|r| r.cached));
+            assert_eq!(repos[0].basic.branch, "cached-branch");
+        }
+
+        for _ in 0..1000 {
+            if *state.scan_complete.lock().await {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        let repos = state.repos.lock().await;
+        assert_eq!(repos.len(), 1);
+        assert_eq!(repos[0].basic.path, fresh);
+        assert!(!repos[0].cached);
+        assert_ne!(repos[0].basic.branch, "cached-branch");
+
+        // The cache now holds the results of this scan
+        let saved = load_cached_repos(&cache).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].basic.branch, repos[0].basic.branch);
+    }
+
+    #[tokio::test]
+    async fn test_scan_without_repos_completes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec![temp_dir.path().to_string_lossy().to_string()];
+        config.internal.scan_poll_interval = 1;
+        let state = init_state(config, None, None).await;
+
+        for _ in 0..1000 {
+            if *state.scan_complete.lock().await {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        }
+        let snapshot = state.get_render_snapshot();
+        assert_eq!(snapshot.empty_state(), Some(EmptyState::NoRepos));
     }
 }
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+
+use crate::core::RepoInfo;
+use crate::core::grouping::{GroupBy, group_repos};
+use crate::core::status::{StatusColor, StatusRule, overall_status};
+
+/// Row of the repository list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListRow {
+    /// Header of a group of repositories
+    Group {
+        key: String,
+        count: usize,
+        expanded: bool,
+    },
+    /// Repository (index into the repository list)
+    Repo(usize),
+}
+
+/// Row under the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cursor {
+    /// Header of the group with this key
+    Group(String),
+    /// Repository (index into the repository list)
+    Repo(usize),
+}
+
+/// Indices of the repositories left out of the list
+///
+/// With `hide_clean`, repositories whose overall status is clean are hidden.
+pub fn hidden_repos(repos: &[RepoInfo], hide_clean: bool, rules: &[StatusRule]) -> HashSet<usize> {
+    if !hide_clean {
+        return HashSet::new();
+    }
+    repos
+        .iter()
+        .enumerate()
+        .filter(|(_, repo)| overall_status(repo, rules).color == StatusColor::Clean)
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Rows shown in the repository list
+///
+/// Without grouping, every repository gets a row in list order. Otherwise
+/// each group gets a header, followed by its repositories unless collapsed.
+/// Hidden repositories get no row, nor do groups with only hidden ones.
+pub fn list_rows(
+    repos: &[RepoInfo],
+    group_by: GroupBy,
+    collapsed: &HashSet<String>,
+    hidden: &HashSet<usize>,
+) -> Vec<ListRow> {
+    let shown = |idx: &usize| !hidden.contains(idx);
+    if group_by == GroupBy::None {
+        return (0..repos.len()).filter(shown).map(ListRow::Repo).collect();
+    }
+
+    let mut rows = Vec::new();
+    for (key, mut indices) in group_repos(repos, group_by) {
+        indices.retain(shown);
+        if indices.is_empty() {
+            continue;
+        }
+        let expanded = !collapsed.contains(&key);
+        rows.push(ListRow::Group {
+            key,
+            count: indices.len(),
+            expanded,
+        });
+        if expanded {
+            rows.extend(indices.into_iter().map(ListRow::Repo));
+        }
+    }
+    rows
+}
+
+/// Indices of the shown repositories in the order they are listed, with every
+/// group expanded
+pub fn display_order(repos: &[RepoInfo], group_by: GroupBy, hidden: &HashSet<usize>) -> Vec<usize> {
+    list_rows(repos, group_by, &HashSet::new(), hidden)
+        .into_iter()
+        .filter_map(|row| match row {
+            ListRow::Repo(idx) => Some(idx),
+            ListRow::Group { .. } => None,
+        })
+        .collect()
+}
+
+/// Position of the cursor among the rows, if its row is shown
+pub fn cursor_position(rows: &[ListRow], cursor: &Cursor) -> Option<usize> {
+    rows.iter().position(|row| match (row, cursor) {
+        (ListRow::Group { key, .. }, Cursor::Group(selected)) => key == selected,
+        (ListRow::Repo(idx), Cursor::Repo(selected)) => idx == selected,
+        _ => false,
+    })
+}
+
+/// Cursor on the row at `position`, if there is one
+pub fn cursor_at(rows: &[ListRow], position: usize) -> Option<Cursor> {
+    rows.get(position).map(|row| match row {
+        ListRow::Group { key, .. } => Cursor::Group(key.clone()),
+        ListRow::Repo(idx) => Cursor::Repo(*idx),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repos() -> Vec<RepoInfo> {
+        ["/work/web", "/home/dots", "/work/api"]
+            .iter()
+            .map(|path| RepoInfo::stub(path.rsplit('/').next().unwrap(), path))
+            .collect()
+    }
+
+    fn group(key: &str, count: usize, expanded: bool) -> ListRow {
+        ListRow::Group {
+            key: key.to_string(),
+            count,
+            expanded,
+        }
+    }
+
+    #[test]
+    fn test_list_rows() {
+        let repos = repos();
+        let mut collapsed = HashSet::new();
+
+        assert_eq!(
+            list_rows(&repos, GroupBy::None, &collapsed, &HashSet::new()),
+            [ListRow::Repo(0), ListRow::Repo(1), ListRow::Repo(2)]
+        );
+        assert_eq!(
+            list_rows(&repos, GroupBy::Directory, &collapsed, &HashSet::new()),
+            [
+                group("/home", 1, true),
+                ListRow::Repo(1),
+                group("/work", 2, true),
+                ListRow::Repo(0),
+                ListRow::Repo(2),
+            ]
+        );
+
+        collapsed.insert("/work".to_string());
+        assert_eq!(
+            list_rows(&repos, GroupBy::Directory, &collapsed, &HashSet::new()),
+            [
+                group("/home", 1, true),
+                ListRow::Repo(1),
+                group("/work", 2, false)
+            ]
+        );
+        assert_eq!(
+            display_order(&repos, GroupBy::Directory, &HashSet::new()),
+            [1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn test_hidden_repos() {
+        let mut repos = repos();
+        for repo in &mut repos {
+            repo.sync.has_upstream = true;
+        }
+        repos[0].working.is_dirty = true;
+        let collapsed = HashSet::new();
+
+        assert!(hidden_repos(&repos, false, &[]).is_empty());
+        let hidden = hidden_repos(&repos, true, &[]);
+        assert_eq!(hidden, HashSet::from([1, 2]));
+
+        // Groups left without a shown repository are dropped
+        assert_eq!(
+            list_rows(&repos, GroupBy::Directory, &collapsed, &hidden),
+            [group("/work", 1, true), ListRow::Repo(0)]
+        );
+        assert_eq!(
+            list_rows(&repos, GroupBy::None, &collapsed, &hidden),
+            [ListRow::Repo(0)]
+        );
+        assert_eq!(display_order(&repos, GroupBy::Directory, &hidden), [0]);
+    }
+
+    #[test]
+    fn test_cursor_position() {
+        let repos = repos();
+        let collapsed = HashSet::from(["/home".to_string()]);
+        let rows = list_rows(&repos, GroupBy::Directory, &collapsed, &HashSet::new());
+
+        let work = Cursor::Group("/work".to_string());
+        assert_eq!(cursor_position(&rows, &work), Some(1));
+        assert_eq!(cursor_position(&rows, &Cursor::Repo(2)), Some(3));
+        // Repositories of collapsed groups have no row
+        assert_eq!(cursor_position(&rows, &Cursor::Repo(1)), None);
+
+        assert_eq!(cursor_at(&rows, 1), Some(work));
+        assert_eq!(cursor_at(&rows, 2), Some(Cursor::Repo(0)));
+        assert_eq!(cursor_at(&rows, 4), None);
+    }
+}
@@ -1,26 +1,52 @@
 use crossterm::event::{self, Event, KeyCode, KeyEventKind};
 use std::io;
-use std::process::Command;
+use std::path::Path;
+use std::process::{Command, Stdio};
 use std::time::Duration;
 
-use crate::tui::state::AppState;
+use crate::config::{KeyBindings, ListLayout};
+use crate::core::grid::GridMove;
+use crate::core::status::Condition;
+use crate::core::{archive, open, safety};
+use crate::tui::state::{AppState, StatusMessage};
+
+/// Longest time to wait for a key press before the UI is redrawn
+///
+/// Kept short so that key presses register promptly, independently of how often
+/// the background tasks poll for scan results.
+pub const INPUT_POLL_TIMEOUT: Duration = Duration::from_millis(33);
 
 /// Handle input events with polling, returns true if should exit
 pub async fn handle_input_events(state: &AppState) -> io::Result<bool> {
-    // Poll for input events with refresh interval timeout
-    if event::poll(Duration::from_millis(
-        state.config.internal.refresh_interval,
-    ))? && let Event::Key(key) = event::read()?
+    handle_polled_input(state, poll_key_press).await
+}
+
+/// Wait up to `timeout` for a key press from the terminal
+fn poll_key_press(timeout: Duration) -> io::Result<Option<KeyCode>> {
+    if event::poll(timeout)?
+        && let Event::Key(key) = event::read()?
         && key.kind == KeyEventKind::Press
     {
-        return handle_key_event(key.code, state).await;
+        return Ok(Some(key.code));
+    }
+    Ok(None)
+}
+
+/// Handle a key press obtained from `poll`, returns true if should exit
+async fn handle_polled_input(
+    state: &AppState,
+    poll: impl FnOnce(Duration) -> io::Result<Option<KeyCode>>,
+) -> io::Result<bool> {
+    match poll(INPUT_POLL_TIMEOUT)? {
+        Some(key_code) => handle_key_event(key_code, state).await,
+        None => Ok(false), // continue running
     }
-    Ok(false) // continue running
 }
 
 /// Convert KeyCode to string for matching
 fn keycode_to_string(key: KeyCode) -> String {
     match key {
+        KeyCode::Char(' ') => "Space".to_string(),
         KeyCode::Char(c) => c.to_string(),
         KeyCode::Enter => "Enter".to_string(),
         KeyCode::Esc => "Esc".to_string(),
@@ -46,6 +72,34 @@ async fn handle_key_event(key_code: KeyCode, state: &AppState) -> io::Result<boo
         return Ok(false);
     }
 
+    // Any key press dismisses the current status message
+    state.clear_status().await;
+    state.touch_input().await;
+
+    // A pending archive is confirmed with "y", any other key cancels it
+    let pending_archive = state.pending_archive.lock().await.take();
+    if let Some((path, force)) = pending_archive {
+        if key_str == "y" {
+            confirm_archive(state, &path, force).await;
+        } else {
+            state
+                .set_status(StatusMessage::Info("Archive cancelled".to_string()))
+                .await;
+        }
+        return Ok(false);
+    }
+
+    if state.git_prompt.lock().await.is_some() {
+        handle_git_prompt_key(key_code, state).await;
+        return Ok(false);
+    }
+    if state.git_output.lock().await.is_some() {
+        return handle_git_output_key(&key_str, state).await;
+    }
+    if state.is_branch_view().await {
+        return handle_branch_view_key(&key_str, state).await;
+    }
+
     let kb = &state.config.ui.keybindings;
 
     if kb.matches("quit", &key_str) {
@@ -56,6 +110,15 @@ async fn handle_key_event(key_code: KeyCode, state: &AppState) -> io::Result<boo
         return handle_cd_to_repo(state).await;
     }
 
+    // In the grid, the cursor also moves sideways, even on keys bound to details
+    if state.config.ui.list_layout == ListLayout::Grid
+        && !state.is_detail_view().await
+        && let Some(direction) = grid_move(kb, &key_str)
+    {
+        state.move_selection_in_grid(direction).await;
+        return Ok(false);
+    }
+
     if kb.matches("back", &key_str) {
         handle_escape(state).await;
     } else if kb.matches("details", &key_str) {
@@ -66,11 +129,270 @@ async fn handle_key_event(key_code: KeyCode, state: &AppState) -> io::Result<boo
         handle_move_up(state).await;
     } else if kb.matches("open", &key_str) {
         handle_open_in_file_manager(state).await;
+    } else if kb.matches("branches", &key_str) {
+        handle_open_branch_view(state).await;
+    } else if kb.matches("next_dirty", &key_str) {
+        handle_jump_to_matching(state, Condition::Dirty, true).await;
+    } else if kb.matches("prev_dirty", &key_str) {
+        handle_jump_to_matching(state, Condition::Dirty, false).await;
+    } else if kb.matches("next_conflict", &key_str) {
+        handle_jump_to_matching(state, Condition::Conflict, true).await;
+    } else if kb.matches("git_command", &key_str) {
+        handle_open_git_prompt(state).await;
+    } else if kb.matches("archive", &key_str) {
+        request_archive(state, false).await;
+    } else if kb.matches("force_archive", &key_str) {
+        request_archive(state, true).await;
+    } else if kb.matches("tmux", &key_str) {
+        handle_open_in_tmux(state).await;
+    } else if kb.matches("terminal", &key_str) {
+        handle_open_in_terminal(state).await;
+    } else if kb.matches("toggle_group", &key_str) {
+        handle_toggle_group(state).await;
+    } else if kb.matches("toggle_clean", &key_str) {
+        handle_toggle_clean(state).await;
+    } else if kb.matches("reload_config", &key_str) {
+        state.request_config_reload().await;
     }
 
     Ok(false)
 }
 
+/// Direction of the grid move bound to a key, if any
+fn grid_move(kb: &KeyBindings, key_str: &str) -> Option<GridMove> {
+    [
+        ("move_up", GridMove::Up),
+        ("move_down", GridMove::Down),
+        ("move_left", GridMove::Left),
+        ("move_right", GridMove::Right),
+    ]
+    .into_iter()
+    .find(|(action, _)| kb.matches(action, key_str))
+    .map(|(_, direction)| direction)
+}
+
+/// Handle opening the prompt for a git command to run in the selected repository
+async fn handle_open_git_prompt(state: &AppState) {
+    if state.is_repos_empty().await || !allow_unsafe_action(state, "Running git commands").await {
+        return;
+    }
+    *state.git_prompt.lock().await = Some(String::new());
+}
+
+/// Handle typing into the git command prompt
+///
+/// Enter runs the command and Esc cancels it; errors are shown in the status line.
+async fn handle_git_prompt_key(key_code: KeyCode, state: &AppState) {
+    let mut prompt = state.git_prompt.lock().await;
+    let Some(input) = prompt.as_mut() else {
+        return;
+    };
+    match key_code {
+        KeyCode::Char(c) => input.push(c),
+        KeyCode::Backspace => {
+            input.pop();
+        }
+        KeyCode::Esc => *prompt = None,
+        KeyCode::Enter => {
+            let input = prompt.take().unwrap_or_default();
+            drop(prompt);
+            if let Err(e) = state.run_git_command(&input).await {
+                state.set_status(StatusMessage::Error(e)).await;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Lines scrolled by PageUp and PageDown in the git output
+const OUTPUT_PAGE_LINES: isize = 10;
+
+/// Handle keyboard input while git command output is shown, returns true if should exit
+async fn handle_git_output_key(key_str: &str, state: &AppState) -> io::Result<bool> {
+    let kb = &state.config.ui.keybindings;
+
+    if kb.matches("quit", key_str) {
+        return Ok(true);
+    }
+
+    let mut git_output = state.git_output.lock().await;
+    if kb.matches("back", key_str) {
+        *git_output = None;
+    } else if let Some(output) = git_output.as_mut() {
+        if kb.matches("move_down", key_str) {
+            output.scroll_by(1);
+        } else if kb.matches("move_up", key_str) {
+            output.scroll_by(-1);
+        } else if key_str == "PageDown" {
+            output.scroll_by(OUTPUT_PAGE_LINES);
+        } else if key_str == "PageUp" {
+            output.scroll_by(-OUTPUT_PAGE_LINES);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Handle keyboard input while the branch view is open, returns true if should exit
+async fn handle_branch_view_key(key_str: &str, state: &AppState) -> io::Result<bool> {
+    // A pending delete is confirmed with "y", any other key cancels it
+    let pending = state
+        .branch_view
+        .lock()
+        .await
+        .as_mut()
+        .and_then(|view| view.pending_delete.take());
+    if let Some((name, force)) = pending {
+        if key_str == "y" {
+            confirm_delete_branch(state, &name, force).await;
+        } else {
+            state
+                .set_status(StatusMessage::Info("Delete cancelled".to_string()))
+                .await;
+        }
+        return Ok(false);
+    }
+
+    let kb = &state.config.ui.keybindings;
+
+    if kb.matches("quit", key_str) {
+        return Ok(true);
+    }
+
+    if kb.matches("back", key_str) {
+        state.close_branch_view().await;
+    } else if kb.matches("move_down", key_str) {
+        state.move_branch_selection(1).await;
+    } else if kb.matches("move_up", key_str) {
+        state.move_branch_selection(-1).await;
+    } else if kb.matches("delete_branch", key_str) {
+        request_delete_branch(state, false).await;
+    } else if kb.matches("force_delete_branch", key_str) {
+        request_delete_branch(state, true).await;
+    }
+
+    Ok(false)
+}
+
+/// Handle opening the branch view for the selected repository
+async fn handle_open_branch_view(state: &AppState) {
+    let is_detail = state.is_detail_view().await;
+    if is_detail {
+        return;
+    }
+    if let Err(e) = state.open_branch_view().await {
+        state.set_status(StatusMessage::Error(e)).await;
+    }
+}
+
+/// Ask for confirmation before deleting the selected branch
+///
+/// The current branch is refused, and so is an unmerged branch unless `force` is set.
+async fn request_delete_branch(state: &AppState, force: bool) {
+    if !allow_unsafe_action(state, "Deleting branches").await {
+        return;
+    }
+
+    let mut branch_view = state.branch_view.lock().await;
+    let Some(view) = branch_view.as_mut() else {
+        return;
+    };
+    let Some(branch) = view.selected_branch().cloned() else {
+        return;
+    };
+
+    let status = if branch.is_head {
+        StatusMessage::Error(format!(
+            "Cannot delete the current branch '{}'",
+            branch.name
+        ))
+    } else if !force && !branch.is_merged {
+        StatusMessage::Error(format!(
+            "Branch '{}' is not fully merged, force delete to remove it anyway",
+            branch.name
+        ))
+    } else {
+        let prompt = if force {
+            format!("Force delete branch '{}'? (y/n)", branch.name)
+        } else {
+            format!("Delete branch '{}'? (y/n)", branch.name)
+        };
+        view.pending_delete = Some((branch.name, force));
+        StatusMessage::Info(prompt)
+    };
+    drop(branch_view);
+
+    state.set_status(status).await;
+}
+
+/// Delete a branch after confirmation and report the outcome in the status line
+async fn confirm_delete_branch(state: &AppState, name: &str, force: bool) {
+    let status = match state.delete_branch(name, force).await {
+        Ok(()) => StatusMessage::Info(format!("Deleted branch '{}'", name)),
+        Err(e) => StatusMessage::Error(e),
+    };
+    state.set_status(status).await;
+}
+
+/// Ask for confirmation before moving the selected repository to the archive directory
+///
+/// Repositories that are not clean and pushed are refused unless `force` is set.
+/// Repositories in the middle of an operation are refused in any case.
+async fn request_archive(state: &AppState, force: bool) {
+    if state.is_repos_empty().await
+        || state.is_group_selected().await
+        || !allow_unsafe_action(state, "Archiving").await
+    {
+        return;
+    }
+    let Some(archive_dir) = state.config.main.archive_dir.as_deref() else {
+        state
+            .set_status(StatusMessage::Info(
+                "Set main.archive_dir in the config to archive repos".to_string(),
+            ))
+            .await;
+        return;
+    };
+
+    let selected = {
+        let repos = state.repos.lock().await;
+        let selected = *state.selected_index.lock().await;
+        repos.get(selected).map(|repo| {
+            let check = safety::can_perform_write(repo).and_then(|()| {
+                archive::check_archivable(repo, force)
+                    .map_err(|e| format!("{}, force archive to move it anyway", e))
+            });
+            (repo.basic.path.clone(), repo.basic.name.clone(), check)
+        })
+    };
+    let Some((path, name, check)) = selected else {
+        return;
+    };
+
+    let status = match check {
+        Err(e) => StatusMessage::Error(e),
+        Ok(()) => {
+            let prompt = if force {
+                format!("Force archive '{}' to {}? (y/n)", name, archive_dir)
+            } else {
+                format!("Archive '{}' to {}? (y/n)", name, archive_dir)
+            };
+            *state.pending_archive.lock().await = Some((path, force));
+            StatusMessage::Info(prompt)
+        }
+    };
+    state.set_status(status).await;
+}
+
+/// Archive a repository after confirmation and report the outcome in the status line
+async fn confirm_archive(state: &AppState, path: &Path, force: bool) {
+    let status = match state.archive_repo(path, force).await {
+        Ok(destination) => StatusMessage::Info(format!("Archived to {}", destination.display())),
+        Err(e) => StatusMessage::Error(e),
+    };
+    state.set_status(status).await;
+}
+
 /// Handle escape action
 async fn handle_escape(state: &AppState) {
     let is_detail = state.is_detail_view().await;
@@ -80,13 +402,34 @@ async fn handle_escape(state: &AppState) {
 }
 
 /// Handle enter action
+///
+/// On a group header, the group is collapsed or expanded instead.
 async fn handle_enter(state: &AppState) {
     let is_detail = state.is_detail_view().await;
-    if !is_detail && !state.is_repos_empty().await {
+    if is_detail || state.is_repos_empty().await {
+        return;
+    }
+    if state.is_group_selected().await {
+        state.toggle_selected_group().await;
+    } else {
         state.set_detail_view(true).await;
     }
 }
 
+/// Handle collapsing or expanding the group of the selected repository
+async fn handle_toggle_group(state: &AppState) {
+    if !state.is_detail_view().await {
+        state.toggle_selected_group().await;
+    }
+}
+
+/// Handle hiding or showing clean repositories in the list
+async fn handle_toggle_clean(state: &AppState) {
+    if !state.is_detail_view().await {
+        state.toggle_hide_clean().await;
+    }
+}
+
 /// Handle moving down action
 async fn handle_move_down(state: &AppState) {
     let is_detail = state.is_detail_view().await;
@@ -95,6 +438,22 @@ async fn handle_move_down(state: &AppState) {
     }
 }
 
+/// Handle jumping to the next or previous repository meeting `condition`
+async fn handle_jump_to_matching(state: &AppState, condition: Condition, forward: bool) {
+    if state.is_detail_view().await {
+        return;
+    }
+    if !state.jump_to_matching(condition, forward).await {
+        let message = match condition {
+            Condition::Conflict => "No repos with conflicts",
+            _ => "No dirty repos",
+        };
+        state
+            .set_status(StatusMessage::Info(message.to_string()))
+            .await;
+    }
+}
+
 /// Handle moving up action
 async fn handle_move_up(state: &AppState) {
     let is_detail = state.is_detail_view().await;
@@ -103,30 +462,450 @@ async fn handle_move_up(state: &AppState) {
     }
 }
 
+/// Check whether an action that runs external commands or writes may proceed
+///
+/// In safe mode the action is refused and the reason is shown in the status line.
+async fn allow_unsafe_action(state: &AppState, action: &str) -> bool {
+    if !state.config.main.safe_mode {
+        return true;
+    }
+    state
+        .set_status(StatusMessage::Info(format!(
+            "{} is disabled in safe mode",
+            action
+        )))
+        .await;
+    false
+}
+
 /// Handle opening the selected repository path in file manager
 async fn handle_open_in_file_manager(state: &AppState) {
     let is_detail = state.is_detail_view().await;
-    if !is_detail && let Some(path) = state.get_selected_repo_path().await {
-        #[cfg(target_os = "macos")]
-        let _ = Command::new("open").arg(&path).spawn();
+    if is_detail || !allow_unsafe_action(state, "Open").await {
+        return;
+    }
+    if let Some(path) = state.get_selected_repo_path().await {
+        let _ = open::file_manager_command(&path).spawn();
+    }
+}
+
+/// Handle opening the selected repository in a new tmux window
+///
+/// Outside of tmux, the repository is opened in the file manager instead.
+async fn handle_open_in_tmux(state: &AppState) {
+    let is_detail = state.is_detail_view().await;
+    if is_detail || !allow_unsafe_action(state, "Open").await {
+        return;
+    }
+    let Some(path) = state.get_selected_repo_path().await else {
+        return;
+    };
+    let tmux_env = std::env::var_os("TMUX");
+    let command =
+        open::open_repo_command(&path, &state.config.main.tmux_command, tmux_env.as_deref());
+    spawn_detached(state, command).await;
+}
 
-        #[cfg(target_os = "linux")]
-        let _ = Command::new("xdg-open").arg(&path).spawn();
+/// Handle opening a terminal emulator in the selected repository's directory
+async fn handle_open_in_terminal(state: &AppState) {
+    let is_detail = state.is_detail_view().await;
+    if is_detail || !allow_unsafe_action(state, "Open").await {
+        return;
+    }
+    let Some(path) = state.get_selected_repo_path().await else {
+        return;
+    };
+    let command = open::terminal_command(state.config.main.terminal_command.as_deref(), &path);
+    spawn_detached(state, command).await;
+}
 
-        #[cfg(target_os = "windows")]
-        let _ = Command::new("explorer").arg(&path).spawn();
+/// Start a command without waiting for it, reporting failures in the status line
+async fn spawn_detached(state: &AppState, command: Result<Command, String>) {
+    let result = command.and_then(|mut command| {
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Failed to run {:?}: {}", command.get_program(), e))
+    });
+    if let Err(e) = result {
+        state.set_status(StatusMessage::Error(e)).await;
     }
 }
 
 /// Handle changing directory to the selected repository (exits TUI)
+///
+/// The TUI only exits once the path has been written to the cwd file. If no
+/// cwd file is configured or writing fails, the reason is shown in the status
+/// line and the TUI keeps running.
 async fn handle_cd_to_repo(state: &AppState) -> io::Result<bool> {
     let is_detail = state.is_detail_view().await;
-    if !is_detail && let Some(path) = state.get_selected_repo_path().await {
-        // Write the path to cwd_file if specified
-        if let Some(cwd_file) = &state.config.internal.cwd_file {
-            std::fs::write(cwd_file, path.to_string_lossy().as_bytes())?;
+    if is_detail {
+        return Ok(false);
+    }
+    let Some(path) = state.get_selected_repo_path().await else {
+        return Ok(false);
+    };
+
+    let Some(cwd_file) = &state.config.internal.cwd_file else {
+        state
+            .set_status(StatusMessage::Info(
+                "CD requires the shell wrapper (see examples/shell_wrapper.sh)".to_string(),
+            ))
+            .await;
+        return Ok(false);
+    };
+
+    match std::fs::write(cwd_file, path.to_string_lossy().as_bytes()) {
+        Ok(()) => Ok(true), // Exit the application
+        Err(e) => {
+            state
+                .set_status(StatusMessage::Error(format!(
+                    "Failed to write cwd file '{}': {}",
+                    cwd_file, e
+                )))
+                .await;
+            Ok(false)
         }
-        return Ok(true); // Exit the application
     }
-    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::core::RepoInfo;
+
+    /// Create a state containing one real repository at the given path
+    async fn state_with_repo(repo_path: &std::path::Path, cwd_file: Option<String>) -> AppState {
+        let mut config = AppConfig::default();
+        config.internal.cwd_file = cwd_file;
+        state_with_repo_and_config(repo_path, config).await
+    }
+
+    /// Create a state with the given config containing one real repository
+    async fn state_with_repo_and_config(
+        repo_path: &std::path::Path,
+        config: AppConfig,
+    ) -> AppState {
+        git2::Repository::init(repo_path).unwrap();
+
+        let state = AppState::new(config, None);
+        let info = RepoInfo::from_path(repo_path.to_path_buf()).unwrap();
+        state.repos.lock().await.push(info);
+        state
+    }
+
+    #[tokio::test]
+    async fn test_cd_writes_cwd_file_and_exits() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cwd_file = temp_dir.path().join("cwd");
+        let state = state_with_repo(
+            temp_dir.path(),
+            Some(cwd_file.to_string_lossy().to_string()),
+        )
+        .await;
+
+        assert!(handle_cd_to_repo(&state).await.unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&cwd_file).unwrap(),
+            temp_dir.path().to_string_lossy()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cd_without_cwd_file_shows_hint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = state_with_repo(temp_dir.path(), None).await;
+
+        assert!(!handle_cd_to_repo(&state).await.unwrap());
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Info(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cd_with_unwritable_cwd_file_shows_error() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let cwd_file = temp_dir.path().join("missing").join("cwd");
+        let state = state_with_repo(
+            temp_dir.path(),
+            Some(cwd_file.to_string_lossy().to_string()),
+        )
+        .await;
+
+        // Writing fails, but the app keeps running instead of erroring out
+        assert!(!handle_cd_to_repo(&state).await.unwrap());
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Error(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_input_poll_ignores_scan_interval() {
+        let mut config = AppConfig::default();
+        config.internal.scan_poll_interval = 5_000;
+        let state = AppState::new(config, None);
+
+        let mut polled = None;
+        let exit = handle_polled_input(&state, |timeout| {
+            polled = Some(timeout);
+            Ok(None)
+        })
+        .await
+        .unwrap();
+
+        assert!(!exit);
+        assert_eq!(polled, Some(INPUT_POLL_TIMEOUT));
+        assert!(INPUT_POLL_TIMEOUT <= Duration::from_millis(50));
+
+        // A polled key press is handled
+        let exit = handle_polled_input(&state, |_| Ok(Some(KeyCode::Char('q'))))
+            .await
+            .unwrap();
+        assert!(exit);
+    }
+
+    #[tokio::test]
+    async fn test_open_disabled_in_safe_mode() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        config.main.safe_mode = true;
+        let state = state_with_repo_and_config(temp_dir.path(), config).await;
+
+        for key in ["O", "t", "T"] {
+            state.clear_status().await;
+            handle_key_event(KeyCode::Char(key.chars().next().unwrap()), &state)
+                .await
+                .unwrap();
+            assert_eq!(
+                *state.status.lock().await,
+                Some(StatusMessage::Info(
+                    "Open is disabled in safe mode".to_string()
+                ))
+            );
+        }
+    }
+
+    /// Create a state for a repository with a commit on `main` and a merged branch `done`
+    async fn state_with_merged_branch(repo_path: &std::path::Path) -> AppState {
+        let state = state_with_repo(repo_path, None).await;
+        let repo = git2::Repository::open(repo_path).unwrap();
+        let sig = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        repo.branch("done", &repo.find_commit(oid).unwrap(), false)
+            .unwrap();
+        state
+    }
+
+    #[tokio::test]
+    async fn test_delete_branch_requires_confirmation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = state_with_merged_branch(temp_dir.path()).await;
+
+        handle_key_event(KeyCode::Char('b'), &state).await.unwrap();
+        state.move_branch_selection(-1).await; // "done" sorts before "main"
+
+        // Any key other than "y" cancels the delete
+        handle_key_event(KeyCode::Char('d'), &state).await.unwrap();
+        handle_key_event(KeyCode::Char('n'), &state).await.unwrap();
+        let view = state.branch_view.lock().await.clone().unwrap();
+        assert_eq!(view.branches.len(), 2);
+
+        handle_key_event(KeyCode::Char('d'), &state).await.unwrap();
+        handle_key_event(KeyCode::Char('y'), &state).await.unwrap();
+        let view = state.branch_view.lock().await.clone().unwrap();
+        assert_eq!(view.branches.len(), 1);
+        assert_eq!(
+            *state.status.lock().await,
+            Some(StatusMessage::Info("Deleted branch 'done'".to_string()))
+        );
+    }
+
+    /// Type `text` into the focused prompt, one key at a time
+    async fn type_text(state: &AppState, text: &str) {
+        for c in text.chars() {
+            handle_key_event(KeyCode::Char(c), state).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_git_prompt_runs_command() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = state_with_merged_branch(temp_dir.path()).await;
+
+        handle_key_event(KeyCode::Char('g'), &state).await.unwrap();
+        type_text(&state, "log --onelinex").await;
+        handle_key_event(KeyCode::Backspace, &state).await.unwrap();
+        handle_key_event(KeyCode::Enter, &state).await.unwrap();
+
+        assert!(state.git_prompt.lock().await.is_none());
+        let output = state.git_output.lock().await.clone().unwrap();
+        assert_eq!(output.command, "git log --oneline");
+        assert_eq!(output.lines.len(), 1);
+        assert!(output.lines[0].ends_with("init"));
+
+        handle_key_event(KeyCode::Esc, &state).await.unwrap();
+        assert!(state.git_output.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_git_prompt_rejects_destructive_command() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = state_with_merged_branch(temp_dir.path()).await;
+
+        handle_key_event(KeyCode::Char('g'), &state).await.unwrap();
+        // Keys bound to actions are typed into the prompt
+        type_text(&state, "reset --hard q").await;
+        handle_key_event(KeyCode::Enter, &state).await.unwrap();
+
+        assert!(state.git_output.lock().await.is_none());
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Error(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_current_branch_refused() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = state_with_merged_branch(temp_dir.path()).await;
+
+        // The branch view opens with the current branch selected
+        handle_key_event(KeyCode::Char('b'), &state).await.unwrap();
+        handle_key_event(KeyCode::Char('D'), &state).await.unwrap();
+
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Error(_))
+        ));
+        let view = state.branch_view.lock().await.clone().unwrap();
+        assert!(view.pending_delete.is_none());
+    }
+
+    /// Create a state for a clean repository at `repo_path` whose `main` is pushed to
+    /// a bare remote, archiving into `archive_dir`
+    async fn state_with_pushed_repo(
+        repo_path: &std::path::Path,
+        archive_dir: &std::path::Path,
+    ) -> AppState {
+        let state = state_with_merged_branch(repo_path).await;
+        let remote_path = repo_path.with_extension("remote");
+        git2::Repository::init_bare(&remote_path).unwrap();
+        let repo = git2::Repository::open(repo_path).unwrap();
+        let mut remote = repo
+            .remote("origin", remote_path.to_str().unwrap())
+            .unwrap();
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+        remote
+            .push(&[format!("refs/heads/{0}:refs/heads/{0}", branch)], None)
+            .unwrap();
+        remote.fetch(&[&branch], None, None).unwrap();
+        repo.find_branch(&branch, git2::BranchType::Local)
+            .unwrap()
+            .set_upstream(Some(&format!("origin/{}", branch)))
+            .unwrap();
+
+        let mut config = AppConfig::default();
+        config.main.archive_dir = Some(archive_dir.to_string_lossy().to_string());
+        let state = AppState {
+            config: std::sync::Arc::new(config),
+            ..state
+        };
+        state.refresh_repo(repo_path).await.unwrap();
+        state
+    }
+
+    #[tokio::test]
+    async fn test_archive_requires_confirmation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("app");
+        let archive_dir = temp_dir.path().join("archive");
+        std::fs::create_dir(&repo_path).unwrap();
+        let state = state_with_pushed_repo(&repo_path, &archive_dir).await;
+
+        // Any key other than "y" cancels the archive
+        handle_key_event(KeyCode::Char('a'), &state).await.unwrap();
+        handle_key_event(KeyCode::Char('n'), &state).await.unwrap();
+        assert!(repo_path.exists());
+        assert_eq!(state.repos.lock().await.len(), 1);
+
+        handle_key_event(KeyCode::Char('a'), &state).await.unwrap();
+        handle_key_event(KeyCode::Char('y'), &state).await.unwrap();
+        assert!(!repo_path.exists());
+        assert!(archive_dir.join("app").join(".git").exists());
+        assert!(state.is_repos_empty().await);
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Info(ref m)) if m.starts_with("Archived to")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_archive_refuses_dirty_repo_unless_forced() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("app");
+        let archive_dir = temp_dir.path().join("archive");
+        std::fs::create_dir(&repo_path).unwrap();
+        let state = state_with_pushed_repo(&repo_path, &archive_dir).await;
+        std::fs::write(repo_path.join("notes.txt"), "unsaved").unwrap();
+        state.refresh_repo(&repo_path).await.unwrap();
+
+        handle_key_event(KeyCode::Char('a'), &state).await.unwrap();
+        assert!(state.pending_archive.lock().await.is_none());
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Error(_))
+        ));
+
+        handle_key_event(KeyCode::Char('A'), &state).await.unwrap();
+        handle_key_event(KeyCode::Char('y'), &state).await.unwrap();
+        assert!(archive_dir.join("app").join("notes.txt").exists());
+        assert!(state.is_repos_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_write_actions_refused_mid_rebase() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("app");
+        let archive_dir = temp_dir.path().join("archive");
+        std::fs::create_dir(&repo_path).unwrap();
+        let state = state_with_pushed_repo(&repo_path, &archive_dir).await;
+        std::fs::create_dir(repo_path.join(".git").join("rebase-merge")).unwrap();
+        state.refresh_repo(&repo_path).await.unwrap();
+
+        handle_key_event(KeyCode::Char('A'), &state).await.unwrap();
+        assert!(state.pending_archive.lock().await.is_none());
+        assert!(matches!(
+            *state.status.lock().await,
+            Some(StatusMessage::Error(ref m)) if m.contains("in the middle of a rebase")
+        ));
+
+        // The state is checked again at write time, in case it changed since the scan
+        let err = state.archive_repo(&repo_path, true).await.unwrap_err();
+        assert!(err.contains("in the middle of a rebase"), "{}", err);
+        handle_key_event(KeyCode::Char('b'), &state).await.unwrap();
+        let err = state.delete_branch("done", true).await.unwrap_err();
+        assert!(err.contains("in the middle of a rebase"), "{}", err);
+        assert!(repo_path.join(".git").exists());
+    }
+
+    #[tokio::test]
+    async fn test_archive_needs_archive_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = state_with_merged_branch(temp_dir.path()).await;
+
+        handle_key_event(KeyCode::Char('A'), &state).await.unwrap();
+        assert!(state.pending_archive.lock().await.is_none());
+        assert!(temp_dir.path().join(".git").exists());
+    }
 }
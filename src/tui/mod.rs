@@ -1,7 +1,10 @@
 mod app;
+mod groups;
 mod input;
+mod prefetch;
 mod state;
 mod task;
 mod ui;
 
-pub use app::run_tui_app;
+pub use app::{TuiSeed, run_tui_app};
+pub use state::ConfigLoader;
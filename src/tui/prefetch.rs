@@ -0,0 +1,277 @@
+//! This module loads the working tree status of repositories in lazy-info mode.
+//!
+//! The selected repository is loaded as soon as possible. While the user is
+//! idle, repositories near the selection are prefetched one at a time, so
+//! moving to them feels instant. Any key press pauses prefetching, and a new
+//! selection changes which neighbours come first. Neighbours are those on
+//! screen, so hidden repos and those of collapsed groups are skipped.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{debug, error};
+
+use crate::core::RepoInfo;
+use crate::core::grouping::GroupBy;
+use crate::tui::groups::{ListRow, hidden_repos, list_rows};
+use crate::tui::state::AppState;
+
+/// Idle time after the last key press before neighbours are prefetched
+const IDLE_DELAY: Duration = Duration::from_millis(300);
+
+/// How many repositories above and below the selection are prefetched
+const PREFETCH_RADIUS: usize = 3;
+
+/// Spawn background task that loads the status of partially loaded repos
+pub fn spawn_prefetch_task(state: &AppState) {
+    let state = state.clone();
+
+    tokio::spawn(async move {
+        let mut failed = HashSet::new();
+        loop {
+            tokio::time::sleep(Duration::from_millis(
+                state.config.internal.scan_poll_interval,
+            ))
+            .await;
+            while prefetch_next(&state, IDLE_DELAY, PREFETCH_RADIUS, &mut failed).await {}
+        }
+    });
+}
+
+/// Indices of the repos with a row on screen, in the order they are listed
+fn shown_order(
+    repos: &[RepoInfo],
+    group_by: GroupBy,
+    collapsed: &HashSet<String>,
+    hidden: &HashSet<usize>,
+) -> Vec<usize> {
+    list_rows(repos, group_by, collapsed, hidden)
+        .into_iter()
+        .filter_map(|row| match row {
+            ListRow::Repo(idx) => Some(idx),
+            ListRow::Group { .. } => None,
+        })
+        .collect()
+}
+
+/// Paths of partially loaded repos near `selected` on screen, nearest first
+///
+/// `order` holds the indices of the shown repos in list order. The selected
+/// repo itself comes first, then the shown repos at distance 1, 2, ... up to
+/// `radius`, with the one below the selection before the one above.
+fn prefetch_candidates(
+    repos: &[RepoInfo],
+    order: &[usize],
+    selected: usize,
+    radius: usize,
+) -> Vec<PathBuf> {
+    let mut indices = vec![selected];
+    if let Some(position) = order.iter().position(|&idx| idx == selected) {
+        for d in 1..=radius {
+            let below = position.checked_add(d).and_then(|p| order.get(p));
+            let above = position.checked_sub(d).and_then(|p| order.get(p));
+            indices.extend(below.into_iter().chain(above).copied());
+        }
+    }
+
+    indices
+        .into_iter()
+        .filter_map(|idx| repos.get(idx))
+        .filter(|repo| repo.partial)
+        .map(|repo| repo.basic.path.clone())
+        .collect()
+}
+
+/// Load the next repo that needs its status, returns false if there is none
+///
+/// The selected repo is loaded right away. Its neighbours are only loaded once
+/// the user has been idle for `idle_delay`. Repos that fail to load are added
+/// to `failed` and skipped afterwards.
+async fn prefetch_next(
+    state: &AppState,
+    idle_delay: Duration,
+    radius: usize,
+    failed: &mut HashSet<PathBuf>,
+) -> bool {
+    let path = {
+        let repos = state.repos.lock().await;
+        let selected = *state.selected_index.lock().await;
+        let radius = if state.idle_for().await >= idle_delay {
+            radius
+        } else {
+            0
+        };
+        let hidden = hidden_repos(
+            &repos,
+            *state.hide_clean.lock().await,
+            &state.config.ui.status_rules,
+        );
+        let collapsed = state.collapsed_groups.lock().await;
+        let order = shown_order(&repos, state.config.ui.group_by, &collapsed, &hidden);
+        prefetch_candidates(&repos, &order, selected, radius)
+            .into_iter()
+            .find(|path| !failed.contains(path))
+    };
+    let Some(path) = path else {
+        return false;
+    };
+
+    debug!("Loading status of {:?}", path);
+    match state.load_repo(&path).await {
+        Ok(info) => {
+            let mut repos = state.repos.lock().await;
+            if let Some(repo) = repos.iter_mut().find(|r| r.basic.path == path) {
+                repo.reload_from(info);
+            }
+        }
+        Err(e) => {
+            error!("Error loading status of {:?}: {}", path, e);
+            failed.insert(path);
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::core::RepoInfoOptions;
+    use git2::Repository;
+    use std::path::Path;
+    use std::time::Instant;
+
+    /// Create a partially loaded repository info
+    fn partial(name: &str) -> RepoInfo {
+        let mut repo = RepoInfo::stub(name, &format!("/repos/{}", name));
+        repo.partial = true;
+        repo
+    }
+
+    fn names(paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    /// Create `count` repos with an untracked file, loaded without their status
+    fn create_partial_repos(root: &Path, count: usize) -> Vec<RepoInfo> {
+        let options = RepoInfoOptions {
+            skip_working_status: true,
+            ..Default::default()
+        };
+        (0..count)
+            .map(|i| {
+                let path = root.join(format!("repo{}", i));
+                Repository::init(&path).unwrap();
+                std::fs::write(path.join("new.txt"), "content").unwrap();
+                RepoInfo::from_path_with_options(path, options).unwrap()
+            })
+            .collect()
+    }
+
+    async fn prefetch_all(state: &AppState, failed: &mut HashSet<PathBuf>) {
+        while prefetch_next(state, IDLE_DELAY, 2, failed).await {}
+    }
+
+    #[test]
+    fn test_prefetch_candidates_nearest_first() {
+        let mut repos: Vec<RepoInfo> = ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .map(|name| partial(name))
+            .collect();
+        repos[3].partial = false;
+
+        let order: Vec<usize> = (0..repos.len()).collect();
+
+        let candidates = prefetch_candidates(&repos, &order, 2, 2);
+        assert_eq!(names(&candidates), vec!["c", "b", "e", "a"]);
+
+        // At the edges, only the existing side is used
+        assert_eq!(
+            names(&prefetch_candidates(&repos, &order, 0, 1)),
+            vec!["a", "b"]
+        );
+        assert_eq!(names(&prefetch_candidates(&repos, &order, 5, 0)), vec!["f"]);
+    }
+
+    #[test]
+    fn test_prefetch_candidates_follow_screen_order() {
+        let repos: Vec<RepoInfo> = ["/work/web", "/home/dots", "/work/api", "/home/notes"]
+            .iter()
+            .map(|path| {
+                let name = path.rsplit('/').next().unwrap();
+                RepoInfo {
+                    partial: true,
+                    ..RepoInfo::stub(name, path)
+                }
+            })
+            .collect();
+
+        // Grouped by directory, /home lists dots and notes before /work lists web and api
+        let order = shown_order(&repos, GroupBy::Directory, &HashSet::new(), &HashSet::new());
+        assert_eq!(order, vec![1, 3, 0, 2]);
+        assert_eq!(
+            names(&prefetch_candidates(&repos, &order, 3, 1)),
+            vec!["notes", "web", "dots"]
+        );
+
+        // Hidden repos and those of collapsed groups have no row and are skipped
+        let hidden = HashSet::from([0]);
+        let order = shown_order(&repos, GroupBy::Directory, &HashSet::new(), &hidden);
+        assert_eq!(
+            names(&prefetch_candidates(&repos, &order, 3, 1)),
+            vec!["notes", "api", "dots"]
+        );
+        let collapsed = HashSet::from(["/work".to_string()]);
+        let order = shown_order(&repos, GroupBy::Directory, &collapsed, &HashSet::new());
+        assert_eq!(
+            names(&prefetch_candidates(&repos, &order, 3, 1)),
+            vec!["notes", "dots"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_neighbours_prefetched_after_idle() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = create_partial_repos(temp_dir.path(), 7);
+        *state.selected_index.lock().await = 3;
+        *state.last_input.lock().await = Instant::now() - IDLE_DELAY * 2;
+
+        prefetch_all(&state, &mut HashSet::new()).await;
+
+        let repos = state.repos.lock().await;
+        let loaded: Vec<bool> = repos.iter().map(|r| !r.partial).collect();
+        assert_eq!(loaded, vec![false, true, true, true, true, true, false]);
+        assert_eq!(repos[2].working.untracked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_only_selected_loaded_while_navigating() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = create_partial_repos(temp_dir.path(), 3);
+        *state.selected_index.lock().await = 1;
+        state.touch_input().await;
+
+        prefetch_all(&state, &mut HashSet::new()).await;
+
+        let repos = state.repos.lock().await;
+        let loaded: Vec<bool> = repos.iter().map(|r| !r.partial).collect();
+        assert_eq!(loaded, vec![false, true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_repo_is_skipped() {
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = vec![partial("missing")];
+        let mut failed = HashSet::new();
+
+        prefetch_all(&state, &mut failed).await;
+
+        assert!(failed.contains(Path::new("/repos/missing")));
+        assert!(state.repos.lock().await[0].partial);
+    }
+}
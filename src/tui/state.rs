@@ -1,18 +1,169 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
 use crate::config::{AppConfig, ColorScheme};
-use crate::core::RepoInfo;
+use crate::core::archive;
+use crate::core::branches::{self, LocalBranch};
+use crate::core::git_command;
+use crate::core::grid::{GridMove, grid_step};
+use crate::core::grouping::group_key;
+use crate::core::safety;
+use crate::core::status::Condition;
+use crate::core::{RepoInfo, RepoInfoOptions, RepoInfoWorker};
+use crate::tui::groups::{
+    Cursor, cursor_at, cursor_position, display_order, hidden_repos, list_rows,
+};
+
+/// Interval at which a single repository load is polled for its result
+const LOAD_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Loads the config again from its layers, with the same command line
+pub type ConfigLoader = Arc<dyn Fn() -> Result<AppConfig, String> + Send + Sync>;
 
 /// Shared application state
 /// We place app config within the state as it may be modified during runtime.
 /// The app config is session specific and should be part of the state.
+#[derive(Clone)]
 pub struct AppState {
-    pub repos: Arc<Mutex<Vec<RepoInfo>>>,  // list of repos
-    pub selected_index: Arc<Mutex<usize>>, // current selected repo index
-    pub detail_view: Arc<Mutex<bool>>,     // whether in detail view
-    pub config: Arc<AppConfig>,            // app config in current session
-    pub colors: ColorScheme,               // color scheme from theme
+    pub repos: Arc<Mutex<Vec<RepoInfo>>>,            // list of repos
+    pub selected_index: Arc<Mutex<usize>>,           // current selected repo index
+    pub detail_view: Arc<Mutex<bool>>,               // whether in detail view
+    pub branch_view: Arc<Mutex<Option<BranchView>>>, // branches of the selected repo
+    pub git_prompt: Arc<Mutex<Option<String>>>,      // git command being typed
+    pub git_output: Arc<Mutex<Option<GitOutput>>>,   // output of the last git command
+    pub pending_archive: Arc<Mutex<Option<(PathBuf, bool)>>>, // repo awaiting archive confirmation
+    pub exit_error: Arc<Mutex<Option<String>>>,      // error that should end the session
+    pub scan_complete: Arc<Mutex<bool>>,             // whether all repos have been loaded
+    pub collapsed_groups: Arc<Mutex<HashSet<String>>>, // keys of collapsed repo groups
+    pub selected_group: Arc<Mutex<Option<String>>>,  // group header under the cursor
+    pub hide_clean: Arc<Mutex<bool>>,                // whether clean repos are hidden
+    pub status: Arc<Mutex<Option<StatusMessage>>>,   // message shown in the status line
+    pub last_input: Arc<Mutex<Instant>>,             // time of the last key press
+    pub reload_requested: Arc<Mutex<bool>>,          // whether the config should be reloaded
+    pub grid_columns: Arc<AtomicUsize>,              // cells per row of the grid as last rendered
+    pub config: Arc<AppConfig>,                      // app config in current session
+    pub colors: ColorScheme,                         // color scheme from theme
+    pub initial_repo: Option<Arc<str>>,              // repo name to open after scanning
+    pub config_loader: Option<ConfigLoader>,         // reloads the config on request
+    pub repo_loader: Arc<Mutex<RepoInfoWorker>>,     // reusable worker loading single repos
+}
+
+/// Message shown in the status line until the next key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusMessage {
+    Info(String),
+    Error(String),
+}
+
+/// Local branches of a repository shown in the branch view
+#[derive(Debug, Clone)]
+pub struct BranchView {
+    pub repo_path: PathBuf,
+    pub branches: Vec<LocalBranch>,
+    pub selected: usize,
+    /// Branch awaiting delete confirmation, and whether the delete is forced
+    pub pending_delete: Option<(String, bool)>,
+}
+
+impl BranchView {
+    /// Get the selected branch
+    pub fn selected_branch(&self) -> Option<&LocalBranch> {
+        self.branches.get(self.selected)
+    }
+}
+
+/// Output of a git command run from the prompt, shown in a modal
+#[derive(Debug, Clone)]
+pub struct GitOutput {
+    pub command: String,
+    pub lines: Vec<String>,
+    /// Index of the first line shown
+    pub scroll: usize,
+}
+
+impl GitOutput {
+    pub fn new(command: String, output: &str) -> Self {
+        Self {
+            command,
+            lines: output.lines().map(str::to_string).collect(),
+            scroll: 0,
+        }
+    }
+
+    /// Scroll by `delta` lines, keeping the last line reachable
+    pub fn scroll_by(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(last);
+    }
+}
+
+/// Close the current batch of a reusable worker, wait for its results and reset it
+async fn run_batch(worker: &RepoInfoWorker) -> Result<Vec<Result<RepoInfo, String>>, String> {
+    worker.finish_submitting();
+    let mut results = Vec::new();
+    loop {
+        let complete = worker.is_complete();
+        results.extend(worker.poll_results());
+        if complete {
+            break;
+        }
+        tokio::time::sleep(LOAD_POLL_INTERVAL).await;
+    }
+    worker.reset()?;
+    Ok(results)
+}
+
+/// Result of looking up a repository by name
+#[derive(Debug, PartialEq, Eq)]
+pub enum RepoNameMatch {
+    /// Exactly one repository has the name (index into the list)
+    Unique(usize),
+    /// Several repositories share the name (indices into the list)
+    Ambiguous(Vec<usize>),
+    /// No repository has the name
+    NotFound,
+}
+
+/// Find the repositories whose name or directory name matches `name` exactly
+pub fn resolve_repo_by_name(repos: &[RepoInfo], name: &str) -> RepoNameMatch {
+    let matches: Vec<usize> = repos
+        .iter()
+        .enumerate()
+        .filter(|(_, repo)| repo.basic.name == name || repo.basic.dir_name == name)
+        .map(|(idx, _)| idx)
+        .collect();
+
+    match matches.len() {
+        0 => RepoNameMatch::NotFound,
+        1 => RepoNameMatch::Unique(matches[0]),
+        _ => RepoNameMatch::Ambiguous(matches),
+    }
+}
+
+/// Find the next repository meeting `condition`, searching from `current`
+///
+/// Repositories are visited in `order` (indices into `repos`, as listed). The
+/// search wraps around the list and visits `current` last.
+pub fn find_next_matching(
+    repos: &[RepoInfo],
+    order: &[usize],
+    current: usize,
+    condition: Condition,
+    forward: bool,
+) -> Option<usize> {
+    let len = order.len();
+    let start = order.iter().position(|&idx| idx == current).unwrap_or(0);
+    (1..=len)
+        .map(|offset| match forward {
+            true => (start + offset) % len,
+            false => (start + len - offset % len) % len,
+        })
+        .map(|position| order[position])
+        .find(|&idx| condition.matches(&repos[idx]))
 }
 
 /// Snapshot of UI state for rendering
@@ -21,20 +172,109 @@ pub struct RenderSnapshot {
     pub repos: Vec<RepoInfo>,
     pub selected_index: usize,
     pub is_detail_view: bool,
+    pub branch_view: Option<BranchView>,
+    pub git_prompt: Option<String>,
+    pub git_output: Option<GitOutput>,
+    pub status: Option<StatusMessage>,
+    pub scan_complete: bool,
+    pub collapsed_groups: HashSet<String>,
+    pub selected_group: Option<String>,
+    /// Indices of the repositories left out of the list
+    pub hidden: HashSet<usize>,
+}
+
+/// What is shown in place of repository details while the list is empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyState {
+    /// The scan is still running, repositories may show up
+    Scanning,
+    /// The scan has completed without finding any repository
+    NoRepos,
+}
+
+impl RenderSnapshot {
+    /// Empty state to show, or None if there are repositories
+    pub fn empty_state(&self) -> Option<EmptyState> {
+        match (self.repos.is_empty(), self.scan_complete) {
+            (false, _) => None,
+            (true, false) => Some(EmptyState::Scanning),
+            (true, true) => Some(EmptyState::NoRepos),
+        }
+    }
+
+    /// Title of the repository list, with the number of hidden clean repositories
+    pub fn list_title(&self) -> String {
+        let counts = match self.hidden.len() {
+            0 => format!("{} found", self.repos.len()),
+            hidden => format!(
+                "{} shown, {} clean hidden",
+                self.repos.len() - hidden,
+                hidden
+            ),
+        };
+        match self.scan_complete {
+            true => format!("Repos ({})", counts),
+            false => format!("Repos ({}, scanning…)", counts),
+        }
+    }
+
+    /// Row under the cursor
+    pub fn cursor(&self) -> Cursor {
+        match &self.selected_group {
+            Some(key) => Cursor::Group(key.clone()),
+            None => Cursor::Repo(self.selected_index),
+        }
+    }
+
+    /// Selected repository, or None if a group header is selected
+    pub fn selected_repo(&self) -> Option<&RepoInfo> {
+        match self.selected_group {
+            Some(_) => None,
+            None => self.repos.get(self.selected_index),
+        }
+    }
 }
 
 impl AppState {
-    pub fn new(config: AppConfig) -> Self {
-        let colors = config.ui.theme.colors();
+    pub fn new(config: AppConfig, initial_repo: Option<String>) -> Self {
+        let colors = config
+            .ui
+            .theme
+            .colors()
+            .for_support(config.ui.color_support);
+        let repo_loader =
+            RepoInfoWorker::reusable_for_repo_info(RepoInfoOptions::from_config(&config));
         Self {
             repos: Arc::new(Mutex::new(Vec::new())),
             selected_index: Arc::new(Mutex::new(0)),
             detail_view: Arc::new(Mutex::new(false)),
+            branch_view: Arc::new(Mutex::new(None)),
+            git_prompt: Arc::new(Mutex::new(None)),
+            git_output: Arc::new(Mutex::new(None)),
+            pending_archive: Arc::new(Mutex::new(None)),
+            exit_error: Arc::new(Mutex::new(None)),
+            scan_complete: Arc::new(Mutex::new(false)),
+            collapsed_groups: Arc::new(Mutex::new(HashSet::new())),
+            selected_group: Arc::new(Mutex::new(None)),
+            hide_clean: Arc::new(Mutex::new(false)),
+            status: Arc::new(Mutex::new(None)),
+            last_input: Arc::new(Mutex::new(Instant::now())),
+            reload_requested: Arc::new(Mutex::new(false)),
+            grid_columns: Arc::new(AtomicUsize::new(1)),
             config: Arc::new(config),
             colors,
+            initial_repo: initial_repo.map(Arc::from),
+            config_loader: None,
+            repo_loader: Arc::new(Mutex::new(repo_loader)),
         }
     }
 
+    /// Set how the config is loaded again when a reload is requested
+    pub fn with_config_loader(mut self, loader: ConfigLoader) -> Self {
+        self.config_loader = Some(loader);
+        self
+    }
+
     /// Check if repository list is empty
     pub async fn is_repos_empty(&self) -> bool {
         let repos_lock = self.repos.lock().await;
@@ -43,17 +283,162 @@ impl AppState {
 
     /// Move selection up
     pub async fn move_selection_up(&self) {
-        let mut selected = self.selected_index.lock().await;
-        if *selected > 0 {
-            *selected -= 1;
-        }
+        self.move_selection_by(-1).await;
     }
 
     /// Move selection down
     pub async fn move_selection_down(&self) {
+        self.move_selection_by(1).await;
+    }
+
+    /// Move the cursor by `delta` rows, over group headers and expanded groups
+    async fn move_selection_by(&self, delta: isize) {
+        let repos = self.repos.lock().await;
+        let collapsed = self.collapsed_groups.lock().await;
+        let hidden = self.hidden_repos(&repos).await;
+        let rows = list_rows(&repos, self.config.ui.group_by, &collapsed, &hidden);
         let mut selected = self.selected_index.lock().await;
+        let mut selected_group = self.selected_group.lock().await;
+
+        let cursor = match selected_group.as_ref() {
+            Some(key) => Cursor::Group(key.clone()),
+            None => Cursor::Repo(*selected),
+        };
+        let position = cursor_position(&rows, &cursor).unwrap_or(0);
+        let last = rows.len().saturating_sub(1);
+        match cursor_at(&rows, position.saturating_add_signed(delta).min(last)) {
+            Some(Cursor::Group(key)) => *selected_group = Some(key),
+            Some(Cursor::Repo(idx)) => {
+                *selected = idx;
+                *selected_group = None;
+            }
+            None => {}
+        }
+    }
+
+    /// Move the selection in the grid layout, over the repositories in list order
+    pub async fn move_selection_in_grid(&self, direction: GridMove) {
+        let repos = self.repos.lock().await;
+        let order = display_order(
+            &repos,
+            self.config.ui.group_by,
+            &self.hidden_repos(&repos).await,
+        );
+        let mut selected = self.selected_index.lock().await;
+        let position = order.iter().position(|&idx| idx == *selected).unwrap_or(0);
+        let columns = self.grid_columns.load(Ordering::Relaxed);
+        if let Some(&idx) = order.get(grid_step(position, order.len(), columns, direction)) {
+            *selected = idx;
+            *self.selected_group.lock().await = None;
+        }
+    }
+
+    /// Collapse or expand the group under the cursor
+    ///
+    /// On a repository, its group is collapsed and the cursor moves to the
+    /// group header. Does nothing when not grouping.
+    pub async fn toggle_selected_group(&self) {
+        let repos = self.repos.lock().await;
+        let mut collapsed = self.collapsed_groups.lock().await;
+        let selected = self.selected_index.lock().await;
+        let mut selected_group = self.selected_group.lock().await;
+
+        let key = match selected_group.clone() {
+            Some(key) => key,
+            None => match repos
+                .get(*selected)
+                .and_then(|repo| group_key(repo, self.config.ui.group_by))
+            {
+                Some(key) => key,
+                None => return,
+            },
+        };
+        if !collapsed.remove(&key) {
+            collapsed.insert(key.clone());
+            *selected_group = Some(key);
+        }
+    }
+
+    /// Hide or show clean repositories
+    ///
+    /// The selected repository stays selected when it is still shown; else the
+    /// cursor moves to the next shown repository in list order.
+    pub async fn toggle_hide_clean(&self) {
+        let hide_clean = {
+            let mut hide_clean = self.hide_clean.lock().await;
+            *hide_clean = !*hide_clean;
+            *hide_clean
+        };
+        if !hide_clean {
+            return;
+        }
+
+        let repos = self.repos.lock().await;
+        let mut collapsed = self.collapsed_groups.lock().await;
+        let mut selected = self.selected_index.lock().await;
+        let mut selected_group = self.selected_group.lock().await;
+        let group_by = self.config.ui.group_by;
+        let hidden = self.hidden_repos(&repos).await;
+        let rows = list_rows(&repos, group_by, &collapsed, &hidden);
+        let cursor = match selected_group.as_ref() {
+            Some(key) => Cursor::Group(key.clone()),
+            None => Cursor::Repo(*selected),
+        };
+        if cursor_position(&rows, &cursor).is_some() {
+            return;
+        }
+
+        // Search from the selection in list order, forward first
+        let order = display_order(&repos, group_by, &HashSet::new());
+        let start = order.iter().position(|&idx| idx == *selected).unwrap_or(0);
+        let (before, after) = order.split_at(start);
+        if let Some(&idx) = after
+            .iter()
+            .chain(before.iter().rev())
+            .find(|idx| !hidden.contains(idx))
+        {
+            if let Some(key) = group_key(&repos[idx], group_by) {
+                collapsed.remove(&key);
+            }
+            *selected = idx;
+            *selected_group = None;
+        }
+    }
+
+    /// Indices of the repositories left out of the list
+    async fn hidden_repos(&self, repos: &[RepoInfo]) -> HashSet<usize> {
+        let hide_clean = *self.hide_clean.lock().await;
+        hidden_repos(repos, hide_clean, &self.config.ui.status_rules)
+    }
+
+    /// Check whether a group header is under the cursor
+    pub async fn is_group_selected(&self) -> bool {
+        self.selected_group.lock().await.is_some()
+    }
+
+    /// Move selection to the next (or previous) repository meeting `condition`
+    ///
+    /// Repositories are searched in the order they are listed, and the group
+    /// of the one found is expanded. Returns false if no repository meets it.
+    pub async fn jump_to_matching(&self, condition: Condition, forward: bool) -> bool {
         let repos = self.repos.lock().await;
-        *selected = (*selected + 1).min(repos.len().saturating_sub(1));
+        let mut collapsed = self.collapsed_groups.lock().await;
+        let mut selected = self.selected_index.lock().await;
+        let mut selected_group = self.selected_group.lock().await;
+
+        let group_by = self.config.ui.group_by;
+        let order = display_order(&repos, group_by, &self.hidden_repos(&repos).await);
+        match find_next_matching(&repos, &order, *selected, condition, forward) {
+            Some(idx) => {
+                if let Some(key) = group_key(&repos[idx], group_by) {
+                    collapsed.remove(&key);
+                }
+                *selected = idx;
+                *selected_group = None;
+                true
+            }
+            None => false,
+        }
     }
 
     /// Get detail view status
@@ -67,16 +452,269 @@ impl AppState {
         *detail = enabled;
     }
 
+    /// Check whether the branch view is open
+    pub async fn is_branch_view(&self) -> bool {
+        self.branch_view.lock().await.is_some()
+    }
+
+    /// Open the branch view for the selected repository
+    pub async fn open_branch_view(&self) -> Result<(), String> {
+        let Some(repo_path) = self.get_selected_repo_path().await else {
+            return Ok(());
+        };
+        let branches = branches::list_local_branches(&repo_path)?;
+        let selected = branches.iter().position(|b| b.is_head).unwrap_or(0);
+        *self.branch_view.lock().await = Some(BranchView {
+            repo_path,
+            branches,
+            selected,
+            pending_delete: None,
+        });
+        Ok(())
+    }
+
+    /// Close the branch view
+    pub async fn close_branch_view(&self) {
+        *self.branch_view.lock().await = None;
+    }
+
+    /// Move the branch selection by `delta` rows
+    pub async fn move_branch_selection(&self, delta: isize) {
+        if let Some(view) = self.branch_view.lock().await.as_mut() {
+            let last = view.branches.len().saturating_sub(1);
+            view.selected = view.selected.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Delete a branch of the repository in the branch view, then reload it
+    ///
+    /// The branch list and the repository's info are refreshed afterwards.
+    pub async fn delete_branch(&self, name: &str, force: bool) -> Result<(), String> {
+        let Some(repo_path) = self
+            .branch_view
+            .lock()
+            .await
+            .as_ref()
+            .map(|v| v.repo_path.clone())
+        else {
+            return Ok(());
+        };
+
+        self.load_for_write(&repo_path)?;
+        branches::delete_local_branch(&repo_path, name, force)?;
+
+        let branches = branches::list_local_branches(&repo_path)?;
+        if let Some(view) = self.branch_view.lock().await.as_mut() {
+            view.selected = view.selected.min(branches.len().saturating_sub(1));
+            view.branches = branches;
+        }
+        self.refresh_repo(&repo_path).await
+    }
+
+    /// Run a read-only git command in the selected repository and show its output
+    pub async fn run_git_command(&self, input: &str) -> Result<(), String> {
+        let Some(repo_path) = self.get_selected_repo_path().await else {
+            return Ok(());
+        };
+        let output = git_command::run_git_command(&repo_path, input).await?;
+        *self.git_output.lock().await =
+            Some(GitOutput::new(format!("git {}", input.trim()), &output));
+        Ok(())
+    }
+
+    /// Move a repository to the archive directory and remove it from the list
+    ///
+    /// Its status is read again right before the move, so that changes made since
+    /// it was last loaded are taken into account. Returns the new path.
+    pub async fn archive_repo(&self, path: &Path, force: bool) -> Result<PathBuf, String> {
+        let Some(archive_dir) = self.config.main.archive_dir.as_deref() else {
+            return Err("No archive_dir is configured".to_string());
+        };
+        let info = self.load_for_write(path)?;
+        archive::check_archivable(&info, force)?;
+
+        let (from, archive_dir) = (path.to_path_buf(), PathBuf::from(archive_dir));
+        let destination =
+            tokio::task::spawn_blocking(move || archive::archive_repo(&from, &archive_dir))
+                .await
+                .map_err(|e| format!("Archive task failed: {}", e))??;
+        self.remove_repo(path).await;
+        Ok(destination)
+    }
+
+    /// Read a repository's status again and check that write actions may touch it
+    ///
+    /// The status is returned for checks specific to the action.
+    fn load_for_write(&self, path: &Path) -> Result<RepoInfo, String> {
+        let options = RepoInfoOptions::from_config(&self.config);
+        let info = RepoInfo::from_path_with_options(path.to_path_buf(), options)?;
+        safety::can_perform_write(&info)?;
+        Ok(info)
+    }
+
+    /// Remove a repository from the list, keeping the same repository selected if possible
+    pub async fn remove_repo(&self, path: &Path) {
+        let mut repos = self.repos.lock().await;
+        let Some(idx) = repos.iter().position(|r| r.basic.path == path) else {
+            return;
+        };
+        repos.remove(idx);
+        let mut selected = self.selected_index.lock().await;
+        if *selected > idx {
+            *selected -= 1;
+        }
+        *selected = (*selected).min(repos.len().saturating_sub(1));
+        *self.detail_view.lock().await = false;
+    }
+
+    /// Reload the information of a single repository
+    pub async fn refresh_repo(&self, path: &std::path::Path) -> Result<(), String> {
+        let info = self.load_repo(path).await?;
+        let mut repos = self.repos.lock().await;
+        if let Some(repo) = repos.iter_mut().find(|r| r.basic.path == path) {
+            repo.reload_from(info);
+        }
+        Ok(())
+    }
+
+    /// Load the full information of a single repository on the shared worker
+    ///
+    /// Loads run one at a time, each as a batch of its own, so that refreshes
+    /// and prefetches reuse one worker instead of starting a new one each time.
+    pub async fn load_repo(&self, path: &Path) -> Result<RepoInfo, String> {
+        let worker = self.repo_loader.lock().await;
+        // A load given up half-way leaves its batch behind, which must not mix with this one
+        run_batch(&worker).await?;
+        worker.submit(path.to_path_buf())?;
+        run_batch(&worker)
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(format!("No information loaded for {:?}", path)))
+    }
+
+    /// Open the initial repository once scanning has completed
+    ///
+    /// A unique match is selected and shown in detail view, an ambiguous name
+    /// narrows the list to the matching repositories, and an unknown name ends
+    /// the session with an error.
+    pub async fn open_initial_repo(&self) {
+        let Some(name) = self.initial_repo.as_deref() else {
+            return;
+        };
+
+        let mut repos = self.repos.lock().await;
+        match resolve_repo_by_name(&repos, name) {
+            RepoNameMatch::Unique(idx) => {
+                if let Some(key) = group_key(&repos[idx], self.config.ui.group_by) {
+                    self.collapsed_groups.lock().await.remove(&key);
+                }
+                *self.selected_index.lock().await = idx;
+                *self.selected_group.lock().await = None;
+                *self.detail_view.lock().await = true;
+            }
+            RepoNameMatch::Ambiguous(indices) => {
+                let mut idx = 0;
+                repos.retain(|_| {
+                    let keep = indices.contains(&idx);
+                    idx += 1;
+                    keep
+                });
+                *self.selected_index.lock().await = 0;
+            }
+            RepoNameMatch::NotFound => {
+                *self.exit_error.lock().await =
+                    Some(format!("No repository named '{}' found", name));
+            }
+        }
+    }
+
+    /// Show a message in the status line
+    pub async fn set_status(&self, message: StatusMessage) {
+        *self.status.lock().await = Some(message);
+    }
+
+    /// Clear the status line message
+    pub async fn clear_status(&self) {
+        *self.status.lock().await = None;
+    }
+
+    /// Record that the user just pressed a key
+    pub async fn touch_input(&self) {
+        *self.last_input.lock().await = Instant::now();
+    }
+
+    /// Time since the user last pressed a key
+    pub async fn idle_for(&self) -> Duration {
+        self.last_input.lock().await.elapsed()
+    }
+
+    /// Ask the event loop to reload the config before the next frame
+    pub async fn request_config_reload(&self) {
+        *self.reload_requested.lock().await = true;
+    }
+
+    /// Take the pending config reload request, if any
+    pub async fn take_reload_request(&self) -> bool {
+        std::mem::take(&mut *self.reload_requested.lock().await)
+    }
+
+    /// Load the config again and apply its UI settings, reporting the outcome in the status line
+    ///
+    /// Only the `[ui]` section is applied: the background tasks keep the
+    /// settings they started with. The selection, filters and other runtime
+    /// state are left alone.
+    pub async fn reload_config(&mut self) {
+        let Some(loader) = self.config_loader.clone() else {
+            self.set_status(StatusMessage::Error(
+                "Config reload is not available".to_string(),
+            ))
+            .await;
+            return;
+        };
+        match loader() {
+            Ok(reloaded) => {
+                self.colors = reloaded
+                    .ui
+                    .theme
+                    .colors()
+                    .for_support(reloaded.ui.color_support);
+                self.config = Arc::new(AppConfig {
+                    ui: reloaded.ui,
+                    ..(*self.config).clone()
+                });
+                self.set_status(StatusMessage::Info("Config reloaded".to_string()))
+                    .await;
+            }
+            Err(e) => {
+                self.set_status(StatusMessage::Error(format!("Config not reloaded: {}", e)))
+                    .await;
+            }
+        }
+    }
+
+    /// Take the error that should end the session, if any
+    pub async fn take_exit_error(&self) -> Option<String> {
+        self.exit_error.lock().await.take()
+    }
+
     /// Get a snapshot of state for rendering (using try_lock for sync context)
     /// Returns default values if locks are unavailable
     pub fn get_render_snapshot(&self) -> RenderSnapshot {
+        let repos = self
+            .repos
+            .try_lock()
+            .ok()
+            .map(|r| r.clone())
+            .unwrap_or_default();
+        let hide_clean = self
+            .hide_clean
+            .try_lock()
+            .ok()
+            .map(|h| *h)
+            .unwrap_or_default();
         RenderSnapshot {
-            repos: self
-                .repos
-                .try_lock()
-                .ok()
-                .map(|r| r.clone())
-                .unwrap_or_default(),
+            hidden: hidden_repos(&repos, hide_clean, &self.config.ui.status_rules),
+            repos,
             selected_index: self
                 .selected_index
                 .try_lock()
@@ -89,13 +727,465 @@ impl AppState {
                 .ok()
                 .map(|d| *d)
                 .unwrap_or_default(),
+            branch_view: self.branch_view.try_lock().ok().and_then(|b| b.clone()),
+            git_prompt: self.git_prompt.try_lock().ok().and_then(|p| p.clone()),
+            git_output: self.git_output.try_lock().ok().and_then(|o| o.clone()),
+            status: self.status.try_lock().ok().and_then(|s| s.clone()),
+            scan_complete: self
+                .scan_complete
+                .try_lock()
+                .ok()
+                .map(|c| *c)
+                .unwrap_or_default(),
+            collapsed_groups: self
+                .collapsed_groups
+                .try_lock()
+                .ok()
+                .map(|c| c.clone())
+                .unwrap_or_default(),
+            selected_group: self.selected_group.try_lock().ok().and_then(|g| g.clone()),
         }
     }
 
     /// Get the path of the currently selected repository
+    ///
+    /// Returns None while a group header is selected.
     pub async fn get_selected_repo_path(&self) -> Option<std::path::PathBuf> {
         let repos = self.repos.lock().await;
         let selected = self.selected_index.lock().await;
+        if self.selected_group.lock().await.is_some() {
+            return None;
+        }
         repos.get(*selected).map(|repo| repo.basic.path.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ColorSupport;
+    use crate::core::grouping::GroupBy;
+
+    /// Create a clean repository info with the given name and path
+    fn repo(name: &str, path: &str) -> RepoInfo {
+        RepoInfo::stub(name, path)
+    }
+
+    #[test]
+    fn test_empty_state() {
+        let snapshot = |repos: Vec<RepoInfo>, scan_complete| RenderSnapshot {
+            repos,
+            selected_index: 0,
+            is_detail_view: false,
+            branch_view: None,
+            git_prompt: None,
+            git_output: None,
+            status: None,
+            scan_complete,
+            collapsed_groups: HashSet::new(),
+            selected_group: None,
+            hidden: HashSet::new(),
+        };
+
+        assert_eq!(
+            snapshot(vec![], false).empty_state(),
+            Some(EmptyState::Scanning)
+        );
+        assert_eq!(
+            snapshot(vec![], true).empty_state(),
+            Some(EmptyState::NoRepos)
+        );
+        let repos = vec![repo("alpha", "/a/alpha")];
+        assert_eq!(snapshot(repos.clone(), false).empty_state(), None);
+        assert_eq!(snapshot(repos, true).empty_state(), None);
+    }
+
+    #[test]
+    fn test_resolve_repo_unique() {
+        let repos = vec![repo("alpha", "/a/alpha"), repo("beta", "/a/beta")];
+        assert_eq!(
+            resolve_repo_by_name(&repos, "beta"),
+            RepoNameMatch::Unique(1)
+        );
+    }
+
+    #[test]
+    fn test_resolve_repo_ambiguous() {
+        let repos = vec![
+            repo("alpha", "/a/alpha"),
+            repo("beta", "/a/beta"),
+            repo("alpha", "/b/alpha"),
+        ];
+        assert_eq!(
+            resolve_repo_by_name(&repos, "alpha"),
+            RepoNameMatch::Ambiguous(vec![0, 2])
+        );
+    }
+
+    #[test]
+    fn test_resolve_repo_not_found() {
+        let repos = vec![repo("alpha", "/a/alpha")];
+        assert_eq!(
+            resolve_repo_by_name(&repos, "gamma"),
+            RepoNameMatch::NotFound
+        );
+        assert_eq!(resolve_repo_by_name(&[], "alpha"), RepoNameMatch::NotFound);
+    }
+
+    /// Create repos where the ones at `dirty` indices have uncommitted changes
+    fn repos_with_dirty(count: usize, dirty: &[usize]) -> Vec<RepoInfo> {
+        (0..count)
+            .map(|idx| {
+                let mut repo = repo(&format!("r{}", idx), &format!("/a/r{}", idx));
+                repo.working.is_dirty = dirty.contains(&idx);
+                repo
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_next_matching_wraps() {
+        let repos = repos_with_dirty(8, &[1, 4, 6]);
+        let order: Vec<usize> = (0..8).collect();
+        let next = |current| find_next_matching(&repos, &order, current, Condition::Dirty, true);
+        let prev = |current| find_next_matching(&repos, &order, current, Condition::Dirty, false);
+
+        assert_eq!(next(0), Some(1));
+        assert_eq!(next(1), Some(4));
+        assert_eq!(next(6), Some(1));
+        assert_eq!(prev(4), Some(1));
+        assert_eq!(prev(1), Some(6));
+        assert_eq!(prev(7), Some(6));
+
+        // A single match is found from itself
+        let repos = repos_with_dirty(3, &[2]);
+        let order = [0, 1, 2];
+        assert_eq!(
+            find_next_matching(&repos, &order, 2, Condition::Dirty, true),
+            Some(2)
+        );
+        assert_eq!(
+            find_next_matching(&repos, &order, 0, Condition::Conflict, true),
+            None
+        );
+        assert_eq!(
+            find_next_matching(&[], &[], 0, Condition::Dirty, false),
+            None
+        );
+
+        // The search follows the listed order
+        let repos = repos_with_dirty(4, &[0, 3]);
+        let order = [2, 3, 0, 1];
+        assert_eq!(
+            find_next_matching(&repos, &order, 2, Condition::Dirty, true),
+            Some(3)
+        );
+        assert_eq!(
+            find_next_matching(&repos, &order, 2, Condition::Dirty, false),
+            Some(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grid_navigation() {
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = repos_with_dirty(8, &[]);
+        state.grid_columns.store(3, Ordering::Relaxed);
+        *state.selected_index.lock().await = 1;
+
+        state.move_selection_in_grid(GridMove::Down).await;
+        assert_eq!(*state.selected_index.lock().await, 4);
+        state.move_selection_in_grid(GridMove::Right).await;
+        assert_eq!(*state.selected_index.lock().await, 5);
+        state.move_selection_in_grid(GridMove::Down).await;
+        assert_eq!(*state.selected_index.lock().await, 7);
+        state.move_selection_in_grid(GridMove::Left).await;
+        assert_eq!(*state.selected_index.lock().await, 6);
+        state.move_selection_in_grid(GridMove::Up).await;
+        assert_eq!(*state.selected_index.lock().await, 3);
+
+        // Hidden repos are skipped: with 0, 2, 4 and 6 dirty, 6 is third in the grid
+        let mut repos = repos_with_dirty(8, &[0, 2, 4, 6]);
+        for repo in &mut repos {
+            repo.sync.has_upstream = true;
+        }
+        *state.repos.lock().await = repos;
+        *state.selected_index.lock().await = 2;
+        state.toggle_hide_clean().await;
+        state.move_selection_in_grid(GridMove::Down).await;
+        assert_eq!(*state.selected_index.lock().await, 6);
+    }
+
+    #[tokio::test]
+    async fn test_load_repo_reuses_worker() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repo_path = temp_dir.path().join("app");
+        git2::Repository::init(&repo_path).unwrap();
+        let state = AppState::new(AppConfig::default(), None);
+
+        assert!(
+            state
+                .load_repo(&temp_dir.path().join("missing"))
+                .await
+                .is_err()
+        );
+        for _ in 0..2 {
+            let info = state.load_repo(&repo_path).await.unwrap();
+            assert_eq!(info.basic.path, repo_path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reload_config() {
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = repos_with_dirty(3, &[]);
+        *state.selected_index.lock().await = 2;
+        *state.hide_clean.lock().await = true;
+        let dark_border = state.colors.border;
+
+        let mut state = state.with_config_loader(Arc::new(|| {
+            let mut config = AppConfig::default();
+            config.ui.theme = "light".parse().unwrap();
+            config.ui.color_support = ColorSupport::TrueColor;
+            config.ui.keybindings.quit = vec!["x".to_string()];
+            Ok(config)
+        }));
+        state.reload_config().await;
+
+        assert_eq!(state.config.ui.theme.name(), "light");
+        assert_eq!(state.colors.border, ColorScheme::light().border);
+        assert_ne!(state.colors.border, dark_border);
+        assert!(state.config.ui.keybindings.matches("quit", "x"));
+        assert!(!state.config.ui.keybindings.matches("quit", "q"));
+        assert_eq!(*state.selected_index.lock().await, 2);
+        assert!(*state.hide_clean.lock().await);
+        assert_eq!(
+            *state.status.lock().await,
+            Some(StatusMessage::Info("Config reloaded".to_string()))
+        );
+
+        // A broken config leaves the current one in place
+        let mut state = state.with_config_loader(Arc::new(|| Err("bad toml".to_string())));
+        state.reload_config().await;
+        assert_eq!(state.config.ui.theme.name(), "light");
+        assert_eq!(
+            *state.status.lock().await,
+            Some(StatusMessage::Error(
+                "Config not reloaded: bad toml".to_string()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jump_to_matching() {
+        let state = AppState::new(AppConfig::default(), None);
+        let mut repos = repos_with_dirty(5, &[0, 3]);
+        repos[2].working.conflicts = 1;
+        *state.repos.lock().await = repos;
+
+        assert!(state.jump_to_matching(Condition::Dirty, true).await);
+        assert_eq!(*state.selected_index.lock().await, 3);
+        assert!(state.jump_to_matching(Condition::Dirty, true).await);
+        assert_eq!(*state.selected_index.lock().await, 0);
+        assert!(state.jump_to_matching(Condition::Conflict, true).await);
+        assert_eq!(*state.selected_index.lock().await, 2);
+        assert!(!state.jump_to_matching(Condition::HasStashes, false).await);
+        assert_eq!(*state.selected_index.lock().await, 2);
+    }
+
+    /// Create a state grouping by directory: /home has r1, /work has r0 and r2
+    async fn grouped_state() -> AppState {
+        let mut config = AppConfig::default();
+        config.ui.group_by = GroupBy::Directory;
+        let state = AppState::new(config, None);
+        *state.repos.lock().await = vec![
+            repo("r0", "/work/r0"),
+            repo("r1", "/home/r1"),
+            repo("r2", "/work/r2"),
+        ];
+        state
+    }
+
+    async fn cursor(state: &AppState) -> Cursor {
+        state.get_render_snapshot().cursor()
+    }
+
+    #[tokio::test]
+    async fn test_grouped_navigation() {
+        let state = grouped_state().await;
+        let home = Cursor::Group("/home".to_string());
+        let work = Cursor::Group("/work".to_string());
+        *state.selected_index.lock().await = 1;
+
+        // Headers are rows of their own, repos follow in their group
+        state.move_selection_down().await;
+        assert_eq!(cursor(&state).await, work);
+        assert!(state.get_selected_repo_path().await.is_none());
+        state.move_selection_down().await;
+        assert_eq!(cursor(&state).await, Cursor::Repo(0));
+        state.move_selection_down().await;
+        state.move_selection_down().await;
+        assert_eq!(cursor(&state).await, Cursor::Repo(2));
+
+        // Collapsed groups are skipped over
+        state
+            .collapsed_groups
+            .lock()
+            .await
+            .insert("/work".to_string());
+        *state.selected_group.lock().await = Some("/home".to_string());
+        state.move_selection_down().await;
+        state.move_selection_down().await;
+        assert_eq!(cursor(&state).await, work);
+        state.move_selection_down().await;
+        assert_eq!(cursor(&state).await, work);
+        state.move_selection_up().await;
+        assert_eq!(cursor(&state).await, Cursor::Repo(1));
+        state.move_selection_up().await;
+        state.move_selection_up().await;
+        assert_eq!(cursor(&state).await, home);
+    }
+
+    #[tokio::test]
+    async fn test_toggle_group() {
+        let state = grouped_state().await;
+        let work = Cursor::Group("/work".to_string());
+        *state.selected_index.lock().await = 2;
+
+        // Collapsing from a repo moves the cursor to its header
+        state.toggle_selected_group().await;
+        assert!(state.collapsed_groups.lock().await.contains("/work"));
+        assert_eq!(cursor(&state).await, work);
+
+        // Expanding keeps the cursor on the header
+        state.toggle_selected_group().await;
+        assert!(state.collapsed_groups.lock().await.is_empty());
+        assert_eq!(cursor(&state).await, work);
+        state.toggle_selected_group().await;
+
+        // Jumping to a repo of a collapsed group expands it
+        state.repos.lock().await[0].working.is_dirty = true;
+        assert!(state.jump_to_matching(Condition::Dirty, true).await);
+        assert_eq!(cursor(&state).await, Cursor::Repo(0));
+        assert!(state.collapsed_groups.lock().await.is_empty());
+
+        // Without grouping there is nothing to toggle
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = vec![repo("a", "/work/a")];
+        state.toggle_selected_group().await;
+        assert!(state.collapsed_groups.lock().await.is_empty());
+        assert_eq!(cursor(&state).await, Cursor::Repo(0));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_hide_clean() {
+        let state = AppState::new(AppConfig::default(), None);
+        let mut repos: Vec<RepoInfo> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| repo(name, &format!("/work/{}", name)))
+            .collect();
+        for repo in &mut repos {
+            repo.sync.has_upstream = true;
+        }
+        repos[0].working.is_dirty = true;
+        repos[2].working.conflicts = 1;
+        *state.repos.lock().await = repos;
+
+        // The selected repo stays selected while it is shown
+        *state.selected_index.lock().await = 2;
+        state.toggle_hide_clean().await;
+        let snapshot = state.get_render_snapshot();
+        assert_eq!(snapshot.hidden, HashSet::from([1, 3]));
+        assert_eq!(snapshot.cursor(), Cursor::Repo(2));
+        state.move_selection_up().await;
+        assert_eq!(cursor(&state).await, Cursor::Repo(0));
+
+        // Showing clean repos again keeps the selection
+        state.toggle_hide_clean().await;
+        assert!(state.get_render_snapshot().hidden.is_empty());
+        assert_eq!(cursor(&state).await, Cursor::Repo(0));
+
+        // A clean selected repo gives way to the next shown one
+        *state.selected_index.lock().await = 1;
+        state.toggle_hide_clean().await;
+        assert_eq!(cursor(&state).await, Cursor::Repo(2));
+        *state.selected_index.lock().await = 3;
+        state.toggle_hide_clean().await;
+        state.toggle_hide_clean().await;
+        assert_eq!(cursor(&state).await, Cursor::Repo(2));
+    }
+
+    #[tokio::test]
+    async fn test_list_title_counts_hidden() {
+        let state = AppState::new(AppConfig::default(), None);
+        let mut repos: Vec<RepoInfo> = (0..4)
+            .map(|i| repo(&format!("r{}", i), &format!("/r{}", i)))
+            .collect();
+        for repo in &mut repos {
+            repo.sync.has_upstream = true;
+        }
+        repos[0].working.is_dirty = true;
+        *state.repos.lock().await = repos;
+
+        assert_eq!(
+            state.get_render_snapshot().list_title(),
+            "Repos (4 found, scanning…)"
+        );
+        state.toggle_hide_clean().await;
+        *state.scan_complete.lock().await = true;
+        assert_eq!(
+            state.get_render_snapshot().list_title(),
+            "Repos (1 shown, 3 clean hidden)"
+        );
+    }
+
+    #[test]
+    fn test_git_output_scroll() {
+        let mut output = GitOutput::new("git log".to_string(), "a\nb\nc\n");
+        assert_eq!(output.lines, ["a", "b", "c"]);
+        output.scroll_by(-1);
+        assert_eq!(output.scroll, 0);
+        output.scroll_by(10);
+        assert_eq!(output.scroll, 2);
+    }
+
+    #[tokio::test]
+    async fn test_remove_repo_keeps_selection() {
+        let state = AppState::new(AppConfig::default(), None);
+        *state.repos.lock().await = vec![repo("a", "/a"), repo("b", "/b"), repo("c", "/c")];
+        *state.selected_index.lock().await = 2;
+
+        state.remove_repo(Path::new("/a")).await;
+        assert_eq!(*state.selected_index.lock().await, 1);
+        assert_eq!(
+            state.get_selected_repo_path().await.unwrap(),
+            Path::new("/c")
+        );
+
+        // Removing the selected last repo selects the one before it
+        state.remove_repo(Path::new("/c")).await;
+        assert_eq!(
+            state.get_selected_repo_path().await.unwrap(),
+            Path::new("/b")
+        );
+        state.remove_repo(Path::new("/missing")).await;
+        assert_eq!(state.repos.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_open_initial_repo() {
+        let state = AppState::new(AppConfig::default(), Some("alpha".to_string()));
+        *state.repos.lock().await = vec![
+            repo("alpha", "/a/alpha"),
+            repo("beta", "/a/beta"),
+            repo("alpha", "/b/alpha"),
+        ];
+
+        state.open_initial_repo().await;
+
+        let repos = state.repos.lock().await;
+        assert_eq!(repos.len(), 2);
+        assert!(repos.iter().all(|r| r.basic.name == "alpha"));
+        assert!(!*state.detail_view.lock().await);
+    }
+}
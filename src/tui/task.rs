@@ -1,33 +1,66 @@
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::error;
 
-use crate::core::{self, RepoInfoWorker};
-use crate::tui::state::AppState;
+use crate::core::{
+    self, RepoInfo, RepoInfoOptions, RepoInfoWorker, append_unique_repos,
+    results_cache::save_cached_repos, sorting::sort_pinned_first, tags::TagStore,
+};
+use crate::tui::state::{AppState, StatusMessage};
 
 /// Spawn background task for repository scanning and info retrieval
 pub fn spawn_scan_repo_and_get_info_task(state: &AppState) {
+    let state = state.clone();
     let repos = state.repos.clone();
     let config = state.config.clone();
 
     tokio::spawn(async move {
         // Create a new worker for this scan operation
-        let git_worker = Arc::new(RepoInfoWorker::for_repo_info());
-
-        // Fast async directory scan to find all Git repositories
-        match core::scan_directories(&config.main.scan_dirs, &config).await {
-            Ok(repo_paths) => {
-                // Submit all paths for background Git processing
-                git_worker.submit_repos(&repo_paths);
-            }
+        let options = RepoInfoOptions {
+            skip_working_status: config.main.lazy_info,
+            ..RepoInfoOptions::from_config(&config)
+        };
+        let git_worker = Arc::new(RepoInfoWorker::for_repo_info(options));
+        let tag_store = match TagStore::from_config(&config) {
+            Ok(store) => store,
             Err(e) => {
-                error!("Error scanning directories: {}", e);
+                state.set_status(StatusMessage::Error(e)).await;
+                TagStore::default()
             }
-        }
+        };
+
+        // Fast async directory scan to find all Git repositories
+        // A failed or truncated scan does not find every repo, so it must not
+        // drop cached repos or replace the results cache
+        let (mut queue, scan_finished) =
+            match core::scan_directories(&config.main.scan_dirs, &config).await {
+                Ok(scan) => {
+                    if scan.truncated {
+                        let message = "Scan timed out, results may be incomplete".to_string();
+                        state.set_status(StatusMessage::Info(message)).await;
+                    }
+                    (VecDeque::from(scan.paths), !scan.truncated)
+                }
+                Err(e) => {
+                    let message = format!("Error scanning directories: {}", e);
+                    error!("{}", message);
+                    state.set_status(StatusMessage::Error(message)).await;
+                    (VecDeque::new(), false)
+                }
+            };
+        // Paths are submitted as earlier results come in, so that a large scan
+        // does not flood the thread pool and results show up progressively
+        let jobs = config.main.jobs_limit();
+
+        // Paths already in the repo list, for deduplication
+        let mut seen = HashSet::new();
 
         // Poll for results periodically and update state
         loop {
-            tokio::time::sleep(Duration::from_millis(config.internal.refresh_interval)).await;
+            git_worker.submit_up_to(&mut queue, jobs);
+            tokio::time::sleep(Duration::from_millis(config.internal.scan_poll_interval)).await;
 
             let results = git_worker.poll_results();
             if results.is_empty() {
@@ -38,23 +71,79 @@ pub fn spawn_scan_repo_and_get_info_task(state: &AppState) {
                 continue;
             }
 
-            let mut repos_lock = repos.lock().await;
-            for result in results {
-                match result {
-                    Ok(repo_info) => {
-                        // Avoid duplicates
-                        if !repos_lock
-                            .iter()
-                            .any(|r| r.basic.path == repo_info.basic.path)
-                        {
-                            repos_lock.push(repo_info);
-                        }
+            // Filter results before locking, then append the whole batch at once
+            let mut batch: Vec<_> = results
+                .into_iter()
+                .filter_map(|result| match result {
+                    Ok(repo_info) if config.main.hide_submodules && repo_info.is_submodule() => {
+                        None
                     }
+                    Ok(repo_info) => Some(repo_info),
                     Err(e) => {
                         error!("Error processing repo: {}", e);
+                        None
                     }
+                })
+                .collect();
+            tag_store.apply(&mut batch);
+            if let Some(ref tag) = config.internal.tag_filter {
+                batch.retain(|repo| repo.has_tag(tag));
+            }
+            if batch.is_empty() {
+                continue;
+            }
+
+            let mut repos_lock = repos.lock().await;
+            append_unique_repos(&mut repos_lock, &mut seen, batch);
+
+            // Keep pinned repos on top without moving the selection to another repo
+            if !config.ui.pinned.is_empty() {
+                let mut selected = state.selected_index.lock().await;
+                let selected_path = repos_lock.get(*selected).map(|r| r.basic.path.clone());
+                sort_pinned_first(&mut repos_lock, &config.ui.pinned);
+                if let Some(path) = selected_path
+                    && let Some(idx) = repos_lock.iter().position(|r| r.basic.path == path)
+                {
+                    *selected = idx;
                 }
             }
         }
+
+        // Cached repos the scan did not find again are gone
+        if scan_finished {
+            let mut repos_lock = repos.lock().await;
+            if repos_lock.iter().any(|repo| repo.cached) {
+                let mut selected = state.selected_index.lock().await;
+                let selected_path = repos_lock.get(*selected).map(|r| r.basic.path.clone());
+                repos_lock.retain(|repo| !repo.cached);
+                *selected = selected_path
+                    .and_then(|path| repos_lock.iter().position(|r| r.basic.path == path))
+                    .unwrap_or(0);
+            }
+            // Lazily loaded repos lack their working status, which the cache must keep
+            if let Some(file) = config.results_cache_file()
+                && !config.main.lazy_info
+            {
+                let snapshot = repos_lock.clone();
+                drop(repos_lock);
+                save_results_cache(&state, file.to_path_buf(), snapshot).await;
+            }
+        }
+
+        *state.scan_complete.lock().await = true;
+        state.open_initial_repo().await;
     });
 }
+
+/// Save the scan results for the next start, reporting failures in the status line
+///
+/// The file is written on a blocking thread, so that rendering and input go on meanwhile.
+async fn save_results_cache(state: &AppState, file: PathBuf, repos: Vec<RepoInfo>) {
+    let result = tokio::task::spawn_blocking(move || save_cached_repos(&file, &repos))
+        .await
+        .unwrap_or_else(|e| Err(format!("Results cache task failed: {}", e)));
+    if let Err(e) = result {
+        error!("{}", e);
+        state.set_status(StatusMessage::Error(e)).await;
+    }
+}
@@ -0,0 +1,62 @@
+use ratatui::{
+    Frame,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, ListItem, ListState},
+};
+
+use crate::config::{ColorScheme, UIConfig};
+use crate::core::branches::LocalBranch;
+use crate::tui::state::BranchView;
+use crate::tui::ui::repo_list::selectable_list;
+
+/// Render the local branches of the selected repository
+pub fn render_branch_list(
+    f: &mut Frame,
+    view: &BranchView,
+    area: ratatui::layout::Rect,
+    colors: &ColorScheme,
+    ui: &UIConfig,
+) {
+    let items: Vec<ListItem> = view
+        .branches
+        .iter()
+        .map(|branch| create_branch_list_item(branch, colors))
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Branches ({}) (ESC to exit)", view.branches.len()))
+        .border_style(Style::default().fg(colors.border));
+
+    let list = selectable_list(items, block, colors, ui);
+
+    let mut list_state = ListState::default();
+    if !view.branches.is_empty() {
+        list_state.select(Some(view.selected));
+    }
+
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Create a single list item for a branch
+fn create_branch_list_item<'a>(branch: &'a LocalBranch, colors: &ColorScheme) -> ListItem<'a> {
+    let mut name_style = Style::default().fg(colors.branch_name);
+    if branch.is_head {
+        name_style = name_style.add_modifier(Modifier::BOLD);
+    }
+
+    let marker = if branch.is_head { "* " } else { "  " };
+    let mut spans = vec![
+        Span::styled(marker, Style::default().fg(colors.branch_name)),
+        Span::styled(branch.name.as_str(), name_style),
+    ];
+    if branch.is_merged && !branch.is_head {
+        spans.push(Span::styled(
+            " (merged)",
+            Style::default().fg(colors.text_muted),
+        ));
+    }
+
+    ListItem::new(Line::from(spans))
+}
@@ -0,0 +1,105 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::config::{AppConfig, ColorScheme};
+use crate::tui::state::EmptyState;
+
+/// Render a centered message in place of the details while the list is empty
+///
+/// While scanning, the scanned directories are shown; once the scan is done,
+/// the message explains where was looked and how to look elsewhere.
+pub fn render_empty_state(
+    f: &mut Frame,
+    state: EmptyState,
+    area: Rect,
+    config: &AppConfig,
+    colors: &ColorScheme,
+) {
+    let lines = empty_state_lines(state, config, colors);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Repo Info")
+        .border_style(Style::default().fg(colors.border));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let [message_area] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+        .flex(Flex::Center)
+        .areas(inner);
+    let message = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(message, message_area);
+}
+
+/// Lines of the empty state message
+fn empty_state_lines<'a>(
+    state: EmptyState,
+    config: &AppConfig,
+    colors: &ColorScheme,
+) -> Vec<Line<'a>> {
+    let muted = Style::default().fg(colors.text_muted);
+    let roots = config.main.scan_dirs.join(", ");
+    match state {
+        EmptyState::Scanning => vec![
+            Line::from(Span::styled(
+                "Scanning for repositories…",
+                Style::default().fg(colors.text_primary),
+            )),
+            Line::from(Span::styled(format!("in {}", roots), muted)),
+        ],
+        EmptyState::NoRepos => {
+            let depth = match config.main.max_depth {
+                0 => "unlimited".to_string(),
+                depth => depth.to_string(),
+            };
+            vec![
+                Line::from(Span::styled(
+                    "No repositories found",
+                    Style::default()
+                        .fg(colors.text_primary)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(Span::styled(format!("Scanned {}", roots), muted)),
+                Line::from(Span::styled(format!("up to depth {}", depth), muted)),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "Pass other paths (reponest PATH...) or a larger --max-depth",
+                    muted,
+                )),
+            ]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_empty_state_lines() {
+        let mut config = AppConfig::default();
+        config.main.scan_dirs = vec!["/work".to_string(), "/src".to_string()];
+        config.main.max_depth = 0;
+        let colors = ColorScheme::default();
+
+        let scanning = text(&empty_state_lines(EmptyState::Scanning, &config, &colors));
+        assert_eq!(scanning, ["Scanning for repositories…", "in /work, /src"]);
+
+        let empty = text(&empty_state_lines(EmptyState::NoRepos, &config, &colors));
+        assert_eq!(empty[0], "No repositories found");
+        assert!(empty.contains(&"Scanned /work, /src".to_string()));
+        assert!(empty.contains(&"up to depth unlimited".to_string()));
+    }
+}
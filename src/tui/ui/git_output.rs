@@ -0,0 +1,59 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::config::ColorScheme;
+use crate::tui::state::GitOutput;
+
+/// Render the output of a git command in a modal over the content area
+pub fn render_git_output(f: &mut Frame, output: &GitOutput, area: Rect, colors: &ColorScheme) {
+    let area = centered(area, 90, 80);
+    let lines: Vec<Line> = match output.lines.is_empty() {
+        true => vec![Line::from(Span::styled(
+            "(no output)",
+            Style::default().fg(colors.text_muted),
+        ))],
+        false => output
+            .lines
+            .iter()
+            .map(|line| {
+                Line::from(Span::styled(
+                    line.as_str(),
+                    Style::default().fg(colors.text_primary),
+                ))
+            })
+            .collect(),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(
+            "{} ({}/{}) (ESC to close)",
+            output.command,
+            (output.scroll + 1).min(output.lines.len()),
+            output.lines.len()
+        ))
+        .border_style(Style::default().fg(colors.border));
+
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((output.scroll.min(u16::MAX as usize) as u16, 0));
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// Get a rectangle of the given percentage size centered in `area`
+fn centered(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(height_percent)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(width_percent)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
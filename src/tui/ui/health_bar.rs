@@ -0,0 +1,138 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::config::ColorScheme;
+use crate::core::status::{StatusCounts, StatusRule};
+use crate::core::text::display_width;
+use crate::tui::state::RenderSnapshot;
+
+/// Narrowest the bar gets, however long the totals are
+const MIN_BAR_WIDTH: usize = 10;
+
+/// Render a one-line bar of repository counts by status
+pub fn render_health_bar(
+    f: &mut Frame,
+    snapshot: &RenderSnapshot,
+    area: Rect,
+    colors: &ColorScheme,
+    rules: &[StatusRule],
+) {
+    let counts = StatusCounts::from_repos(&snapshot.repos, rules);
+    let line = build_health_line(&counts, area.width as usize, colors);
+    f.render_widget(Paragraph::new(line), area);
+}
+
+/// Build the bar followed by the totals, fitting `width` if possible
+fn build_health_line(counts: &StatusCounts, width: usize, colors: &ColorScheme) -> Line<'static> {
+    let segments = [
+        (counts.clean, "clean", colors.status_clean),
+        (counts.dirty, "dirty", colors.status_dirty),
+        (counts.conflict, "conflict", colors.status_conflict),
+        (counts.other, "other", colors.text_muted),
+    ];
+
+    let mut totals = vec![Span::styled(
+        format!(" {} repos", counts.total()),
+        Style::default().fg(colors.text_primary),
+    )];
+    for &(count, label, color) in &segments {
+        if count > 0 {
+            totals.push(Span::styled(
+                format!("  {} {}", count, label),
+                Style::default().fg(color),
+            ));
+        }
+    }
+    let totals_width: usize = totals.iter().map(|s| display_width(&s.content)).sum();
+    let bar_width = width.saturating_sub(totals_width).max(MIN_BAR_WIDTH);
+
+    let counts: Vec<usize> = segments.iter().map(|&(count, _, _)| count).collect();
+    let mut spans: Vec<Span> = match counts.iter().sum::<usize>() {
+        0 => vec![Span::styled(
+            "░".repeat(bar_width),
+            Style::default().fg(colors.text_muted),
+        )],
+        _ => segment_widths(&counts, bar_width)
+            .into_iter()
+            .zip(segments.iter().map(|&(_, _, color)| color))
+            .filter(|&(cells, _)| cells > 0)
+            .map(|(cells, color)| Span::styled("█".repeat(cells), Style::default().fg(color)))
+            .collect(),
+    };
+    spans.extend(totals);
+    Line::from(spans)
+}
+
+/// Split `width` cells between `counts` proportionally
+///
+/// Every non-zero count gets at least one cell if there is room, and the
+/// widths always add up to `width` when any count is non-zero.
+fn segment_widths(counts: &[usize], width: usize) -> Vec<usize> {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return vec![0; counts.len()];
+    }
+
+    let mut widths: Vec<usize> = counts.iter().map(|&c| c * width / total).collect();
+    for (w, &c) in widths.iter_mut().zip(counts) {
+        if c > 0 && *w == 0 {
+            *w = 1;
+        }
+    }
+    // Hand out the cells lost to rounding, largest counts first
+    let mut order: Vec<usize> = (0..counts.len()).filter(|&i| counts[i] > 0).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(counts[i]));
+    let mut used: usize = widths.iter().sum();
+    for &i in order.iter().cycle() {
+        if used >= width {
+            break;
+        }
+        widths[i] += 1;
+        used += 1;
+    }
+    // Take back cells given to small counts from the widest segments
+    while used > width {
+        let widest = (0..widths.len()).max_by_key(|&i| widths[i]).unwrap_or(0);
+        if widths[widest] <= 1 {
+            break;
+        }
+        widths[widest] -= 1;
+        used -= 1;
+    }
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_widths() {
+        assert_eq!(segment_widths(&[6, 3, 1, 0], 20), [12, 6, 2, 0]);
+        // Rounding leftovers go to the largest counts
+        assert_eq!(segment_widths(&[1, 1, 1, 0], 10), [4, 3, 3, 0]);
+        // A small count still shows up
+        assert_eq!(segment_widths(&[98, 1, 0, 1], 10), [8, 1, 0, 1]);
+        assert_eq!(segment_widths(&[0, 0, 0, 0], 10), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_health_line() {
+        let counts = StatusCounts {
+            clean: 3,
+            dirty: 1,
+            conflict: 0,
+            other: 0,
+        };
+        let line = build_health_line(&counts, 40, &ColorScheme::default());
+        let text = line.to_string();
+        assert_eq!(display_width(&text), 40);
+        assert!(text.ends_with(" 4 repos  3 clean  1 dirty"), "{}", text);
+        assert!(!text.contains("conflict"));
+    }
+}
@@ -6,34 +6,110 @@ use ratatui::{
 };
 
 use crate::config::ColorScheme;
-use crate::tui::state::RenderSnapshot;
+use crate::core::status::Condition;
+use crate::tui::state::{RenderSnapshot, StatusMessage};
 
 /// Render keyhint bar at the bottom
+///
+/// The git command prompt or a pending status message takes the place of the keyhints.
 pub fn render_keyhint_bar(
     f: &mut Frame,
     snapshot: &RenderSnapshot,
     colors: &ColorScheme,
     main_chunks: &[ratatui::layout::Rect],
 ) {
-    let keyhints = if snapshot.is_detail_view {
+    let keyhints = if snapshot.git_output.is_some() {
+        get_git_output_keyhints(colors)
+    } else if snapshot.branch_view.is_some() {
+        get_branch_keyhints(colors)
+    } else if snapshot.is_detail_view {
         get_detail_keyhints(colors)
     } else {
-        get_main_keyhints(colors)
+        get_main_keyhints(snapshot, colors)
     };
 
-    let keyhint_line = build_keyhint_line(&keyhints);
-    let paragraph = Paragraph::new(vec![keyhint_line]);
-    f.render_widget(paragraph, main_chunks[1]);
+    let line = match (&snapshot.git_prompt, &snapshot.status) {
+        (Some(input), _) => build_prompt_line(input, colors),
+        (None, Some(status)) => build_status_line(status, colors),
+        (None, None) => build_keyhint_line(&keyhints),
+    };
+
+    let paragraph = Paragraph::new(vec![line]);
+    f.render_widget(paragraph, main_chunks[3]);
+}
+
+/// Build the status line for a status message
+fn build_status_line<'a>(status: &'a StatusMessage, colors: &ColorScheme) -> Line<'a> {
+    let (text, color) = match status {
+        StatusMessage::Info(text) => (text, colors.text_secondary),
+        StatusMessage::Error(text) => (text, colors.key_danger),
+    };
+    Line::from(Span::styled(
+        format!(" {}", text),
+        Style::default().fg(color),
+    ))
+}
+
+/// Build the prompt line for a git command being typed
+fn build_prompt_line<'a>(input: &'a str, colors: &ColorScheme) -> Line<'a> {
+    Line::from(vec![
+        Span::styled(
+            " git ",
+            Style::default()
+                .fg(colors.key_action)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(input, Style::default().fg(colors.text_primary)),
+        Span::styled("█", Style::default().fg(colors.text_secondary)),
+    ])
 }
 
 /// Get keyhints for main view
-fn get_main_keyhints(colors: &ColorScheme) -> Vec<KeyHint> {
-    vec![
+///
+/// Jumps to dirty or conflicted repos are only advertised when there are any.
+fn get_main_keyhints(snapshot: &RenderSnapshot, colors: &ColorScheme) -> Vec<KeyHint> {
+    let mut keyhints = vec![
         KeyHint::new("↓/j", "Down", colors.key_action),
         KeyHint::new("↑/k", "Up", colors.key_action),
         KeyHint::new("→/l", "Details", colors.key_action),
         KeyHint::new("o", "CD", colors.key_action),
         KeyHint::new("O/Enter", "Open", colors.key_action),
+        KeyHint::new("b", "Branches", colors.key_action),
+        KeyHint::new("g", "Git", colors.key_action),
+    ];
+    if snapshot.repos.iter().any(|r| Condition::Dirty.matches(r)) {
+        keyhints.push(KeyHint::new("n/N", "Next dirty", colors.key_action));
+    }
+    if snapshot
+        .repos
+        .iter()
+        .any(|r| Condition::Conflict.matches(r))
+    {
+        keyhints.push(KeyHint::new("c", "Next conflict", colors.key_action));
+    }
+    keyhints.push(KeyHint::new("q", "Quit", colors.key_danger));
+    keyhints
+}
+
+/// Get keyhints for branch view
+fn get_branch_keyhints(colors: &ColorScheme) -> Vec<KeyHint> {
+    vec![
+        KeyHint::new("↓/j", "Down", colors.key_action),
+        KeyHint::new("↑/k", "Up", colors.key_action),
+        KeyHint::new("d", "Delete merged", colors.key_warning),
+        KeyHint::new("D", "Force delete", colors.key_danger),
+        KeyHint::new("ESC", "Back", colors.key_warning),
+        KeyHint::new("q", "Quit", colors.key_danger),
+    ]
+}
+
+/// Get keyhints for the git command output
+fn get_git_output_keyhints(colors: &ColorScheme) -> Vec<KeyHint> {
+    vec![
+        KeyHint::new("↓/j", "Scroll down", colors.key_action),
+        KeyHint::new("↑/k", "Scroll up", colors.key_action),
+        KeyHint::new("PgDn/PgUp", "Page", colors.key_action),
+        KeyHint::new("ESC", "Close", colors.key_warning),
         KeyHint::new("q", "Quit", colors.key_danger),
     ]
 }
@@ -41,6 +117,7 @@ fn get_main_keyhints(colors: &ColorScheme) -> Vec<KeyHint> {
 /// Get keyhints for detail view
 fn get_detail_keyhints(colors: &ColorScheme) -> Vec<KeyHint> {
     vec![
+        KeyHint::new("g", "Git", colors.key_action),
         KeyHint::new("ESC", "Back", colors.key_warning),
         KeyHint::new("q", "Quit", colors.key_danger),
     ]
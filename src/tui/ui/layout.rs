@@ -1,30 +1,31 @@
-use ratatui::{
-    Frame,
-    layout::{Constraint, Direction, Layout},
-};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
 /// Create the layout for the TUI
+///
+/// The main chunks are the header row (empty unless `show_header`), the main
+/// content area and the bottom keyhint bar.
 pub fn create_layout(
-    f: &Frame,
+    area: Rect,
     is_detail_view: bool,
-) -> (
-    std::rc::Rc<[ratatui::layout::Rect]>,
-    std::rc::Rc<[ratatui::layout::Rect]>,
-) {
+    show_header: bool,
+    show_footer: bool,
+) -> (std::rc::Rc<[Rect]>, std::rc::Rc<[Rect]>) {
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(3),    // main content area
-            Constraint::Length(1), // bottom keyhint bar
+            Constraint::Length(show_header as u16), // top health bar
+            Constraint::Min(3),                     // main content area
+            Constraint::Length(show_footer as u16), // totals bar
+            Constraint::Length(1),                  // bottom keyhint bar
         ])
-        .split(f.area());
+        .split(area);
 
     let content_chunks = if is_detail_view {
         // Detailed view uses full width
         Layout::default()
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(100)])
-            .split(main_chunks[0])
+            .split(main_chunks[1])
     } else {
         Layout::default()
             .direction(Direction::Horizontal)
@@ -32,8 +33,39 @@ pub fn create_layout(
                 Constraint::Percentage(45), // left side repo list
                 Constraint::Percentage(55), // right side details
             ])
-            .split(main_chunks[0])
+            .split(main_chunks[1])
     };
 
     (main_chunks, content_chunks)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_reserves_header_row() {
+        let area = Rect::new(0, 0, 100, 30);
+
+        let (main, content) = create_layout(area, false, false, false);
+        assert_eq!(main[0].height, 0);
+        assert_eq!(content[0].height, 29);
+        assert_eq!(main[3], Rect::new(0, 29, 100, 1));
+
+        let (main, content) = create_layout(area, false, true, false);
+        assert_eq!(main[0], Rect::new(0, 0, 100, 1));
+        assert_eq!(content[0].y, 1);
+        assert_eq!(content[0].height, 28);
+        assert_eq!(main[3], Rect::new(0, 29, 100, 1));
+    }
+
+    #[test]
+    fn test_layout_reserves_footer_row() {
+        let area = Rect::new(0, 0, 100, 30);
+
+        let (main, content) = create_layout(area, false, true, true);
+        assert_eq!(content[0].height, 27);
+        assert_eq!(main[2], Rect::new(0, 28, 100, 1));
+        assert_eq!(main[3], Rect::new(0, 29, 100, 1));
+    }
+}
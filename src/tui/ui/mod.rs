@@ -1,7 +1,12 @@
+mod branch_list;
+mod empty_state;
+mod git_output;
+mod health_bar;
 mod keyhint_bar;
 mod layout;
 mod render;
 mod repo_detail;
 mod repo_list;
+mod totals_bar;
 
 pub use render::render_ui;
@@ -1,20 +1,92 @@
 use ratatui::Frame;
+use std::sync::atomic::Ordering;
+
+use crate::config::ListLayout;
 
 use crate::tui::state::AppState;
+use crate::tui::ui::branch_list::render_branch_list;
+use crate::tui::ui::empty_state::render_empty_state;
+use crate::tui::ui::git_output::render_git_output;
+use crate::tui::ui::health_bar::render_health_bar;
 use crate::tui::ui::keyhint_bar::render_keyhint_bar;
 use crate::tui::ui::layout::create_layout;
 use crate::tui::ui::repo_detail::render_repository_details;
-use crate::tui::ui::repo_list::render_repository_list;
+use crate::tui::ui::repo_list::{render_repository_grid, render_repository_list};
+use crate::tui::ui::totals_bar::render_totals_bar;
 
 /// Render the TUI interface frame
 pub fn render_ui(f: &mut Frame, state: &AppState) {
     let snapshot = state.get_render_snapshot();
     let colors = &state.colors;
 
-    let (main_chunks, content_chunks) = create_layout(f, snapshot.is_detail_view);
-    if !snapshot.is_detail_view {
-        render_repository_list(f, &snapshot, &content_chunks, colors);
+    let show_header = state.config.ui.health_bar;
+    let show_footer = state.config.ui.totals_bar;
+    // The grid spans the full width, without details next to it
+    let grid = state.config.ui.list_layout == ListLayout::Grid && !snapshot.is_detail_view;
+    let (main_chunks, content_chunks) = create_layout(
+        f.area(),
+        snapshot.is_detail_view || grid,
+        show_header,
+        show_footer,
+    );
+    if show_header {
+        let rules = &state.config.ui.status_rules;
+        render_health_bar(f, &snapshot, main_chunks[0], colors, rules);
+    }
+    if grid {
+        let area = content_chunks[0];
+        match (&snapshot.branch_view, snapshot.empty_state()) {
+            (Some(view), _) => render_branch_list(f, view, area, colors, &state.config.ui),
+            (_, Some(empty)) => render_empty_state(f, empty, area, &state.config, colors),
+            _ => {
+                let stale_threshold = state.config.main.stale_threshold;
+                let columns = render_repository_grid(
+                    f,
+                    &snapshot,
+                    area,
+                    colors,
+                    &state.config.ui,
+                    stale_threshold,
+                );
+                state.grid_columns.store(columns, Ordering::Relaxed);
+            }
+        }
+    } else {
+        if !snapshot.is_detail_view {
+            let stale_threshold = state.config.main.stale_threshold;
+            render_repository_list(
+                f,
+                &snapshot,
+                &content_chunks,
+                colors,
+                &state.config.ui,
+                stale_threshold,
+            );
+        }
+        match (&snapshot.branch_view, snapshot.empty_state()) {
+            (Some(view), _) if !snapshot.is_detail_view => {
+                render_branch_list(f, view, content_chunks[1], colors, &state.config.ui);
+            }
+            (_, Some(empty)) if !snapshot.is_detail_view => {
+                render_empty_state(f, empty, content_chunks[1], &state.config, colors);
+            }
+            _ => {
+                render_repository_details(
+                    f,
+                    &snapshot,
+                    &content_chunks,
+                    colors,
+                    &state.config.ui.detail,
+                    state.config.ui.redact_remotes,
+                );
+            }
+        }
+    }
+    if let Some(output) = &snapshot.git_output {
+        render_git_output(f, output, main_chunks[1], colors);
+    }
+    if show_footer {
+        render_totals_bar(f, &snapshot, main_chunks[2], colors);
     }
-    render_repository_details(f, &snapshot, &content_chunks, colors);
     render_keyhint_bar(f, &snapshot, colors, &main_chunks);
 }
@@ -1,14 +1,24 @@
+use std::ops::Range;
+
 use ratatui::{
     Frame,
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::config::ColorScheme;
+use crate::config::{
+    ColorScheme, CompareAgainst, DetailConfig, DetailLayout, DetailSection, FileLayout,
+};
+use crate::core::author::initials_and_color;
+use crate::core::remote_url::display_remote_url;
 use crate::core::repo_info::{
-    FileChangeStatus, RepoBasicInfo, RepoCommitInfo, RepoFileChanges, RepoInfo, RepoRemoteInfo,
-    RepoStashInfo, RepoSyncStatus, RepoWorkingStatus,
+    FileChange, FileChangeStatus, RepoAnomalies, RepoBasicInfo, RepoCommitInfo, RepoFileChanges,
+    RepoInfo, RepoRemoteInfo, RepoStashInfo, RepoSyncStatus, RepoWorkingStatus,
+};
+use crate::core::sorting::{group_file_changes, sort_file_changes};
+use crate::core::text::{
+    display_width, format_relative_time, label_prefix_len, truncate_to_width, wrap_ranges,
 };
 use crate::tui::state::RenderSnapshot;
 
@@ -18,9 +28,30 @@ pub fn render_repository_details(
     snapshot: &RenderSnapshot,
     content_chunks: &[ratatui::layout::Rect],
     colors: &ColorScheme,
+    detail: &DetailConfig,
+    redact_remotes: bool,
 ) {
-    let detail_text = match snapshot.repos.get(snapshot.selected_index) {
-        Some(repo) => build_repo_detail_lines(repo, snapshot.is_detail_view, colors),
+    let sections = detail.sections.as_deref().unwrap_or(&DEFAULT_SECTIONS);
+    let detail_chunk_idx = if snapshot.is_detail_view { 0 } else { 1 };
+    let detail_chunk = content_chunks[detail_chunk_idx];
+    let inner_width = detail_chunk.width.saturating_sub(2) as usize; // exclude borders
+
+    let detail_text = match snapshot.selected_repo() {
+        Some(repo) => {
+            let lines = build_repo_detail_lines(
+                repo,
+                snapshot.is_detail_view,
+                inner_width,
+                colors,
+                sections,
+                redact_remotes,
+                detail.file_layout,
+            );
+            match detail.layout {
+                DetailLayout::Stacked => lines,
+                DetailLayout::Columns => column_lines(lines, inner_width),
+            }
+        }
         None => vec![
             Line::from(""),
             Line::from(Span::styled(
@@ -39,41 +70,169 @@ pub fn render_repository_details(
         .title(detail_title)
         .border_style(Style::default().fg(colors.border));
 
-    let detail_paragraph = Paragraph::new(detail_text).block(detail_block);
-    let detail_chunk_idx = if snapshot.is_detail_view { 0 } else { 1 };
-    f.render_widget(detail_paragraph, content_chunks[detail_chunk_idx]);
+    // The expanded detail view shows full text, so wrap instead of clipping
+    let mut detail_paragraph = Paragraph::new(detail_text).block(detail_block);
+    if snapshot.is_detail_view {
+        detail_paragraph = detail_paragraph.wrap(Wrap { trim: false });
+    }
+    f.render_widget(detail_paragraph, detail_chunk);
 }
 
+/// Sections of the detail panel unless configured otherwise
+const DEFAULT_SECTIONS: [DetailSection; 8] = [
+    DetailSection::Basic,
+    DetailSection::Sync,
+    DetailSection::Working,
+    DetailSection::Anomalies,
+    DetailSection::Stash,
+    DetailSection::Remote,
+    DetailSection::Commit,
+    DetailSection::Files,
+];
+
 /// Build detailed information lines for a repository in TUI
+///
+/// Long text is truncated to `width` columns unless in the expanded detail view.
+/// The file list is only shown in the expanded detail view.
+/// With `redact_remotes`, the host of the remote URL is masked.
+/// `file_layout` decides how the file list is ordered.
 fn build_repo_detail_lines<'a>(
     repo: &'a RepoInfo,
     is_detail_view: bool,
+    width: usize,
     colors: &'a ColorScheme,
+    sections: &[DetailSection],
+    redact_remotes: bool,
+    file_layout: FileLayout,
 ) -> Vec<Line<'a>> {
+    let max_width = if is_detail_view { usize::MAX } else { width };
     let mut lines = Vec::new();
 
-    lines.extend(repo.basic.render_lines(colors));
-    lines.extend(repo.sync.render_lines(colors));
-    lines.extend(repo.working.render_lines(colors));
-    lines.extend(repo.stash.render_lines(colors));
-    lines.extend(repo.remote.render_lines(colors));
-    lines.extend(repo.commit.render_lines(colors));
-
-    if is_detail_view {
-        lines.extend(repo.files.render_lines(colors));
+    for section in sections {
+        let component: &dyn RenderDetail = match section {
+            DetailSection::Basic => &repo.basic,
+            DetailSection::Sync => &repo.sync,
+            DetailSection::Working if repo.partial => {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    "Loading status...",
+                    Style::default().fg(colors.text_muted),
+                )));
+                continue;
+            }
+            DetailSection::Working => &repo.working,
+            DetailSection::Anomalies => match repo.anomalies {
+                Some(ref anomalies) => anomalies,
+                None => continue,
+            },
+            DetailSection::Stash => &repo.stash,
+            DetailSection::Remote => {
+                lines.extend(render_remote_lines(&repo.remote, colors, redact_remotes));
+                continue;
+            }
+            DetailSection::Commit => &repo.commit,
+            DetailSection::Files if !is_detail_view || repo.partial => continue,
+            DetailSection::Files => {
+                lines.extend(render_file_lines(&repo.files, colors, file_layout));
+                continue;
+            }
+        };
+        lines.extend(component.render_lines(colors, max_width));
     }
 
     lines
 }
 
+/// Align the labels of detail lines in a column and wrap their values to `width`
+///
+/// A line is labelled when its first span is a `Label: ` prefix. Other lines,
+/// such as section headers and file entries, are kept as they are.
+fn column_lines(lines: Vec<Line<'_>>, width: usize) -> Vec<Line<'_>> {
+    let label = |line: &Line| {
+        line.spans
+            .first()
+            .filter(|span| label_prefix_len(&span.content) == Some(span.content.len()))
+            .map(|span| display_width(&span.content))
+    };
+    let column = lines.iter().filter_map(label).max().unwrap_or(0);
+
+    let mut out = Vec::with_capacity(lines.len());
+    for line in lines {
+        let Some(label_width) = label(&line) else {
+            out.push(line);
+            continue;
+        };
+        let mut spans = line.spans.into_iter();
+        let label = spans.next().unwrap_or_default();
+        let value: Vec<Span> = spans.collect();
+        let text: String = value.iter().map(|span| span.content.as_ref()).collect();
+        for (i, range) in wrap_ranges(&text, width.saturating_sub(column))
+            .into_iter()
+            .enumerate()
+        {
+            let mut row = match i {
+                0 => vec![label.clone(), Span::raw(" ".repeat(column - label_width))],
+                _ => vec![Span::raw(" ".repeat(column))],
+            };
+            row.extend(slice_spans(&value, range));
+            out.push(Line::from(row));
+        }
+    }
+    out
+}
+
+/// Parts of `spans` covering the byte `range` of their joined text
+fn slice_spans<'a>(spans: &[Span<'a>], range: Range<usize>) -> Vec<Span<'a>> {
+    let mut out = Vec::new();
+    let mut offset = 0;
+    for span in spans {
+        let len = span.content.len();
+        let (start, end) = (range.start.max(offset), range.end.min(offset + len));
+        if start < end {
+            out.push(Span::styled(
+                span.content[start - offset..end - offset].to_string(),
+                span.style,
+            ));
+        }
+        offset += len;
+    }
+    out
+}
+
 /// Trait for rendering detail sections in TUI
 trait RenderDetail {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>>;
+    /// Render lines for this section, fitting long text within `max_width` columns
+    fn render_lines(&self, colors: &ColorScheme, max_width: usize) -> Vec<Line<'_>>;
 }
 
 impl RenderDetail for RepoBasicInfo {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
-        vec![
+    fn render_lines(&self, colors: &ColorScheme, max_width: usize) -> Vec<Line<'_>> {
+        let branch_label = "Branch: ";
+        let mut branch_spans = vec![
+            Span::styled(branch_label, Style::default().fg(colors.text_secondary)),
+            Span::styled(
+                truncate_to_width(&self.branch, max_width.saturating_sub(branch_label.len())),
+                Style::default().fg(colors.branch_name),
+            ),
+        ];
+        if self.is_unborn {
+            branch_spans.push(Span::styled(
+                " (empty, no commits)",
+                Style::default().fg(colors.text_muted),
+            ));
+        } else if self.is_detached {
+            branch_spans.push(Span::styled(
+                " (detached)",
+                Style::default().fg(colors.commit_behind),
+            ));
+        }
+        if let Some(ref main) = self.worktree_of {
+            branch_spans.push(Span::styled(
+                format!(" (worktree of {})", main),
+                Style::default().fg(colors.text_muted),
+            ));
+        }
+        let mut lines = vec![
             Line::from(vec![Span::styled(
                 self.name.clone(),
                 Style::default()
@@ -85,60 +244,152 @@ impl RenderDetail for RepoBasicInfo {
                 Style::default().fg(colors.text_muted),
             )]),
             Line::from(""),
-            Line::from(vec![
-                Span::styled("Branch: ", Style::default().fg(colors.text_secondary)),
-                Span::styled(self.branch.clone(), Style::default().fg(colors.branch_name)),
-            ]),
-        ]
+            Line::from(branch_spans),
+        ];
+        if let Some(ref superproject) = self.superproject {
+            lines.push(Line::from(vec![
+                Span::styled("Submodule of: ", Style::default().fg(colors.text_secondary)),
+                Span::styled(
+                    superproject.display().to_string(),
+                    Style::default().fg(colors.text_muted),
+                ),
+            ]));
+        }
+        if !self.tags.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("Tags: ", Style::default().fg(colors.text_secondary)),
+                Span::styled(
+                    self.tags.join(", "),
+                    Style::default().fg(colors.section_stash),
+                ),
+            ]));
+        }
+        lines
     }
 }
 
 impl RenderDetail for RepoSyncStatus {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
-        if self.ahead == 0 && self.behind == 0 {
-            return vec![];
+    fn render_lines(&self, colors: &ColorScheme, _max_width: usize) -> Vec<Line<'_>> {
+        let mut lines = vec![sync_line(self, colors)];
+        if self.default_branch_changed {
+            lines.push(Line::from(vec![
+                Span::styled("[!] ", Style::default().fg(colors.commit_behind)),
+                Span::styled(
+                    "Default branch changed upstream (see origin/HEAD)",
+                    Style::default().fg(colors.commit_behind),
+                ),
+            ]));
         }
+        if let Some(count) = self.unpushed_tags.filter(|&n| n > 0) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Unpushed tags: ",
+                    Style::default().fg(colors.text_secondary),
+                ),
+                Span::styled(
+                    count.to_string(),
+                    Style::default()
+                        .fg(colors.commit_ahead)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        if let Some(count) = self.unmerged_branches.filter(|&n| n > 0) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Unmerged branches: ",
+                    Style::default().fg(colors.text_secondary),
+                ),
+                Span::styled(
+                    count.to_string(),
+                    Style::default()
+                        .fg(colors.status_dirty)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        lines
+    }
+}
 
-        let mut sync_spans = vec![Span::styled(
-            "Sync: ",
-            Style::default().fg(colors.text_secondary),
-        )];
-
-        if self.ahead > 0 && self.behind > 0 {
-            sync_spans.push(Span::styled(
-                format!("↑{} ", self.ahead),
-                Style::default()
-                    .fg(colors.commit_ahead)
-                    .add_modifier(Modifier::BOLD),
-            ));
-            sync_spans.push(Span::styled(
-                format!("↓{}", self.behind),
-                Style::default()
-                    .fg(colors.commit_behind)
-                    .add_modifier(Modifier::BOLD),
-            ));
-        } else if self.ahead > 0 {
-            sync_spans.push(Span::styled(
-                format!("↑{} ahead", self.ahead),
-                Style::default()
-                    .fg(colors.commit_ahead)
-                    .add_modifier(Modifier::BOLD),
-            ));
-        } else {
-            sync_spans.push(Span::styled(
-                format!("↓{} behind", self.behind),
+/// Line showing the ahead/behind state of a repository
+fn sync_line<'a>(sync: &'a RepoSyncStatus, colors: &ColorScheme) -> Line<'a> {
+    if sync.upstream_gone {
+        return Line::from(vec![
+            Span::styled("Sync: ", Style::default().fg(colors.text_secondary)),
+            Span::styled(
+                "upstream gone (deleted on remote?)",
                 Style::default()
                     .fg(colors.commit_behind)
                     .add_modifier(Modifier::BOLD),
-            ));
-        }
+            ),
+        ]);
+    }
 
-        vec![Line::from(sync_spans)]
+    if !sync.has_upstream {
+        let text = match sync.compare_against {
+            CompareAgainst::Upstream => "no upstream",
+            CompareAgainst::DefaultBranch => "no default branch",
+        };
+        return Line::from(vec![
+            Span::styled("Sync: ", Style::default().fg(colors.text_secondary)),
+            Span::styled(text, Style::default().fg(colors.text_muted)),
+        ]);
+    }
+
+    let compare_span = Span::styled(
+        format!(" (vs {})", sync.compare_ref.as_deref().unwrap_or("?")),
+        Style::default().fg(colors.text_muted),
+    );
+
+    if sync.ahead == 0 && sync.behind == 0 {
+        return Line::from(vec![
+            Span::styled("Sync: ", Style::default().fg(colors.text_secondary)),
+            Span::styled("in sync", Style::default().fg(colors.status_sync)),
+            compare_span,
+        ]);
     }
+
+    let mut sync_spans = vec![Span::styled(
+        "Sync: ",
+        Style::default().fg(colors.text_secondary),
+    )];
+
+    if sync.ahead > 0 && sync.behind > 0 {
+        sync_spans.push(Span::styled(
+            format!("↑{} ", sync.ahead),
+            Style::default()
+                .fg(colors.commit_ahead)
+                .add_modifier(Modifier::BOLD),
+        ));
+        sync_spans.push(Span::styled(
+            format!("↓{}", sync.behind),
+            Style::default()
+                .fg(colors.commit_behind)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else if sync.ahead > 0 {
+        sync_spans.push(Span::styled(
+            format!("↑{} ahead", sync.ahead),
+            Style::default()
+                .fg(colors.commit_ahead)
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else {
+        sync_spans.push(Span::styled(
+            format!("↓{} behind", sync.behind),
+            Style::default()
+                .fg(colors.commit_behind)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    sync_spans.push(compare_span);
+
+    Line::from(sync_spans)
 }
 
 impl RenderDetail for RepoWorkingStatus {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
+    fn render_lines(&self, colors: &ColorScheme, _max_width: usize) -> Vec<Line<'_>> {
         let mut lines = vec![Line::from("")];
 
         let (prefix, status_text, color) = if self.conflicts > 0 {
@@ -178,6 +429,16 @@ impl RenderDetail for RepoWorkingStatus {
                 ),
             ]));
         }
+        if let Some(hunks) = self.unstaged_hunks.filter(|&n| n > 0) {
+            lines.push(Line::from(vec![
+                Span::raw("   "),
+                Span::styled("● ", Style::default().fg(colors.status_dirty)),
+                Span::styled(
+                    format!("{} unstaged hunks", hunks),
+                    Style::default().fg(colors.status_dirty),
+                ),
+            ]));
+        }
         if self.untracked > 0 {
             lines.push(Line::from(vec![
                 Span::raw("   "),
@@ -193,20 +454,99 @@ impl RenderDetail for RepoWorkingStatus {
                 Span::raw("   "),
                 Span::styled("● ", Style::default().fg(colors.status_conflict)),
                 Span::styled(
-                    format!("{} conflicts", self.conflicts),
+                    match self.conflict_hunks {
+                        Some(hunks) => format!("{} conflicts ({} hunks)", self.conflicts, hunks),
+                        None => format!("{} conflicts", self.conflicts),
+                    },
                     Style::default()
                         .fg(colors.status_conflict)
                         .add_modifier(Modifier::BOLD),
                 ),
             ]));
         }
+        if let Some(time) = self.workdir_mtime {
+            lines.push(Line::from(vec![
+                Span::styled("Last edit: ", Style::default().fg(colors.text_secondary)),
+                Span::styled(
+                    format_relative_time(time),
+                    Style::default().fg(colors.text_primary),
+                ),
+            ]));
+        }
+        if self.locked {
+            lines.push(Line::from(vec![
+                Span::styled("[!] ", Style::default().fg(colors.status_conflict)),
+                Span::styled(
+                    "INDEX LOCKED",
+                    Style::default()
+                        .fg(colors.status_conflict)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    " (index.lock exists)",
+                    Style::default().fg(colors.text_secondary),
+                ),
+            ]));
+        }
+        if let Some(count) = self.lfs_locks.filter(|&n| n > 0) {
+            lines.push(Line::from(vec![
+                Span::styled("LFS locks: ", Style::default().fg(colors.text_secondary)),
+                Span::styled(count.to_string(), Style::default().fg(colors.status_dirty)),
+            ]));
+        }
+        if let Some(count) = self.assume_unchanged.filter(|&n| n > 0) {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Hidden from status: ",
+                    Style::default().fg(colors.text_secondary),
+                ),
+                Span::styled(count.to_string(), Style::default().fg(colors.status_dirty)),
+                Span::styled(
+                    " (assume-unchanged or skip-worktree)",
+                    Style::default().fg(colors.text_secondary),
+                ),
+            ]));
+        }
+        if let Some(operation) = self.operation {
+            lines.push(Line::from(vec![
+                Span::styled("[!] ", Style::default().fg(colors.status_dirty)),
+                Span::styled(
+                    format!("{} IN PROGRESS", operation.label().to_uppercase()),
+                    Style::default()
+                        .fg(colors.status_dirty)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
 
         lines
     }
 }
 
+impl RenderDetail for RepoAnomalies {
+    fn render_lines(&self, colors: &ColorScheme, _max_width: usize) -> Vec<Line<'_>> {
+        if !self.has_any() {
+            return vec![];
+        }
+
+        vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Anomalies: ", Style::default().fg(colors.text_secondary)),
+                Span::styled(
+                    format!(
+                        "{} ignored present, {} tracked but ignored",
+                        self.ignored, self.tracked_ignored
+                    ),
+                    Style::default().fg(colors.status_dirty),
+                ),
+            ]),
+        ]
+    }
+}
+
 impl RenderDetail for RepoStashInfo {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
+    fn render_lines(&self, colors: &ColorScheme, _max_width: usize) -> Vec<Line<'_>> {
         if self.count == 0 {
             return vec![];
         }
@@ -224,28 +564,64 @@ impl RenderDetail for RepoStashInfo {
     }
 }
 
-impl RenderDetail for RepoRemoteInfo {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
-        let Some(ref url) = self.url else {
+/// Render the remote section, masking the host of the URL if `redact` is set
+fn render_remote_lines(
+    remote: &RepoRemoteInfo,
+    colors: &ColorScheme,
+    redact: bool,
+) -> Vec<Line<'static>> {
+    let Some(ref url) = remote.url else {
+        if remote.count > 0 {
             return vec![];
-        };
-
-        vec![
+        }
+        return vec![
             Line::from(""),
-            Line::from(vec![Span::styled(
-                "Remote:",
-                Style::default().fg(colors.section_remote),
-            )]),
             Line::from(vec![
-                Span::raw("  "),
-                Span::styled(url.clone(), Style::default().fg(colors.text_secondary)),
+                Span::styled("Remote: ", Style::default().fg(colors.section_remote)),
+                Span::styled(
+                    "none (local only)",
+                    Style::default().fg(colors.status_dirty),
+                ),
             ]),
-        ]
+        ];
+    };
+
+    let mut url_spans = vec![
+        Span::raw("  "),
+        Span::styled(
+            display_remote_url(url, redact).into_owned(),
+            Style::default().fg(colors.text_secondary),
+        ),
+    ];
+    if remote.count > 1 {
+        url_spans.push(Span::styled(
+            format!(" (+{} more)", remote.count - 1),
+            Style::default().fg(colors.text_muted),
+        ));
     }
+
+    vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Remote:",
+            Style::default().fg(colors.section_remote),
+        )]),
+        Line::from(url_spans),
+        Line::from(vec![
+            Span::raw("  last fetch: "),
+            Span::styled(
+                remote
+                    .last_fetch
+                    .map(format_relative_time)
+                    .unwrap_or_else(|| "never".to_string()),
+                Style::default().fg(colors.text_muted),
+            ),
+        ]),
+    ]
 }
 
 impl RenderDetail for RepoCommitInfo {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
+    fn render_lines(&self, colors: &ColorScheme, max_width: usize) -> Vec<Line<'_>> {
         let Some(ref message) = self.message else {
             return vec![];
         };
@@ -258,14 +634,42 @@ impl RenderDetail for RepoCommitInfo {
             )]),
             Line::from(vec![
                 Span::raw("  "),
-                Span::styled(message.clone(), Style::default().fg(colors.text_primary)),
+                Span::styled(
+                    truncate_to_width(message, max_width.saturating_sub(2)),
+                    Style::default().fg(colors.text_primary),
+                ),
             ]),
         ];
 
         if let Some(ref author) = self.author {
-            lines.push(Line::from(vec![
+            let (initials, initials_color) =
+                initials_and_color(author, self.author_email.as_deref());
+            let mut spans = vec![
                 Span::raw("  by "),
+                Span::styled(
+                    initials,
+                    Style::default()
+                        .fg(initials_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" "),
                 Span::styled(author.clone(), Style::default().fg(colors.text_secondary)),
+            ];
+            if let Some(time) = self.time {
+                spans.push(Span::styled(
+                    format!(", {}", format_relative_time(time)),
+                    Style::default().fg(colors.text_muted),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        if let Some(count) = self.contributors {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "  Contributors: ",
+                    Style::default().fg(colors.text_secondary),
+                ),
+                Span::styled(count.to_string(), Style::default().fg(colors.text_primary)),
             ]));
         }
 
@@ -273,41 +677,219 @@ impl RenderDetail for RepoCommitInfo {
     }
 }
 
-impl RenderDetail for RepoFileChanges {
-    fn render_lines(&self, colors: &ColorScheme) -> Vec<Line<'_>> {
-        if self.changes.is_empty() {
-            return vec![];
+/// Render the file list, sorted by category or grouped under category headers
+fn render_file_lines<'a>(
+    files: &'a RepoFileChanges,
+    colors: &ColorScheme,
+    layout: FileLayout,
+) -> Vec<Line<'a>> {
+    if files.changes.is_empty() {
+        return vec![];
+    }
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "File Changes:",
+            Style::default()
+                .fg(colors.status_dirty)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(""),
+    ];
+
+    match layout {
+        FileLayout::Sorted => {
+            for change in sort_file_changes(&files.changes) {
+                lines.push(file_change_line(change, "  ", colors));
+            }
         }
+        FileLayout::Grouped => {
+            for (status, group) in group_file_changes(&files.changes) {
+                lines.push(Line::from(Span::styled(
+                    format!("  {} ({}):", status.label(), group.len()),
+                    Style::default().fg(colors.text_secondary),
+                )));
+                for change in group {
+                    lines.push(file_change_line(change, "    ", colors));
+                }
+            }
+        }
+    }
 
-        let mut lines = vec![
-            Line::from(""),
-            Line::from(vec![Span::styled(
-                "File Changes:",
-                Style::default()
-                    .fg(colors.status_dirty)
-                    .add_modifier(Modifier::BOLD),
-            )]),
-            Line::from(""),
-        ];
+    lines
+}
 
-        for change in &self.changes {
-            let (symbol, color) = match change.status {
-                FileChangeStatus::Staged => ("● ", colors.status_clean),
-                FileChangeStatus::Modified => ("● ", colors.status_dirty),
-                FileChangeStatus::Untracked => ("● ", colors.status_sync),
-                FileChangeStatus::Conflicted => ("● ", colors.status_conflict),
-            };
+/// Render one file change with its status symbol and conflict hunk count
+fn file_change_line<'a>(change: &'a FileChange, indent: &'a str, colors: &ColorScheme) -> Line<'a> {
+    let color = match change.status {
+        FileChangeStatus::Staged => colors.status_clean,
+        FileChangeStatus::Modified => colors.status_dirty,
+        FileChangeStatus::Untracked => colors.status_sync,
+        FileChangeStatus::Conflicted => colors.status_conflict,
+    };
 
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(symbol, Style::default().fg(color)),
-                Span::styled(
-                    change.path.clone(),
-                    Style::default().fg(colors.text_primary),
-                ),
-            ]));
-        }
+    let mut spans = vec![
+        Span::raw(indent),
+        Span::styled("● ", Style::default().fg(color)),
+        Span::styled(
+            change.path.as_str(),
+            Style::default().fg(colors.text_primary),
+        ),
+    ];
+    if let Some(hunks) = change.conflict_hunks {
+        spans.push(Span::styled(
+            format!(" ({} hunks)", hunks),
+            Style::default().fg(colors.status_conflict),
+        ));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    fn line_texts(lines: &[Line]) -> Vec<String> {
         lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_sections_in_order() {
+        let colors = ColorScheme::default();
+        let mut repo = RepoInfo::stub("sample", "/tmp/sample");
+        repo.stash.count = 2;
+        repo.commit.message = Some("Fix parser".to_string());
+        repo.basic.tags = vec!["work".to_string(), "rust".to_string()];
+
+        let sections = [DetailSection::Commit, DetailSection::Basic];
+        let texts = line_texts(&build_repo_detail_lines(
+            &repo,
+            true,
+            80,
+            &colors,
+            &sections,
+            false,
+            FileLayout::Sorted,
+        ));
+
+        let commit = texts.iter().position(|t| t == "Last Commit:").unwrap();
+        let branch = texts
+            .iter()
+            .position(|t| t.starts_with("Branch: "))
+            .unwrap();
+        assert!(commit < branch);
+        assert!(texts.contains(&"Tags: work, rust".to_string()));
+        assert!(!texts.iter().any(|t| t.contains("Stash")));
+
+        // The default layout includes the stash section
+        let texts = line_texts(&build_repo_detail_lines(
+            &repo,
+            true,
+            80,
+            &colors,
+            &DEFAULT_SECTIONS,
+            false,
+            FileLayout::Sorted,
+        ));
+        assert!(texts.iter().any(|t| t.contains("Stash")));
+    }
+
+    #[test]
+    fn test_redacted_remote() {
+        let colors = ColorScheme::default();
+        let mut repo = RepoInfo::stub("sample", "/tmp/sample");
+        repo.remote.url = Some("https://git.internal.corp/owner/repo.git".to_string());
+        repo.remote.count = 1;
+
+        let sections = [DetailSection::Remote];
+        let texts = line_texts(&build_repo_detail_lines(
+            &repo,
+            true,
+            80,
+            &colors,
+            &sections,
+            true,
+            FileLayout::Sorted,
+        ));
+        assert!(texts.contains(&"  https://*****/owner/repo.git".to_string()));
+        assert!(!texts.iter().any(|t| t.contains("internal")));
+    }
+
+    #[test]
+    fn test_column_lines() {
+        let lines = vec![
+            Line::from(vec![Span::raw("Branch: "), Span::raw("main")]),
+            Line::from(vec![
+                Span::raw("Tags: "),
+                Span::raw("work, "),
+                Span::raw("rust, infra"),
+            ]),
+            Line::from("Last Commit:"),
+        ];
+        let texts = line_texts(&column_lines(lines, 20));
+        assert_eq!(
+            texts,
+            [
+                "Branch: main",
+                "Tags:   work, rust,",
+                "        infra",
+                "Last Commit:",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_layouts() {
+        let colors = ColorScheme::default();
+        let mut repo = RepoInfo::stub("sample", "/tmp/sample");
+        for (path, status, hunks) in [
+            ("z.txt", FileChangeStatus::Untracked, None),
+            ("b.rs", FileChangeStatus::Modified, None),
+            ("a.rs", FileChangeStatus::Modified, None),
+            ("lib.rs", FileChangeStatus::Conflicted, Some(2)),
+            ("Cargo.toml", FileChangeStatus::Staged, None),
+        ] {
+            repo.files.changes.push(FileChange {
+                path: path.to_string(),
+                status,
+                conflict_hunks: hunks,
+            });
+        }
+
+        let texts = line_texts(&render_file_lines(&repo.files, &colors, FileLayout::Sorted));
+        assert_eq!(
+            texts[3..],
+            [
+                "  ● lib.rs (2 hunks)",
+                "  ● Cargo.toml",
+                "  ● a.rs",
+                "  ● b.rs",
+                "  ● z.txt",
+            ]
+        );
+
+        let texts = line_texts(&render_file_lines(
+            &repo.files,
+            &colors,
+            FileLayout::Grouped,
+        ));
+        assert_eq!(
+            texts[3..],
+            [
+                "  Conflicted (1):",
+                "    ● lib.rs (2 hunks)",
+                "  Staged (1):",
+                "    ● Cargo.toml",
+                "  Modified (2):",
+                "    ● a.rs",
+                "    ● b.rs",
+                "  Untracked (1):",
+                "    ● z.txt",
+            ]
+        );
     }
 }
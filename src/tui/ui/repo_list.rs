@@ -1,71 +1,456 @@
 use ratatui::{
     Frame,
-    style::{Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
 };
+use std::time::{Duration, SystemTime};
 
-use crate::config::ColorScheme;
+use crate::config::{ColorBy, ColorScheme, ColorSupport, HighlightMode, UIConfig};
 use crate::core::RepoInfo;
+use crate::core::grid::{CELL_GAP, GridLayout};
+use crate::core::grouping::GroupBy;
+use crate::core::heatmap::{DEFAULT_HORIZON, commit_time_color};
+use crate::core::status::{StatusColor, overall_status};
+use crate::core::text::{display_width, truncate_to_width};
+use crate::tui::groups::{Cursor, ListRow, cursor_position, display_order, list_rows};
 use crate::tui::state::RenderSnapshot;
 
+/// Indentation of repositories under a group header
+const GROUP_INDENT: &str = "  ";
+
+/// How repository names are colored, shared by all rows of a frame
+struct NameColoring {
+    stale_threshold: Option<Duration>,
+    /// Age at which names are fully red, when colored by commit age
+    heatmap: Option<Duration>,
+    support: ColorSupport,
+    now: SystemTime,
+}
+
+impl NameColoring {
+    fn new(ui: &UIConfig, stale_threshold: Option<Duration>) -> Self {
+        Self {
+            stale_threshold,
+            heatmap: (ui.color_by == ColorBy::Age)
+                .then(|| stale_threshold.unwrap_or(DEFAULT_HORIZON)),
+            support: ui.color_support.resolve(),
+            now: SystemTime::now(),
+        }
+    }
+
+    fn is_stale(&self, repo: &RepoInfo) -> bool {
+        self.stale_threshold
+            .is_some_and(|threshold| repo.is_stale(threshold, self.now))
+    }
+}
+
 /// Render the repository list on the left side
 pub fn render_repository_list(
     f: &mut Frame,
     snapshot: &RenderSnapshot,
     content_chunks: &[ratatui::layout::Rect],
     colors: &ColorScheme,
+    ui: &UIConfig,
+    stale_threshold: Option<Duration>,
 ) {
-    let items: Vec<ListItem> = snapshot
-        .repos
+    let highlight_symbol = ui.highlight_symbol();
+    let list_chunk = content_chunks[0];
+    let item_width = (list_chunk.width.saturating_sub(2) as usize) // exclude borders
+        .saturating_sub(display_width(highlight_symbol));
+
+    let coloring = NameColoring::new(ui, stale_threshold);
+    let rows = list_rows(
+        &snapshot.repos,
+        ui.group_by,
+        &snapshot.collapsed_groups,
+        &snapshot.hidden,
+    );
+    let indent = match ui.group_by {
+        GroupBy::None => "",
+        _ => GROUP_INDENT,
+    };
+    let cursor = snapshot.cursor();
+    let items: Vec<ListItem> = rows
         .iter()
-        .enumerate()
-        .map(|(idx, repo)| create_repo_list_item(repo, idx, snapshot.selected_index, colors))
+        .map(|row| match row {
+            ListRow::Group {
+                key,
+                count,
+                expanded,
+            } => {
+                let selected = matches!(&cursor, Cursor::Group(k) if k == key);
+                create_group_list_item(key, *count, *expanded, selected, item_width, colors)
+            }
+            ListRow::Repo(idx) => {
+                let repo = &snapshot.repos[*idx];
+                let selected = cursor == Cursor::Repo(*idx);
+                create_repo_list_item(repo, selected, &coloring, indent, item_width, colors, ui)
+            }
+        })
         .collect();
 
     let list_block = Block::default()
         .borders(Borders::ALL)
-        .title(format!("Repos ({} found)", snapshot.repos.len()))
+        .title(snapshot.list_title())
         .border_style(Style::default().fg(colors.border));
 
-    let list = List::new(items)
-        .block(list_block)
-        .highlight_style(Style::default().bg(colors.highlight_bg))
-        .highlight_symbol("▶ ");
+    let list = selectable_list(items, list_block, colors, ui);
 
     let mut list_state = ListState::default();
-    if !snapshot.repos.is_empty() {
-        list_state.select(Some(snapshot.selected_index));
-    }
+    list_state.select(cursor_position(&rows, &cursor));
 
-    f.render_stateful_widget(list, content_chunks[0], &mut list_state);
+    f.render_stateful_widget(list, list_chunk, &mut list_state);
+}
+
+/// Render the repositories as a dense grid of names over the whole content area
+///
+/// Group headers are left out, and rows scroll to keep the selected repository
+/// visible. Returns the number of cells per row, which moving up and down in
+/// the grid depends on.
+pub fn render_repository_grid(
+    f: &mut Frame,
+    snapshot: &RenderSnapshot,
+    area: Rect,
+    colors: &ColorScheme,
+    ui: &UIConfig,
+    stale_threshold: Option<Duration>,
+) -> usize {
+    let coloring = NameColoring::new(ui, stale_threshold);
+    let order = display_order(&snapshot.repos, ui.group_by, &snapshot.hidden);
+    let cells: Vec<(String, Style)> = order
+        .iter()
+        .map(|&idx| repo_label_and_style(&snapshot.repos[idx], &coloring, "", colors, ui))
+        .collect();
+    let selected = order
+        .iter()
+        .position(|&idx| idx == snapshot.selected_index)
+        .unwrap_or(0);
+
+    let width = area.width.saturating_sub(2) as usize; // exclude borders
+    let height = area.height.saturating_sub(2) as usize;
+    let layout = GridLayout::fit(cells.iter().map(|(label, _)| display_width(label)), width);
+    let first_row = (selected / layout.columns + 1).saturating_sub(height);
+    let lines: Vec<Line> = grid_lines(&cells, layout, selected, colors, ui)
+        .into_iter()
+        .skip(first_row)
+        .take(height)
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(snapshot.list_title())
+        .border_style(Style::default().fg(colors.border));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+    layout.columns
+}
+
+/// Lines of the grid, with the cell at position `selected` highlighted
+fn grid_lines<'a>(
+    cells: &[(String, Style)],
+    layout: GridLayout,
+    selected: usize,
+    colors: &ColorScheme,
+    ui: &UIConfig,
+) -> Vec<Line<'a>> {
+    let gap = " ".repeat(CELL_GAP);
+    cells
+        .chunks(layout.columns)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let mut spans = Vec::new();
+            for (column, (label, style)) in chunk.iter().enumerate() {
+                let position = row * layout.columns + column;
+                let mut text = truncate_to_width(label, layout.cell_width);
+                text.push_str(&" ".repeat(layout.cell_width - display_width(&text)));
+                let style = match position == selected {
+                    true => style
+                        .add_modifier(Modifier::BOLD)
+                        .patch(selected_row_style(colors, ui.highlight)),
+                    false => *style,
+                };
+                if column > 0 {
+                    spans.push(Span::raw(gap.clone()));
+                }
+                spans.push(Span::styled(text, style));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Create the header of a group, marked as expanded or collapsed
+fn create_group_list_item<'a>(
+    key: &str,
+    count: usize,
+    expanded: bool,
+    selected: bool,
+    max_width: usize,
+    colors: &ColorScheme,
+) -> ListItem<'a> {
+    let marker = if expanded { "▾" } else { "▸" };
+    let label = format!("{} {} ({})", marker, key, count);
+    let style = row_style(colors.text_secondary, selected);
+    ListItem::new(truncate_to_width(&label, max_width)).style(style)
 }
 
 /// Create a single list item for a repository
+///
+/// Stale repositories without other notable status are shown muted, and cached
+/// ones are dimmed until refreshed. The name is preceded by the prefix
+/// configured for the repository's status.
 fn create_repo_list_item<'a>(
     repo: &'a RepoInfo,
-    idx: usize,
-    current_selected: usize,
+    selected: bool,
+    coloring: &NameColoring,
+    indent: &str,
+    max_width: usize,
     colors: &'a ColorScheme,
+    ui: &UIConfig,
 ) -> ListItem<'a> {
+    let (label, style) = repo_label_and_style(repo, coloring, indent, colors, ui);
+    let style = match selected {
+        true => style.add_modifier(Modifier::BOLD),
+        false => style,
+    };
+    ListItem::new(truncate_to_width(&label, max_width)).style(style)
+}
+
+/// Label of a repository, with the prefix of its status, and its style colored
+/// by status or, with `color_by = "age"`, by the age of its last commit
+fn repo_label_and_style(
+    repo: &RepoInfo,
+    coloring: &NameColoring,
+    indent: &str,
+    colors: &ColorScheme,
+    ui: &UIConfig,
+) -> (String, Style) {
     // Determine repo name color based on repo status
-    let color = if repo.working.conflicts > 0 {
-        colors.status_conflict
-    } else if repo.working.is_dirty {
-        colors.status_dirty
-    } else if repo.sync.ahead > 0 || repo.sync.behind > 0 {
-        colors.status_sync
-    } else {
-        colors.status_clean
+    let status = overall_status(repo, &ui.status_rules);
+    let label = format!(
+        "{}{}",
+        indent,
+        ui.status_prefixes.apply(&status.label, &repo.basic.name)
+    );
+    let status = match status.color {
+        StatusColor::Clean if coloring.is_stale(repo) => StatusColor::Muted,
+        status => status,
+    };
+    let color = match coloring.heatmap {
+        // Repos without commits have no age and are muted
+        Some(horizon) => commit_time_color(repo.commit.time, horizon)
+            .map_or(colors.text_muted, |color| coloring.support.apply(color)),
+        None => match status {
+            StatusColor::Conflict => colors.status_conflict,
+            StatusColor::Dirty => colors.status_dirty,
+            StatusColor::Sync => colors.status_sync,
+            StatusColor::Gone => colors.commit_behind,
+            StatusColor::Muted => colors.text_muted,
+            StatusColor::Clean => colors.status_clean,
+        },
     };
 
-    // Apply bold modifier if selected
-    let style = if idx == current_selected {
-        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    // Cached results are dimmed until the scan refreshes them
+    let mut style = row_style(color, false);
+    if repo.cached {
+        style = style.add_modifier(Modifier::DIM);
+    }
+    (label, style)
+}
+
+/// Style of a row's content, colored by repository status and bold if selected
+fn row_style(status_color: Color, selected: bool) -> Style {
+    let style = Style::default().fg(status_color);
+    if selected {
+        style.add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(color)
-    };
+        style
+    }
+}
+
+/// List widget highlighting its selected row as configured
+pub(super) fn selectable_list<'a>(
+    items: Vec<ListItem<'a>>,
+    block: Block<'a>,
+    colors: &ColorScheme,
+    ui: &'a UIConfig,
+) -> List<'a> {
+    List::new(items)
+        .block(block)
+        .highlight_style(selected_row_style(colors, ui.highlight))
+        .highlight_symbol(ui.highlight_symbol())
+}
+
+/// Style patched over the full selected row, on top of its content style
+pub(super) fn selected_row_style(colors: &ColorScheme, highlight: HighlightMode) -> Style {
+    match highlight {
+        HighlightMode::Background => Style::default().bg(colors.highlight_bg),
+        HighlightMode::Reverse => Style::default().add_modifier(Modifier::REVERSED),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+    /// Text of a list item rendered on one line
+    fn rendered_text(item: ListItem, width: u16) -> String {
+        let area = Rect::new(0, 0, width, 1);
+        let mut buffer = Buffer::empty(area);
+        List::new([item]).render(area, &mut buffer);
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_status_prefix_before_name() {
+        let colors = ColorScheme::default();
+        let ui = UIConfig {
+            status_prefixes: toml::from_str("conflict = \"!! \"").unwrap(),
+            ..Default::default()
+        };
+        let coloring = NameColoring::new(&ui, None);
+        let mut repo = RepoInfo::stub("app", "/work/app");
+        repo.working.conflicts = 1;
+
+        let item = create_repo_list_item(&repo, false, &coloring, "", 10, &colors, &ui);
+        assert_eq!(rendered_text(item, 10), "!! app    ");
+
+        // Other statuses keep the bare name
+        repo.working.conflicts = 0;
+        let item = create_repo_list_item(&repo, false, &coloring, "", 10, &colors, &ui);
+        assert_eq!(rendered_text(item, 10), "app       ");
+    }
 
-    let repo_name = repo.basic.name.clone();
+    #[test]
+    fn test_grouped_rows() {
+        let colors = ColorScheme::default();
+        let ui = UIConfig::default();
+        let repo = RepoInfo::stub("app", "/work/app");
+        let coloring = NameColoring::new(&ui, None);
 
-    ListItem::new(repo_name).style(style)
+        let item = create_group_list_item("/work", 2, true, false, 12, &colors);
+        assert_eq!(rendered_text(item, 12), "▾ /work (2) ");
+        let item = create_group_list_item("/work", 2, false, false, 12, &colors);
+        assert_eq!(rendered_text(item, 12), "▸ /work (2) ");
+        let item = create_repo_list_item(&repo, false, &coloring, GROUP_INDENT, 8, &colors, &ui);
+        assert_eq!(rendered_text(item, 8), "  app   ");
+    }
+
+    #[test]
+    fn test_heatmap_name_color() {
+        let colors = ColorScheme::default();
+        let ui = UIConfig {
+            color_by: ColorBy::Age,
+            color_support: ColorSupport::TrueColor,
+            ..Default::default()
+        };
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut repo = RepoInfo::stub("app", "/work/app");
+        repo.working.conflicts = 1;
+
+        // Names are colored by commit age over the stale threshold, not by status
+        let coloring = NameColoring::new(&ui, Some(10 * day));
+        repo.commit.time = Some(SystemTime::now());
+        let (_, style) = repo_label_and_style(&repo, &coloring, "", &colors, &ui);
+        assert_eq!(style.fg, Some(Color::Rgb(0, 255, 0)));
+        repo.commit.time = Some(SystemTime::now() - 20 * day);
+        let (_, style) = repo_label_and_style(&repo, &coloring, "", &colors, &ui);
+        assert_eq!(style.fg, Some(Color::Rgb(255, 0, 0)));
+        repo.commit.time = None;
+        let (_, style) = repo_label_and_style(&repo, &coloring, "", &colors, &ui);
+        assert_eq!(style.fg, Some(colors.text_muted));
+    }
+
+    #[test]
+    fn test_grid_lines() {
+        let colors = ColorScheme::default();
+        let ui = UIConfig::default();
+        let cells: Vec<(String, Style)> = ["api", "web", "billing", "docs"]
+            .iter()
+            .map(|name| (name.to_string(), Style::default()))
+            .collect();
+        let layout = GridLayout {
+            columns: 3,
+            cell_width: 5,
+        };
+
+        let lines = grid_lines(&cells, layout, 3, &colors, &ui);
+        let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+        assert_eq!(text, ["api    web    bill…", "docs "]);
+        assert_eq!(lines[1].spans[0].style.bg, Some(colors.highlight_bg));
+        assert_eq!(lines[0].spans[0].style.bg, None);
+    }
+
+    #[test]
+    fn test_selection_symbol() {
+        let colors = ColorScheme::default();
+        let render_selected = |ui: &UIConfig| {
+            let area = Rect::new(0, 0, 8, 1);
+            let mut buffer = Buffer::empty(area);
+            let list = selectable_list(vec![ListItem::new("app")], Block::default(), &colors, ui);
+            let mut state = ListState::default().with_selected(Some(0));
+            ratatui::widgets::StatefulWidget::render(list, area, &mut buffer, &mut state);
+            buffer
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        let ui = UIConfig::default();
+        assert_eq!(ui.highlight_symbol(), "▶ ");
+        assert_eq!(render_selected(&ui), "▶ app   ");
+
+        let ascii = UIConfig {
+            ascii_symbols: true,
+            ..Default::default()
+        };
+        assert_eq!(render_selected(&ascii), "> app   ");
+
+        // A configured symbol wins over the ASCII fallback, and may be empty
+        let custom = UIConfig {
+            ascii_symbols: true,
+            selection_symbol: Some("* ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(render_selected(&custom), "* app   ");
+        let none = UIConfig {
+            selection_symbol: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(render_selected(&none), "app     ");
+    }
+
+    #[test]
+    fn test_row_style_selected_vs_unselected() {
+        let unselected = row_style(Color::Green, false);
+        assert_eq!(unselected.fg, Some(Color::Green));
+        assert!(!unselected.add_modifier.contains(Modifier::BOLD));
+
+        let selected = row_style(Color::Green, true);
+        assert_eq!(selected.fg, Some(Color::Green));
+        assert!(selected.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn test_selected_row_style_composes_with_status_color() {
+        let colors = ColorScheme::default();
+        let content = row_style(colors.status_dirty, true);
+
+        // Background highlight keeps the status color and adds the highlight background
+        let background = content.patch(selected_row_style(&colors, HighlightMode::Background));
+        assert_eq!(background.fg, Some(colors.status_dirty));
+        assert_eq!(background.bg, Some(colors.highlight_bg));
+
+        // Reverse video keeps the status color and reverses the whole row
+        let reverse = content.patch(selected_row_style(&colors, HighlightMode::Reverse));
+        assert_eq!(reverse.fg, Some(colors.status_dirty));
+        assert!(
+            reverse
+                .add_modifier
+                .contains(Modifier::REVERSED | Modifier::BOLD)
+        );
+    }
 }
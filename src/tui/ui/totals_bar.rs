@@ -0,0 +1,65 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use crate::config::ColorScheme;
+use crate::core::status::RepoTotals;
+use crate::tui::state::RenderSnapshot;
+
+/// Render a one-line bar of pending work totals across all repositories
+pub fn render_totals_bar(
+    f: &mut Frame,
+    snapshot: &RenderSnapshot,
+    area: Rect,
+    colors: &ColorScheme,
+) {
+    let totals = RepoTotals::from_repos(&snapshot.repos);
+    f.render_widget(Paragraph::new(build_totals_line(&totals, colors)), area);
+}
+
+/// Build the totals, leaving out those at zero except the repository count
+fn build_totals_line(totals: &RepoTotals, colors: &ColorScheme) -> Line<'static> {
+    let counts = [
+        (totals.uncommitted, "uncommitted files", colors.status_dirty),
+        (totals.stashes, "stashes", colors.text_secondary),
+        (totals.ahead, "ahead", colors.commit_ahead),
+        (totals.behind, "behind", colors.commit_behind),
+    ];
+    let mut spans = vec![Span::styled(
+        format!(" {} repos", totals.repos),
+        Style::default().fg(colors.text_primary),
+    )];
+    for (count, label, color) in counts {
+        if count > 0 {
+            spans.push(Span::styled(
+                format!("  {} {}", count, label),
+                Style::default().fg(color),
+            ));
+        }
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::RepoInfo;
+
+    #[test]
+    fn test_totals_line() {
+        let mut api = RepoInfo::stub("api", "/repos/api");
+        api.working.modified = 2;
+        api.sync.ahead = 1;
+        let mut web = RepoInfo::stub("web", "/repos/web");
+        web.working.untracked = 3;
+        web.sync.ahead = 4;
+
+        let totals = RepoTotals::from_repos(&[api, web]);
+        let line = build_totals_line(&totals, &ColorScheme::default());
+        assert_eq!(line.to_string(), " 2 repos  5 uncommitted files  5 ahead");
+    }
+}